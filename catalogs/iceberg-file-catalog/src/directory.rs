@@ -0,0 +1,838 @@
+/*!
+ * Catalog that stores tables in the directories of an object store
+ *
+ * The [DirectoryCatalog] doesn't require an external catalog service. The namespaces are the
+ * directories below the location of the catalog and the tables the directories of a namespace:
+ *
+ * ```text
+ * s3://bucket/warehouse/<namespace>/<name>/metadata/v1.metadata.json
+ * s3://bucket/warehouse/<namespace>/<name>/metadata/v2.metadata.json
+ * s3://bucket/warehouse/<namespace>/<name>/metadata/version-hint.text
+ * ```
+ *
+ * Like the Hadoop catalog, the version hint contains the version of the current metadata file.
+ * A commit creates the metadata file of the next version with a conditional write, so that only
+ * one of several concurrent writers succeeds. The version hint is updated afterwards. If another
+ * writer changed it in the meantime, it is only overwritten if it still points to an older
+ * version, and readers check for newer metadata files than the one of the version hint.
+*/
+
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+use iceberg_rust::{
+    catalog::{
+        cache::MetadataCache,
+        commit::{
+            apply_table_updates, apply_view_updates, check_view_requirements,
+            validate_table_requirements, CommitTable, CommitView,
+        },
+        create::{CreateMaterializedView, CreateTable, CreateView},
+        identifier::Identifier,
+        namespace::Namespace,
+        tabular::Tabular,
+        Catalog,
+    },
+    error::Error as IcebergError,
+    materialized_view::MaterializedView,
+    object_store::{store::parse_metadata, Bucket, ObjectStoreBuilder},
+    spec::{
+        identifier::FullIdentifier, materialized_view_metadata::MaterializedViewMetadata,
+        table_metadata::TableMetadata, tabular::TabularMetadata, util::strip_prefix,
+        view_metadata::ViewMetadata,
+    },
+    table::Table,
+    view::View,
+};
+use object_store::{path::Path, ObjectStore, PutMode, UpdateVersion};
+
+/// Name of the file that marks an empty namespace
+static NAMESPACE_MARKER: &str = ".namespace";
+
+/// Maximum number of attempts to commit a new version of a tabular
+static MAX_COMMIT_ATTEMPTS: usize = 10;
+
+/// Catalog that stores tables in the directories of an object store and points to their current
+/// metadata with a version hint
+#[derive(Debug)]
+pub struct DirectoryCatalog {
+    name: String,
+    location: String,
+    object_store: ObjectStoreBuilder,
+    metadata_cache: Option<MetadataCache>,
+}
+
+/// The current metadata file of a tabular
+struct CurrentVersion {
+    /// Version of the metadata file
+    version: u64,
+    /// Version of the version hint file, used to update it conditionally
+    hint: UpdateVersion,
+}
+
+impl DirectoryCatalog {
+    /// Creates a new catalog for the tables below the location
+    ///
+    /// # Arguments
+    /// * `name` - Name of the catalog
+    /// * `location` - Location of the catalog, like `s3://bucket/warehouse`
+    /// * `object_store` - Builder for the object store of the location
+    pub fn new(name: &str, location: &str, object_store: ObjectStoreBuilder) -> Self {
+        DirectoryCatalog {
+            name: name.to_owned(),
+            location: location.trim_end_matches('/').to_owned(),
+            object_store,
+            metadata_cache: None,
+        }
+    }
+
+    /// Caches the metadata files that are loaded by the catalog
+    ///
+    /// Loading a table, view or materialized view whose version didn't change returns the cached
+    /// metadata instead of reading the metadata file again.
+    pub fn with_metadata_cache(mut self, metadata_cache: MetadataCache) -> Self {
+        self.metadata_cache = Some(metadata_cache);
+        self
+    }
+
+    fn store(&self) -> Result<Arc<dyn ObjectStore>, IcebergError> {
+        self.object_store.build(Bucket::from_path(&self.location)?)
+    }
+
+    fn namespace_location(&self, namespace: &[String]) -> String {
+        namespace
+            .iter()
+            .fold(self.location.clone(), |location, level| {
+                location + "/" + level
+            })
+    }
+
+    fn tabular_location(&self, identifier: &Identifier) -> String {
+        self.namespace_location(identifier.namespace()) + "/" + identifier.name()
+    }
+
+    /// Reads the version hint of the tabular at the location and looks for newer metadata files
+    ///
+    /// Returns None if the location doesn't contain a tabular.
+    async fn current_version(
+        &self,
+        object_store: &dyn ObjectStore,
+        location: &str,
+    ) -> Result<Option<CurrentVersion>, IcebergError> {
+        let result = match object_store
+            .get(&path(&version_hint_location(location)))
+            .await
+        {
+            Ok(result) => result,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let hint = UpdateVersion {
+            e_tag: result.meta.e_tag.clone(),
+            version: result.meta.version.clone(),
+        };
+        let bytes = result.bytes().await?;
+        let mut version = parse_version_hint(std::str::from_utf8(&bytes)?)?;
+
+        // The version hint is updated after the metadata file is written, so it may be behind
+        loop {
+            match object_store
+                .head(&path(&metadata_location(location, version + 1)))
+                .await
+            {
+                Ok(_) => version += 1,
+                Err(object_store::Error::NotFound { .. }) => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(Some(CurrentVersion { version, hint }))
+    }
+
+    async fn read_metadata(
+        &self,
+        object_store: &dyn ObjectStore,
+        identifier: &Identifier,
+        metadata_location: &str,
+    ) -> Result<TabularMetadata, IcebergError> {
+        let load = async {
+            let bytes = object_store
+                .get(&path(metadata_location))
+                .await?
+                .bytes()
+                .await?;
+            parse_metadata(&bytes)
+        };
+        match &self.metadata_cache {
+            Some(metadata_cache) => {
+                metadata_cache
+                    .get_or_load(identifier, metadata_location, load)
+                    .await
+            }
+            None => load.await,
+        }
+    }
+
+    /// Writes the first version of a new tabular
+    async fn create_tabular(
+        &self,
+        object_store: &dyn ObjectStore,
+        metadata: TabularMetadata,
+    ) -> Result<(), IcebergError> {
+        let location = metadata.as_ref().location().to_owned();
+        if self
+            .current_version(object_store, &location)
+            .await?
+            .is_some()
+        {
+            return Err(IcebergError::InvalidFormat(format!(
+                "Tabular at {location} already exists. Location"
+            )));
+        }
+        if !write_version(object_store, &metadata, 1).await? {
+            return Err(IcebergError::InvalidFormat(format!(
+                "Tabular at {location} already exists. Location"
+            )));
+        }
+        update_version_hint(object_store, &location, 1, None).await
+    }
+
+    /// Commits the changes to the current metadata of the tabular as its next version
+    ///
+    /// If another writer committed the same version first, the changes are applied to the new
+    /// metadata of the other writer, as long as their requirements are still met. The commit
+    /// fails with a [`IcebergError::ConcurrentCommits`] after `MAX_COMMIT_ATTEMPTS` lost races.
+    async fn commit_tabular(
+        &self,
+        identifier: &Identifier,
+        update: impl Fn(TabularMetadata) -> Result<TabularMetadata, IcebergError> + Send,
+    ) -> Result<TabularMetadata, IcebergError> {
+        let object_store = self.store()?;
+        let location = self.tabular_location(identifier);
+        for _ in 0..MAX_COMMIT_ATTEMPTS {
+            let current = self
+                .current_version(object_store.as_ref(), &location)
+                .await?
+                .ok_or(IcebergError::CatalogNotFound)?;
+            let metadata = self
+                .read_metadata(
+                    object_store.as_ref(),
+                    identifier,
+                    &metadata_location(&location, current.version),
+                )
+                .await?;
+            let metadata = update(metadata)?;
+            let version = current.version + 1;
+            if write_version(object_store.as_ref(), &metadata, version).await? {
+                advance_version_hint(object_store.as_ref(), &location, version, current.hint)
+                    .await?;
+                return Ok(metadata);
+            }
+        }
+        Err(IcebergError::ConcurrentCommits {
+            identifier: identifier.to_string(),
+            attempts: MAX_COMMIT_ATTEMPTS,
+        })
+    }
+
+    /// Returns the names of the directories below the location that contain a tabular or not
+    async fn directories(
+        &self,
+        location: &str,
+        tabulars: bool,
+    ) -> Result<Vec<String>, IcebergError> {
+        let object_store = self.store()?;
+        let mut names = Vec::new();
+        for prefix in object_store
+            .list_with_delimiter(Some(&path(location)))
+            .await?
+            .common_prefixes
+        {
+            let Some(name) = prefix.filename() else {
+                continue;
+            };
+            let is_tabular = self
+                .current_version(object_store.as_ref(), &format!("{location}/{name}"))
+                .await?
+                .is_some();
+            if is_tabular == tabulars {
+                names.push(name.to_owned());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    async fn drop_tabular(&self, identifier: &Identifier) -> Result<(), IcebergError> {
+        let object_store = self.store()?;
+        let location = self.tabular_location(identifier);
+        if self
+            .current_version(object_store.as_ref(), &location)
+            .await?
+            .is_none()
+        {
+            return Err(IcebergError::CatalogNotFound);
+        }
+        // Removing the version hint first makes the tabular invisible to other readers
+        object_store
+            .delete(&path(&version_hint_location(&location)))
+            .await?;
+        let metadata_files = object_store
+            .list_with_delimiter(Some(&path(&format!("{location}/metadata"))))
+            .await?
+            .objects;
+        for file in metadata_files {
+            object_store.delete(&file.location).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Catalog for DirectoryCatalog {
+    /// Catalog name
+    fn name(&self) -> &str {
+        &self.name
+    }
+    /// Create a namespace in the catalog
+    async fn create_namespace(
+        &self,
+        namespace: &Namespace,
+        properties: Option<HashMap<String, String>>,
+    ) -> Result<HashMap<String, String>, IcebergError> {
+        if properties.is_some_and(|properties| !properties.is_empty()) {
+            return Err(IcebergError::NotSupported(
+                "Namespace properties in a directory catalog".to_owned(),
+            ));
+        }
+        if self.namespace_exists(namespace).await? {
+            return Err(IcebergError::InvalidFormat(format!(
+                "Namespace {namespace} already exists. Namespace"
+            )));
+        }
+        // Object stores don't have empty directories, the marker makes the namespace visible
+        self.store()?
+            .put(
+                &path(&(self.namespace_location(namespace) + "/" + NAMESPACE_MARKER)),
+                Vec::new().into(),
+            )
+            .await?;
+        Ok(HashMap::new())
+    }
+    /// Drop a namespace in the catalog
+    async fn drop_namespace(&self, namespace: &Namespace) -> Result<(), IcebergError> {
+        if !self.list_tabulars(namespace).await?.is_empty() {
+            return Err(IcebergError::InvalidFormat(format!(
+                "Namespace {namespace} is not empty. Namespace"
+            )));
+        }
+        match self
+            .store()?
+            .delete(&path(
+                &(self.namespace_location(namespace) + "/" + NAMESPACE_MARKER),
+            ))
+            .await
+        {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+    /// Load the namespace properties from the catalog
+    async fn load_namespace(
+        &self,
+        namespace: &Namespace,
+    ) -> Result<HashMap<String, String>, IcebergError> {
+        if self.namespace_exists(namespace).await? {
+            Ok(HashMap::new())
+        } else {
+            Err(IcebergError::NotFound(format!("Namespace {namespace}")))
+        }
+    }
+    /// Update the namespace properties in the catalog
+    async fn update_namespace(
+        &self,
+        _namespace: &Namespace,
+        _updates: Option<HashMap<String, String>>,
+        _removals: Option<Vec<String>>,
+    ) -> Result<(), IcebergError> {
+        Err(IcebergError::NotSupported(
+            "Namespace properties in a directory catalog".to_owned(),
+        ))
+    }
+    /// Check if a namespace exists
+    async fn namespace_exists(&self, namespace: &Namespace) -> Result<bool, IcebergError> {
+        let location = self.namespace_location(namespace);
+        Ok(self
+            .store()?
+            .list_with_delimiter(Some(&path(&location)))
+            .await
+            .map(|result| !result.objects.is_empty() || !result.common_prefixes.is_empty())?)
+    }
+    async fn list_tabulars(&self, namespace: &Namespace) -> Result<Vec<Identifier>, IcebergError> {
+        Ok(self
+            .directories(&self.namespace_location(namespace), true)
+            .await?
+            .into_iter()
+            .map(|name| Identifier::new(namespace, &name))
+            .collect())
+    }
+    async fn list_namespaces(&self, parent: Option<&str>) -> Result<Vec<Namespace>, IcebergError> {
+        let parent: Vec<String> = parent
+            .map(|parent| parent.split('.').map(ToOwned::to_owned).collect())
+            .unwrap_or_default();
+        self.directories(&self.namespace_location(&parent), false)
+            .await?
+            .into_iter()
+            .map(|name| {
+                let mut levels = parent.clone();
+                levels.push(name);
+                Namespace::try_new(&levels).map_err(IcebergError::from)
+            })
+            .collect()
+    }
+    async fn tabular_exists(&self, identifier: &Identifier) -> Result<bool, IcebergError> {
+        Ok(self
+            .current_version(self.store()?.as_ref(), &self.tabular_location(identifier))
+            .await?
+            .is_some())
+    }
+    async fn table_exists(&self, identifier: &Identifier) -> Result<bool, IcebergError> {
+        self.tabular_exists(identifier).await
+    }
+    async fn drop_table(&self, identifier: &Identifier) -> Result<(), IcebergError> {
+        self.drop_tabular(identifier).await
+    }
+    async fn drop_view(&self, identifier: &Identifier) -> Result<(), IcebergError> {
+        self.drop_tabular(identifier).await
+    }
+    async fn drop_materialized_view(&self, identifier: &Identifier) -> Result<(), IcebergError> {
+        self.drop_tabular(identifier).await
+    }
+    async fn load_tabular(
+        self: Arc<Self>,
+        identifier: &Identifier,
+    ) -> Result<Tabular, IcebergError> {
+        let object_store = self.store()?;
+        let location = self.tabular_location(identifier);
+        let current = self
+            .current_version(object_store.as_ref(), &location)
+            .await?
+            .ok_or(IcebergError::CatalogNotFound)?;
+        let metadata = self
+            .read_metadata(
+                object_store.as_ref(),
+                identifier,
+                &metadata_location(&location, current.version),
+            )
+            .await?;
+
+        match metadata {
+            TabularMetadata::Table(metadata) => Ok(Tabular::Table(
+                Table::new(identifier.clone(), self.clone(), metadata).await?,
+            )),
+            TabularMetadata::View(metadata) => Ok(Tabular::View(
+                View::new(identifier.clone(), self.clone(), metadata).await?,
+            )),
+            TabularMetadata::MaterializedView(metadata) => Ok(Tabular::MaterializedView(
+                MaterializedView::new(identifier.clone(), self.clone(), metadata).await?,
+            )),
+        }
+    }
+
+    async fn create_table(
+        self: Arc<Self>,
+        identifier: Identifier,
+        mut create_table: CreateTable,
+    ) -> Result<Table, IcebergError> {
+        create_table.location = Some(self.tabular_location(&identifier));
+        let metadata: TableMetadata = create_table.try_into()?;
+
+        self.create_tabular(self.store()?.as_ref(), metadata.clone().into())
+            .await?;
+
+        Table::new(identifier, self.clone(), metadata).await
+    }
+
+    async fn create_view(
+        self: Arc<Self>,
+        identifier: Identifier,
+        mut create_view: CreateView<Option<()>>,
+    ) -> Result<View, IcebergError> {
+        create_view.location = Some(self.tabular_location(&identifier));
+        let metadata: ViewMetadata = create_view.try_into()?;
+
+        self.create_tabular(self.store()?.as_ref(), metadata.clone().into())
+            .await?;
+
+        View::new(identifier, self.clone(), metadata).await
+    }
+
+    async fn create_materialized_view(
+        self: Arc<Self>,
+        identifier: Identifier,
+        create_view: CreateMaterializedView,
+    ) -> Result<MaterializedView, IcebergError> {
+        let (mut create_view, mut create_table) = create_view.into();
+
+        create_view.location = Some(self.tabular_location(&identifier));
+        let metadata: MaterializedViewMetadata = create_view.try_into()?;
+
+        let storage_table = metadata.current_version(None)?.storage_table();
+        create_table.location = Some(self.tabular_location(&Identifier::new(
+            storage_table.namespace(),
+            storage_table.name(),
+        )));
+        let table_metadata: TableMetadata = create_table.try_into()?;
+
+        let object_store = self.store()?;
+        self.create_tabular(object_store.as_ref(), table_metadata.into())
+            .await?;
+        self.create_tabular(object_store.as_ref(), metadata.clone().into())
+            .await?;
+
+        MaterializedView::new(identifier, self.clone(), metadata).await
+    }
+
+    async fn update_table(self: Arc<Self>, commit: CommitTable) -> Result<Table, IcebergError> {
+        let metadata = self
+            .commit_tabular(&commit.identifier, |metadata| {
+                let TabularMetadata::Table(mut metadata) = metadata else {
+                    return Err(IcebergError::InvalidFormat(
+                        "Table update on entity that is not a table".to_owned(),
+                    ));
+                };
+                validate_table_requirements(&commit.requirements, &metadata)?;
+                apply_table_updates(&mut metadata, commit.updates.clone())?;
+                Ok(metadata.into())
+            })
+            .await?;
+        let TabularMetadata::Table(metadata) = metadata else {
+            return Err(IcebergError::InvalidFormat(
+                "Entity is not a table".to_owned(),
+            ));
+        };
+        Table::new(commit.identifier, self.clone(), metadata).await
+    }
+
+    async fn update_view(
+        self: Arc<Self>,
+        commit: CommitView<Option<()>>,
+    ) -> Result<View, IcebergError> {
+        let metadata = self
+            .commit_tabular(&commit.identifier, |metadata| {
+                let TabularMetadata::View(mut metadata) = metadata else {
+                    return Err(IcebergError::InvalidFormat(
+                        "View update on entity that is not a view".to_owned(),
+                    ));
+                };
+                if !check_view_requirements(&commit.requirements, &metadata) {
+                    return Err(IcebergError::InvalidFormat(
+                        "View requirements not valid".to_owned(),
+                    ));
+                }
+                apply_view_updates(&mut metadata, commit.updates.clone())?;
+                Ok(metadata.into())
+            })
+            .await?;
+        let TabularMetadata::View(metadata) = metadata else {
+            return Err(IcebergError::InvalidFormat(
+                "Entity is not a view".to_owned(),
+            ));
+        };
+        View::new(commit.identifier, self.clone(), metadata).await
+    }
+
+    async fn update_materialized_view(
+        self: Arc<Self>,
+        commit: CommitView<FullIdentifier>,
+    ) -> Result<MaterializedView, IcebergError> {
+        let metadata = self
+            .commit_tabular(&commit.identifier, |metadata| {
+                let TabularMetadata::MaterializedView(mut metadata) = metadata else {
+                    return Err(IcebergError::InvalidFormat(
+                        "Materialized view update on entity that is not a materialized view"
+                            .to_owned(),
+                    ));
+                };
+                if !check_view_requirements(&commit.requirements, &metadata) {
+                    return Err(IcebergError::InvalidFormat(
+                        "Materialized view requirements not valid".to_owned(),
+                    ));
+                }
+                apply_view_updates(&mut metadata, commit.updates.clone())?;
+                Ok(metadata.into())
+            })
+            .await?;
+        let TabularMetadata::MaterializedView(metadata) = metadata else {
+            return Err(IcebergError::InvalidFormat(
+                "Entity is not a materialized view".to_owned(),
+            ));
+        };
+        MaterializedView::new(commit.identifier, self.clone(), metadata).await
+    }
+
+    async fn register_table(
+        self: Arc<Self>,
+        _identifier: Identifier,
+        _metadata_location: &str,
+    ) -> Result<Table, IcebergError> {
+        Err(IcebergError::NotSupported(
+            "Registering tables in a directory catalog".to_owned(),
+        ))
+    }
+
+    async fn rename_table(&self, _from: &Identifier, _to: &Identifier) -> Result<(), IcebergError> {
+        Err(IcebergError::NotSupported(
+            "Renaming tables in a directory catalog".to_owned(),
+        ))
+    }
+
+    fn object_store(&self, bucket: Bucket) -> Arc<dyn ObjectStore> {
+        self.object_store.build(bucket).unwrap()
+    }
+}
+
+fn path(location: &str) -> Path {
+    strip_prefix(location).into()
+}
+
+fn version_hint_location(location: &str) -> String {
+    location.to_owned() + "/metadata/version-hint.text"
+}
+
+fn metadata_location(location: &str, version: u64) -> String {
+    format!("{location}/metadata/v{version}.metadata.json")
+}
+
+/// Parses the version of a version hint, which contains the version or the location of the
+/// metadata file
+fn parse_version_hint(hint: &str) -> Result<u64, IcebergError> {
+    let hint = hint.trim();
+    hint.rsplit('/')
+        .next()
+        .unwrap_or(hint)
+        .trim_start_matches('v')
+        .trim_end_matches(".metadata.json")
+        .parse()
+        .map_err(|_| IcebergError::InvalidFormat(format!("Version hint {hint}")))
+}
+
+/// Writes the metadata as the given version of the tabular
+///
+/// Returns false if the version already exists, because another writer committed it first.
+async fn write_version(
+    object_store: &dyn ObjectStore,
+    metadata: &TabularMetadata,
+    version: u64,
+) -> Result<bool, IcebergError> {
+    let location = metadata_location(metadata.as_ref().location(), version);
+    match object_store
+        .put_opts(
+            &path(&location),
+            serde_json::to_vec(&metadata.as_ref())?.into(),
+            PutMode::Create.into(),
+        )
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(object_store::Error::AlreadyExists { .. }) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Points the version hint of the tabular to a version that was just committed
+///
+/// If another writer updated the version hint in the meantime, the hint is read again and only
+/// updated if it still points to an older version. The last precondition error is returned if
+/// the hint couldn't be updated in `MAX_COMMIT_ATTEMPTS` attempts.
+async fn advance_version_hint(
+    object_store: &dyn ObjectStore,
+    location: &str,
+    version: u64,
+    mut previous: UpdateVersion,
+) -> Result<(), IcebergError> {
+    let mut attempts = 1;
+    loop {
+        match update_version_hint(object_store, location, version, Some(previous)).await {
+            Err(IcebergError::ObjectStore(object_store::Error::Precondition { .. }))
+                if attempts < MAX_COMMIT_ATTEMPTS =>
+            {
+                attempts += 1;
+                let result = object_store
+                    .get(&path(&version_hint_location(location)))
+                    .await?;
+                previous = UpdateVersion {
+                    e_tag: result.meta.e_tag.clone(),
+                    version: result.meta.version.clone(),
+                };
+                let bytes = result.bytes().await?;
+                if parse_version_hint(std::str::from_utf8(&bytes)?)? >= version {
+                    return Ok(());
+                }
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Points the version hint of the tabular to the version
+///
+/// The version hint is only overwritten if it didn't change since it was read, otherwise the
+/// precondition error of the object store is returned. A new version hint must not exist yet.
+/// Object stores without conditional updates overwrite the version hint unconditionally.
+async fn update_version_hint(
+    object_store: &dyn ObjectStore,
+    location: &str,
+    version: u64,
+    previous: Option<UpdateVersion>,
+) -> Result<(), IcebergError> {
+    let hint_path = path(&version_hint_location(location));
+    let mode = match previous {
+        Some(previous) => PutMode::Update(previous),
+        None => PutMode::Create,
+    };
+    match object_store
+        .put_opts(&hint_path, version.to_string().into(), mode.into())
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(object_store::Error::NotImplemented) => {
+            object_store
+                .put(&hint_path, version.to_string().into())
+                .await?;
+            Ok(())
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use iceberg_rust::{
+        catalog::{identifier::Identifier, namespace::Namespace, Catalog},
+        object_store::ObjectStoreBuilder,
+        spec::{
+            schema::Schema,
+            types::{PrimitiveType, StructField, Type},
+            util::strip_prefix,
+        },
+        table::Table,
+    };
+    use object_store::{ObjectStore, UpdateVersion};
+
+    use super::{advance_version_hint, path, version_hint_location, DirectoryCatalog};
+
+    #[tokio::test]
+    async fn test_directory_catalog() {
+        let object_store = ObjectStoreBuilder::memory();
+        let catalog = Arc::new(DirectoryCatalog::new(
+            "warehouse",
+            "/warehouse",
+            object_store.clone(),
+        ));
+
+        let namespace = Namespace::try_new(&["tpch".to_owned()]).unwrap();
+        catalog.create_namespace(&namespace, None).await.unwrap();
+        assert_eq!(
+            catalog.list_namespaces(None).await.unwrap(),
+            vec![namespace.clone()]
+        );
+
+        let schema = Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+
+        let mut table = Table::builder()
+            .with_name("orders")
+            .with_schema(schema)
+            .build(&namespace, catalog.clone())
+            .await
+            .unwrap();
+
+        let identifier = Identifier::new(&namespace, "orders");
+        assert_eq!(
+            catalog.list_tabulars(&namespace).await.unwrap(),
+            vec![identifier.clone()]
+        );
+        assert_eq!(
+            catalog.list_namespaces(None).await.unwrap(),
+            vec![namespace.clone()]
+        );
+
+        // A second writer commits with an outdated version of the table
+        let mut other = catalog.clone().load_table(&identifier).await.unwrap();
+        table
+            .new_transaction(None)
+            .update_properties(vec![("first".to_owned(), "1".to_owned())])
+            .commit()
+            .await
+            .unwrap();
+        other
+            .new_transaction(None)
+            .update_properties(vec![("second".to_owned(), "2".to_owned())])
+            .commit()
+            .await
+            .unwrap();
+
+        let table = catalog.clone().load_table(&identifier).await.unwrap();
+        assert_eq!(table.metadata().properties.get("first").unwrap(), "1");
+        assert_eq!(table.metadata().properties.get("second").unwrap(), "2");
+
+        let store = object_store
+            .build(iceberg_rust::object_store::Bucket::Local)
+            .unwrap();
+        let hint_path = strip_prefix("/warehouse/tpch/orders/metadata/version-hint.text").into();
+        let hint = store.get(&hint_path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(&hint[..], b"3");
+
+        // Newer metadata files than the one of a stale version hint are found
+        store.put(&hint_path, "1".into()).await.unwrap();
+        let table = catalog.clone().load_table(&identifier).await.unwrap();
+        assert_eq!(table.metadata().properties.get("second").unwrap(), "2");
+
+        catalog.drop_table(&identifier).await.unwrap();
+        assert!(!catalog.table_exists(&identifier).await.unwrap());
+        catalog.drop_namespace(&namespace).await.unwrap();
+        assert!(catalog.list_namespaces(None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_advance_version_hint() {
+        let store = ObjectStoreBuilder::memory()
+            .build(iceberg_rust::object_store::Bucket::Local)
+            .unwrap();
+        let location = "/warehouse/tpch/orders";
+        let hint_path = path(&version_hint_location(location));
+
+        let put = store.put(&hint_path, "1".into()).await.unwrap();
+        let previous = UpdateVersion {
+            e_tag: put.e_tag,
+            version: put.version,
+        };
+
+        // Another writer updated the version hint to an older version in the meantime
+        store.put(&hint_path, "2".into()).await.unwrap();
+        advance_version_hint(store.as_ref(), location, 3, previous.clone())
+            .await
+            .unwrap();
+        let hint = store.get(&hint_path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(&hint[..], b"3");
+
+        // A newer version of another writer is kept
+        store.put(&hint_path, "5".into()).await.unwrap();
+        advance_version_hint(store.as_ref(), location, 4, previous)
+            .await
+            .unwrap();
+        let hint = store.get(&hint_path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(&hint[..], b"5");
+    }
+}
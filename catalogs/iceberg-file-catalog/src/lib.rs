@@ -8,9 +8,10 @@ use async_trait::async_trait;
 use futures::{future, TryStreamExt};
 use iceberg_rust::{
     catalog::{
+        cache::MetadataCache,
         commit::{
-            apply_table_updates, apply_view_updates, check_table_requirements,
-            check_view_requirements, CommitTable, CommitView, TableRequirement,
+            apply_table_updates, apply_view_updates, check_view_requirements,
+            validate_table_requirements, CommitTable, CommitView, TableRequirement,
         },
         create::{CreateMaterializedView, CreateTable, CreateView},
         identifier::Identifier,
@@ -20,7 +21,10 @@ use iceberg_rust::{
     },
     error::Error as IcebergError,
     materialized_view::MaterializedView,
-    object_store::{store::IcebergStore, Bucket, ObjectStoreBuilder},
+    object_store::{
+        store::{parse_metadata, IcebergStore},
+        Bucket, ObjectStoreBuilder,
+    },
     spec::{
         identifier::FullIdentifier,
         materialized_view_metadata::MaterializedViewMetadata,
@@ -41,8 +45,10 @@ pub struct FileCatalog {
     path: String,
     object_store: ObjectStoreBuilder,
     cache: Arc<RwLock<HashMap<Identifier, (String, TabularMetadata)>>>,
+    metadata_cache: Option<MetadataCache>,
 }
 
+pub mod directory;
 pub mod error;
 
 impl FileCatalog {
@@ -51,9 +57,19 @@ impl FileCatalog {
             path: path.to_owned(),
             object_store,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            metadata_cache: None,
         })
     }
 
+    /// Caches the metadata files that are loaded by the catalog
+    ///
+    /// Loading a table, view or materialized view whose metadata location didn't change returns
+    /// the cached metadata instead of reading the metadata file again.
+    pub fn with_metadata_cache(mut self, metadata_cache: MetadataCache) -> Self {
+        self.metadata_cache = Some(metadata_cache);
+        self
+    }
+
     pub fn catalog_list(&self) -> Arc<FileCatalogList> {
         Arc::new(FileCatalogList {
             path: self.path.clone(),
@@ -138,6 +154,9 @@ impl Catalog for FileCatalog {
             .map(|_| true)
             .or(Ok(false))
     }
+    async fn table_exists(&self, identifier: &Identifier) -> Result<bool, IcebergError> {
+        self.tabular_exists(identifier).await
+    }
     async fn drop_table(&self, _identifierr: &Identifier) -> Result<(), IcebergError> {
         todo!()
     }
@@ -156,14 +175,23 @@ impl Catalog for FileCatalog {
 
         let metadata_location = self.metadata_location(identifier).await?;
 
-        let bytes = object_store
-            .get(&strip_prefix(&metadata_location).as_str().into())
-            .await
-            .map_err(|_| IcebergError::CatalogNotFound)?
-            .bytes()
-            .await?;
-
-        let metadata: TabularMetadata = serde_json::from_slice(&bytes)?;
+        let load = async {
+            let bytes = object_store
+                .get(&strip_prefix(&metadata_location).as_str().into())
+                .await
+                .map_err(|_| IcebergError::CatalogNotFound)?
+                .bytes()
+                .await?;
+            parse_metadata(&bytes)
+        };
+        let metadata = match &self.metadata_cache {
+            Some(metadata_cache) => {
+                metadata_cache
+                    .get_or_load(identifier, &metadata_location, load)
+                    .await?
+            }
+            None => load.await?,
+        };
 
         self.cache.write().unwrap().insert(
             identifier.clone(),
@@ -329,11 +357,7 @@ impl Catalog for FileCatalog {
                 "Table update on entity that is not a table".to_owned(),
             ));
         };
-        if !check_table_requirements(&commit.requirements, &metadata) {
-            return Err(IcebergError::InvalidFormat(
-                "Table requirements not valid".to_owned(),
-            ));
-        }
+        validate_table_requirements(&commit.requirements, &metadata)?;
         apply_table_updates(&mut metadata, commit.updates)?;
         let temp_metadata_location = new_metadata_location(&metadata);
 
@@ -492,6 +516,12 @@ impl Catalog for FileCatalog {
         unimplemented!()
     }
 
+    async fn rename_table(&self, _from: &Identifier, _to: &Identifier) -> Result<(), IcebergError> {
+        Err(IcebergError::NotSupported(
+            "Renaming tables in a file catalog".to_owned(),
+        ))
+    }
+
     fn object_store(&self, bucket: Bucket) -> Arc<dyn object_store::ObjectStore> {
         Arc::new(self.object_store.build(bucket).unwrap())
     }
@@ -622,6 +652,7 @@ impl CatalogList for FileCatalogList {
             path: self.path.clone() + "/" + name,
             object_store: self.object_store.clone(),
             cache: Arc::new(RwLock::new(HashMap::new())),
+            metadata_cache: None,
         }))
     }
     async fn list_catalogs(&self) -> Vec<String> {
@@ -741,6 +741,13 @@ pub async fn load_credentials(
     if let Some(ref token) = configuration.bearer_access_token {
         req_builder = req_builder.bearer_auth(token.to_owned());
     };
+    if let Some(ref token_provider) = configuration.token_provider {
+        let token = token_provider
+            .token(configuration)
+            .await
+            .map_err(super::fetch::token_error)?;
+        req_builder = req_builder.bearer_auth(token);
+    };
 
     let req = req_builder.build()?;
     let resp = configuration.client.execute(req).await?;
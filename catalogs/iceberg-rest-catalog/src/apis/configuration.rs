@@ -12,7 +12,9 @@ use aws_sigv4::http_request::{sign, SignableRequest, SigningParams, SigningSetti
 use derive_builder::Builder;
 use http;
 use secrecy::{ExposeSecret, SecretString};
-use std::time::SystemTime;
+use std::{sync::Arc, time::SystemTime};
+
+use crate::token::TokenProvider;
 
 #[derive(Debug, Clone, Builder)]
 pub struct Configuration {
@@ -31,6 +33,8 @@ pub struct Configuration {
     pub api_key: Option<ApiKey>,
     #[builder(setter(into, strip_option), default)]
     pub aws_v4_key: Option<AWSv4Key>,
+    #[builder(setter(strip_option), default)]
+    pub token_provider: Option<Arc<dyn TokenProvider>>,
 }
 
 pub type BasicAuth = (String, Option<String>);
@@ -107,6 +111,7 @@ impl Default for Configuration {
             bearer_access_token: None,
             api_key: None,
             aws_v4_key: None,
+            token_provider: None,
         }
     }
 }
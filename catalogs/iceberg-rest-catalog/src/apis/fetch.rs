@@ -18,56 +18,16 @@ where
     T: for<'a> serde::Deserialize<'a>,
     E: for<'a> serde::Deserialize<'a>,
 {
-    let uri_base = match prefix {
-        Some(prefix) => format!(
-            "{}/v1/{prefix}/",
-            configuration.base_path,
-            prefix = crate::apis::urlencode(prefix)
-        ),
-        None => format!("{}/v1/", configuration.base_path,),
-    };
-    let client = &configuration.client;
-
-    let uri = uri_base + uri_str;
-    let mut req_builder = client.request(method.clone(), &uri);
-
-    if let Some(ref aws_v4_key) = configuration.aws_v4_key {
-        let new_headers = match aws_v4_key.sign(
-            &uri,
-            method.as_str(),
-            &serde_json::to_string(&request).expect("param should serialize to string"),
-        ) {
-            Ok(new_headers) => new_headers,
-            Err(err) => return Err(Error::AWSV4SignatureError(err)),
-        };
-        for (name, value) in new_headers.iter() {
-            req_builder = req_builder.header(name.as_str(), value.as_str());
-        }
-    }
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref token) = configuration.oauth_access_token {
-        req_builder = req_builder.bearer_auth(token.to_owned());
-    };
-    if let Some(ref token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(token.to_owned());
-    };
-    for (key, value) in headers.unwrap_or_default() {
-        req_builder = req_builder.header(key, value);
-    }
-    for (key, value) in query_params.unwrap_or_default() {
-        req_builder = req_builder.query(&[(key, value)]);
-    }
-    if let &reqwest::Method::POST | &reqwest::Method::PUT = &method {
-        req_builder = req_builder.json(request);
-    }
-
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
-
-    let status = resp.status();
-    let content = resp.text().await?;
+    let (status, content) = send(
+        configuration,
+        method,
+        prefix,
+        uri_str,
+        request,
+        headers,
+        query_params,
+    )
+    .await?;
 
     if !status.is_client_error() && !status.is_server_error() {
         serde_json::from_str(&content).map_err(Error::from)
@@ -94,6 +54,45 @@ pub(crate) async fn fetch_empty<R, E>(
 where
     R: serde::Serialize + ?Sized,
     E: for<'a> serde::Deserialize<'a>,
+{
+    let (status, content) = send(
+        configuration,
+        method,
+        prefix,
+        uri_str,
+        request,
+        headers,
+        query_params,
+    )
+    .await?;
+
+    if !status.is_client_error() && !status.is_server_error() {
+        Ok(())
+    } else {
+        let entity: Option<E> = serde_json::from_str(&content).ok();
+        let error = ResponseContent {
+            status,
+            content,
+            entity,
+        };
+        Err(Error::ResponseError(error))
+    }
+}
+
+/// Sends the request and returns the status and content of the response.
+/// If the request is rejected as unauthorized and a token provider is configured,
+/// the request is retried once with a new token.
+async fn send<R, E>(
+    configuration: &configuration::Configuration,
+    method: reqwest::Method,
+    prefix: Option<&str>,
+    uri_str: &str,
+    request: &R,
+    headers: Option<HashMap<String, String>>,
+    query_params: Option<HashMap<String, String>>,
+) -> Result<(reqwest::StatusCode, String), Error<E>>
+where
+    R: serde::Serialize + ?Sized,
 {
     let uri_base = match prefix {
         Some(prefix) => format!(
@@ -106,55 +105,80 @@ where
     let client = &configuration.client;
 
     let uri = uri_base + uri_str;
-    let mut req_builder = client.request(method.clone(), &uri);
+    let headers = headers.unwrap_or_default();
+    let query_params = query_params.unwrap_or_default();
 
-    if let Some(ref aws_v4_key) = configuration.aws_v4_key {
-        let new_headers = match aws_v4_key.sign(
-            &uri,
-            method.as_str(),
-            &serde_json::to_string(&request).expect("param should serialize to string"),
-        ) {
-            Ok(new_headers) => new_headers,
-            Err(err) => return Err(Error::AWSV4SignatureError(err)),
+    let mut retried = false;
+    loop {
+        let mut req_builder = client.request(method.clone(), &uri);
+
+        if let Some(ref aws_v4_key) = configuration.aws_v4_key {
+            let new_headers = match aws_v4_key.sign(
+                &uri,
+                method.as_str(),
+                &serde_json::to_string(&request).expect("param should serialize to string"),
+            ) {
+                Ok(new_headers) => new_headers,
+                Err(err) => return Err(Error::AWSV4SignatureError(err)),
+            };
+            for (name, value) in new_headers.iter() {
+                req_builder = req_builder.header(name.as_str(), value.as_str());
+            }
+        }
+        if let Some(ref user_agent) = configuration.user_agent {
+            req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
+        }
+        // Only one token is sent, the token provider takes precedence over static tokens
+        if let Some(ref token_provider) = configuration.token_provider {
+            let token = token_provider
+                .token(configuration)
+                .await
+                .map_err(token_error)?;
+            req_builder = req_builder.bearer_auth(token);
+        } else if let Some(ref token) = configuration.oauth_access_token {
+            req_builder = req_builder.bearer_auth(token.to_owned());
+        } else if let Some(ref token) = configuration.bearer_access_token {
+            req_builder = req_builder.bearer_auth(token.to_owned());
         };
-        for (name, value) in new_headers.iter() {
-            req_builder = req_builder.header(name.as_str(), value.as_str());
+        for (key, value) in &headers {
+            req_builder = req_builder.header(key, value);
+        }
+        for (key, value) in &query_params {
+            req_builder = req_builder.query(&[(key, value)]);
+        }
+        if let &reqwest::Method::POST | &reqwest::Method::PUT = &method {
+            req_builder = req_builder.json(request);
         }
-    }
-    if let Some(ref user_agent) = configuration.user_agent {
-        req_builder = req_builder.header(reqwest::header::USER_AGENT, user_agent.clone());
-    }
-    if let Some(ref token) = configuration.oauth_access_token {
-        req_builder = req_builder.bearer_auth(token.to_owned());
-    };
-    if let Some(ref token) = configuration.bearer_access_token {
-        req_builder = req_builder.bearer_auth(token.to_owned());
-    };
-    for (key, value) in headers.unwrap_or_default() {
-        req_builder = req_builder.header(key, value);
-    }
-    for (key, value) in query_params.unwrap_or_default() {
-        req_builder = req_builder.query(&[(key, value)]);
-    }
-    if let &reqwest::Method::POST | &reqwest::Method::PUT = &method {
-        req_builder = req_builder.json(request);
-    }
 
-    let req = req_builder.build()?;
-    let resp = client.execute(req).await?;
+        let req = req_builder.build()?;
+        let resp = client.execute(req).await?;
 
-    let status = resp.status();
-    let content = resp.text().await?;
+        let status = resp.status();
 
-    if !status.is_client_error() && !status.is_server_error() {
-        Ok(())
-    } else {
-        let entity: Option<E> = serde_json::from_str(&content).ok();
-        let error = ResponseContent {
-            status,
-            content,
-            entity,
-        };
-        Err(Error::ResponseError(error))
+        if status == reqwest::StatusCode::UNAUTHORIZED && !retried {
+            if let Some(ref token_provider) = configuration.token_provider {
+                token_provider.invalidate().await;
+                retried = true;
+                continue;
+            }
+        }
+
+        let content = resp.text().await?;
+        return Ok((status, content));
+    }
+}
+
+/// Converts an error of the token endpoint into an error of the request
+pub(crate) fn token_error<T, E>(err: Error<T>) -> Error<E> {
+    match err {
+        Error::Reqwest(err) => Error::Reqwest(err),
+        Error::Serde(err) => Error::Serde(err),
+        Error::Io(err) => Error::Io(err),
+        Error::ResponseError(response) => Error::ResponseError(ResponseContent {
+            status: response.status,
+            content: response.content,
+            entity: None,
+        }),
+        Error::AWSV4SignatureError(err) => Error::AWSV4SignatureError(err),
     }
 }
@@ -5,12 +5,12 @@ Iceberg rest catalog implementation
 */
 use iceberg_rust::{
     catalog::{
-        commit::CommitView,
+        commit::{CommitView, TableRequirement},
         create::{CreateMaterializedView, CreateTable, CreateView},
         identifier::{self, Identifier},
         namespace::Namespace,
         tabular::Tabular,
-        Catalog, CatalogList,
+        Catalog, CatalogList, TabularPage,
     },
     error::Error,
     materialized_view::MaterializedView,
@@ -35,6 +35,7 @@ use crate::{
         configuration::Configuration,
     },
     models,
+    signer::RemoteSigningS3,
 };
 
 #[derive(Debug)]
@@ -56,6 +57,86 @@ impl RestCatalog {
             object_store_builder,
         }
     }
+
+    /// Creates the object store for a table from its config and the storage credentials that the
+    /// catalog vended for it.
+    ///
+    /// The table config is applied first and is overridden by the credential with the longest
+    /// prefix that matches the table location. If remote signing is enabled, the requests to S3
+    /// are signed by the catalog. If `s3.cross-region-access-enabled` is set and no region is
+    /// configured, the region of the bucket is looked up.
+    ///
+    /// The credentials only apply to the loaded table, tables in the same bucket can have other
+    /// credentials. The object store is therefore set on the table and not shared through
+    /// [RestCatalog::object_store]. Returns `None` if the catalog didn't return any config.
+    async fn vended_object_store(
+        &self,
+        result: &models::LoadTableResult,
+    ) -> Result<Option<Arc<dyn ObjectStore>>, Error> {
+        let location = &result.metadata.location;
+        let config: HashMap<String, String> = result
+            .config
+            .iter()
+            .flatten()
+            .chain(
+                result
+                    .storage_credentials
+                    .iter()
+                    .flatten()
+                    .filter(|credential| location.starts_with(&credential.prefix))
+                    .max_by_key(|credential| credential.prefix.len())
+                    .map(|credential| &credential.config)
+                    .into_iter()
+                    .flatten(),
+            )
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if config.is_empty() {
+            return Ok(None);
+        }
+        let bucket = Bucket::from_path(location)?;
+        let object_store: Arc<dyn ObjectStore> = match bucket {
+            Bucket::S3(name)
+                if config
+                    .get("s3.remote-signing-enabled")
+                    .is_some_and(|x| x == "true") =>
+            {
+                Arc::new(RemoteSigningS3::new(&self.configuration, name, &config))
+            }
+            bucket => {
+                let builder = self
+                    .object_store_builder
+                    .clone()
+                    .with_iceberg_properties(&config);
+                let builder = if config
+                    .get("s3.cross-region-access-enabled")
+                    .is_some_and(|x| x == "true")
+                    && !config.contains_key("s3.region")
+                    && !config.contains_key("client.region")
+                {
+                    builder.with_bucket_region(&bucket).await?
+                } else {
+                    builder
+                };
+                builder.build(bucket)?
+            }
+        };
+        Ok(Some(object_store))
+    }
+
+    /// Creates the table of a load table result with the object store for its vended credentials
+    async fn table(
+        self: Arc<Self>,
+        identifier: Identifier,
+        result: models::LoadTableResult,
+    ) -> Result<Table, Error> {
+        let object_store = self.vended_object_store(&result).await?;
+        let table = Table::new(identifier, self, result.metadata).await?;
+        Ok(match object_store {
+            Some(object_store) => table.with_object_store(object_store),
+            None => table,
+        })
+    }
 }
 
 #[async_trait]
@@ -175,6 +256,33 @@ impl Catalog for RestCatalog {
             .chain(tables)
             .collect())
     }
+    /// Lists one page of the tables in the namespace using the `pageToken` and `pageSize` query parameters.
+    async fn list_tables_paginated(
+        &self,
+        namespace: &Namespace,
+        page_token: Option<&str>,
+        page_size: Option<usize>,
+    ) -> Result<TabularPage, Error> {
+        let page_size = page_size
+            .map(|size| {
+                i32::try_from(size).map_err(|_| Error::InvalidFormat(format!("Page size {size}")))
+            })
+            .transpose()?;
+        // An empty page token requests the first page from servers that support pagination
+        let tables = catalog_api_api::list_tables(
+            &self.configuration,
+            self.name.as_deref(),
+            &namespace.to_string(),
+            Some(page_token.unwrap_or("")),
+            page_size,
+        )
+        .await
+        .map_err(Into::<Error>::into)?;
+        Ok(TabularPage {
+            identifiers: tables.identifiers.unwrap_or_default(),
+            next_page_token: tables.next_page_token.flatten(),
+        })
+    }
     /// Lists all namespaces in the catalog.
     async fn list_namespaces(&self, parent: Option<&str>) -> Result<Vec<Namespace>, Error> {
         let namespaces = catalog_api_api::list_namespaces(
@@ -218,6 +326,25 @@ impl Catalog for RestCatalog {
         .map(|_| true)
         .map_err(Into::<Error>::into)
     }
+    /// Check if a table exists with a HEAD request
+    async fn table_exists(&self, identifier: &Identifier) -> Result<bool, Error> {
+        match catalog_api_api::table_exists(
+            &self.configuration,
+            self.name.as_deref(),
+            &identifier.namespace().to_string(),
+            identifier.name(),
+        )
+        .await
+        {
+            Ok(()) => Ok(true),
+            Err(apis::Error::ResponseError(err))
+                if err.status == reqwest::StatusCode::NOT_FOUND =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
     /// Drop a table and delete all data and metadata files.
     async fn drop_table(&self, identifier: &Identifier) -> Result<(), Error> {
         catalog_api_api::drop_table(
@@ -253,6 +380,7 @@ impl Catalog for RestCatalog {
         .map_err(Into::<Error>::into)
     }
     /// Load a table.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     async fn load_tabular(self: Arc<Self>, identifier: &Identifier) -> Result<Tabular, Error> {
         // Load View/Matview metadata, is loaded as tabular to enable both possibilities. Must not be table metadata
         let tabular_metadata = catalog_api_api::load_view(
@@ -272,7 +400,7 @@ impl Catalog for RestCatalog {
             )),
             Err(apis::Error::ResponseError(content)) => {
                 if content.status == 404 {
-                    let table_metadata = catalog_api_api::load_table(
+                    let table_result = catalog_api_api::load_table(
                         &self.configuration,
                         self.name.as_deref(),
                         &identifier.namespace().to_string(),
@@ -281,11 +409,10 @@ impl Catalog for RestCatalog {
                         None,
                     )
                     .await
-                    .map(|x| x.metadata)
                     .map_err(|_| Error::CatalogNotFound)?;
 
                     Ok(Tabular::Table(
-                        Table::new(identifier.clone(), self.clone(), table_metadata).await?,
+                        self.clone().table(identifier.clone(), table_result).await?,
                     ))
                 } else {
                     Err(Into::<Error>::into(apis::Error::ResponseError(content)))
@@ -310,32 +437,87 @@ impl Catalog for RestCatalog {
             None,
         )
         .map_err(Into::<Error>::into)
-        .and_then(|response| {
-            let clone = self.clone();
-            async move { Table::new(identifier.clone(), clone, response.metadata).await }
-        })
+        .and_then(|response| self.clone().table(identifier.clone(), response))
         .await
     }
     /// Update a table by atomically changing the pointer to the metadata file
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(table = %commit.identifier))
+    )]
     async fn update_table(
         self: Arc<Self>,
         commit: iceberg_rust::catalog::commit::CommitTable,
     ) -> Result<Table, Error> {
         let identifier = commit.identifier.clone();
-        catalog_api_api::update_table(
+        // Keep the asserted snapshots to report a commit conflict
+        let asserted_refs = commit
+            .requirements
+            .iter()
+            .filter_map(|requirement| match requirement {
+                TableRequirement::AssertRefSnapshotId { r#ref, snapshot_id } => {
+                    Some((r#ref.clone(), *snapshot_id))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let response = match catalog_api_api::update_table(
             &self.configuration,
             self.name.as_deref(),
             &identifier.namespace().to_string(),
             identifier.name(),
             commit,
         )
-        .map_err(Into::<Error>::into)
-        .and_then(|response| {
-            let clone = self.clone();
-            let identifier = identifier.clone();
-            async move { Table::new(identifier, clone, response.metadata).await }
-        })
         .await
+        {
+            Ok(response) => response,
+            Err(apis::Error::ResponseError(content))
+                if content.status == 409 && !asserted_refs.is_empty() =>
+            {
+                let metadata = catalog_api_api::load_table(
+                    &self.configuration,
+                    self.name.as_deref(),
+                    &identifier.namespace().to_string(),
+                    identifier.name(),
+                    None,
+                    None,
+                )
+                .await
+                .map(|x| x.metadata)
+                .ok();
+                let actual = |r#ref: &str| {
+                    metadata
+                        .as_ref()
+                        .and_then(|x| x.refs.get(r#ref))
+                        .map(|x| x.snapshot_id)
+                        .unwrap_or(-1)
+                };
+                let (r#ref, expected) = asserted_refs
+                    .iter()
+                    .find(|(r#ref, expected)| actual(r#ref) != *expected)
+                    .unwrap_or(&asserted_refs[0])
+                    .clone();
+                return Err(Error::CommitConflict {
+                    actual: actual(&r#ref),
+                    reference: r#ref,
+                    expected,
+                });
+            }
+            Err(err) => return Err(err.into()),
+        };
+        Table::new(identifier, self.clone(), response.metadata).await
+    }
+    async fn commit_transaction(
+        &self,
+        changes: Vec<iceberg_rust::catalog::commit::CommitTable>,
+    ) -> Result<(), Error> {
+        catalog_api_api::commit_transaction(
+            &self.configuration,
+            self.name.as_deref(),
+            models::CommitTransactionRequest::new(changes),
+        )
+        .await
+        .map_err(Into::into)
     }
     async fn create_view(
         self: Arc<Self>,
@@ -473,13 +655,19 @@ impl Catalog for RestCatalog {
             request,
         )
         .map_err(Into::<Error>::into)
-        .and_then(|response| {
-            let clone = self.clone();
-            async move { Table::new(identifier.clone(), clone, response.metadata).await }
-        })
+        .and_then(|response| self.clone().table(identifier.clone(), response))
         .await
     }
-    /// Return an object store for the desired bucket
+    /// Rename a table. The server rejects the request if the destination already exists.
+    async fn rename_table(&self, from: &Identifier, to: &Identifier) -> Result<(), Error> {
+        let request = models::RenameTableRequest::new(from.clone(), to.clone());
+
+        catalog_api_api::rename_table(&self.configuration, self.name.as_deref(), request)
+            .await
+            .map_err(Into::<Error>::into)
+    }
+    /// Return an object store for the desired bucket. Tables with vended credentials use their
+    /// own object store.
     fn object_store(&self, bucket: Bucket) -> Arc<dyn ObjectStore> {
         self.object_store_builder.build(bucket).unwrap()
     }
@@ -596,6 +784,7 @@ pub mod tests {
             bearer_access_token: None,
             api_key: None,
             aws_v4_key: None,
+            token_provider: None,
         }
     }
     #[tokio::test]
@@ -15,3 +15,5 @@ pub mod catalog;
 pub mod error;
 #[allow(clippy::all)]
 pub mod models;
+pub mod signer;
+pub mod token;
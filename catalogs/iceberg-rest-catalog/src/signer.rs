@@ -0,0 +1,708 @@
+/*!
+Remote signing of S3 requests
+
+If the config of a table contains `s3.remote-signing-enabled=true`, the client doesn't receive
+credentials for S3. Instead every S3 request is sent to the signer endpoint of the REST catalog,
+which returns the signed headers for the request. [`RemoteSigningS3`] implements [`ObjectStore`]
+on top of this protocol.
+
+Objects are listed with `ListObjectsV2` and copied with `CopyObject`. S3 doesn't support
+conditional copies, so [`ObjectStore::copy_if_not_exists`] reads the object and writes it with a
+conditional put.
+*/
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
+use object_store::{
+    path::{Path, DELIMITER},
+    Attributes, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, UploadPart,
+};
+use reqwest::{
+    header::{HeaderMap, CONTENT_LENGTH, CONTENT_RANGE, ETAG, LAST_MODIFIED},
+    Method, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::apis::configuration::Configuration;
+
+static STORE: &str = "RemoteSigningS3";
+
+/// Path of the signer endpoint relative to the signer uri, if not configured otherwise
+pub static DEFAULT_SIGNER_ENDPOINT: &str = "v1/aws/s3/sign";
+
+#[derive(Debug, Serialize)]
+struct S3SignRequest {
+    region: String,
+    uri: String,
+    method: String,
+    headers: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct S3SignResponse {
+    uri: String,
+    headers: HashMap<String, Vec<String>>,
+}
+
+/// Response of a `ListObjectsV2` request
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListResponse {
+    #[serde(default)]
+    contents: Vec<ListContents>,
+    #[serde(default)]
+    common_prefixes: Vec<ListPrefix>,
+    #[serde(default)]
+    next_continuation_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListContents {
+    key: String,
+    size: usize,
+    last_modified: DateTime<Utc>,
+    #[serde(rename = "ETag")]
+    e_tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListPrefix {
+    prefix: String,
+}
+
+/// Response of a `CreateMultipartUpload` request
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct InitiateMultipartUploadResult {
+    upload_id: String,
+}
+
+/// Response of a `CompleteMultipartUpload` or `CopyObject` request. S3 can report an error with
+/// a successful status, the body then contains an error code instead of an ETag.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CompleteResult {
+    #[serde(rename = "ETag")]
+    e_tag: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+/// Object store for a S3 bucket whose requests are signed by the REST catalog
+#[derive(Debug, Clone)]
+pub struct RemoteSigningS3 {
+    configuration: Configuration,
+    signer_uri: String,
+    bucket: String,
+    region: String,
+    endpoint: Option<String>,
+}
+
+impl RemoteSigningS3 {
+    /// Creates an object store for the bucket from the config of a table.
+    ///
+    /// Uses the `s3.signer.uri` and `s3.signer.endpoint` entries to locate the signer and falls
+    /// back to the base path of the catalog. The region is taken from `client.region` or `s3.region`.
+    pub fn new(
+        configuration: &Configuration,
+        bucket: &str,
+        config: &HashMap<String, String>,
+    ) -> Self {
+        let signer_base = config
+            .get("s3.signer.uri")
+            .unwrap_or(&configuration.base_path);
+        let signer_endpoint = config
+            .get("s3.signer.endpoint")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_SIGNER_ENDPOINT);
+        RemoteSigningS3 {
+            configuration: configuration.clone(),
+            signer_uri: format!(
+                "{}/{}",
+                signer_base.trim_end_matches('/'),
+                signer_endpoint.trim_start_matches('/')
+            ),
+            bucket: bucket.to_owned(),
+            region: config
+                .get("client.region")
+                .or(config.get("s3.region"))
+                .cloned()
+                .unwrap_or("us-east-1".to_owned()),
+            endpoint: config.get("s3.endpoint").cloned(),
+        }
+    }
+
+    fn bucket_url(&self) -> Result<Url, object_store::Error> {
+        let base = match &self.endpoint {
+            Some(endpoint) => format!("{}/{}", endpoint.trim_end_matches('/'), self.bucket),
+            None => format!("https://{}.s3.{}.amazonaws.com", self.bucket, self.region),
+        };
+        Url::parse(&base).map_err(generic)
+    }
+
+    fn object_url(&self, location: &Path) -> Result<Url, object_store::Error> {
+        let mut url = self.bucket_url()?;
+        let base = url.to_string();
+        url.path_segments_mut()
+            .map_err(|_| generic(format!("Endpoint {base} can't be a base url")))?
+            .pop_if_empty()
+            .extend(location.as_ref().split('/'));
+        Ok(url)
+    }
+
+    /// Requests one page of the objects below the prefix. With a delimiter, the objects in
+    /// subdirectories are returned as common prefixes.
+    async fn list_page(
+        &self,
+        prefix: Option<&Path>,
+        delimiter: bool,
+        continuation_token: Option<&str>,
+    ) -> Result<ListResponse, object_store::Error> {
+        let mut url = self.bucket_url()?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("list-type", "2");
+            if let Some(prefix) = prefix.filter(|prefix| !prefix.as_ref().is_empty()) {
+                query.append_pair("prefix", &format!("{prefix}{}", DELIMITER));
+            }
+            if delimiter {
+                query.append_pair("delimiter", DELIMITER);
+            }
+            if let Some(token) = continuation_token {
+                query.append_pair("continuation-token", token);
+            }
+        }
+        let location = prefix.cloned().unwrap_or_default();
+        let response = self.send(Method::GET, url, HashMap::new(), None).await?;
+        let body = check_status(response, &location)?
+            .text()
+            .await
+            .map_err(generic)?;
+        quick_xml::de::from_str(&body).map_err(generic)
+    }
+
+    /// Signs the request with the signer endpoint of the catalog and sends it to S3
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        headers: HashMap<String, Vec<String>>,
+        body: Option<Bytes>,
+    ) -> Result<reqwest::Response, object_store::Error> {
+        let client = &self.configuration.client;
+
+        let sign_request = S3SignRequest {
+            region: self.region.clone(),
+            uri: url.to_string(),
+            method: method.to_string(),
+            headers: headers.clone(),
+        };
+        let mut signer = client.post(&self.signer_uri).json(&sign_request);
+        if let Some(ref token_provider) = self.configuration.token_provider {
+            let token = token_provider
+                .token(&self.configuration)
+                .await
+                .map_err(generic)?;
+            signer = signer.bearer_auth(token);
+        } else if let Some(ref token) = self.configuration.oauth_access_token {
+            signer = signer.bearer_auth(token);
+        } else if let Some(ref token) = self.configuration.bearer_access_token {
+            signer = signer.bearer_auth(token);
+        }
+        let response = signer.send().await.map_err(generic)?;
+        if !response.status().is_success() {
+            return Err(generic(format!(
+                "Signer returned status {} for {} {}",
+                response.status(),
+                method,
+                url
+            )));
+        }
+        let signed: S3SignResponse = response.json().await.map_err(generic)?;
+
+        let mut headers = headers;
+        headers.extend(signed.headers);
+
+        let mut request = client.request(method, &signed.uri);
+        for (name, values) in &headers {
+            for value in values {
+                request = request.header(name, value);
+            }
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        request.send().await.map_err(generic)
+    }
+}
+
+impl Display for RemoteSigningS3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", STORE, self.bucket)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for RemoteSigningS3 {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult, object_store::Error> {
+        let mut headers = HashMap::new();
+        match &opts.mode {
+            PutMode::Overwrite => (),
+            PutMode::Create => {
+                headers.insert("If-None-Match".to_owned(), vec!["*".to_owned()]);
+            }
+            PutMode::Update(version) => {
+                if let Some(e_tag) = &version.e_tag {
+                    headers.insert("If-Match".to_owned(), vec![e_tag.clone()]);
+                }
+            }
+        }
+        let response = self
+            .send(
+                Method::PUT,
+                self.object_url(location)?,
+                headers,
+                Some(payload.into()),
+            )
+            .await?;
+        let response = match check_status(response, location) {
+            Err(object_store::Error::Precondition { path, source })
+                if matches!(opts.mode, PutMode::Create) =>
+            {
+                return Err(object_store::Error::AlreadyExists { path, source })
+            }
+            x => x?,
+        };
+        Ok(PutResult {
+            e_tag: header_value(response.headers(), ETAG.as_str()),
+            version: header_value(response.headers(), "x-amz-version-id"),
+        })
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        _opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>, object_store::Error> {
+        let mut url = self.object_url(location)?;
+        url.set_query(Some("uploads"));
+        let response = self.send(Method::POST, url, HashMap::new(), None).await?;
+        let body = check_status(response, location)?
+            .text()
+            .await
+            .map_err(generic)?;
+        let result: InitiateMultipartUploadResult =
+            quick_xml::de::from_str(&body).map_err(generic)?;
+        Ok(Box::new(RemoteSigningUpload {
+            store: self.clone(),
+            location: location.clone(),
+            upload_id: result.upload_id,
+            parts: Arc::new(Mutex::new(Vec::new())),
+        }))
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> Result<GetResult, object_store::Error> {
+        let mut url = self.object_url(location)?;
+        if let Some(version) = &options.version {
+            url.query_pairs_mut().append_pair("versionId", version);
+        }
+        let mut headers = HashMap::new();
+        if let Some(range) = &options.range {
+            headers.insert("Range".to_owned(), vec![range.to_string()]);
+        }
+        if let Some(e_tag) = &options.if_match {
+            headers.insert("If-Match".to_owned(), vec![e_tag.clone()]);
+        }
+        if let Some(e_tag) = &options.if_none_match {
+            headers.insert("If-None-Match".to_owned(), vec![e_tag.clone()]);
+        }
+        if let Some(date) = &options.if_modified_since {
+            headers.insert("If-Modified-Since".to_owned(), vec![http_date(date)]);
+        }
+        if let Some(date) = &options.if_unmodified_since {
+            headers.insert("If-Unmodified-Since".to_owned(), vec![http_date(date)]);
+        }
+        let method = if options.head {
+            Method::HEAD
+        } else {
+            Method::GET
+        };
+
+        let response = check_status(self.send(method, url, headers, None).await?, location)?;
+
+        let content_range = content_range(response.headers());
+        let size = match &content_range {
+            Some((_, size)) => *size,
+            None => header_value(response.headers(), CONTENT_LENGTH.as_str())
+                .and_then(|x| x.parse().ok())
+                .unwrap_or_default(),
+        };
+        let meta = ObjectMeta {
+            location: location.clone(),
+            last_modified: header_value(response.headers(), LAST_MODIFIED.as_str())
+                .and_then(|x| DateTime::parse_from_rfc2822(&x).ok())
+                .map(|x| x.with_timezone(&Utc))
+                .unwrap_or_default(),
+            size,
+            e_tag: header_value(response.headers(), ETAG.as_str()),
+            version: header_value(response.headers(), "x-amz-version-id"),
+        };
+        let range = content_range.map(|(range, _)| range).unwrap_or(0..size);
+
+        let payload = if options.head {
+            stream::empty().boxed()
+        } else {
+            let bytes = response.bytes().await.map_err(generic)?;
+            stream::once(async move { Ok(bytes) }).boxed()
+        };
+
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(payload),
+            meta,
+            range,
+            attributes: Attributes::default(),
+        })
+    }
+
+    async fn delete(&self, location: &Path) -> Result<(), object_store::Error> {
+        let response = self
+            .send(
+                Method::DELETE,
+                self.object_url(location)?,
+                HashMap::new(),
+                None,
+            )
+            .await?;
+        check_status(response, location)?;
+        Ok(())
+    }
+
+    fn list(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'_, Result<ObjectMeta, object_store::Error>> {
+        let prefix = prefix.cloned();
+        // The state is the continuation token of the next page, None after the last page
+        stream::try_unfold(Some(None), move |token: Option<Option<String>>| {
+            let prefix = prefix.clone();
+            async move {
+                let Some(token) = token else {
+                    return Ok::<_, object_store::Error>(None);
+                };
+                let page = self
+                    .list_page(prefix.as_ref(), false, token.as_deref())
+                    .await?;
+                Ok(Some((
+                    stream::iter(page.contents.into_iter().map(object_meta)),
+                    page.next_continuation_token.map(Some),
+                )))
+            }
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(
+        &self,
+        prefix: Option<&Path>,
+    ) -> Result<ListResult, object_store::Error> {
+        let mut result = ListResult {
+            common_prefixes: Vec::new(),
+            objects: Vec::new(),
+        };
+        let mut token = None;
+        loop {
+            let page = self.list_page(prefix, true, token.as_deref()).await?;
+            for common_prefix in page.common_prefixes {
+                result
+                    .common_prefixes
+                    .push(Path::parse(common_prefix.prefix).map_err(generic)?);
+            }
+            for contents in page.contents {
+                result.objects.push(object_meta(contents)?);
+            }
+            token = page.next_continuation_token;
+            if token.is_none() {
+                return Ok(result);
+            }
+        }
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<(), object_store::Error> {
+        // The copy source is the url encoded path of the object including the bucket
+        let source = self.object_url(from)?;
+        let source = match &self.endpoint {
+            Some(_) => source.path().to_owned(),
+            None => format!("/{}{}", self.bucket, source.path()),
+        };
+        let response = self
+            .send(
+                Method::PUT,
+                self.object_url(to)?,
+                HashMap::from_iter([("x-amz-copy-source".to_owned(), vec![source])]),
+                None,
+            )
+            .await?;
+        let body = check_status(response, from)?
+            .text()
+            .await
+            .map_err(generic)?;
+        let result: CompleteResult = quick_xml::de::from_str(&body).map_err(generic)?;
+        if let Some(code) = result.code {
+            return Err(generic(format!(
+                "Failed to copy {from} to {to}: {code} {}",
+                result.message.unwrap_or_default()
+            )));
+        }
+        Ok(())
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<(), object_store::Error> {
+        let bytes = self.get(from).await?.bytes().await?;
+        self.put_opts(to, bytes.into(), PutMode::Create.into())
+            .await?;
+        Ok(())
+    }
+}
+
+/// Multipart upload whose requests are signed by the REST catalog
+#[derive(Debug)]
+struct RemoteSigningUpload {
+    store: RemoteSigningS3,
+    location: Path,
+    upload_id: String,
+    /// ETags of the uploaded parts in the order of their part numbers
+    parts: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl RemoteSigningUpload {
+    fn upload_url(&self, part_number: Option<usize>) -> Result<Url, object_store::Error> {
+        let mut url = self.store.object_url(&self.location)?;
+        if let Some(part_number) = part_number {
+            url.query_pairs_mut()
+                .append_pair("partNumber", &part_number.to_string());
+        }
+        url.query_pairs_mut()
+            .append_pair("uploadId", &self.upload_id);
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for RemoteSigningUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let index = {
+            let mut parts = self.parts.lock().unwrap();
+            parts.push(None);
+            parts.len() - 1
+        };
+        let url = self.upload_url(Some(index + 1));
+        let store = self.store.clone();
+        let location = self.location.clone();
+        let parts = self.parts.clone();
+        Box::pin(async move {
+            let response = store
+                .send(Method::PUT, url?, HashMap::new(), Some(data.into()))
+                .await?;
+            let response = check_status(response, &location)?;
+            let e_tag = header_value(response.headers(), ETAG.as_str())
+                .ok_or_else(|| generic(format!("Missing ETag for part {}", index + 1)))?;
+            parts.lock().unwrap()[index] = Some(e_tag);
+            Ok(())
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult, object_store::Error> {
+        let body = {
+            let parts = self.parts.lock().unwrap();
+            parts
+                .iter()
+                .enumerate()
+                .map(|(index, e_tag)| {
+                    e_tag
+                        .as_ref()
+                        .map(|e_tag| {
+                            format!(
+                                "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                                index + 1,
+                                e_tag
+                            )
+                        })
+                        .ok_or_else(|| generic(format!("Part {} wasn't uploaded", index + 1)))
+                })
+                .collect::<Result<String, _>>()?
+        };
+        let body = format!("<CompleteMultipartUpload>{body}</CompleteMultipartUpload>");
+        let response = self
+            .store
+            .send(
+                Method::POST,
+                self.upload_url(None)?,
+                HashMap::new(),
+                Some(body.into()),
+            )
+            .await?;
+        let content = check_status(response, &self.location)?
+            .text()
+            .await
+            .map_err(generic)?;
+        let result: CompleteResult = quick_xml::de::from_str(&content).map_err(generic)?;
+        if let Some(code) = result.code {
+            return Err(generic(format!(
+                "Failed to complete multipart upload of {}: {code} {}",
+                self.location,
+                result.message.unwrap_or_default()
+            )));
+        }
+        Ok(PutResult {
+            e_tag: result.e_tag,
+            version: None,
+        })
+    }
+
+    async fn abort(&mut self) -> Result<(), object_store::Error> {
+        let response = self
+            .store
+            .send(Method::DELETE, self.upload_url(None)?, HashMap::new(), None)
+            .await?;
+        check_status(response, &self.location)?;
+        Ok(())
+    }
+}
+
+fn generic(
+    source: impl Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: source.into(),
+    }
+}
+
+fn check_status(
+    response: reqwest::Response,
+    location: &Path,
+) -> Result<reqwest::Response, object_store::Error> {
+    let status = response.status();
+    let path = location.to_string();
+    let source = format!("S3 returned status {status} for {location}").into();
+    match status {
+        x if x.is_success() => Ok(response),
+        StatusCode::NOT_FOUND => Err(object_store::Error::NotFound { path, source }),
+        StatusCode::NOT_MODIFIED => Err(object_store::Error::NotModified { path, source }),
+        StatusCode::PRECONDITION_FAILED => Err(object_store::Error::Precondition { path, source }),
+        _ => Err(object_store::Error::Generic {
+            store: STORE,
+            source,
+        }),
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|x| x.to_str().ok())
+        .map(ToOwned::to_owned)
+}
+
+/// Parses a content range header of the form `bytes {start}-{end}/{size}`
+fn content_range(headers: &HeaderMap) -> Option<(Range<usize>, usize)> {
+    let value = header_value(headers, CONTENT_RANGE.as_str())?;
+    let (range, size) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, end) = range.split_once('-')?;
+    Some((
+        start.parse().ok()?..end.parse::<usize>().ok()? + 1,
+        size.parse().ok()?,
+    ))
+}
+
+fn http_date(date: &DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn object_meta(contents: ListContents) -> Result<ObjectMeta, object_store::Error> {
+    Ok(ObjectMeta {
+        location: Path::parse(contents.key).map_err(generic)?,
+        last_modified: contents.last_modified,
+        size: contents.size,
+        e_tag: contents.e_tag,
+        version: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_response_deserialization() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+    <Name>warehouse</Name>
+    <Prefix>tpch/lineitem/</Prefix>
+    <KeyCount>2</KeyCount>
+    <IsTruncated>true</IsTruncated>
+    <NextContinuationToken>token</NextContinuationToken>
+    <Contents>
+        <Key>tpch/lineitem/metadata/v1.metadata.json</Key>
+        <LastModified>2024-01-01T12:00:00.000Z</LastModified>
+        <ETag>"e1"</ETag>
+        <Size>1024</Size>
+        <StorageClass>STANDARD</StorageClass>
+    </Contents>
+    <CommonPrefixes>
+        <Prefix>tpch/lineitem/data/</Prefix>
+    </CommonPrefixes>
+</ListBucketResult>"#;
+
+        let response: ListResponse = quick_xml::de::from_str(body).unwrap();
+        assert_eq!(response.next_continuation_token.as_deref(), Some("token"));
+        assert_eq!(response.common_prefixes[0].prefix, "tpch/lineitem/data/");
+
+        let meta = object_meta(response.contents.into_iter().next().unwrap()).unwrap();
+        assert_eq!(
+            meta.location,
+            Path::from("tpch/lineitem/metadata/v1.metadata.json")
+        );
+        assert_eq!(meta.size, 1024);
+        assert_eq!(meta.e_tag.as_deref(), Some("\"e1\""));
+    }
+
+    #[test]
+    fn test_complete_result_error() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+    <Code>InternalError</Code>
+    <Message>We encountered an internal error. Please try again.</Message>
+</Error>"#;
+
+        let result: CompleteResult = quick_xml::de::from_str(body).unwrap();
+        assert_eq!(result.code.as_deref(), Some("InternalError"));
+        assert_eq!(result.e_tag, None);
+    }
+}
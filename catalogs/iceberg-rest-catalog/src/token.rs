@@ -0,0 +1,119 @@
+/*!
+Access tokens for the REST catalog
+
+A [`TokenProvider`] supplies the bearer token that is sent with every catalog request. The
+[`OAuth2TokenProvider`] exchanges client credentials for an access token at the `oauth/tokens`
+endpoint, caches it and refreshes it shortly before it expires. If the catalog rejects a request
+with `401 Unauthorized`, the cached token is invalidated and the request is retried once with a
+new token.
+*/
+
+use std::{
+    fmt::Debug,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures::lock::Mutex;
+use secrecy::{ExposeSecret, SecretString};
+
+use crate::apis::{
+    configuration::Configuration,
+    o_auth2_api_api::{self, GetTokenError},
+    Error,
+};
+
+/// Default time before the expiry of a token at which it is refreshed
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Supplies the bearer token for requests to the REST catalog
+#[async_trait]
+pub trait TokenProvider: Debug + Send + Sync {
+    /// Returns a valid access token, fetching a new one if required
+    async fn token(&self, configuration: &Configuration) -> Result<String, Error<GetTokenError>>;
+    /// Discards the cached token, so that the next call to [`TokenProvider::token`] fetches a new one
+    async fn invalidate(&self);
+}
+
+#[derive(Debug)]
+struct CachedToken {
+    access_token: String,
+    expires_at: Option<Instant>,
+}
+
+/// Fetches access tokens with the OAuth2 client credentials flow
+#[derive(Debug)]
+pub struct OAuth2TokenProvider {
+    client_id: String,
+    client_secret: SecretString,
+    scope: Option<String>,
+    refresh_margin: Duration,
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl OAuth2TokenProvider {
+    /// Creates a token provider for the given client credentials
+    pub fn new(client_id: &str, client_secret: SecretString) -> Self {
+        Self {
+            client_id: client_id.to_owned(),
+            client_secret,
+            scope: None,
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Sets the scope that is requested for the access token
+    pub fn with_scope(mut self, scope: &str) -> Self {
+        self.scope = Some(scope.to_owned());
+        self
+    }
+
+    /// Sets how long before its expiry a token is refreshed
+    pub fn with_refresh_margin(mut self, refresh_margin: Duration) -> Self {
+        self.refresh_margin = refresh_margin;
+        self
+    }
+}
+
+#[async_trait]
+impl TokenProvider for OAuth2TokenProvider {
+    async fn token(&self, configuration: &Configuration) -> Result<String, Error<GetTokenError>> {
+        let mut token = self.token.lock().await;
+        if let Some(cached) = token.as_ref() {
+            if cached
+                .expires_at
+                .is_none_or(|expires_at| Instant::now() + self.refresh_margin < expires_at)
+            {
+                return Ok(cached.access_token.clone());
+            }
+        }
+
+        let response = o_auth2_api_api::get_token(
+            configuration,
+            Some("client_credentials"),
+            self.scope.as_deref(),
+            Some(&self.client_id),
+            Some(self.client_secret.expose_secret().as_str()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+        let expires_at = response
+            .expires_in
+            .map(|seconds| Instant::now() + Duration::from_secs(seconds.max(0) as u64));
+        *token = Some(CachedToken {
+            access_token: response.access_token.clone(),
+            expires_at,
+        });
+        Ok(response.access_token)
+    }
+
+    async fn invalidate(&self) {
+        *self.token.lock().await = None;
+    }
+}
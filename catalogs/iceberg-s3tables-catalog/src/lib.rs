@@ -12,9 +12,10 @@ use aws_sdk_s3tables::{
 };
 use iceberg_rust::{
     catalog::{
+        cache::MetadataCache,
         commit::{
-            apply_table_updates, apply_view_updates, check_table_requirements,
-            check_view_requirements, CommitTable, CommitView, TableRequirement,
+            apply_table_updates, apply_view_updates, check_view_requirements,
+            validate_table_requirements, CommitTable, CommitView, TableRequirement,
         },
         create::{CreateMaterializedView, CreateTable, CreateView},
         identifier::Identifier,
@@ -24,7 +25,10 @@ use iceberg_rust::{
     },
     error::Error as IcebergError,
     materialized_view::MaterializedView,
-    object_store::{store::IcebergStore, Bucket, ObjectStoreBuilder},
+    object_store::{
+        store::{parse_metadata, IcebergStore},
+        Bucket, ObjectStoreBuilder,
+    },
     spec::{
         self,
         identifier::FullIdentifier,
@@ -48,6 +52,7 @@ pub struct S3TablesCatalog {
     client: Client,
     object_store: ObjectStoreBuilder,
     cache: Arc<RwLock<HashMap<Identifier, (String, TabularMetadata)>>>,
+    metadata_cache: Option<MetadataCache>,
 }
 
 pub mod error;
@@ -63,8 +68,18 @@ impl S3TablesCatalog {
             client: Client::new(config),
             object_store,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            metadata_cache: None,
         })
     }
+
+    /// Caches the metadata files that are loaded by the catalog
+    ///
+    /// Loading a table, view or materialized view whose metadata location didn't change returns
+    /// the cached metadata instead of reading the metadata file again.
+    pub fn with_metadata_cache(mut self, metadata_cache: MetadataCache) -> Self {
+        self.metadata_cache = Some(metadata_cache);
+        self
+    }
 }
 
 #[async_trait]
@@ -187,6 +202,9 @@ impl Catalog for S3TablesCatalog {
             .map_err(Error::from)
             .is_ok())
     }
+    async fn table_exists(&self, identifier: &Identifier) -> Result<bool, IcebergError> {
+        self.tabular_exists(identifier).await
+    }
     async fn drop_table(&self, identifier: &Identifier) -> Result<(), IcebergError> {
         // TODO get version_token
         self.client
@@ -248,12 +266,22 @@ impl Catalog for S3TablesCatalog {
         let bucket = Bucket::from_path(&metadata_location)?;
         let object_store = self.object_store(bucket);
 
-        let bytes = object_store
-            .get(&strip_prefix(&metadata_location).as_str().into())
-            .await?
-            .bytes()
-            .await?;
-        let metadata: TabularMetadata = serde_json::from_slice(&bytes)?;
+        let load = async {
+            let bytes = object_store
+                .get(&strip_prefix(&metadata_location).as_str().into())
+                .await?
+                .bytes()
+                .await?;
+            parse_metadata(&bytes)
+        };
+        let metadata = match &self.metadata_cache {
+            Some(metadata_cache) => {
+                metadata_cache
+                    .get_or_load(identifier, &metadata_location, load)
+                    .await?
+            }
+            None => load.await?,
+        };
 
         self.cache
             .write()
@@ -536,11 +564,7 @@ impl Catalog for S3TablesCatalog {
                 "Table update on entity that is not a table".to_owned(),
             ));
         };
-        if !check_table_requirements(&commit.requirements, &metadata) {
-            return Err(IcebergError::InvalidFormat(
-                "Table requirements not valid".to_owned(),
-            ));
-        }
+        validate_table_requirements(&commit.requirements, &metadata)?;
         apply_table_updates(&mut metadata, commit.updates)?;
         let metadata_location = new_metadata_location(&metadata);
 
@@ -577,7 +601,11 @@ impl Catalog for S3TablesCatalog {
                 .await?
                 .bytes()
                 .await?;
-            let metadata: TableMetadata = serde_json::from_slice(&bytes)?;
+            let TabularMetadata::Table(metadata) = parse_metadata(&bytes)? else {
+                return Err(IcebergError::InvalidFormat(
+                    "Table update on entity that is not a table".to_owned(),
+                ));
+            };
             metadata
         };
 
@@ -753,13 +781,18 @@ impl Catalog for S3TablesCatalog {
         let bucket = Bucket::from_path(metadata_location)?;
         let object_store = self.object_store(bucket);
 
-        let metadata: TableMetadata = serde_json::from_slice(
+        let TabularMetadata::Table(metadata) = parse_metadata(
             &object_store
                 .get(&metadata_location.into())
                 .await?
                 .bytes()
                 .await?,
-        )?;
+        )?
+        else {
+            return Err(IcebergError::InvalidFormat(
+                "Registered metadata file is not a table".to_owned(),
+            ));
+        };
 
         let table = self
             .client
@@ -790,6 +823,16 @@ impl Catalog for S3TablesCatalog {
         Ok(Table::new(identifier.clone(), self.clone(), metadata).await?)
     }
 
+    async fn rename_table(
+        &self,
+        _from: &Identifier,
+        _to: &Identifier,
+    ) -> Result<(), IcebergError> {
+        Err(IcebergError::NotSupported(
+            "Renaming tables in an S3 Tables catalog".to_owned(),
+        ))
+    }
+
     fn object_store(&self, bucket: Bucket) -> Arc<dyn object_store::ObjectStore> {
         Arc::new(self.object_store.build(bucket).unwrap())
     }
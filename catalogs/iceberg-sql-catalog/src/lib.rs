@@ -1,28 +1,32 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     sync::{Arc, RwLock},
 };
 
 use async_trait::async_trait;
 use iceberg_rust::{
     catalog::{
+        cache::MetadataCache,
         commit::{
-            apply_table_updates, apply_view_updates, check_table_requirements,
-            check_view_requirements, CommitTable, CommitView, TableRequirement,
+            apply_table_updates, apply_view_updates, check_view_requirements,
+            validate_table_requirements, CommitTable, CommitView, TableRequirement,
         },
         create::{CreateMaterializedView, CreateTable, CreateView},
         identifier::Identifier,
         namespace::Namespace,
         tabular::Tabular,
-        Catalog, CatalogList,
+        Catalog, CatalogList, TabularPage,
     },
     error::Error as IcebergError,
     materialized_view::MaterializedView,
-    object_store::{store::IcebergStore, Bucket, ObjectStoreBuilder},
+    object_store::{
+        store::{parse_metadata, IcebergStore},
+        Bucket, ObjectStoreBuilder,
+    },
     spec::{
         identifier::FullIdentifier,
         materialized_view_metadata::MaterializedViewMetadata,
-        table_metadata::{new_metadata_location, TableMetadata},
+        table_metadata::{new_metadata_location, TableMetadata, MAIN_BRANCH},
         tabular::TabularMetadata,
         util::strip_prefix,
         view_metadata::ViewMetadata,
@@ -34,7 +38,7 @@ use object_store::ObjectStore;
 use sqlx::{
     any::{install_default_drivers, AnyPoolOptions, AnyRow},
     pool::PoolOptions,
-    AnyPool, Executor, Row,
+    Any, AnyPool, Executor, Row,
 };
 
 use crate::error::Error;
@@ -45,10 +49,27 @@ pub struct SqlCatalog {
     pool: AnyPool,
     object_store: ObjectStoreBuilder,
     cache: Arc<RwLock<HashMap<Identifier, (String, TabularMetadata)>>>,
+    metadata_cache: Option<MetadataCache>,
 }
 
 pub mod error;
 
+/// Outcome of swapping the metadata location of a table within a database transaction
+enum TableCommit {
+    /// The metadata location now points to the new metadata
+    Committed {
+        identifier: Identifier,
+        metadata_location: String,
+        metadata: TableMetadata,
+    },
+    /// Another writer committed to the table after its metadata location was read
+    Conflict {
+        identifier: Identifier,
+        requirements: Vec<TableRequirement>,
+        base_snapshot_id: i64,
+    },
+}
+
 impl SqlCatalog {
     pub async fn new(
         url: &str,
@@ -98,9 +119,19 @@ impl SqlCatalog {
             pool,
             object_store,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            metadata_cache: None,
         })
     }
 
+    /// Caches the metadata files that are loaded by the catalog
+    ///
+    /// Loading a table, view or materialized view whose metadata location didn't change returns
+    /// the cached metadata instead of reading the metadata file again.
+    pub fn with_metadata_cache(mut self, metadata_cache: MetadataCache) -> Self {
+        self.metadata_cache = Some(metadata_cache);
+        self
+    }
+
     pub fn catalog_list(&self) -> Arc<SqlCatalogList> {
         Arc::new(SqlCatalogList {
             pool: self.pool.clone(),
@@ -109,6 +140,21 @@ impl SqlCatalog {
     }
 }
 
+/// Property that marks a namespace as created, namespaces without it only exist through their
+/// tables
+static NAMESPACE_EXISTS_PROPERTY: &str = "exists";
+
+/// Result of a namespace property update, matching the response of the REST catalog
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UpdateNamespacePropertiesResponse {
+    /// Keys of the properties that were added or updated
+    pub updated: Vec<String>,
+    /// Keys of the properties that were removed
+    pub removed: Vec<String>,
+    /// Keys of the properties that should be removed but didn't exist
+    pub missing: Option<Vec<String>>,
+}
+
 #[derive(Debug)]
 struct TableRef {
     table_namespace: String,
@@ -117,6 +163,38 @@ struct TableRef {
     _previous_metadata_location: Option<String>,
 }
 
+/// Key of the Postgres advisory lock that serializes the commits to a table.
+/// Uses FNV-1a so that all writers compute the same key independent of the Rust version.
+fn advisory_lock_key(catalog_name: &str, namespace: &str, name: &str) -> i64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in [catalog_name, namespace, name].join("\0").bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash as i64
+}
+
+/// Writes the `?` placeholders of the bind parameters of a statement in the syntax of the
+/// database of the pool. Postgres only supports numbered placeholders.
+fn statement(pool: &AnyPool, statement: &str) -> String {
+    if !pool
+        .connect_options()
+        .database_url
+        .scheme()
+        .starts_with("postgres")
+    {
+        return statement.to_owned();
+    }
+    let mut result = String::with_capacity(statement.len());
+    for (i, part) in statement.split('?').enumerate() {
+        if i > 0 {
+            result.push_str(&format!("${i}"));
+        }
+        result.push_str(part);
+    }
+    result
+}
+
 fn query_map(row: &AnyRow) -> Result<TableRef, sqlx::Error> {
     Ok(TableRef {
         table_namespace: row.try_get(0)?,
@@ -142,37 +220,120 @@ impl Catalog for SqlCatalog {
     fn name(&self) -> &str {
         &self.name
     }
-    /// Create a namespace in the catalog
+    /// Create a namespace in the catalog. Nested namespaces are stored with their levels joined
+    /// by dots, the parent namespaces don't have to be created first.
     async fn create_namespace(
         &self,
-        _namespace: &Namespace,
-        _properties: Option<HashMap<String, String>>,
+        namespace: &Namespace,
+        properties: Option<HashMap<String, String>>,
     ) -> Result<HashMap<String, String>, IcebergError> {
-        todo!()
+        if self.namespace_exists(namespace).await? {
+            return Err(IcebergError::InvalidFormat(
+                "Namespace already exists. Namespace".to_owned(),
+            ));
+        }
+        let catalog_name = self.name.clone();
+        let namespace = namespace.to_string();
+        let properties = properties.unwrap_or_default();
+
+        let mut transaction = self.pool.begin().await.map_err(Error::from)?;
+
+        let insert = statement(
+            &self.pool,
+            "insert into iceberg_namespace_properties (catalog_name, namespace, property_key, property_value) values (?, ?, ?, ?);",
+        );
+
+        sqlx::query(&insert)
+            .bind(&catalog_name)
+            .bind(&namespace)
+            .bind(NAMESPACE_EXISTS_PROPERTY)
+            .bind("true")
+            .execute(&mut *transaction)
+            .await
+            .map_err(Error::from)?;
+        for (key, value) in &properties {
+            sqlx::query(&insert)
+                .bind(&catalog_name)
+                .bind(&namespace)
+                .bind(key)
+                .bind(value)
+                .execute(&mut *transaction)
+                .await
+                .map_err(Error::from)?;
+        }
+
+        transaction.commit().await.map_err(Error::from)?;
+
+        Ok(properties)
     }
-    /// Drop a namespace in the catalog
-    async fn drop_namespace(&self, _namespace: &Namespace) -> Result<(), IcebergError> {
-        todo!()
+    /// Drop an empty namespace in the catalog. Namespaces that contain tables or other
+    /// namespaces are dropped with [SqlCatalog::drop_namespace_cascade].
+    async fn drop_namespace(&self, namespace: &Namespace) -> Result<(), IcebergError> {
+        let name = namespace.to_string();
+        let prefix = name.clone() + ".";
+
+        let namespaces = self.namespaces().await?;
+        if !namespaces
+            .iter()
+            .any(|x| *x == name || x.starts_with(&prefix))
+        {
+            return Err(IcebergError::NotFound(format!("Namespace {name}")));
+        }
+        let tables = {
+            sqlx::query(&format!("select table_name from iceberg_tables where catalog_name = '{}' and table_namespace = '{}' limit 1;", &self.name, &name)).fetch_optional(&self.pool).await.map_err(Error::from)?
+        };
+        if tables.is_some() || namespaces.iter().any(|x| x.starts_with(&prefix)) {
+            return Err(IcebergError::InvalidFormat(
+                "Namespace is not empty. Namespace".to_owned(),
+            ));
+        }
+
+        sqlx::query(&format!("delete from iceberg_namespace_properties where catalog_name = '{}' and namespace = '{}';", &self.name, &name)).execute(&self.pool).await.map_err(Error::from)?;
+        Ok(())
     }
     /// Load the namespace properties from the catalog
     async fn load_namespace(
         &self,
-        _namespace: &Namespace,
+        namespace: &Namespace,
     ) -> Result<HashMap<String, String>, IcebergError> {
-        todo!()
+        let catalog_name = self.name.clone();
+        let namespace = namespace.to_string();
+
+        let rows = {
+            sqlx::query(&format!("select property_key, property_value from iceberg_namespace_properties where catalog_name = '{}' and namespace = '{}' and property_key <> '{}';",&catalog_name,
+                &namespace, NAMESPACE_EXISTS_PROPERTY)).fetch_all(&self.pool).await.map_err(Error::from)?
+        };
+        Ok(rows
+            .iter()
+            .map(|row| Ok((row.try_get(0)?, row.try_get(1)?)))
+            .collect::<Result<HashMap<String, String>, sqlx::Error>>()
+            .map_err(Error::from)?)
     }
     /// Update the namespace properties in the catalog
     async fn update_namespace(
         &self,
-        _namespace: &Namespace,
-        _updates: Option<HashMap<String, String>>,
-        _removals: Option<Vec<String>>,
+        namespace: &Namespace,
+        updates: Option<HashMap<String, String>>,
+        removals: Option<Vec<String>>,
     ) -> Result<(), IcebergError> {
-        todo!()
+        self.update_namespace_properties(
+            namespace,
+            updates.unwrap_or_default(),
+            removals.unwrap_or_default(),
+        )
+        .await?;
+        Ok(())
     }
-    /// Check if a namespace exists
-    async fn namespace_exists(&self, _namespace: &Namespace) -> Result<bool, IcebergError> {
-        todo!()
+    /// Check if a namespace exists. A namespace exists if it was created, contains tables or
+    /// contains other namespaces.
+    async fn namespace_exists(&self, namespace: &Namespace) -> Result<bool, IcebergError> {
+        let name = namespace.to_string();
+        let prefix = name.clone() + ".";
+        Ok(self
+            .namespaces()
+            .await?
+            .iter()
+            .any(|x| *x == name || x.starts_with(&prefix)))
     }
     async fn list_tabulars(&self, namespace: &Namespace) -> Result<Vec<Identifier>, IcebergError> {
         let name = self.name.clone();
@@ -193,29 +354,84 @@ impl Catalog for SqlCatalog {
             .collect::<Result<_, sqlx::Error>>()
             .map_err(Error::from)?)
     }
-    async fn list_namespaces(&self, _parent: Option<&str>) -> Result<Vec<Namespace>, IcebergError> {
+    /// Lists one page of the tabular objects ordered by name. The page token is the name of the
+    /// last table of the previous page.
+    async fn list_tables_paginated(
+        &self,
+        namespace: &Namespace,
+        page_token: Option<&str>,
+        page_size: Option<usize>,
+    ) -> Result<TabularPage, IcebergError> {
+        if page_size == Some(0) {
+            return Err(IcebergError::InvalidFormat("Page size 0".to_owned()));
+        }
         let name = self.name.clone();
+        let namespace_name = namespace.to_string();
 
-        let rows = {
-            sqlx::query(&format!(
-                "select distinct table_namespace from iceberg_tables where catalog_name = '{}';",
-                &name
-            ))
-            .fetch_all(&self.pool)
-            .await
-            .map_err(Error::from)?
+        let after = if page_token.is_some() {
+            " and table_name > ?"
+        } else {
+            ""
+        };
+        // Fetch one more row to know whether there is a next page
+        let limit = page_size
+            .map(|size| format!(" limit {}", size + 1))
+            .unwrap_or_default();
+
+        let select = statement(
+            &self.pool,
+            &format!("select table_namespace, table_name, metadata_location, previous_metadata_location from iceberg_tables where catalog_name = ? and table_namespace = ?{} order by table_name{};", after, limit),
+        );
+        let mut query = sqlx::query(&select).bind(&name).bind(&namespace_name);
+        if let Some(token) = page_token {
+            query = query.bind(token);
+        }
+        let rows = query.fetch_all(&self.pool).await.map_err(Error::from)?;
+
+        let mut identifiers = rows
+            .iter()
+            .map(query_map)
+            .map(|x| x.map(|y| Identifier::new(namespace, &y.table_name)))
+            .collect::<Result<Vec<_>, sqlx::Error>>()
+            .map_err(Error::from)?;
+
+        let next_page_token = match page_size {
+            Some(size) if identifiers.len() > size => {
+                identifiers.truncate(size);
+                identifiers.last().map(|x| x.name().to_owned())
+            }
+            _ => None,
         };
-        let iter = rows.iter().map(|row| row.try_get::<String, _>(0));
 
-        Ok(iter
-            .map(|x| {
-                x.and_then(|y| {
-                    Namespace::try_new(&y.split('.').map(ToString::to_string).collect::<Vec<_>>())
-                        .map_err(|err| sqlx::Error::Decode(Box::new(err)))
-                })
-            })
-            .collect::<Result<_, sqlx::Error>>()
-            .map_err(Error::from)?)
+        Ok(TabularPage {
+            identifiers,
+            next_page_token,
+        })
+    }
+    /// Lists all namespaces if no parent is given, otherwise the namespaces one level below the
+    /// parent
+    async fn list_namespaces(&self, parent: Option<&str>) -> Result<Vec<Namespace>, IcebergError> {
+        let namespaces = self.namespaces().await?;
+
+        let namespaces = match parent {
+            None => namespaces,
+            Some(parent) => {
+                let prefix = parent.to_owned() + ".";
+                namespaces
+                    .iter()
+                    .filter_map(|x| x.strip_prefix(&prefix))
+                    .map(|x| prefix.clone() + x.split('.').next().unwrap_or(x))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect()
+            }
+        };
+
+        namespaces
+            .iter()
+            .map(|x| Namespace::try_new(&x.split('.').map(ToString::to_string).collect::<Vec<_>>()))
+            .collect::<Result<_, iceberg_rust::spec::error::Error>>()
+            .map_err(IcebergError::from)
     }
     async fn tabular_exists(&self, identifier: &Identifier) -> Result<bool, IcebergError> {
         let catalog_name = self.name.clone();
@@ -231,6 +447,19 @@ impl Catalog for SqlCatalog {
 
         Ok(iter.next().is_some())
     }
+    async fn table_exists(&self, identifier: &Identifier) -> Result<bool, IcebergError> {
+        let catalog_name = self.name.clone();
+        let namespace = identifier.namespace().to_string();
+        let name = identifier.name().to_string();
+
+        let row = {
+            sqlx::query(&format!("select 1 from iceberg_tables where catalog_name = '{}' and table_namespace = '{}' and table_name = '{}' limit 1;",&catalog_name,
+                &namespace,
+                &name)).fetch_optional(&self.pool).await.map_err(Error::from)?
+        };
+
+        Ok(row.is_some())
+    }
     async fn drop_table(&self, identifier: &Identifier) -> Result<(), IcebergError> {
         let catalog_name = self.name.clone();
         let namespace = identifier.namespace().to_string();
@@ -261,6 +490,10 @@ impl Catalog for SqlCatalog {
                 &name)).execute(&self.pool).await.map_err(Error::from)?;
         Ok(())
     }
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), fields(catalog = %self.name))
+    )]
     async fn load_tabular(
         self: Arc<Self>,
         identifier: &Identifier,
@@ -283,12 +516,18 @@ impl Catalog for SqlCatalog {
         let bucket = Bucket::from_path(&path)?;
         let object_store = self.object_store(bucket);
 
-        let bytes = object_store
-            .get(&strip_prefix(&path).as_str().into())
-            .await?
-            .bytes()
-            .await?;
-        let metadata: TabularMetadata = serde_json::from_slice(&bytes)?;
+        let load = async {
+            let bytes = object_store
+                .get(&strip_prefix(&path).as_str().into())
+                .await?
+                .bytes()
+                .await?;
+            parse_metadata(&bytes)
+        };
+        let metadata = match &self.metadata_cache {
+            Some(metadata_cache) => metadata_cache.get_or_load(identifier, &path, load).await?,
+            None => load.await?,
+        };
         self.cache
             .write()
             .unwrap()
@@ -426,56 +665,83 @@ impl Catalog for SqlCatalog {
         Ok(MaterializedView::new(identifier.clone(), self.clone(), metadata).await?)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(catalog = %self.name, table = %commit.identifier)
+        )
+    )]
     async fn update_table(self: Arc<Self>, commit: CommitTable) -> Result<Table, IcebergError> {
-        let identifier = commit.identifier;
-        let Some(entry) = self.cache.read().unwrap().get(&identifier).cloned() else {
-            #[allow(clippy::if_same_then_else)]
-            if !matches!(commit.requirements[0], TableRequirement::AssertCreate) {
-                return Err(IcebergError::InvalidFormat(
-                    "Create table assertion".to_owned(),
-                ));
-            } else {
-                return Err(IcebergError::InvalidFormat(
-                    "Create table assertion".to_owned(),
-                ));
-            }
-        };
-        let (previous_metadata_location, metadata) = entry;
-
-        let bucket = Bucket::from_path(&previous_metadata_location)?;
-        let object_store = self.object_store(bucket);
+        let mut transaction = self.pool.begin().await.map_err(Error::from)?;
+
+        match self.commit_table(&mut transaction, commit).await? {
+            TableCommit::Committed {
+                identifier,
+                metadata_location,
+                metadata,
+            } => {
+                transaction.commit().await.map_err(Error::from)?;
+
+                self.cache.write().unwrap().insert(
+                    identifier.clone(),
+                    (metadata_location, metadata.clone().into()),
+                );
 
-        let TabularMetadata::Table(mut metadata) = metadata else {
-            return Err(IcebergError::InvalidFormat(
-                "Table update on entity that is not a table".to_owned(),
-            ));
-        };
-        if !check_table_requirements(&commit.requirements, &metadata) {
-            return Err(IcebergError::InvalidFormat(
-                "Table requirements not valid".to_owned(),
-            ));
+                Ok(Table::new(identifier, self.clone(), metadata).await?)
+            }
+            TableCommit::Conflict {
+                identifier,
+                requirements,
+                base_snapshot_id,
+            } => {
+                transaction.rollback().await.map_err(Error::from)?;
+                Err(self
+                    .commit_conflict(&identifier, &requirements, base_snapshot_id)
+                    .await)
+            }
         }
-        apply_table_updates(&mut metadata, commit.updates)?;
-        let metadata_location = new_metadata_location(&metadata);
-        object_store
-            .put_metadata(&metadata_location, metadata.as_ref())
-            .await?;
-        object_store.put_version_hint(&metadata_location).await.ok();
+    }
 
-        let catalog_name = self.name.clone();
-        let namespace = identifier.namespace().to_string();
-        let name = identifier.name().to_string();
-        let metadata_file_location = metadata_location.to_string();
-        let previous_metadata_file_location = previous_metadata_location.to_string();
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(catalog = %self.name, tables = changes.len()))
+    )]
+    async fn commit_transaction(&self, mut changes: Vec<CommitTable>) -> Result<(), IcebergError> {
+        // Lock the tables in a consistent order to avoid deadlocks between concurrent transactions
+        changes.sort_by_key(|commit| commit.identifier.to_string());
+
+        let mut transaction = self.pool.begin().await.map_err(Error::from)?;
+
+        let mut committed = Vec::with_capacity(changes.len());
+        for commit in changes {
+            match self.commit_table(&mut transaction, commit).await? {
+                TableCommit::Committed {
+                    identifier,
+                    metadata_location,
+                    metadata,
+                } => committed.push((identifier, metadata_location, metadata)),
+                TableCommit::Conflict {
+                    identifier,
+                    requirements,
+                    base_snapshot_id,
+                } => {
+                    transaction.rollback().await.map_err(Error::from)?;
+                    return Err(self
+                        .commit_conflict(&identifier, &requirements, base_snapshot_id)
+                        .await);
+                }
+            }
+        }
 
-        sqlx::query(&format!("update iceberg_tables set metadata_location = '{}', previous_metadata_location = '{}' where catalog_name = '{}' and table_namespace = '{}' and table_name = '{}' and metadata_location = '{}';", metadata_file_location, previous_metadata_file_location,catalog_name,namespace,name, previous_metadata_file_location)).execute(&self.pool).await.map_err(Error::from)?;
+        transaction.commit().await.map_err(Error::from)?;
 
-        self.cache.write().unwrap().insert(
-            identifier.clone(),
-            (metadata_location.clone(), metadata.clone().into()),
-        );
+        let mut cache = self.cache.write().unwrap();
+        for (identifier, metadata_location, metadata) in committed {
+            cache.insert(identifier, (metadata_location, metadata.into()));
+        }
 
-        Ok(Table::new(identifier.clone(), self.clone(), metadata).await?)
+        Ok(())
     }
 
     async fn update_view(
@@ -595,16 +861,27 @@ impl Catalog for SqlCatalog {
         identifier: Identifier,
         metadata_location: &str,
     ) -> Result<Table, IcebergError> {
+        if self.tabular_exists(&identifier).await? {
+            return Err(IcebergError::InvalidFormat(
+                "Table already exists. Identifier".to_owned(),
+            ));
+        }
+
         let bucket = Bucket::from_path(metadata_location)?;
         let object_store = self.object_store(bucket);
 
-        let metadata: TableMetadata = serde_json::from_slice(
+        let TabularMetadata::Table(metadata) = parse_metadata(
             &object_store
-                .get(&metadata_location.into())
+                .get(&strip_prefix(metadata_location).as_str().into())
                 .await?
                 .bytes()
                 .await?,
-        )?;
+        )?
+        else {
+            return Err(IcebergError::InvalidFormat(
+                "Registered metadata file is not a table".to_owned(),
+            ));
+        };
 
         {
             let catalog_name = self.name.clone();
@@ -621,18 +898,347 @@ impl Catalog for SqlCatalog {
         Ok(Table::new(identifier.clone(), self.clone(), metadata).await?)
     }
 
+    async fn rename_table(&self, from: &Identifier, to: &Identifier) -> Result<(), IcebergError> {
+        let catalog_name = self.name.clone();
+        let namespace = from.namespace().to_string();
+        let name = from.name().to_string();
+        let new_namespace = to.namespace().to_string();
+        let new_name = to.name().to_string();
+
+        // The primary key on the identifier prevents a concurrent insert of the destination
+        let mut transaction = self.pool.begin().await.map_err(Error::from)?;
+
+        let existing = sqlx::query(&format!("select table_namespace, table_name, metadata_location, previous_metadata_location from iceberg_tables where catalog_name = '{}' and table_namespace = '{}' and table_name = '{}';",&catalog_name,
+                &new_namespace,
+                &new_name)).fetch_all(&mut *transaction).await.map_err(Error::from)?;
+        if !existing.is_empty() {
+            return Err(IcebergError::InvalidFormat(format!(
+                "Rename destination {to} already exists. Identifier"
+            )));
+        }
+
+        let result = sqlx::query(&format!("update iceberg_tables set table_namespace = '{}', table_name = '{}' where catalog_name = '{}' and table_namespace = '{}' and table_name = '{}';", &new_namespace, &new_name, &catalog_name, &namespace, &name)).execute(&mut *transaction).await.map_err(Error::from)?;
+        if result.rows_affected() == 0 {
+            return Err(IcebergError::NotFound(format!("Table {from}")));
+        }
+
+        transaction.commit().await.map_err(Error::from)?;
+
+        let mut cache = self.cache.write().unwrap();
+        if let Some(entry) = cache.remove(from) {
+            cache.insert(to.clone(), entry);
+        }
+        Ok(())
+    }
+
     fn object_store(&self, bucket: Bucket) -> Arc<dyn object_store::ObjectStore> {
         Arc::new(self.object_store.build(bucket).unwrap())
     }
 }
 
 impl SqlCatalog {
+    /// Returns the conflict for a table commit whose metadata location was changed by a
+    /// concurrent commit after it was read.
+    ///
+    /// The conflict is reported for the first asserted reference that was moved, or for the main
+    /// branch if the commit didn't assert a moved reference.
+    async fn commit_conflict(
+        &self,
+        identifier: &Identifier,
+        requirements: &[TableRequirement],
+        base_snapshot_id: i64,
+    ) -> IcebergError {
+        let refs = async {
+            let row = sqlx::query(&format!("select table_namespace, table_name, metadata_location, previous_metadata_location from iceberg_tables where catalog_name = '{}' and table_namespace = '{}' and table_name = '{}';", &self.name, identifier.namespace(), identifier.name())).fetch_one(&self.pool).await.map_err(Error::from)?;
+            let metadata_location = query_map(&row).map_err(Error::from)?.metadata_location;
+            let metadata = self
+                .object_store(Bucket::from_path(&metadata_location)?)
+                .get_metadata(&metadata_location)
+                .await?;
+            Ok::<HashMap<String, i64>, IcebergError>(match metadata {
+                TabularMetadata::Table(metadata) => metadata
+                    .refs
+                    .into_iter()
+                    .map(|(name, reference)| (name, reference.snapshot_id))
+                    .collect(),
+                _ => HashMap::new(),
+            })
+        }
+        .await
+        .unwrap_or_default();
+
+        let actual = |r#ref: &str| refs.get(r#ref).copied().unwrap_or(-1);
+        let (r#ref, expected) = requirements
+            .iter()
+            .filter_map(|requirement| match requirement {
+                TableRequirement::AssertRefSnapshotId { r#ref, snapshot_id } => {
+                    Some((r#ref.clone(), *snapshot_id))
+                }
+                _ => None,
+            })
+            .find(|(r#ref, expected)| actual(r#ref) != *expected)
+            .unwrap_or((MAIN_BRANCH.to_owned(), base_snapshot_id));
+        IcebergError::CommitConflict {
+            actual: actual(&r#ref),
+            reference: r#ref,
+            expected,
+        }
+    }
+
+    /// Writes the new metadata of a table commit and swaps the metadata location of the table
+    /// within the database transaction.
+    ///
+    /// The tables row is locked until the transaction ends. The caller is responsible for
+    /// committing or rolling back the transaction.
+    async fn commit_table(
+        &self,
+        transaction: &mut sqlx::Transaction<'static, Any>,
+        commit: CommitTable,
+    ) -> Result<TableCommit, IcebergError> {
+        let identifier = commit.identifier;
+        let Some(entry) = self.cache.read().unwrap().get(&identifier).cloned() else {
+            #[allow(clippy::if_same_then_else)]
+            if !matches!(commit.requirements[0], TableRequirement::AssertCreate) {
+                return Err(IcebergError::InvalidFormat(
+                    "Create table assertion".to_owned(),
+                ));
+            } else {
+                return Err(IcebergError::InvalidFormat(
+                    "Create table assertion".to_owned(),
+                ));
+            }
+        };
+        let (cached_metadata_location, metadata) = entry;
+
+        let catalog_name = self.name.clone();
+        let namespace = identifier.namespace().to_string();
+        let name = identifier.name().to_string();
+
+        // Serialize the commits to the table until the transaction ends
+        let for_update = match transaction.backend_name() {
+            "PostgreSQL" => {
+                sqlx::query(&format!(
+                    "select pg_advisory_xact_lock({});",
+                    advisory_lock_key(&catalog_name, &namespace, &name)
+                ))
+                .execute(&mut **transaction)
+                .await
+                .map_err(Error::from)?;
+                ""
+            }
+            "MySQL" => " for update",
+            _ => "",
+        };
+
+        let previous_metadata_location = {
+            let row = {
+                sqlx::query(&format!("select table_namespace, table_name, metadata_location, previous_metadata_location from iceberg_tables where catalog_name = '{}' and table_namespace = '{}' and table_name = '{}'{};",&catalog_name,
+                    &namespace,
+                    &name, for_update)).fetch_one(&mut **transaction).await.map_err(|_| IcebergError::CatalogNotFound)?
+            };
+            query_map(&row).map_err(Error::from)?.metadata_location
+        };
+
+        let bucket = Bucket::from_path(&previous_metadata_location)?;
+        let object_store = self.object_store(bucket);
+
+        // Another writer committed since the table was loaded
+        let metadata = if previous_metadata_location == cached_metadata_location {
+            metadata
+        } else {
+            let bytes = object_store
+                .get(&strip_prefix(&previous_metadata_location).as_str().into())
+                .await?
+                .bytes()
+                .await?;
+            parse_metadata(&bytes)?
+        };
+
+        let TabularMetadata::Table(mut metadata) = metadata else {
+            return Err(IcebergError::InvalidFormat(
+                "Table update on entity that is not a table".to_owned(),
+            ));
+        };
+        validate_table_requirements(&commit.requirements, &metadata)?;
+        let base_snapshot_id = metadata
+            .refs
+            .get(MAIN_BRANCH)
+            .map(|x| x.snapshot_id)
+            .unwrap_or(-1);
+        apply_table_updates(&mut metadata, commit.updates)?;
+        let metadata_location = new_metadata_location(&metadata);
+        object_store
+            .put_metadata(&metadata_location, metadata.as_ref())
+            .await?;
+        object_store.put_version_hint(&metadata_location).await.ok();
+
+        let metadata_file_location = metadata_location.to_string();
+        let previous_metadata_file_location = previous_metadata_location.to_string();
+
+        // Only swap the metadata location if no other writer committed since it was read
+        let result = sqlx::query(&format!("update iceberg_tables set metadata_location = '{}', previous_metadata_location = '{}' where catalog_name = '{}' and table_namespace = '{}' and table_name = '{}' and metadata_location = '{}';", metadata_file_location, previous_metadata_file_location,catalog_name,namespace,name, previous_metadata_file_location)).execute(&mut **transaction).await.map_err(Error::from)?;
+        if result.rows_affected() == 0 {
+            return Ok(TableCommit::Conflict {
+                identifier,
+                requirements: commit.requirements,
+                base_snapshot_id,
+            });
+        }
+
+        Ok(TableCommit::Committed {
+            identifier,
+            metadata_location,
+            metadata,
+        })
+    }
+
+    /// Returns the names of all namespaces that were created or contain tables ordered by name
+    async fn namespaces(&self) -> Result<Vec<String>, IcebergError> {
+        let rows = {
+            sqlx::query(&format!("select distinct table_namespace from iceberg_tables where catalog_name = '{}' union select distinct namespace from iceberg_namespace_properties where catalog_name = '{}';", &self.name, &self.name)).fetch_all(&self.pool).await.map_err(Error::from)?
+        };
+        Ok(rows
+            .iter()
+            .map(|row| row.try_get::<String, _>(0))
+            .collect::<Result<BTreeSet<_>, sqlx::Error>>()
+            .map_err(Error::from)?
+            .into_iter()
+            .collect())
+    }
+
+    /// Drops a namespace together with all tables and namespaces it contains.
+    ///
+    /// The tables are removed from the catalog, their data and metadata files are not deleted.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The namespace doesn't exist
+    /// * The database operation fails
+    pub async fn drop_namespace_cascade(&self, namespace: &Namespace) -> Result<(), IcebergError> {
+        let name = namespace.to_string();
+        let prefix = name.clone() + ".";
+
+        let namespaces = self
+            .namespaces()
+            .await?
+            .into_iter()
+            .filter(|x| *x == name || x.starts_with(&prefix))
+            .collect::<Vec<_>>();
+        if namespaces.is_empty() {
+            return Err(IcebergError::NotFound(format!("Namespace {name}")));
+        }
+
+        let mut transaction = self.pool.begin().await.map_err(Error::from)?;
+
+        for namespace in &namespaces {
+            sqlx::query(&format!(
+                "delete from iceberg_tables where catalog_name = '{}' and table_namespace = '{}';",
+                &self.name, namespace
+            ))
+            .execute(&mut *transaction)
+            .await
+            .map_err(Error::from)?;
+            sqlx::query(&format!("delete from iceberg_namespace_properties where catalog_name = '{}' and namespace = '{}';", &self.name, namespace)).execute(&mut *transaction).await.map_err(Error::from)?;
+        }
+
+        transaction.commit().await.map_err(Error::from)?;
+
+        Ok(())
+    }
+
+    /// Updates and removes properties of a namespace in a single transaction.
+    ///
+    /// # Arguments
+    /// * `namespace` - The namespace whose properties are changed
+    /// * `updates` - Properties to add or overwrite
+    /// * `removals` - Keys of the properties to remove
+    ///
+    /// # Returns
+    /// * `Result<UpdateNamespacePropertiesResponse, IcebergError>` - The keys that were updated,
+    ///   removed and the keys to remove that didn't exist
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * A key is contained in both the updates and the removals
+    /// * The database operation fails
+    pub async fn update_namespace_properties(
+        &self,
+        namespace: &Namespace,
+        updates: HashMap<String, String>,
+        removals: Vec<String>,
+    ) -> Result<UpdateNamespacePropertiesResponse, IcebergError> {
+        if let Some(key) = removals.iter().find(|key| updates.contains_key(*key)) {
+            return Err(IcebergError::InvalidFormat(format!(
+                "Property {key} is updated and removed. Namespace properties update"
+            )));
+        }
+
+        let catalog_name = self.name.clone();
+        let namespace = namespace.to_string();
+
+        let mut response = UpdateNamespacePropertiesResponse::default();
+        let mut missing = Vec::new();
+
+        let mut transaction = self.pool.begin().await.map_err(Error::from)?;
+
+        let delete = statement(
+            &self.pool,
+            "delete from iceberg_namespace_properties where catalog_name = ? and namespace = ? and property_key = ?;",
+        );
+        let insert = statement(
+            &self.pool,
+            "insert into iceberg_namespace_properties (catalog_name, namespace, property_key, property_value) values (?, ?, ?, ?);",
+        );
+
+        for key in removals {
+            let result = sqlx::query(&delete)
+                .bind(&catalog_name)
+                .bind(&namespace)
+                .bind(&key)
+                .execute(&mut *transaction)
+                .await
+                .map_err(Error::from)?;
+            if result.rows_affected() == 0 {
+                missing.push(key);
+            } else {
+                response.removed.push(key);
+            }
+        }
+
+        for (key, value) in updates {
+            sqlx::query(&delete)
+                .bind(&catalog_name)
+                .bind(&namespace)
+                .bind(&key)
+                .execute(&mut *transaction)
+                .await
+                .map_err(Error::from)?;
+            sqlx::query(&insert)
+                .bind(&catalog_name)
+                .bind(&namespace)
+                .bind(&key)
+                .bind(&value)
+                .execute(&mut *transaction)
+                .await
+                .map_err(Error::from)?;
+            response.updated.push(key);
+        }
+
+        transaction.commit().await.map_err(Error::from)?;
+
+        if !missing.is_empty() {
+            response.missing = Some(missing);
+        }
+        Ok(response)
+    }
+
     pub fn duplicate(&self, name: &str) -> Self {
         Self {
             name: name.to_owned(),
             pool: self.pool.clone(),
             object_store: self.object_store.clone(),
             cache: Arc::new(RwLock::new(HashMap::new())),
+            metadata_cache: self.metadata_cache.clone(),
         }
     }
 }
@@ -696,6 +1302,7 @@ impl CatalogList for SqlCatalogList {
             pool: self.pool.clone(),
             object_store: self.object_store.clone(),
             cache: Arc::new(RwLock::new(HashMap::new())),
+            metadata_cache: None,
         }))
     }
     async fn list_catalogs(&self) -> Vec<String> {
@@ -727,15 +1334,25 @@ pub mod tests {
         planner::{iceberg_transform, IcebergQueryPlanner},
     };
     use iceberg_rust::{
-        catalog::{namespace::Namespace, Catalog},
+        catalog::{
+            commit::{CommitTable, TableRequirement, TableUpdate},
+            identifier::Identifier,
+            namespace::Namespace,
+            Catalog,
+        },
         object_store::ObjectStoreBuilder,
-        spec::util::strip_prefix,
+        spec::{
+            schema::Schema,
+            types::{PrimitiveType, StructField, Type},
+            util::strip_prefix,
+        },
+        table::Table,
     };
     use testcontainers::{core::ExecCommand, runners::AsyncRunner, ImageExt};
     use testcontainers_modules::{localstack::LocalStack, postgres::Postgres};
     use tokio::time::sleep;
 
-    use std::{sync::Arc, time::Duration};
+    use std::{collections::HashMap, sync::Arc, time::Duration};
 
     use crate::SqlCatalog;
 
@@ -948,4 +1565,263 @@ pub mod tests {
             .unwrap()
             .ends_with(".metadata.json"));
     }
+
+    #[tokio::test]
+    async fn test_update_namespace_properties() {
+        let catalog = SqlCatalog::new("sqlite://", "test", ObjectStoreBuilder::memory())
+            .await
+            .unwrap();
+        let namespace = Namespace::try_new(&["tpch".to_owned()]).unwrap();
+
+        let response = catalog
+            .update_namespace_properties(
+                &namespace,
+                HashMap::from_iter(vec![
+                    ("owner".to_owned(), "analytics".to_owned()),
+                    ("retention".to_owned(), "30".to_owned()),
+                ]),
+                vec![],
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.updated.len(), 2);
+
+        let response = catalog
+            .update_namespace_properties(
+                &namespace,
+                HashMap::from_iter(vec![("owner".to_owned(), "finance".to_owned())]),
+                vec!["retention".to_owned(), "location".to_owned()],
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.updated, vec!["owner".to_owned()]);
+        assert_eq!(response.removed, vec!["retention".to_owned()]);
+        assert_eq!(response.missing, Some(vec!["location".to_owned()]));
+
+        let properties = catalog.load_namespace(&namespace).await.unwrap();
+        assert_eq!(
+            properties,
+            HashMap::from_iter(vec![("owner".to_owned(), "finance".to_owned())])
+        );
+
+        assert!(catalog
+            .update_namespace_properties(
+                &namespace,
+                HashMap::from_iter(vec![("owner".to_owned(), "finance".to_owned())]),
+                vec!["owner".to_owned()],
+            )
+            .await
+            .is_err());
+
+        // Quotes in keys and values are stored as they are
+        let comment = "it's the 'tpch' namespace".to_owned();
+        catalog
+            .update_namespace_properties(
+                &namespace,
+                HashMap::from_iter(vec![("owner's comment".to_owned(), comment.clone())]),
+                vec![],
+            )
+            .await
+            .unwrap();
+        let properties = catalog.load_namespace(&namespace).await.unwrap();
+        assert_eq!(properties.get("owner's comment"), Some(&comment));
+    }
+
+    #[tokio::test]
+    async fn test_list_tables_paginated() {
+        let catalog = SqlCatalog::new("sqlite://", "test", ObjectStoreBuilder::memory())
+            .await
+            .unwrap();
+        let namespace = Namespace::try_new(&["tpch".to_owned()]).unwrap();
+
+        for name in ["lineitem", "customer", "orders", "nation", "region"] {
+            sqlx::query(&format!("insert into iceberg_tables (catalog_name, table_namespace, table_name, metadata_location) values ('test', 'tpch', '{}', 'memory:///tpch/{}/metadata.json');", name, name))
+                .execute(&catalog.pool)
+                .await
+                .unwrap();
+        }
+
+        let mut names = Vec::new();
+        let mut page_token = None;
+        loop {
+            let page = catalog
+                .list_tables_paginated(&namespace, page_token.as_deref(), Some(2))
+                .await
+                .unwrap();
+            assert!(page.identifiers.len() <= 2);
+            names.extend(page.identifiers.iter().map(|x| x.name().to_owned()));
+            page_token = page.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        assert_eq!(
+            names,
+            vec!["customer", "lineitem", "nation", "orders", "region"]
+        );
+
+        let page = catalog
+            .list_tables_paginated(&namespace, None, None)
+            .await
+            .unwrap();
+        assert_eq!(page.identifiers.len(), 5);
+        assert_eq!(page.next_page_token, None);
+
+        // The page token is compared as a value and not as part of the statement
+        let page = catalog
+            .list_tables_paginated(&namespace, Some("orders' or '1' = '1"), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            page.identifiers
+                .iter()
+                .map(|x| x.name().to_owned())
+                .collect::<Vec<_>>(),
+            vec!["region"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_table_exists() {
+        let catalog = SqlCatalog::new("sqlite://", "test", ObjectStoreBuilder::memory())
+            .await
+            .unwrap();
+
+        sqlx::query("insert into iceberg_tables (catalog_name, table_namespace, table_name, metadata_location) values ('test', 'tpch', 'lineitem', 'memory:///tpch/lineitem/metadata.json');")
+            .execute(&catalog.pool)
+            .await
+            .unwrap();
+
+        let lineitem = Identifier::new(&["tpch".to_owned()], "lineitem");
+        let orders = Identifier::new(&["tpch".to_owned()], "orders");
+        assert!(catalog.table_exists(&lineitem).await.unwrap());
+        assert!(!catalog.table_exists(&orders).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_nested_namespaces() {
+        let catalog = SqlCatalog::new("sqlite://", "test", ObjectStoreBuilder::memory())
+            .await
+            .unwrap();
+        let a = Namespace::try_new(&["a".to_owned()]).unwrap();
+        let ab = Namespace::try_new(&["a".to_owned(), "b".to_owned()]).unwrap();
+        let abc = Namespace::try_new(&["a".to_owned(), "b".to_owned(), "c".to_owned()]).unwrap();
+
+        catalog
+            .create_namespace(
+                &abc,
+                Some(HashMap::from_iter(vec![(
+                    "owner".to_owned(),
+                    "iceberg's".to_owned(),
+                )])),
+            )
+            .await
+            .unwrap();
+        assert!(catalog.create_namespace(&abc, None).await.is_err());
+
+        assert!(catalog.namespace_exists(&a).await.unwrap());
+        assert!(catalog.namespace_exists(&ab).await.unwrap());
+        assert!(catalog.namespace_exists(&abc).await.unwrap());
+        assert!(!catalog
+            .namespace_exists(&Namespace::try_new(&["b".to_owned()]).unwrap())
+            .await
+            .unwrap());
+        assert_eq!(
+            catalog.load_namespace(&abc).await.unwrap(),
+            HashMap::from_iter(vec![("owner".to_owned(), "iceberg's".to_owned())])
+        );
+        assert_eq!(catalog.list_namespaces(Some("a")).await.unwrap(), vec![ab]);
+
+        sqlx::query("insert into iceberg_tables (catalog_name, table_namespace, table_name, metadata_location) values ('test', 'a.b.c', 'lineitem', 'memory:///a/b/c/lineitem/metadata.json');")
+            .execute(&catalog.pool)
+            .await
+            .unwrap();
+
+        assert!(catalog.drop_namespace(&abc).await.is_err());
+        assert!(catalog.drop_namespace(&a).await.is_err());
+
+        catalog.drop_namespace_cascade(&a).await.unwrap();
+        assert!(!catalog.namespace_exists(&abc).await.unwrap());
+        assert!(!catalog
+            .table_exists(&Identifier::new(
+                &["a".to_owned(), "b".to_owned(), "c".to_owned()],
+                "lineitem"
+            ))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_commit_transaction() {
+        let catalog = Arc::new(
+            SqlCatalog::new("sqlite://", "test", ObjectStoreBuilder::memory())
+                .await
+                .unwrap(),
+        );
+
+        let schema = Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+
+        for name in ["fact", "aggregate"] {
+            Table::builder()
+                .with_name(name)
+                .with_location(format!("/tpch/{name}"))
+                .with_schema(schema.clone())
+                .build(&["tpch".to_owned()], catalog.clone())
+                .await
+                .unwrap();
+        }
+
+        let fact = Identifier::new(&["tpch".to_owned()], "fact");
+        let aggregate = Identifier::new(&["tpch".to_owned()], "aggregate");
+        let commit = |identifier: &Identifier, requirements: Vec<TableRequirement>| CommitTable {
+            identifier: identifier.clone(),
+            requirements,
+            updates: vec![TableUpdate::SetProperties {
+                updates: HashMap::from_iter(vec![("batch".to_owned(), "1".to_owned())]),
+            }],
+        };
+
+        catalog
+            .commit_transaction(vec![commit(&fact, vec![]), commit(&aggregate, vec![])])
+            .await
+            .unwrap();
+
+        for identifier in [&fact, &aggregate] {
+            let table = catalog.clone().load_table(identifier).await.unwrap();
+            assert_eq!(
+                table.metadata().properties.get("batch"),
+                Some(&"1".to_owned())
+            );
+        }
+
+        // A failing requirement on one table aborts the commits to all tables
+        let failing = vec![TableRequirement::AssertRefSnapshotId {
+            r#ref: "main".to_owned(),
+            snapshot_id: 1,
+        }];
+        let mut changes = vec![commit(&fact, vec![]), commit(&aggregate, failing)];
+        for change in &mut changes {
+            change.updates = vec![TableUpdate::SetProperties {
+                updates: HashMap::from_iter(vec![("batch".to_owned(), "2".to_owned())]),
+            }];
+        }
+        assert!(catalog.commit_transaction(changes).await.is_err());
+
+        for identifier in [&fact, &aggregate] {
+            let table = catalog.clone().load_table(identifier).await.unwrap();
+            assert_eq!(
+                table.metadata().properties.get("batch"),
+                Some(&"1".to_owned())
+            );
+        }
+    }
 }
@@ -58,12 +58,49 @@ pub enum Error {
 
 impl From<Error> for DataFusionError {
     fn from(value: Error) -> Self {
-        DataFusionError::External(Box::new(value))
+        match value {
+            // Datafusion errors are passed on as they are, to not hide them behind another layer
+            Error::Datafusion(err) => err,
+            Error::Arrow(err) => DataFusionError::ArrowError(err, None),
+            err => DataFusionError::External(Box::new(err)),
+        }
     }
 }
 
 impl From<Error> for IcebergError {
     fn from(value: Error) -> Self {
-        IcebergError::External(Box::new(value))
+        match value {
+            Error::Iceberg(err) => err,
+            Error::IcebergSpec(err) => IcebergError::Iceberg(err),
+            Error::Arrow(err) => IcebergError::Arrow(err),
+            // Iceberg errors that were raised inside of a datafusion plan are recovered
+            Error::Datafusion(DataFusionError::External(err)) => {
+                match err.downcast::<IcebergError>() {
+                    Ok(err) => *err,
+                    Err(err) => IcebergError::External(err),
+                }
+            }
+            err => IcebergError::External(Box::new(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::error::DataFusionError;
+    use iceberg_rust::error::Error as IcebergError;
+
+    use super::Error;
+
+    #[test]
+    fn iceberg_error_round_trip() {
+        let err =
+            DataFusionError::External(Box::new(IcebergError::NotFound("Table test".to_owned())));
+        let err: IcebergError = Error::from(err).into();
+        assert!(matches!(err, IcebergError::NotFound(name) if name == "Table test"));
+
+        let err: DataFusionError =
+            Error::from(DataFusionError::Plan("missing column".to_owned())).into();
+        assert!(matches!(err, DataFusionError::Plan(msg) if msg == "missing column"));
     }
 }
@@ -3,10 +3,11 @@ use std::{
     fmt::{self, Debug},
     hash::Hash,
     iter,
+    num::NonZeroUsize,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        Arc, Mutex, OnceLock,
     },
     task::{Context, Poll},
 };
@@ -32,43 +33,55 @@ use futures::{
 use pin_project_lite::pin_project;
 
 pub fn fork_node(plan: Arc<LogicalPlan>) -> (ForkNode, ForkNode) {
-    let parallelism = std::thread::available_parallelism().unwrap().get();
-    let (sender, receiver): (Vec<_>, Vec<_>) = iter::repeat_n((), parallelism)
-        .map(|_| {
-            let (sender, receiver) = channel(1);
-            (
-                Arc::new(Mutex::new(Some(sender))),
-                Arc::new(Mutex::new(Some(receiver))),
-            )
-        })
-        .unzip();
-    let executed: Vec<Arc<AtomicBool>> = iter::repeat_n((), parallelism)
-        .map(|_| Arc::new(AtomicBool::new(false)))
-        .collect();
+    // The channels are created once the physical plan is created, so that their number matches the target partitions of the session
+    let channels = Arc::new(OnceLock::new());
     (
         ForkNode {
             input: plan.clone(),
-            sender: sender.clone(),
-            receiver: receiver.clone(),
-            executed: executed.clone(),
+            channels: channels.clone(),
         },
         ForkNode {
             input: plan,
-            sender,
-            receiver,
-            executed,
+            channels,
         },
     )
 }
 
-#[allow(clippy::type_complexity)]
 pub struct ForkNode {
     pub(crate) input: Arc<LogicalPlan>,
+    channels: Arc<OnceLock<ForkChannels>>,
+}
+
+/// Channels that send the record batches of every partition from one fork to the other
+#[allow(clippy::type_complexity)]
+struct ForkChannels {
     sender: Vec<Arc<Mutex<Option<Sender<Result<RecordBatch, DataFusionError>>>>>>,
     receiver: Vec<Arc<Mutex<Option<Receiver<Result<RecordBatch, DataFusionError>>>>>>,
     executed: Vec<Arc<AtomicBool>>,
 }
 
+impl ForkChannels {
+    fn new(parallelism: usize, capacity: usize) -> Self {
+        let (sender, receiver): (Vec<_>, Vec<_>) = iter::repeat_n((), parallelism)
+            .map(|_| {
+                let (sender, receiver) = channel(capacity);
+                (
+                    Arc::new(Mutex::new(Some(sender))),
+                    Arc::new(Mutex::new(Some(receiver))),
+                )
+            })
+            .unzip();
+        let executed = iter::repeat_n((), parallelism)
+            .map(|_| Arc::new(AtomicBool::new(false)))
+            .collect();
+        ForkChannels {
+            sender,
+            receiver,
+            executed,
+        }
+    }
+}
+
 impl PartialEq for ForkNode {
     fn eq(&self, other: &Self) -> bool {
         self.input.eq(&other.input)
@@ -130,9 +143,7 @@ impl UserDefinedLogicalNodeCore for ForkNode {
         assert_eq!(exprs.len(), 0, "expression size inconsistent");
         Ok(Self {
             input: Arc::new(inputs.pop().unwrap()),
-            sender: self.sender.clone(),
-            receiver: self.receiver.clone(),
-            executed: self.executed.clone(),
+            channels: self.channels.clone(),
         })
     }
 }
@@ -192,13 +203,18 @@ impl ExecutionPlan for PhysicalForkNode {
         self: Arc<Self>,
         mut children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> datafusion::error::Result<Arc<dyn ExecutionPlan>> {
-        assert_eq!(children.len(), 1);
-        let properties = children[0]
+        let (Some(input), true) = (children.pop(), children.is_empty()) else {
+            return Err(DataFusionError::Internal(
+                "Fork node requires exactly one child.".to_string(),
+            ));
+        };
+        // The channels are shared with the new node, whether they were already taken or not
+        let properties = input
             .properties()
             .clone()
             .with_partitioning(Partitioning::UnknownPartitioning(self.executed.len()));
         Ok(Arc::new(PhysicalForkNode {
-            input: children.pop().unwrap(),
+            input,
             properties,
             sender: self.sender.clone(),
             receiver: self.receiver.clone(),
@@ -214,19 +230,19 @@ impl ExecutionPlan for PhysicalForkNode {
         let input_partitions = self.input.properties().partitioning.partition_count();
         let n_partitions = self.executed.len();
 
-        let executed = self.executed[partition]
+        let executed = self
+            .executed
+            .get(partition)
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Fork node has no partition {partition}, it has {n_partitions} partitions."
+                ))
+            })?
             .compare_exchange(false, true, Ordering::Release, Ordering::Acquire)
             .is_err();
 
         if executed {
-            let receiver = {
-                let mut lock = self.receiver[partition].lock().unwrap();
-                lock.take()
-            }
-            .ok_or(DataFusionError::Internal(
-                "Fork node can only be executed once.".to_string(),
-            ))
-            .unwrap();
+            let receiver = take_once(&self.receiver[partition])?;
 
             return Ok(Box::pin(RecordBatchStreamAdapter::new(
                 self.schema().clone(),
@@ -234,14 +250,7 @@ impl ExecutionPlan for PhysicalForkNode {
             )));
         }
 
-        let sender = {
-            let mut lock = self.sender[partition].lock().unwrap();
-            lock.take()
-        }
-        .ok_or(DataFusionError::Internal(
-            "Fork node can only be executed once.".to_string(),
-        ))
-        .unwrap();
+        let sender = take_once(&self.sender[partition])?;
 
         let schema = self.schema().clone();
 
@@ -288,11 +297,35 @@ impl ExecutionPlan for PhysicalForkNode {
     }
 }
 
-pub struct ForkNodePlanner {}
+/// Takes the sender or receiver of a partition, which is only possible once
+fn take_once<T>(slot: &Mutex<Option<T>>) -> Result<T, DataFusionError> {
+    slot.lock()
+        .map_err(|_| DataFusionError::Internal("Fork node channel is poisoned.".to_string()))?
+        .take()
+        .ok_or(DataFusionError::Internal(
+            "Fork node can only be executed once.".to_string(),
+        ))
+}
+
+/// Default number of record batches that are buffered between the two forks of a fork node
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 1;
+
+pub struct ForkNodePlanner {
+    channel_capacity: usize,
+}
 
 impl ForkNodePlanner {
     pub(crate) fn new() -> Self {
-        Self {}
+        Self {
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+        }
+    }
+
+    /// Sets the number of record batches that are buffered for every partition. The sending fork
+    /// waits until the receiving fork consumed a batch once the buffer is full.
+    pub(crate) fn with_channel_capacity(mut self, channel_capacity: usize) -> Self {
+        self.channel_capacity = channel_capacity;
+        self
     }
 }
 
@@ -304,23 +337,34 @@ impl ExtensionPlanner for ForkNodePlanner {
         node: &dyn UserDefinedLogicalNode,
         logical_inputs: &[&LogicalPlan],
         physical_inputs: &[Arc<dyn ExecutionPlan>],
-        _session_state: &SessionState,
+        session_state: &SessionState,
     ) -> Result<Option<Arc<dyn ExecutionPlan>>, DataFusionError> {
         if let Some(fork_node) = node.as_any().downcast_ref::<ForkNode>() {
-            assert_eq!(physical_inputs.len(), 1);
-            assert_eq!(logical_inputs.len(), 1);
-
-            let len = fork_node.sender.len();
-            let properties = physical_inputs[0]
+            let ([physical_input], [_]) = (physical_inputs, logical_inputs) else {
+                return Err(DataFusionError::Internal(
+                    "Fork node requires exactly one input.".to_string(),
+                ));
+            };
+
+            let channels = fork_node.channels.get_or_init(|| {
+                let parallelism = match session_state.config().target_partitions() {
+                    0 => std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+                    target_partitions => target_partitions,
+                };
+                ForkChannels::new(parallelism, self.channel_capacity)
+            });
+
+            let len = channels.sender.len();
+            let properties = physical_input
                 .properties()
                 .clone()
                 .with_partitioning(Partitioning::UnknownPartitioning(len));
             Ok(Some(Arc::new(PhysicalForkNode {
-                input: physical_inputs[0].clone(),
+                input: physical_input.clone(),
                 properties,
-                sender: fork_node.sender.clone(),
-                receiver: fork_node.receiver.clone(),
-                executed: fork_node.executed.clone(),
+                sender: channels.sender.clone(),
+                receiver: channels.receiver.clone(),
+                executed: channels.executed.clone(),
             })))
         } else {
             Ok(None)
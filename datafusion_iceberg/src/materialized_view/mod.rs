@@ -13,13 +13,15 @@ use futures::{stream, StreamExt, TryStreamExt};
 use iceberg_rust::{
     arrow::write::{write_equality_deletes_parquet_partitioned, write_parquet_partitioned},
     catalog::{identifier::Identifier, tabular::Tabular, CatalogList},
-    materialized_view::MaterializedView,
+    materialized_view::{MaterializedView, RefreshMode},
     spec::materialized_view_metadata::{SourceTables, SourceViews},
 };
 use iceberg_rust::{
     error::Error,
-    spec::{materialized_view_metadata::RefreshState, view_metadata::ViewRepresentation},
-    sql::find_relations,
+    spec::{
+        materialized_view_metadata::RefreshState, snapshot::Operation,
+        table_metadata::TableMetadata, view_metadata::ViewRepresentation,
+    },
 };
 
 use crate::{
@@ -29,6 +31,12 @@ use crate::{
 
 pub(crate) mod delta_queries;
 
+/// Refreshes the storage table of a materialized view
+///
+/// If the query of the view allows it, only the changes of the source tables since the last refresh
+/// are computed with the delta query nodes and appended to or deleted from the storage table.
+/// A full refresh is performed if the view was never refreshed or a source table had a change
+/// other than an append since the last refresh.
 pub async fn refresh_materialized_view(
     matview: &mut MaterializedView,
     catalog_list: Arc<dyn CatalogList>,
@@ -53,7 +61,7 @@ pub async fn refresh_materialized_view(
 
     let storage_table = matview.storage_table().await?;
 
-    let relations = find_relations(sql)?;
+    let relations = matview.source_tables()?;
 
     let branch = branch.map(ToString::to_string);
 
@@ -64,8 +72,14 @@ pub async fn refresh_materialized_view(
     );
 
     // Load source tables
-    let (source_tables, source_table_states) =
-        get_source_tables(relations, catalog_list, &branch, old_refresh_state, version).await?;
+    let (source_tables, source_table_states) = get_source_tables(
+        relations,
+        catalog_list,
+        &branch,
+        old_refresh_state.clone(),
+        version,
+    )
+    .await?;
 
     let source_tables = Arc::new(source_tables);
 
@@ -80,8 +94,15 @@ pub async fn refresh_materialized_view(
         .await
         .map_err(DatafusionIcebergError::from)?;
 
-    let refresh_strategy =
-        determine_refresh_strategy(&logical_plan).map_err(DatafusionIcebergError::from)?;
+    // Perform a full refresh if the view requires it or the changes of a source table since the
+    // last refresh are unknown
+    let refresh_strategy = if matview.refresh_mode()? == RefreshMode::Full
+        || (old_refresh_state.is_some() && source_tables.values().any(|(fresh, _)| fresh.is_none()))
+    {
+        RefreshStrategy::FullOverwrite
+    } else {
+        determine_refresh_strategy(&logical_plan).map_err(DatafusionIcebergError::from)?
+    };
 
     let refresh_version_id = matview.metadata().current_version_id;
 
@@ -261,20 +282,30 @@ async fn get_source_tables(
                     x => x,
                 };
                 let uuid = *tabular.metadata().as_ref().uuid();
-                let current_snapshot_id = match &tabular {
-                    Tabular::Table(table) => Ok(*table
-                        .metadata()
-                        .current_snapshot(branch.as_deref())?
-                        // Fallback to main branch
-                        .or(table.metadata().current_snapshot(None)?)
-                        .ok_or(Error::NotFound(format!(
-                            "Snapshot in source table {}",
-                            (&identifier.name()),
-                        )))?
-                        .snapshot_id()),
+                let revision_id = old_refresh_state
+                    .as_ref()
+                    .as_ref()
+                    .and_then(|state| state.source_table_states.get(&(uuid, None)).copied());
+                let (current_snapshot_id, only_appends) = match &tabular {
+                    Tabular::Table(table) => {
+                        let current_snapshot_id = *table
+                            .metadata()
+                            .current_snapshot(branch.as_deref())?
+                            // Fallback to main branch
+                            .or(table.metadata().current_snapshot(None)?)
+                            .ok_or(Error::NotFound(format!(
+                                "Snapshot in source table {}",
+                                (&identifier.name()),
+                            )))?
+                            .snapshot_id();
+                        Ok((
+                            current_snapshot_id,
+                            only_appends_since(table.metadata(), revision_id, current_snapshot_id),
+                        ))
+                    }
                     Tabular::MaterializedView(mv) => {
                         let storage_table = mv.storage_table().await?;
-                        Ok(*storage_table
+                        let current_snapshot_id = *storage_table
                             .metadata()
                             .current_snapshot(branch.as_deref())?
                             // Fallback to main branch
@@ -283,7 +314,15 @@ async fn get_source_tables(
                                 "Snapshot in source table {}",
                                 (&identifier.name()),
                             )))?
-                            .snapshot_id())
+                            .snapshot_id();
+                        Ok((
+                            current_snapshot_id,
+                            only_appends_since(
+                                storage_table.metadata(),
+                                revision_id,
+                                current_snapshot_id,
+                            ),
+                        ))
                     }
                     _ => Err(Error::InvalidFormat("storage table".to_string())),
                 }?;
@@ -292,8 +331,8 @@ async fn get_source_tables(
                 let source_table_provider: (
                     Option<Arc<dyn TableProvider>>,
                     Option<Arc<dyn TableProvider>>,
-                ) = if let Some(old_refresh_state) = old_refresh_state.as_ref() {
-                    let revision_id = old_refresh_state.source_table_states.get(&(uuid, None));
+                ) = if old_refresh_state.is_some() {
+                    let revision_id = revision_id.as_ref();
                     if Some(&current_snapshot_id) == revision_id {
                         // Fresh
                         (
@@ -305,8 +344,8 @@ async fn get_source_tables(
                             ))),
                             None,
                         )
-                    } else if Some(&-1) == revision_id {
-                        // Invalid
+                    } else if Some(&-1) == revision_id || !only_appends {
+                        // Invalid, the changes since the last refresh can't be computed incrementally
                         (
                             None,
                             Some(Arc::new(DataFusionTable::new(
@@ -367,6 +406,32 @@ async fn get_source_tables(
         .await
 }
 
+/// Checks whether all snapshots after the last refreshed snapshot only appended data. Only then the
+/// changes of the source table can be read incrementally.
+fn only_appends_since(
+    metadata: &TableMetadata,
+    revision_id: Option<i64>,
+    current_snapshot_id: i64,
+) -> bool {
+    let Some(revision_id) = revision_id else {
+        return false;
+    };
+    let mut snapshot_id = current_snapshot_id;
+    while snapshot_id != revision_id {
+        let Some(snapshot) = metadata.snapshots.get(&snapshot_id) else {
+            return false;
+        };
+        if snapshot.summary().operation != Operation::Append {
+            return false;
+        }
+        let Some(parent_snapshot_id) = snapshot.parent_snapshot_id() else {
+            return false;
+        };
+        snapshot_id = *parent_snapshot_id;
+    }
+    true
+}
+
 #[derive(Debug)]
 /// Refresh strategy that can be used for the operation
 enum RefreshStrategy {
@@ -63,6 +63,15 @@ impl IcebergQueryPlanner {
             Arc::new(ForkNodePlanner::new()),
         ]))
     }
+
+    /// Creates a query planner that buffers at most `capacity` record batches per partition
+    /// between the two branches of a forked plan during materialized view refreshes
+    pub fn with_fork_channel_capacity(capacity: usize) -> Self {
+        IcebergQueryPlanner(DefaultPhysicalPlanner::with_extension_planners(vec![
+            Arc::new(IcebergExtensionPlanner {}),
+            Arc::new(ForkNodePlanner::new().with_channel_capacity(capacity)),
+        ]))
+    }
 }
 
 #[async_trait]
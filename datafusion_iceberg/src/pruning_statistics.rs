@@ -12,7 +12,7 @@
  * For the second level the trait PruningStatistics is implemented for the Manifest
 */
 
-use std::{any::Any, sync::Arc};
+use std::{any::Any, collections::HashMap, sync::Arc};
 
 use crate::error::Error as DatafusionIcebergError;
 use datafusion::{
@@ -37,8 +37,8 @@ use iceberg_rust::{
     error::Error,
     spec::{
         manifest::ManifestEntry,
-        manifest_list::ManifestListEntry,
-        partition::{BoundPartitionField, Transform},
+        manifest_list::{FieldSummary, ManifestListEntry},
+        partition::{BoundPartitionField, PartitionSpec, Transform},
         schema::Schema,
         values::Value,
     },
@@ -46,80 +46,93 @@ use iceberg_rust::{
 
 pub(crate) struct PruneManifests<'table, 'manifests> {
     partition_fields: &'table [BoundPartitionField<'table>],
+    partition_specs: &'table HashMap<i32, PartitionSpec>,
     files: &'manifests [ManifestListEntry],
 }
 
 impl<'table, 'manifests> PruneManifests<'table, 'manifests> {
     pub(crate) fn new(
         partition_fields: &'table [BoundPartitionField<'table>],
+        partition_specs: &'table HashMap<i32, PartitionSpec>,
         files: &'manifests [ManifestListEntry],
     ) -> Self {
         Self {
             partition_fields,
+            partition_specs,
             files,
         }
     }
+
+    /// Returns the summary of the partition field for every manifest.
+    /// The summaries of a manifest are ordered by the fields of the partition spec the manifest was written with,
+    /// manifests whose spec doesn't contain the field have no summary.
+    fn field_summaries<'a>(
+        &'a self,
+        field_id: i32,
+    ) -> impl Iterator<Item = Option<&'a FieldSummary>> + 'a {
+        self.files.iter().map(move |manifest| {
+            let index = self
+                .partition_specs
+                .get(&manifest.partition_spec_id)?
+                .fields()
+                .iter()
+                .position(|field| *field.field_id() == field_id)?;
+            manifest.partitions.as_ref()?.get(index)
+        })
+    }
 }
 
 impl PruningStatistics for PruneManifests<'_, '_> {
     fn min_values(&self, column: &Column) -> Option<ArrayRef> {
-        let (index, partition_field) = self
+        let partition_field = self
             .partition_fields
             .iter()
-            .enumerate()
-            .find(|(_, field)| field.name() == column.name())?;
+            .find(|field| field.name() == column.name())?;
         let data_type = partition_field
             .field_type()
             .tranform(partition_field.transform())
             .ok()?;
-        let min_values = self.files.iter().filter_map(|manifest| {
-            manifest.partitions.as_ref().and_then(|partitions| {
-                partitions[index]
-                    .lower_bound
-                    .as_ref()
-                    .map(|min| Some(min.clone().into_any()))
-            })
-        });
+        let min_values = self
+            .field_summaries(partition_field.field_id())
+            .map(|summary| {
+                summary
+                    .and_then(|summary| summary.lower_bound.as_ref())
+                    .map(|min| min.clone().into_any())
+            });
         any_iter_to_array(min_values, &(&data_type).try_into().ok()?).ok()
     }
     fn max_values(&self, column: &Column) -> Option<ArrayRef> {
-        let (index, partition_field) = self
+        let partition_field = self
             .partition_fields
             .iter()
-            .enumerate()
-            .find(|(_, field)| field.name() == column.name())?;
+            .find(|field| field.name() == column.name())?;
         let data_type = partition_field
             .field_type()
             .tranform(partition_field.transform())
             .ok()?;
-        let max_values = self.files.iter().filter_map(|manifest| {
-            manifest.partitions.as_ref().and_then(|partitions| {
-                partitions[index]
-                    .upper_bound
-                    .as_ref()
-                    .map(|max| Some(max.clone().into_any()))
-            })
-        });
+        let max_values = self
+            .field_summaries(partition_field.field_id())
+            .map(|summary| {
+                summary
+                    .and_then(|summary| summary.upper_bound.as_ref())
+                    .map(|max| max.clone().into_any())
+            });
         any_iter_to_array(max_values, &(&data_type).try_into().ok()?).ok()
     }
     fn num_containers(&self) -> usize {
         self.files.len()
     }
     fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
-        let (index, _) = self
+        let partition_field = self
             .partition_fields
             .iter()
-            .enumerate()
-            .find(|(_, field)| field.source_name() == column.name())?;
-        let contains_null = self.files.iter().filter_map(|manifest| {
-            manifest.partitions.as_ref().map(|partitions| {
-                if !partitions[index].contains_null {
-                    Some(0)
-                } else {
-                    None
-                }
-            })
-        });
+            .find(|field| field.source_name() == column.name())?;
+        let contains_null = self
+            .field_summaries(partition_field.field_id())
+            .map(|summary| match summary {
+                Some(summary) if !summary.contains_null => Some(0),
+                _ => None,
+            });
         ScalarValue::iter_to_array(contains_null.map(ScalarValue::Int32)).ok()
     }
     fn contained(
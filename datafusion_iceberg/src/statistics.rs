@@ -27,6 +27,43 @@ impl DataFusionTable {
             }
         }
     }
+
+    /// Estimates the statistics of the table from the summary of its snapshot without reading
+    /// the manifest list or any manifest. Returns `None` if the summary doesn't contain the totals,
+    /// the table is currently locked for writing or the scan is incremental.
+    pub(crate) fn summary_statistics(&self) -> Option<Statistics> {
+        if self.snapshot_range.0.is_some() {
+            return None;
+        }
+        let tabular = self.tabular.try_read().ok()?;
+        let Tabular::Table(table) = tabular.deref() else {
+            return None;
+        };
+        let metadata = table.metadata();
+        let snapshot = match self.snapshot_range.1 {
+            Some(snapshot_id) => metadata.snapshots.get(&snapshot_id),
+            None => metadata.current_snapshot(self.branch.as_deref()).ok()?,
+        };
+        let Some(snapshot) = snapshot else {
+            return Some(Statistics {
+                num_rows: Precision::Exact(0),
+                total_byte_size: Precision::Exact(0),
+                column_statistics: Statistics::unknown_column(&self.schema),
+            });
+        };
+        let summary = &snapshot.summary().other;
+        let total = |key: &str| {
+            summary
+                .get(key)
+                .and_then(|x| x.parse::<usize>().ok())
+                .map(Precision::Inexact)
+        };
+        Some(Statistics {
+            num_rows: total("total-records")?,
+            total_byte_size: total("total-files-size").unwrap_or(Precision::Absent),
+            column_statistics: Statistics::unknown_column(&self.schema),
+        })
+    }
 }
 
 pub(crate) async fn table_statistics(
@@ -111,7 +148,7 @@ fn column_statistics<'a>(
                 .as_ref()
                 .and_then(|x| x.get(&id))
                 .and_then(|x| {
-                    Some(Precision::Exact(
+                    Some(bound_precision(
                         convert_value_to_scalar_value(x.clone()).ok()?,
                     ))
                 })
@@ -121,7 +158,7 @@ fn column_statistics<'a>(
                 .as_ref()
                 .and_then(|x| x.get(&id))
                 .and_then(|x| {
-                    Some(Precision::Exact(
+                    Some(bound_precision(
                         convert_value_to_scalar_value(x.clone()).ok()?,
                     ))
                 })
@@ -137,6 +174,14 @@ fn column_statistics<'a>(
     })
 }
 
+/// String and binary bounds can be truncated, so they are only exact for other types
+fn bound_precision(value: ScalarValue) -> Precision<ScalarValue> {
+    match value {
+        ScalarValue::Utf8(_) | ScalarValue::Binary(_) => Precision::Inexact(value),
+        value => Precision::Exact(value),
+    }
+}
+
 pub(crate) fn manifest_statistics(schema: &Schema, manifest: &ManifestEntry) -> Statistics {
     Statistics {
         num_rows: Precision::Exact(*manifest.data_file().record_count() as usize),
@@ -33,6 +33,7 @@ use datafusion::{
     physical_expr::create_physical_expr,
     physical_optimizer::pruning::PruningPredicate,
     physical_plan::{
+        empty::EmptyExec,
         expressions::Column,
         insert::{DataSink, DataSinkExec},
         joins::{HashJoinExec, PartitionMode},
@@ -159,6 +160,9 @@ impl TableProvider for DataFusionTable {
     fn table_type(&self) -> TableType {
         TableType::Base
     }
+    fn statistics(&self) -> Option<Statistics> {
+        self.summary_statistics()
+    }
     async fn scan(
         &self,
         session: &dyn Session,
@@ -372,8 +376,11 @@ async fn table_scan(
             )?;
             let pruning_predicate =
                 PruningPredicate::try_new(physical_partition_predicate, partition_schema.clone())?;
-            let manifests_to_prune =
-                pruning_predicate.prune(&PruneManifests::new(partition_fields, &manifests))?;
+            let manifests_to_prune = pruning_predicate.prune(&PruneManifests::new(
+                partition_fields,
+                &table.metadata().partition_specs,
+                &manifests,
+            ))?;
 
             table
                 .datafiles(&manifests, Some(manifests_to_prune), sequence_number_range)
@@ -530,9 +537,11 @@ async fn table_scan(
                             data_files.push(data_file);
                         }
                         async move {
-                            let delete_schema = schema.project(
-                                delete_manifest.data_file().equality_ids().as_ref().unwrap(),
-                            );
+                            let delete_schema = schema
+                                .project(
+                                    delete_manifest.data_file().equality_ids().as_ref().unwrap(),
+                                )
+                                .map_err(DataFusionIcebergError::from)?;
                             let delete_file_schema: SchemaRef =
                                 Arc::new((delete_schema.fields()).try_into().unwrap());
                             let equality_projection: Option<Vec<usize>> =
@@ -595,7 +604,7 @@ async fn table_scan(
                                 output_ordering: vec![],
                             };
 
-                            let data_files_scan = ParquetFormat::default()
+                            let data_files_scan = parquet_format(session)
                                 .create_physical_plan(
                                     session,
                                     file_scan_config,
@@ -667,7 +676,7 @@ async fn table_scan(
                         output_ordering: vec![],
                     };
 
-                    let data_files_scan = ParquetFormat::default()
+                    let data_files_scan = parquet_format(session)
                         .create_physical_plan(
                             session,
                             file_scan_config,
@@ -691,6 +700,14 @@ async fn table_scan(
         .try_collect::<Vec<_>>()
         .await?;
 
+    // Without filters, a limit bounds the number of data files that have to be read.
+    // Partitions with delete files are not bounded, because deleted rows reduce the number of rows of the data files.
+    if let (Some(limit), true) = (limit, filters.is_empty()) {
+        data_file_groups = limit_data_files(data_file_groups, limit);
+    }
+
+    let no_data_files = data_file_groups.is_empty();
+
     // Create plan for partitions without delete files
     let file_scan_config = FileScanConfig {
         object_store_url,
@@ -714,11 +731,16 @@ async fn table_scan(
         output_ordering: vec![],
     };
 
-    let other_plan = ParquetFormat::default()
+    let other_plan = parquet_format(session)
         .create_physical_plan(session, file_scan_config, physical_predicate.as_ref())
         .await?;
 
     if plans.is_empty() {
+        // A scan without files has no partitions, which can't be distributed for joins that
+        // collect one side into a single partition
+        if no_data_files {
+            return Ok(Arc::new(EmptyExec::new(other_plan.schema())));
+        }
         Ok(other_plan)
     } else {
         plans.push(other_plan);
@@ -791,6 +813,39 @@ impl DataSink for IcebergDataSink {
     }
 }
 
+/// Parquet format for data files with the parquet options of the session
+///
+/// Row groups are pruned by their statistics unless `datafusion.execution.parquet.pruning` is
+/// disabled. With `datafusion.execution.parquet.pushdown_filters` the filters are also evaluated
+/// while decoding the remaining row groups, so that non-matching rows are never materialized.
+fn parquet_format(session: &SessionState) -> ParquetFormat {
+    let mut options = session.table_options().parquet.clone();
+    options.global = session.config().options().execution.parquet.clone();
+    ParquetFormat::default().with_options(options)
+}
+
+/// Keeps only as many data files as are required to produce at least `limit` rows
+fn limit_data_files(
+    data_file_groups: HashMap<Struct, Vec<ManifestEntry>>,
+    limit: usize,
+) -> HashMap<Struct, Vec<ManifestEntry>> {
+    let mut rows = 0;
+    data_file_groups
+        .into_iter()
+        .filter_map(|(partition_value, data_files)| {
+            let data_files: Vec<_> = data_files
+                .into_iter()
+                .take_while(|manifest| {
+                    let take = rows < limit;
+                    rows += *manifest.data_file().record_count() as usize;
+                    take
+                })
+                .collect();
+            (!data_files.is_empty()).then_some((partition_value, data_files))
+        })
+        .collect()
+}
+
 fn generate_partitioned_file(
     schema: &Schema,
     manifest: &ManifestEntry,
@@ -862,7 +917,10 @@ fn value_to_scalarvalue(value: &Value) -> Result<ScalarValue, DataFusionError> {
 #[cfg(test)]
 mod tests {
 
-    use datafusion::{arrow::array::Int64Array, prelude::SessionContext};
+    use datafusion::{
+        arrow::array::Int64Array,
+        prelude::{SessionConfig, SessionContext},
+    };
     use iceberg_rust::{
         catalog::tabular::Tabular,
         object_store::ObjectStoreBuilder,
@@ -883,10 +941,16 @@ mod tests {
     };
     use iceberg_sql_catalog::SqlCatalog;
 
-    use std::{ops::Deref, sync::Arc};
+    use futures::TryStreamExt;
+    use std::{
+        ops::{Deref, DerefMut},
+        sync::Arc,
+    };
 
     use crate::{catalog::catalog::IcebergCatalog, DataFusionTable};
 
+    use super::parquet_format;
+
     #[tokio::test]
     pub async fn test_datafusion_table_insert() {
         let object_store = ObjectStoreBuilder::memory();
@@ -1299,6 +1363,198 @@ mod tests {
         };
     }
 
+    #[tokio::test]
+    pub async fn test_datafusion_table_insert_after_set_location() {
+        let object_store = ObjectStoreBuilder::memory();
+
+        let catalog: Arc<dyn Catalog> = Arc::new(
+            SqlCatalog::new("sqlite://", "test", object_store)
+                .await
+                .unwrap(),
+        );
+
+        let schema = Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .with_struct_field(StructField {
+                id: 2,
+                name: "amount".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+
+        let table = Table::builder()
+            .with_name("orders")
+            .with_location("/test/orders")
+            .with_schema(schema)
+            .build(&["test".to_owned()], catalog)
+            .await
+            .expect("Failed to create table");
+
+        let table = Arc::new(DataFusionTable::from(table));
+
+        let ctx = SessionContext::new();
+
+        ctx.register_table("orders", table.clone()).unwrap();
+
+        ctx.sql("INSERT INTO orders (id, amount) VALUES (1, 1), (2, 2), (3, 3);")
+            .await
+            .expect("Failed to create query plan for insert")
+            .collect()
+            .await
+            .expect("Failed to insert values into table");
+
+        if let Tabular::Table(table) = table.tabular.write().await.deref_mut() {
+            assert!(table
+                .new_transaction(None)
+                .set_location("test/relative")
+                .commit()
+                .await
+                .is_err());
+
+            table
+                .new_transaction(None)
+                .set_location("/test/orders_moved/")
+                .commit()
+                .await
+                .expect("Failed to set location");
+
+            assert_eq!(table.metadata().location, "/test/orders_moved");
+        };
+
+        ctx.sql("INSERT INTO orders (id, amount) VALUES (4, 4), (5, 5);")
+            .await
+            .expect("Failed to create query plan for insert")
+            .collect()
+            .await
+            .expect("Failed to insert values into table");
+
+        let batches = ctx
+            .sql("select sum(amount) from orders;")
+            .await
+            .expect("Failed to create plan for select")
+            .collect()
+            .await
+            .expect("Failed to execute select query");
+
+        let sum = batches
+            .iter()
+            .filter(|batch| batch.num_rows() != 0)
+            .map(|batch| {
+                batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .value(0)
+            })
+            .sum::<i64>();
+        assert_eq!(sum, 15);
+
+        // Files of the first insert keep their absolute paths below the old location
+        if let Tabular::Table(table) = table.tabular.read().await.deref() {
+            let manifests = table.manifests(None, None).await.unwrap();
+            let paths = table
+                .data_files(&manifests, None, None, (None, None))
+                .await
+                .unwrap()
+                .map_ok(|entry| entry.data_file().file_path().clone())
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            assert!(paths.iter().any(|x| x.starts_with("/test/orders/data/")));
+            assert!(paths
+                .iter()
+                .any(|x| x.starts_with("/test/orders_moved/data/")));
+        };
+    }
+
+    #[tokio::test]
+    pub async fn test_multi_branch_transaction() {
+        let object_store = ObjectStoreBuilder::memory();
+
+        let catalog: Arc<dyn Catalog> = Arc::new(
+            SqlCatalog::new("sqlite://", "test", object_store)
+                .await
+                .unwrap(),
+        );
+
+        let schema = Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+
+        let table = Table::builder()
+            .with_name("orders")
+            .with_location("/test/orders")
+            .with_schema(schema)
+            .build(&["test".to_owned()], catalog)
+            .await
+            .expect("Failed to create table");
+
+        let table = Arc::new(DataFusionTable::from(table));
+
+        let ctx = SessionContext::new();
+
+        ctx.register_table("orders", table.clone()).unwrap();
+
+        ctx.sql("INSERT INTO orders (id) VALUES (1), (2), (3);")
+            .await
+            .expect("Failed to create query plan for insert")
+            .collect()
+            .await
+            .expect("Failed to insert values into table");
+
+        if let Tabular::Table(table) = table.tabular.write().await.deref_mut() {
+            let old_snapshot_id = table.metadata().refs["main"].snapshot_id;
+
+            let manifests = table.manifests(None, None).await.unwrap();
+            let data_files = table
+                .data_files(&manifests, None, None, (None, None))
+                .await
+                .unwrap()
+                .map_ok(|entry| entry.data_file().clone())
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+
+            table
+                .new_transaction(None)
+                .append_data(data_files.clone())
+                .with_branch("staging")
+                .append_data(data_files)
+                .commit()
+                .await
+                .expect("Failed to commit to multiple branches");
+
+            let metadata = table.metadata();
+            let main = metadata.current_snapshot(None).unwrap().unwrap();
+            let staging = metadata.current_snapshot(Some("staging")).unwrap().unwrap();
+
+            assert_eq!(*main.parent_snapshot_id(), Some(old_snapshot_id));
+            assert_eq!(*staging.parent_snapshot_id(), None);
+            assert_ne!(main.sequence_number(), staging.sequence_number());
+            assert_eq!(
+                metadata.last_sequence_number,
+                *main.sequence_number().max(staging.sequence_number())
+            );
+        };
+    }
+
     #[tokio::test]
     pub async fn test_datafusion_table_branch_insert() {
         let object_store = ObjectStoreBuilder::memory();
@@ -1633,4 +1889,17 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_parquet_format_session_options() {
+        let config = SessionConfig::new()
+            .set_bool("datafusion.execution.parquet.pruning", false)
+            .set_bool("datafusion.execution.parquet.pushdown_filters", true);
+        let ctx = SessionContext::new_with_config(config);
+
+        let format = parquet_format(&ctx.state());
+        assert!(!format.options().global.pruning);
+        assert!(format.options().global.pushdown_filters);
+        assert!(!format.options().global.reorder_filters);
+    }
 }
@@ -36,6 +36,7 @@ fn configuration(host: &str, port: u16) -> Configuration {
         bearer_access_token: None,
         api_key: None,
         aws_v4_key: None,
+        token_provider: None,
     }
 }
 
@@ -80,3 +80,82 @@ pub enum Error {
     #[error(transparent)]
     PartitionSpec(#[from] crate::spec::partition::PartitionSpecBuilderError),
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+/// Category of an [`Error`] that allows to handle errors without matching on their message
+pub enum ErrorKind {
+    /// A value or file doesn't have the expected format
+    InvalidFormat,
+    /// A value doesn't have the expected type
+    Type,
+    /// A column is missing in the schema
+    Schema,
+    /// A value can't be converted or parsed
+    Conversion,
+    /// An entity doesn't exist
+    NotFound,
+    /// A feature is not supported
+    NotSupported,
+    /// Avro encoding or decoding failed
+    Avro,
+    /// JSON serialization or deserialization failed
+    Serde,
+    /// Reading or writing failed
+    Io,
+    /// A builder is missing a required field
+    Builder,
+}
+
+impl Error {
+    /// Returns the category of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::InvalidFormat(_) => ErrorKind::InvalidFormat,
+            Error::Type(_, _) => ErrorKind::Type,
+            Error::ColumnNotInSchema(_, _) => ErrorKind::Schema,
+            Error::Conversion(_, _)
+            | Error::Chrono(_)
+            | Error::Uuid(_)
+            | Error::TryFromSlice(_)
+            | Error::TryFromInt(_)
+            | Error::UTF8(_)
+            | Error::FromUTF8(_)
+            | Error::ParseInt(_) => ErrorKind::Conversion,
+            Error::NotFound(_) => ErrorKind::NotFound,
+            Error::NotSupported(_) => ErrorKind::NotSupported,
+            Error::Avro(_) => ErrorKind::Avro,
+            Error::JSONSerde(_) => ErrorKind::Serde,
+            Error::IO(_) => ErrorKind::Io,
+            Error::TableMetadataBuilder(_)
+            | Error::ViewMetadataBuilder(_)
+            | Error::VersionBuilder(_)
+            | Error::ManifestEntryBuilder(_)
+            | Error::DatafileBuilder(_)
+            | Error::SnapshotBuilder(_)
+            | Error::StructTypeBuilder(_)
+            | Error::PartitionSpec(_) => ErrorKind::Builder,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ErrorKind};
+
+    #[test]
+    fn error_kind() {
+        assert_eq!(
+            Error::NotFound("Field 3".to_owned()).kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(
+            Error::from("x".parse::<i32>().unwrap_err()).kind(),
+            ErrorKind::Conversion
+        );
+        assert_eq!(
+            Error::ColumnNotInSchema("a".to_owned(), "b".to_owned()).kind(),
+            ErrorKind::Schema
+        );
+    }
+}
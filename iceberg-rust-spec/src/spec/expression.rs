@@ -0,0 +1,290 @@
+/*!
+ * Bound predicates to filter table data and metadata.
+ *
+ * A [BoundPredicate] references fields by their id. Depending on the context the id refers to
+ * a column of the table schema or to a field of a partition spec.
+ *
+ * Predicates can be evaluated against lower and upper bounds of a field with
+ * [BoundPredicate::might_match]. The evaluation is inclusive, meaning that it only returns
+ * `false` if it is certain that no row within the bounds can match the predicate.
+ * [BoundPredicate::must_match] is the exclusive counterpart and only returns `true` if every row
+ * within the bounds matches the predicate.
+*/
+
+use std::mem::discriminant;
+
+use super::values::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A predicate whose terms are bound to field ids
+pub enum BoundPredicate {
+    /// Both predicates have to be true
+    And(Box<BoundPredicate>, Box<BoundPredicate>),
+    /// One of the predicates has to be true
+    Or(Box<BoundPredicate>, Box<BoundPredicate>),
+    /// The field is null
+    IsNull(i32),
+    /// The field is not null
+    NotNull(i32),
+    /// The field is equal to the value
+    Eq(i32, Value),
+    /// The field is not equal to the value
+    NotEq(i32, Value),
+    /// The field is less than the value
+    Lt(i32, Value),
+    /// The field is less than or equal to the value
+    LtEq(i32, Value),
+    /// The field is greater than the value
+    Gt(i32, Value),
+    /// The field is greater than or equal to the value
+    GtEq(i32, Value),
+    /// The field is equal to one of the values
+    In(i32, Vec<Value>),
+}
+
+#[derive(Debug, Clone, Default)]
+/// Lower and upper bounds of the values of a field
+pub struct FieldBounds<'a> {
+    /// Lower bound of the non-null values
+    pub lower: Option<&'a Value>,
+    /// Upper bound of the non-null values
+    pub upper: Option<&'a Value>,
+    /// Whether the field contains null values, if known
+    pub contains_null: Option<bool>,
+}
+
+impl BoundPredicate {
+    /// Combines two predicates with a logical and
+    pub fn and(self, other: BoundPredicate) -> BoundPredicate {
+        BoundPredicate::And(Box::new(self), Box::new(other))
+    }
+
+    /// Combines two predicates with a logical or
+    pub fn or(self, other: BoundPredicate) -> BoundPredicate {
+        BoundPredicate::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Returns the ids of all fields that are referenced by the predicate
+    pub fn field_ids(&self) -> Vec<i32> {
+        match self {
+            BoundPredicate::And(left, right) | BoundPredicate::Or(left, right) => {
+                let mut ids = left.field_ids();
+                for id in right.field_ids() {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+                ids
+            }
+            BoundPredicate::IsNull(id)
+            | BoundPredicate::NotNull(id)
+            | BoundPredicate::Eq(id, _)
+            | BoundPredicate::NotEq(id, _)
+            | BoundPredicate::Lt(id, _)
+            | BoundPredicate::LtEq(id, _)
+            | BoundPredicate::Gt(id, _)
+            | BoundPredicate::GtEq(id, _)
+            | BoundPredicate::In(id, _) => vec![*id],
+        }
+    }
+
+    /// Evaluates whether a row with field values within the given bounds might match the predicate.
+    ///
+    /// # Arguments
+    /// * `bounds` - Function that returns the bounds for a field id, or `None` if no bounds are known
+    ///
+    /// # Returns
+    /// * `false` if no row within the bounds can match the predicate
+    /// * `true` if a row might match or the bounds are not sufficient to decide
+    pub fn might_match<'a, F>(&self, bounds: &F) -> bool
+    where
+        F: Fn(i32) -> Option<FieldBounds<'a>>,
+    {
+        match self {
+            BoundPredicate::And(left, right) => {
+                left.might_match(bounds) && right.might_match(bounds)
+            }
+            BoundPredicate::Or(left, right) => {
+                left.might_match(bounds) || right.might_match(bounds)
+            }
+            BoundPredicate::IsNull(id) => bounds(*id).and_then(|x| x.contains_null).unwrap_or(true),
+            // Missing bounds don't tell whether a field only contains null values
+            BoundPredicate::NotNull(_) => true,
+            BoundPredicate::Eq(id, value) => bounds(*id).is_none_or(|x| {
+                compare(x.lower, value, |lower, value| lower <= value)
+                    && compare(x.upper, value, |upper, value| upper >= value)
+            }),
+            BoundPredicate::NotEq(id, value) => bounds(*id).is_none_or(|x| {
+                !(x.lower == Some(value)
+                    && x.upper == Some(value)
+                    && x.contains_null == Some(false))
+            }),
+            BoundPredicate::Lt(id, value) => {
+                bounds(*id).is_none_or(|x| compare(x.lower, value, |lower, value| lower < value))
+            }
+            BoundPredicate::LtEq(id, value) => {
+                bounds(*id).is_none_or(|x| compare(x.lower, value, |lower, value| lower <= value))
+            }
+            BoundPredicate::Gt(id, value) => {
+                bounds(*id).is_none_or(|x| compare(x.upper, value, |upper, value| upper > value))
+            }
+            BoundPredicate::GtEq(id, value) => {
+                bounds(*id).is_none_or(|x| compare(x.upper, value, |upper, value| upper >= value))
+            }
+            BoundPredicate::In(id, values) => bounds(*id).is_none_or(|x| {
+                values.iter().any(|value| {
+                    compare(x.lower, value, |lower, value| lower <= value)
+                        && compare(x.upper, value, |upper, value| upper >= value)
+                })
+            }),
+        }
+    }
+
+    /// Evaluates whether all rows with field values within the given bounds match the predicate.
+    ///
+    /// # Arguments
+    /// * `bounds` - Function that returns the bounds for a field id, or `None` if no bounds are known
+    ///
+    /// # Returns
+    /// * `true` if every row within the bounds matches the predicate
+    /// * `false` if a row might not match or the bounds are not sufficient to decide
+    pub fn must_match<'a, F>(&self, bounds: &F) -> bool
+    where
+        F: Fn(i32) -> Option<FieldBounds<'a>>,
+    {
+        match self {
+            BoundPredicate::And(left, right) => left.must_match(bounds) && right.must_match(bounds),
+            BoundPredicate::Or(left, right) => left.must_match(bounds) || right.must_match(bounds),
+            // Bounds only describe non-null values
+            BoundPredicate::IsNull(_) => false,
+            BoundPredicate::NotNull(id) => {
+                bounds(*id).is_some_and(|x| x.contains_null == Some(false))
+            }
+            BoundPredicate::Eq(id, value) => bounds(*id).is_some_and(|x| {
+                x.contains_null == Some(false)
+                    && compare_strict(x.lower, value, |lower, value| lower == value)
+                    && compare_strict(x.upper, value, |upper, value| upper == value)
+            }),
+            BoundPredicate::NotEq(id, value) => bounds(*id).is_some_and(|x| {
+                x.contains_null == Some(false)
+                    && (compare_strict(x.lower, value, |lower, value| lower > value)
+                        || compare_strict(x.upper, value, |upper, value| upper < value))
+            }),
+            BoundPredicate::Lt(id, value) => bounds(*id).is_some_and(|x| {
+                x.contains_null == Some(false)
+                    && compare_strict(x.upper, value, |upper, value| upper < value)
+            }),
+            BoundPredicate::LtEq(id, value) => bounds(*id).is_some_and(|x| {
+                x.contains_null == Some(false)
+                    && compare_strict(x.upper, value, |upper, value| upper <= value)
+            }),
+            BoundPredicate::Gt(id, value) => bounds(*id).is_some_and(|x| {
+                x.contains_null == Some(false)
+                    && compare_strict(x.lower, value, |lower, value| lower > value)
+            }),
+            BoundPredicate::GtEq(id, value) => bounds(*id).is_some_and(|x| {
+                x.contains_null == Some(false)
+                    && compare_strict(x.lower, value, |lower, value| lower >= value)
+            }),
+            BoundPredicate::In(id, values) => bounds(*id).is_some_and(|x| {
+                x.contains_null == Some(false)
+                    && x.lower.is_some()
+                    && x.lower == x.upper
+                    && values.iter().any(|value| x.lower == Some(value))
+            }),
+        }
+    }
+}
+
+/// Compares a bound with a value. Returns true if the bound is missing or has a different type.
+#[inline]
+fn compare(bound: Option<&Value>, value: &Value, op: impl Fn(&Value, &Value) -> bool) -> bool {
+    match bound {
+        Some(bound) if discriminant(bound) == discriminant(value) => op(bound, value),
+        _ => true,
+    }
+}
+
+/// Compares a bound with a value. Returns false if the bound is missing or has a different type.
+#[inline]
+fn compare_strict(
+    bound: Option<&Value>,
+    value: &Value,
+    op: impl Fn(&Value, &Value) -> bool,
+) -> bool {
+    match bound {
+        Some(bound) if discriminant(bound) == discriminant(value) => op(bound, value),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds<'a>(lower: &'a Value, upper: &'a Value) -> impl Fn(i32) -> Option<FieldBounds<'a>> {
+        move |id| {
+            if id == 1 {
+                Some(FieldBounds {
+                    lower: Some(lower),
+                    upper: Some(upper),
+                    contains_null: Some(false),
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn test_might_match_comparison() {
+        let (lower, upper) = (Value::Int(10), Value::Int(20));
+        let bounds = bounds(&lower, &upper);
+
+        assert!(BoundPredicate::Eq(1, Value::Int(15)).might_match(&bounds));
+        assert!(!BoundPredicate::Eq(1, Value::Int(25)).might_match(&bounds));
+        assert!(!BoundPredicate::Lt(1, Value::Int(10)).might_match(&bounds));
+        assert!(BoundPredicate::LtEq(1, Value::Int(10)).might_match(&bounds));
+        assert!(!BoundPredicate::Gt(1, Value::Int(20)).might_match(&bounds));
+        assert!(BoundPredicate::GtEq(1, Value::Int(20)).might_match(&bounds));
+        assert!(!BoundPredicate::IsNull(1).might_match(&bounds));
+        assert!(BoundPredicate::In(1, vec![Value::Int(1), Value::Int(12)]).might_match(&bounds));
+    }
+
+    #[test]
+    fn test_might_match_logical() {
+        let (lower, upper) = (Value::Int(10), Value::Int(20));
+        let bounds = bounds(&lower, &upper);
+
+        let predicate =
+            BoundPredicate::Gt(1, Value::Int(30)).and(BoundPredicate::Eq(2, Value::Int(1)));
+        assert!(!predicate.might_match(&bounds));
+
+        let predicate =
+            BoundPredicate::Gt(1, Value::Int(30)).or(BoundPredicate::Eq(2, Value::Int(1)));
+        assert!(predicate.might_match(&bounds));
+    }
+
+    #[test]
+    fn test_might_match_different_type() {
+        let (lower, upper) = (Value::Int(10), Value::Int(20));
+        let bounds = bounds(&lower, &upper);
+
+        assert!(BoundPredicate::Eq(1, Value::LongInt(25)).might_match(&bounds));
+    }
+
+    #[test]
+    fn test_must_match() {
+        let (lower, upper) = (Value::Int(10), Value::Int(20));
+        let bounds = bounds(&lower, &upper);
+
+        assert!(BoundPredicate::GtEq(1, Value::Int(10)).must_match(&bounds));
+        assert!(!BoundPredicate::Gt(1, Value::Int(10)).must_match(&bounds));
+        assert!(BoundPredicate::Lt(1, Value::Int(21)).must_match(&bounds));
+        assert!(!BoundPredicate::Eq(1, Value::Int(15)).must_match(&bounds));
+        assert!(BoundPredicate::NotEq(1, Value::Int(25)).must_match(&bounds));
+        assert!(BoundPredicate::NotNull(1).must_match(&bounds));
+        assert!(!BoundPredicate::GtEq(2, Value::Int(0)).must_match(&bounds));
+        assert!(!BoundPredicate::GtEq(1, Value::LongInt(0)).must_match(&bounds));
+    }
+}
@@ -24,6 +24,7 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 use crate::{error::Error, partition::BoundPartitionField};
 
 use super::{
+    expression::{BoundPredicate, FieldBounds},
     partition::PartitionSpec,
     schema::Schema,
     table_metadata::FormatVersion,
@@ -31,6 +32,11 @@ use super::{
     values::{Struct, Value},
 };
 
+/// Field id of the `file_path` column of position delete files
+pub static POSITION_DELETE_FILE_PATH_ID: i32 = 2147483546;
+/// Field id of the `pos` column of position delete files
+pub static POSITION_DELETE_POS_ID: i32 = 2147483545;
+
 /// Entry in manifest with the iceberg spec version 2.
 #[derive(Debug, Serialize, PartialEq, Clone, Getters, Builder)]
 #[serde(into = "ManifestEntryEnum")]
@@ -85,6 +91,14 @@ impl ManifestEntry {
     pub fn snapshot_id_mut(&mut self) -> &mut Option<i64> {
         &mut self.snapshot_id
     }
+
+    /// Returns a mutable reference to the format version of this manifest entry.
+    ///
+    /// The format version determines how the entry is serialized. Modifying this allows
+    /// writing entries of a V1 manifest into a V2 manifest after the table was upgraded.
+    pub fn format_version_mut(&mut self) -> &mut FormatVersion {
+        &mut self.format_version
+    }
 }
 
 impl ManifestEntry {
@@ -488,19 +502,21 @@ impl AvroMap<ByteBuf> {
             self.0
                 .into_iter()
                 .map(|(k, v)| {
-                    Ok((
-                        k,
-                        Value::try_from_bytes(
-                            &v,
-                            &schema
-                                .get(k as usize)
-                                .ok_or(Error::ColumnNotInSchema(
-                                    k.to_string(),
-                                    format!("{:?}", schema),
-                                ))?
-                                .field_type,
-                        )?,
-                    ))
+                    let field_type = match schema.get(k as usize) {
+                        Some(field) => field.field_type.clone(),
+                        // The bounds of position delete files are stored for their own columns
+                        None if k == POSITION_DELETE_FILE_PATH_ID => {
+                            Type::Primitive(PrimitiveType::String)
+                        }
+                        None if k == POSITION_DELETE_POS_ID => Type::Primitive(PrimitiveType::Long),
+                        None => {
+                            return Err(Error::ColumnNotInSchema(
+                                k.to_string(),
+                                format!("{:?}", schema),
+                            ))
+                        }
+                    };
+                    Ok((k, Value::try_from_bytes(&v, &field_type)?))
                 })
                 .collect::<Result<Vec<_>, Error>>()?,
         ))
@@ -516,34 +532,44 @@ impl From<HashMap<i32, Value>> for AvroMap<ByteBuf> {
 }
 
 #[derive(Debug, PartialEq, Clone, Getters, Builder)]
-#[builder(setter(prefix = "with"))]
+#[builder(setter(prefix = "with"), build_fn(validate = "Self::validate"))]
 /// DataFile found in Manifest.
 pub struct DataFile {
     ///Type of content in data file.
+    #[builder(default = "Content::Data")]
     content: Content,
     /// Full URI for the file with a FS scheme.
     file_path: String,
     /// String file format name, avro, orc or parquet
+    #[builder(default = "FileFormat::Parquet")]
     file_format: FileFormat,
     /// Partition data tuple, schema based on the partition spec output using partition field ids for the struct field ids
+    #[builder(default = "Struct::from_iter(Vec::new())")]
     partition: Struct,
     /// Number of records in this file
     record_count: i64,
     /// Total file size in bytes
     file_size_in_bytes: i64,
     /// Map from column id to total size on disk
+    #[builder(default)]
     column_sizes: Option<AvroMap<i64>>,
     /// Map from column id to number of values in the column (including null and NaN values)
+    #[builder(default)]
     value_counts: Option<AvroMap<i64>>,
     /// Map from column id to number of null values
+    #[builder(default)]
     null_value_counts: Option<AvroMap<i64>>,
     /// Map from column id to number of NaN values
+    #[builder(default)]
     nan_value_counts: Option<AvroMap<i64>>,
     /// Map from column id to number of distinct values in the column.
+    #[builder(default)]
     distinct_counts: Option<AvroMap<i64>>,
     /// Map from column id to lower bound in the column
+    #[builder(default)]
     lower_bounds: Option<HashMap<i32, Value>>,
     /// Map from column id to upper bound in the column
+    #[builder(default)]
     upper_bounds: Option<HashMap<i32, Value>>,
     /// Implementation specific key metadata for encryption
     #[builder(default)]
@@ -559,12 +585,121 @@ pub struct DataFile {
     sort_order_id: Option<i32>,
 }
 
+impl DataFileBuilder {
+    /// Checks the counts, the field ids of the column statistics and the equality ids of an equality delete file
+    fn validate(&self) -> Result<(), String> {
+        if let Some(record_count) = self.record_count {
+            if record_count < 0 {
+                return Err(format!("Negative record count {record_count}"));
+            }
+        }
+        if let Some(file_size_in_bytes) = self.file_size_in_bytes {
+            if file_size_in_bytes < 0 {
+                return Err(format!("Negative file size {file_size_in_bytes}"));
+            }
+        }
+
+        let counts = [
+            ("column sizes", &self.column_sizes),
+            ("value counts", &self.value_counts),
+            ("null value counts", &self.null_value_counts),
+            ("nan value counts", &self.nan_value_counts),
+            ("distinct counts", &self.distinct_counts),
+        ];
+        for (name, map) in counts {
+            if let Some(Some(map)) = map {
+                if let Some(id) = map.keys().find(|id| **id <= 0) {
+                    return Err(format!("Invalid field id {id} in the {name}"));
+                }
+            }
+        }
+
+        let lower_bounds = self.lower_bounds.as_ref().and_then(Option::as_ref);
+        let upper_bounds = self.upper_bounds.as_ref().and_then(Option::as_ref);
+        for (name, bounds) in [
+            ("lower bounds", lower_bounds),
+            ("upper bounds", upper_bounds),
+        ] {
+            if let Some(id) = bounds.and_then(|bounds| bounds.keys().find(|id| **id <= 0)) {
+                return Err(format!("Invalid field id {id} in the {name}"));
+            }
+        }
+        if let (Some(lower_bounds), Some(upper_bounds)) = (lower_bounds, upper_bounds) {
+            for (id, lower) in lower_bounds {
+                let Some(upper) = upper_bounds.get(id) else {
+                    continue;
+                };
+                if std::mem::discriminant(lower) != std::mem::discriminant(upper) {
+                    return Err(format!(
+                        "Lower and upper bound of field {id} have different types"
+                    ));
+                }
+                if lower > upper {
+                    return Err(format!(
+                        "Lower bound {lower} of field {id} is greater than the upper bound {upper}"
+                    ));
+                }
+            }
+        }
+
+        if let Some(Content::EqualityDeletes) = self.content {
+            if self
+                .equality_ids
+                .as_ref()
+                .and_then(Option::as_ref)
+                .is_none_or(Vec::is_empty)
+            {
+                return Err("Equality delete file without equality ids".to_owned());
+            }
+        }
+        Ok(())
+    }
+}
+
 impl DataFile {
     pub fn builder() -> DataFileBuilder {
         DataFileBuilder::default()
     }
 }
 
+impl DataFile {
+    /// Returns the column statistics of the data file for a field id
+    ///
+    /// The lower and upper bounds are decoded according to the column type when the manifest is read.
+    ///
+    /// # Returns
+    /// * `Some(FieldBounds)` if the data file has bounds or a null value count for the column
+    /// * `None` if no statistics are known for the column
+    pub fn field_bounds(&self, id: i32) -> Option<FieldBounds<'_>> {
+        let lower = self.lower_bounds.as_ref().and_then(|x| x.get(&id));
+        let upper = self.upper_bounds.as_ref().and_then(|x| x.get(&id));
+        let contains_null = self
+            .null_value_counts
+            .as_ref()
+            .and_then(|x| x.get(&id))
+            .map(|x| *x > 0);
+        if lower.is_none() && upper.is_none() && contains_null.is_none() {
+            return None;
+        }
+        Some(FieldBounds {
+            lower,
+            upper,
+            contains_null,
+        })
+    }
+
+    /// Evaluates whether rows of the data file might match a predicate on the table columns
+    ///
+    /// Uses the lower bounds, upper bounds and null value counts of the columns.
+    ///
+    /// # Returns
+    /// * `false` if the column statistics prove that no row of the data file matches the predicate
+    /// * `true` if a row might match or the statistics are not sufficient to decide
+    pub fn might_match(&self, predicate: &BoundPredicate) -> bool {
+        predicate.might_match(&|id| self.field_bounds(id))
+    }
+}
+
 impl DataFile {
     pub(crate) fn try_from_v2(
         value: DataFileV2,
@@ -1651,4 +1786,79 @@ mod tests {
             assert_eq!(partition_values, result);
         }
     }
+
+    #[test]
+    fn test_data_file_might_match() {
+        let data_file = DataFile::builder()
+            .with_content(Content::Data)
+            .with_file_path("/data/file.parquet".to_string())
+            .with_file_format(FileFormat::Parquet)
+            .with_partition(Struct::from_iter(vec![]))
+            .with_record_count(10)
+            .with_file_size_in_bytes(1200)
+            .with_column_sizes(None)
+            .with_value_counts(None)
+            .with_null_value_counts(Some(AvroMap(HashMap::from_iter(vec![(1, 0), (2, 3)]))))
+            .with_nan_value_counts(None)
+            .with_distinct_counts(None)
+            .with_lower_bounds(Some(HashMap::from_iter(vec![(1, Value::LongInt(10))])))
+            .with_upper_bounds(Some(HashMap::from_iter(vec![(1, Value::LongInt(20))])))
+            .build()
+            .unwrap();
+
+        assert!(data_file.might_match(&BoundPredicate::Eq(1, Value::LongInt(15))));
+        assert!(!data_file.might_match(&BoundPredicate::Gt(1, Value::LongInt(20))));
+        assert!(!data_file.might_match(&BoundPredicate::Lt(1, Value::LongInt(10))));
+        assert!(!data_file.might_match(&BoundPredicate::IsNull(1)));
+        assert!(data_file.might_match(&BoundPredicate::IsNull(2)));
+        assert!(data_file.might_match(&BoundPredicate::Eq(3, Value::LongInt(0))));
+    }
+
+    #[test]
+    fn data_file_builder() {
+        let data_file = DataFile::builder()
+            .with_file_path("/test/data.parquet".to_owned())
+            .with_record_count(4)
+            .with_file_size_in_bytes(1200)
+            .with_lower_bounds(Some(HashMap::from_iter(vec![(1, Value::Int(1))])))
+            .with_upper_bounds(Some(HashMap::from_iter(vec![(1, Value::Int(7))])))
+            .build()
+            .unwrap();
+
+        assert_eq!(data_file.content(), &Content::Data);
+        assert_eq!(data_file.file_format(), &FileFormat::Parquet);
+        assert_eq!(data_file.column_sizes(), &None);
+        assert!(data_file.partition().fields.is_empty());
+
+        assert!(DataFile::builder()
+            .with_record_count(4)
+            .with_file_size_in_bytes(1200)
+            .build()
+            .is_err());
+
+        assert!(DataFile::builder()
+            .with_file_path("/test/data.parquet".to_owned())
+            .with_record_count(4)
+            .with_file_size_in_bytes(1200)
+            .with_lower_bounds(Some(HashMap::from_iter(vec![(0, Value::Int(1))])))
+            .build()
+            .is_err());
+
+        assert!(DataFile::builder()
+            .with_file_path("/test/data.parquet".to_owned())
+            .with_record_count(4)
+            .with_file_size_in_bytes(1200)
+            .with_lower_bounds(Some(HashMap::from_iter(vec![(1, Value::Int(8))])))
+            .with_upper_bounds(Some(HashMap::from_iter(vec![(1, Value::Int(7))])))
+            .build()
+            .is_err());
+
+        assert!(DataFile::builder()
+            .with_content(Content::EqualityDeletes)
+            .with_file_path("/test/delete.parquet".to_owned())
+            .with_record_count(4)
+            .with_file_size_in_bytes(1200)
+            .build()
+            .is_err());
+    }
 }
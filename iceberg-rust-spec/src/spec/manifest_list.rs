@@ -661,13 +661,22 @@ pub fn manifest_list_schema_v2() -> &'static AvroSchema {
     })
 }
 
-/// Convert an avro value result to a manifest list version according to the provided format version
+/// Convert an avro value result to a manifest list entry according to the format version of the manifest list file.
+/// Entries of V1 manifest lists are upgraded to the format version of the table.
 pub fn avro_value_to_manifest_list_entry(
     value: Result<AvroValue, apache_avro::Error>,
     table_metadata: &TableMetadata,
 ) -> Result<ManifestListEntry, Error> {
     let entry = value?;
-    match table_metadata.format_version {
+    // Manifest lists that were written before the table was upgraded to V2 keep the V1 layout
+    let format_version = match &entry {
+        AvroValue::Record(fields) if fields.iter().any(|(name, _)| name == "sequence_number") => {
+            FormatVersion::V2
+        }
+        AvroValue::Record(_) => FormatVersion::V1,
+        _ => table_metadata.format_version,
+    };
+    let mut entry = match format_version {
         FormatVersion::V1 => ManifestListEntry::try_from_v1(
             apache_avro::from_value::<_serde::ManifestListEntryV1>(&entry)?,
             table_metadata,
@@ -676,7 +685,9 @@ pub fn avro_value_to_manifest_list_entry(
             apache_avro::from_value::<_serde::ManifestListEntryV2>(&entry)?,
             table_metadata,
         ),
-    }
+    }?;
+    entry.format_version = table_metadata.format_version;
+    Ok(entry)
 }
 
 #[cfg(test)]
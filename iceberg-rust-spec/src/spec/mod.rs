@@ -8,11 +8,13 @@
 //! - Partition specifications
 //! - Sort orders
 //! - Data types and values
+//! - Predicates for filtering table data
 //! - View and materialized view metadata
 //!
 //! Each submodule implements a specific part of the specification, providing
 //! serialization/deserialization and validation logic.
 
+pub mod expression;
 pub mod identifier;
 pub mod manifest;
 pub mod manifest_list;
@@ -24,7 +24,12 @@ use serde::{
 
 use derive_builder::Builder;
 
-use crate::{error::Error, types::StructField};
+use crate::{
+    error::Error,
+    expression::BoundPredicate,
+    types::StructField,
+    values::{Struct, Value},
+};
 
 use super::types::{StructType, Type};
 
@@ -197,6 +202,119 @@ impl PartitionSpec {
             })
             .collect::<Result<Vec<_>, Error>>()
     }
+    /// Computes the partition values of a row by applying the partition transforms to the source columns
+    ///
+    /// # Arguments
+    /// * `schema` - The table schema, used to look up the source columns
+    /// * `row` - The values of the row keyed by column name
+    ///
+    /// # Returns
+    /// * The partition values keyed by partition field name. Null source values and the
+    ///   void transform produce null partition values.
+    pub fn partition_value(&self, schema: &StructType, row: &Struct) -> Result<Struct, Error> {
+        self.fields
+            .iter()
+            .map(|field| {
+                let source = schema
+                    .get(field.source_id as usize)
+                    .ok_or(Error::NotFound(format!("Schema field {}", field.name)))?;
+                let value = row
+                    .get(&source.name)
+                    .ok_or(Error::NotFound(format!("Column {} in row", source.name)))?;
+                let value = match (value, &field.transform) {
+                    (None, _) | (_, Transform::Void) => None,
+                    (Some(value), transform) => Some(value.transform(transform)?),
+                };
+                Ok((field.name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Projects a predicate on table columns to an inclusive predicate on the partition fields
+    ///
+    /// Every row that matches the predicate is stored in a partition that matches the projected
+    /// predicate. This allows to prune manifests and data files by their partition values while
+    /// the original predicate remains the residual filter for the rows of the remaining files.
+    ///
+    /// # Arguments
+    /// * `predicate` - The predicate bound to the field ids of the table schema
+    ///
+    /// # Returns
+    /// * The predicate bound to the partition field ids, or `None` if the predicate can't be
+    ///   used to prune partitions
+    pub fn project_predicate(&self, predicate: &BoundPredicate) -> Option<BoundPredicate> {
+        match predicate {
+            BoundPredicate::And(left, right) => {
+                match (self.project_predicate(left), self.project_predicate(right)) {
+                    (Some(left), Some(right)) => Some(left.and(right)),
+                    (left, right) => left.or(right),
+                }
+            }
+            BoundPredicate::Or(left, right) => Some(
+                self.project_predicate(left)?
+                    .or(self.project_predicate(right)?),
+            ),
+            _ => {
+                let source_id = *predicate.field_ids().first()?;
+                self.fields
+                    .iter()
+                    .filter(|field| field.source_id == source_id)
+                    .filter_map(|field| project_term(field, predicate))
+                    .reduce(BoundPredicate::and)
+            }
+        }
+    }
+}
+
+/// Projects a predicate on a single column to the partition field
+fn project_term(field: &PartitionField, predicate: &BoundPredicate) -> Option<BoundPredicate> {
+    let id = field.field_id;
+    let transform = &field.transform;
+    let apply = |value: &Value| value.transform(transform).ok();
+    match (transform, predicate) {
+        (Transform::Void, _) => None,
+        (_, BoundPredicate::IsNull(_)) => Some(BoundPredicate::IsNull(id)),
+        (_, BoundPredicate::NotNull(_)) => Some(BoundPredicate::NotNull(id)),
+        (Transform::Identity, BoundPredicate::NotEq(_, value)) => {
+            Some(BoundPredicate::NotEq(id, value.clone()))
+        }
+        (Transform::Identity, BoundPredicate::Lt(_, value)) => {
+            Some(BoundPredicate::Lt(id, value.clone()))
+        }
+        (Transform::Identity, BoundPredicate::Gt(_, value)) => {
+            Some(BoundPredicate::Gt(id, value.clone()))
+        }
+        (_, BoundPredicate::Eq(_, value)) => Some(BoundPredicate::Eq(id, apply(value)?)),
+        (_, BoundPredicate::In(_, values)) => Some(BoundPredicate::In(
+            id,
+            values.iter().map(apply).collect::<Option<_>>()?,
+        )),
+        (Transform::Bucket(_), _) | (_, BoundPredicate::NotEq(..)) => None,
+        // x < v is equivalent to x <= v - 1 for integers, which results in a tighter bound
+        (Transform::Truncate(_), BoundPredicate::Lt(_, value)) => {
+            let value = match value {
+                Value::Int(x) => Value::Int(x.checked_sub(1)?),
+                Value::LongInt(x) => Value::LongInt(x.checked_sub(1)?),
+                x => x.clone(),
+            };
+            Some(BoundPredicate::LtEq(id, apply(&value)?))
+        }
+        (Transform::Truncate(_), BoundPredicate::Gt(_, value)) => {
+            let value = match value {
+                Value::Int(x) => Value::Int(x.checked_add(1)?),
+                Value::LongInt(x) => Value::LongInt(x.checked_add(1)?),
+                x => x.clone(),
+            };
+            Some(BoundPredicate::GtEq(id, apply(&value)?))
+        }
+        (_, BoundPredicate::Lt(_, value)) | (_, BoundPredicate::LtEq(_, value)) => {
+            Some(BoundPredicate::LtEq(id, apply(value)?))
+        }
+        (_, BoundPredicate::Gt(_, value)) | (_, BoundPredicate::GtEq(_, value)) => {
+            Some(BoundPredicate::GtEq(id, apply(value)?))
+        }
+        (_, BoundPredicate::And(..)) | (_, BoundPredicate::Or(..)) => None,
+    }
 }
 
 impl fmt::Display for PartitionSpec {
@@ -322,4 +440,90 @@ mod tests {
         assert_eq!("id_truncate", partition_spec.fields[2].name);
         assert_eq!(Transform::Truncate(4), partition_spec.fields[2].transform);
     }
+
+    #[test]
+    fn partition_value() {
+        use crate::types::PrimitiveType;
+
+        let schema = StructType::new(vec![
+            StructField::new(1, "id", true, Type::Primitive(PrimitiveType::Long), None),
+            StructField::new(
+                2,
+                "ts",
+                false,
+                Type::Primitive(PrimitiveType::Timestamp),
+                None,
+            ),
+            StructField::new(
+                3,
+                "name",
+                false,
+                Type::Primitive(PrimitiveType::String),
+                None,
+            ),
+        ]);
+        let spec = PartitionSpec::builder()
+            .with_partition_field(PartitionField::new(2, 1000, "ts_day", Transform::Day))
+            .with_partition_field(PartitionField::new(
+                3,
+                1001,
+                "name_trunc",
+                Transform::Truncate(2),
+            ))
+            .with_partition_field(PartitionField::new(1, 1002, "id_void", Transform::Void))
+            .build()
+            .unwrap();
+        let row = Struct::from_iter(vec![
+            ("id".to_owned(), Some(Value::LongInt(7))),
+            // 2024-01-02 03:04:05
+            (
+                "ts".to_owned(),
+                Some(Value::Timestamp(1_704_164_645_000_000)),
+            ),
+            ("name".to_owned(), None),
+        ]);
+
+        let partition = spec.partition_value(&schema, &row).unwrap();
+        assert_eq!(partition.get("ts_day"), Some(&Some(Value::Int(19724))));
+        assert_eq!(partition.get("name_trunc"), Some(&None));
+        assert_eq!(partition.get("id_void"), Some(&None));
+    }
+
+    #[test]
+    fn project_predicate() {
+        let spec = PartitionSpec::builder()
+            .with_partition_field(PartitionField::new(2, 1000, "ts_day", Transform::Day))
+            .with_partition_field(PartitionField::new(
+                1,
+                1001,
+                "id_bucket",
+                Transform::Bucket(4),
+            ))
+            .with_partition_field(PartitionField::new(
+                3,
+                1002,
+                "num_trunc",
+                Transform::Truncate(10),
+            ))
+            .build()
+            .unwrap();
+
+        // 2024-01-02 03:04:05
+        let predicate = BoundPredicate::GtEq(2, Value::Timestamp(1_704_164_645_000_000))
+            .and(BoundPredicate::Gt(1, Value::LongInt(3)));
+        assert_eq!(
+            spec.project_predicate(&predicate),
+            Some(BoundPredicate::GtEq(1000, Value::Int(19724)))
+        );
+
+        let predicate = BoundPredicate::Lt(3, Value::Int(20));
+        assert_eq!(
+            spec.project_predicate(&predicate),
+            Some(BoundPredicate::LtEq(1002, Value::Int(10)))
+        );
+
+        let predicate =
+            BoundPredicate::Eq(1, Value::LongInt(3)).or(BoundPredicate::NotEq(3, Value::Int(5)));
+        assert_eq!(spec.project_predicate(&predicate), None);
+    }
 }
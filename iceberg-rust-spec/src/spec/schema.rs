@@ -17,7 +17,7 @@
 
 use std::{fmt, ops::Deref, str};
 
-use super::types::{StructField, StructType, StructTypeBuilder};
+use super::types::{StructField, StructType, StructTypeBuilder, Type};
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
@@ -82,14 +82,22 @@ impl Schema {
 
     /// Creates a new Schema containing only the specified field IDs
     ///
+    /// Nested fields of structs can be selected by their ID. Their parent structs are kept
+    /// with only the selected children. Selecting a struct keeps all of its children. Lists
+    /// and maps are kept as a whole if any of their nested fields is selected.
+    ///
     /// # Arguments
     /// * `ids` - Array of field IDs to include in the projected schema
     ///
     /// # Returns
     /// * A new Schema containing only the specified fields, maintaining the original
     ///   schema ID and any identifier fields that were included in the projection
-    pub fn project(&self, ids: &[i32]) -> Schema {
-        Schema {
+    /// * An error if one of the IDs is not part of the schema
+    pub fn project(&self, ids: &[i32]) -> Result<Schema, Error> {
+        if let Some(id) = ids.iter().find(|id| !contains_id(&self.fields, **id)) {
+            return Err(Error::NotFound(format!("Field {id} in schema")));
+        }
+        Ok(Schema {
             schema_id: self.schema_id,
             identifier_field_ids: self.identifier_field_ids.as_ref().map(|x| {
                 x.iter()
@@ -97,13 +105,57 @@ impl Schema {
                     .map(ToOwned::to_owned)
                     .collect()
             }),
-            fields: StructType::new(
-                self.fields()
-                    .iter()
-                    .filter(|x| ids.contains(&x.id))
-                    .map(ToOwned::to_owned)
-                    .collect(),
-            ),
+            fields: project_struct(&self.fields, ids),
+        })
+    }
+}
+
+/// Keeps the fields of the struct that are selected or contain selected nested fields
+fn project_struct(struct_type: &StructType, ids: &[i32]) -> StructType {
+    StructType::new(
+        struct_type
+            .iter()
+            .filter_map(|field| {
+                if ids.contains(&field.id) {
+                    return Some(field.clone());
+                }
+                match &field.field_type {
+                    Type::Struct(nested) => {
+                        let nested = project_struct(nested, ids);
+                        (!nested.is_empty()).then(|| StructField {
+                            field_type: Type::Struct(nested),
+                            ..field.clone()
+                        })
+                    }
+                    Type::List(_) | Type::Map(_) => ids
+                        .iter()
+                        .any(|id| type_contains_id(&field.field_type, *id))
+                        .then(|| field.clone()),
+                    Type::Primitive(_) => None,
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Checks whether the struct or one of its nested types has a field with the given ID
+fn contains_id(struct_type: &StructType, id: i32) -> bool {
+    struct_type
+        .iter()
+        .any(|field| field.id == id || type_contains_id(&field.field_type, id))
+}
+
+/// Checks whether the type has a nested field with the given ID
+fn type_contains_id(field_type: &Type, id: i32) -> bool {
+    match field_type {
+        Type::Primitive(_) => false,
+        Type::Struct(nested) => contains_id(nested, id),
+        Type::List(list) => list.element_id == id || type_contains_id(&list.element, id),
+        Type::Map(map) => {
+            map.key_id == id
+                || map.value_id == id
+                || type_contains_id(&map.key, id)
+                || type_contains_id(&map.value, id)
         }
     }
 }
@@ -310,4 +362,58 @@ mod tests {
         assert_eq!(2, result.fields[1].id);
         assert!(!result.fields[1].required);
     }
+
+    #[test]
+    fn project_nested() {
+        let schema = Schema::builder()
+            .with_schema_id(1)
+            .with_struct_field(StructField::new(
+                1,
+                "id",
+                true,
+                Type::Primitive(PrimitiveType::Long),
+                None,
+            ))
+            .with_struct_field(StructField::new(
+                2,
+                "location",
+                false,
+                Type::Struct(StructType::new(vec![
+                    StructField::new(3, "lat", true, Type::Primitive(PrimitiveType::Double), None),
+                    StructField::new(4, "lon", true, Type::Primitive(PrimitiveType::Double), None),
+                ])),
+                None,
+            ))
+            .with_struct_field(StructField::new(
+                5,
+                "name",
+                false,
+                Type::Primitive(PrimitiveType::String),
+                None,
+            ))
+            .build()
+            .unwrap();
+
+        let projected = schema.project(&[4, 1]).unwrap();
+        assert_eq!(projected.fields().len(), 2);
+        assert_eq!(projected.fields()[0].id, 1);
+        assert_eq!(
+            projected.fields()[1].field_type,
+            Type::Struct(StructType::new(vec![StructField::new(
+                4,
+                "lon",
+                true,
+                Type::Primitive(PrimitiveType::Double),
+                None
+            )]))
+        );
+
+        let projected = schema.project(&[2]).unwrap();
+        assert_eq!(
+            projected.fields()[0].field_type,
+            schema.fields()[1].field_type
+        );
+
+        assert!(schema.project(&[6]).is_err());
+    }
 }
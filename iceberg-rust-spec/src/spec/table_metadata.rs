@@ -45,8 +45,13 @@ static DEFAULT_SPEC_ID: i32 = 0;
 
 pub const WRITE_PARQUET_COMPRESSION_CODEC: &str = "write.parquet.compression-codec";
 pub const WRITE_PARQUET_COMPRESSION_LEVEL: &str = "write.parquet.compression-level";
+pub const WRITE_PARQUET_ROW_GROUP_SIZE_BYTES: &str = "write.parquet.row-group-size-bytes";
+pub const WRITE_PARQUET_PAGE_SIZE_BYTES: &str = "write.parquet.page-size-bytes";
 pub const WRITE_OBJECT_STORAGE_ENABLED: &str = "write.object-storage.enabled";
 pub const WRITE_DATA_PATH: &str = "write.data.path";
+pub const WRITE_TARGET_FILE_SIZE_BYTES: &str = "write.target-file-size-bytes";
+pub const MANIFEST_TARGET_SIZE_BYTES: &str = "commit.manifest.target-size-bytes";
+pub const MANIFEST_MIN_MERGE_COUNT: &str = "commit.manifest.min-count-to-merge";
 
 pub use _serde::{TableMetadataV1, TableMetadataV2};
 
@@ -325,6 +330,118 @@ impl TableMetadata {
             .map(|x| *x.sequence_number())
     }
 
+    /// Checks whether a snapshot is an ancestor of another snapshot by following the parent snapshot ids
+    ///
+    /// # Arguments
+    /// * `ancestor_id` - The ID of the potential ancestor
+    /// * `snapshot_id` - The ID of the snapshot whose ancestry is checked
+    ///
+    /// # Returns
+    /// * `bool` - True if `ancestor_id` equals `snapshot_id` or is one of its ancestors
+    pub fn is_ancestor_of(&self, ancestor_id: i64, snapshot_id: i64) -> bool {
+        let mut current = Some(snapshot_id);
+        while let Some(id) = current {
+            if id == ancestor_id {
+                return true;
+            }
+            current = self
+                .snapshots
+                .get(&id)
+                .and_then(|x| *x.parent_snapshot_id());
+        }
+        false
+    }
+
+    /// Returns a compact, human readable summary of the table metadata, similar to a `DESCRIBE TABLE`
+    ///
+    /// The summary contains the format version, the location, the current schema id, the default
+    /// partition spec and sort order, the number of snapshots and the current snapshot id.
+    /// Partition and sort fields are shown with the names of their source columns in the current schema.
+    ///
+    /// # Returns
+    /// * `String` - One line per property
+    pub fn describe(&self) -> String {
+        let schema = self.schemas.get(&self.current_schema_id);
+        let column = |source_id: i32| {
+            schema
+                .and_then(|schema| schema.get(source_id as usize))
+                .map(|field| field.name.clone())
+                .unwrap_or_else(|| source_id.to_string())
+        };
+
+        let partition_spec = match self.partition_specs.get(&self.default_spec_id) {
+            Some(spec) if !spec.fields().is_empty() => spec
+                .fields()
+                .iter()
+                .map(|field| {
+                    format!(
+                        "{}({}) as {}",
+                        field.transform(),
+                        column(*field.source_id()),
+                        field.name()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => "unpartitioned".to_owned(),
+        };
+
+        let sort_order = match self.sort_orders.get(&self.default_sort_order_id) {
+            Some(order) if !order.fields.is_empty() => order
+                .fields
+                .iter()
+                .map(|field| {
+                    let direction = match field.direction {
+                        sort::SortDirection::Ascending => "asc",
+                        sort::SortDirection::Descending => "desc",
+                    };
+                    let null_order = match field.null_order {
+                        sort::NullOrder::First => "nulls first",
+                        sort::NullOrder::Last => "nulls last",
+                    };
+                    format!(
+                        "{}({}) {} {}",
+                        field.transform,
+                        column(field.source_id),
+                        direction,
+                        null_order
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => "unsorted".to_owned(),
+        };
+
+        let format_version = match self.format_version {
+            FormatVersion::V1 => 1,
+            FormatVersion::V2 => 2,
+        };
+
+        let current_snapshot_id = self
+            .current_snapshot_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "none".to_owned());
+
+        format!(
+            "format version: {}\n\
+             location: {}\n\
+             current schema id: {}\n\
+             partition spec {}: {}\n\
+             sort order {}: {}\n\
+             snapshots: {}\n\
+             current snapshot id: {}",
+            format_version,
+            self.location,
+            self.current_schema_id,
+            self.default_spec_id,
+            partition_spec,
+            self.default_sort_order_id,
+            sort_order,
+            self.snapshots.len(),
+            current_snapshot_id
+        )
+    }
+
     pub fn as_ref(&self) -> TabularMetadataRef {
         TabularMetadataRef::Table(self)
     }
@@ -1433,4 +1550,80 @@ mod tests {
             "data did not match any variant of untagged enum TableMetadataEnum"
         )
     }
+
+    #[test]
+    fn test_describe() {
+        let data = r#"
+            {
+                "format-version" : 2,
+                "table-uuid": "fb072c92-a02b-11e9-ae9c-1bb7bc9eca94",
+                "location": "s3://b/wh/data.db/table",
+                "last-sequence-number" : 1,
+                "last-updated-ms": 1515100955770,
+                "last-column-id": 2,
+                "schemas": [
+                    {
+                        "schema-id" : 1,
+                        "type" : "struct",
+                        "fields" :[
+                            {
+                                "id": 1,
+                                "name": "id",
+                                "required": true,
+                                "type": "long"
+                            },
+                            {
+                                "id": 2,
+                                "name": "ts",
+                                "required": false,
+                                "type": "timestamp"
+                            }
+                        ]
+                    }
+                ],
+                "current-schema-id" : 1,
+                "partition-specs": [
+                    {
+                        "spec-id": 1,
+                        "fields": [
+                            {
+                                "source-id": 2,
+                                "field-id": 1000,
+                                "name": "ts_day",
+                                "transform": "day"
+                            }
+                        ]
+                    }
+                ],
+                "default-spec-id": 1,
+                "last-partition-id": 1000,
+                "properties": {},
+                "sort-orders": [
+                    {
+                        "order-id": 1,
+                        "fields": [
+                            {
+                                "source-id": 1,
+                                "transform": "identity",
+                                "direction": "desc",
+                                "null-order": "nulls-last"
+                            }
+                        ]
+                    }
+                ],
+                "default-sort-order-id": 1
+            }
+        "#;
+        let metadata: TableMetadata = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            metadata.describe(),
+            "format version: 2\n\
+             location: s3://b/wh/data.db/table\n\
+             current schema id: 1\n\
+             partition spec 1: day(ts) as ts_day\n\
+             sort order 1: identity(id) desc nulls last\n\
+             snapshots: 0\n\
+             current snapshot id: none"
+        );
+    }
 }
@@ -358,17 +358,38 @@ impl Value {
         match transform {
             Transform::Identity => Ok(self.clone()),
             Transform::Bucket(n) => {
-                let mut bytes = Cursor::new(<Value as Into<ByteBuf>>::into(self.clone()));
-                let hash = murmur3::murmur3_32(&mut bytes, 0).unwrap();
-                Ok(Value::Int((hash % n) as i32))
+                // Integer types are hashed as longs and decimals by their unscaled value
+                let bytes = match self {
+                    Value::Int(x) | Value::Date(x) => (*x as i64).to_le_bytes().to_vec(),
+                    Value::Decimal(x) => {
+                        let bytes = x.mantissa().to_be_bytes();
+                        // Minimal two's complement representation
+                        let start = (0..bytes.len() - 1)
+                            .find(|i| {
+                                !((bytes[*i] == 0x00 && bytes[*i + 1] & 0x80 == 0)
+                                    || (bytes[*i] == 0xFF && bytes[*i + 1] & 0x80 != 0))
+                            })
+                            .unwrap_or(bytes.len() - 1);
+                        bytes[start..].to_vec()
+                    }
+                    x => <Value as Into<ByteBuf>>::into(x.clone()).into_vec(),
+                };
+                let hash = murmur3::murmur3_32(&mut Cursor::new(bytes), 0).unwrap();
+                Ok(Value::Int(((hash & i32::MAX as u32) % n) as i32))
             }
             Transform::Truncate(w) => match self {
                 Value::Int(i) => Ok(Value::Int(i - i.rem_euclid(*w as i32))),
                 Value::LongInt(i) => Ok(Value::LongInt(i - i.rem_euclid(*w as i64))),
-                Value::String(s) => {
-                    let mut s = s.clone();
-                    s.truncate(*w as usize);
-                    Ok(Value::String(s))
+                Value::String(s) => Ok(Value::String(s.chars().take(*w as usize).collect())),
+                Value::Binary(b) => {
+                    Ok(Value::Binary(b.iter().take(*w as usize).copied().collect()))
+                }
+                Value::Decimal(d) => {
+                    let unscaled = d.mantissa();
+                    Ok(Value::Decimal(Decimal::from_i128_with_scale(
+                        unscaled - unscaled.rem_euclid(*w as i128),
+                        d.scale(),
+                    )))
                 }
                 _ => Err(Error::NotSupported(
                     "Datatype for truncate partition transform.".to_string(),
@@ -1376,6 +1397,32 @@ mod tests {
         assert!(matches!(result, Value::Int(_)));
     }
 
+    #[test]
+    fn test_transform_bucket_hash() {
+        // Hash values from the Iceberg specification
+        let n = i32::MAX as u32;
+        assert_eq!(
+            Value::Int(34).transform(&Transform::Bucket(n)).unwrap(),
+            Value::Int(2017239379)
+        );
+        assert_eq!(
+            Value::LongInt(34).transform(&Transform::Bucket(n)).unwrap(),
+            Value::Int(2017239379)
+        );
+        assert_eq!(
+            Value::String("iceberg".to_string())
+                .transform(&Transform::Bucket(n))
+                .unwrap(),
+            Value::Int(1210000089)
+        );
+        assert_eq!(
+            Value::Decimal(Decimal::from_str_exact("14.20").unwrap())
+                .transform(&Transform::Bucket(n))
+                .unwrap(),
+            Value::Int(-500754589 & i32::MAX)
+        );
+    }
+
     #[test]
     fn test_transform_truncate_int() {
         let value = Value::Int(42);
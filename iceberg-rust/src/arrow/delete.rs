@@ -0,0 +1,717 @@
+//! Writers for the delete files of row level deletes
+//!
+//! Delete files mark rows of existing data files as deleted without rewriting the data files.
+//! Readers apply them when a table is read, which makes deletes and updates cheap for tables
+//! that are read with merge-on-read.
+//!
+//! - [`PositionDeleteWriter`]: Writes position delete files that reference rows by the path of
+//!   their data file and their position in the file
+//! - [`EqualityDeleteWriter`]: Writes equality delete files that reference rows by the values
+//!   of a set of columns
+//!
+//! The written files are committed with
+//! [`TableTransaction::row_delta`](crate::table::transaction::TableTransaction::row_delta).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{Int64Array, StringArray},
+    datatypes::Schema as ArrowSchema,
+    record_batch::RecordBatch,
+};
+use futures::{future, stream, TryStreamExt};
+use iceberg_rust_spec::{
+    spec::{
+        manifest::{Content, DataFile, Status},
+        schema::Schema,
+        types::{PrimitiveType, StructField, Type},
+        values::{Struct, Value},
+    },
+    table_metadata::{WRITE_DATA_PATH, WRITE_OBJECT_STORAGE_ENABLED},
+};
+
+pub use iceberg_rust_spec::spec::manifest::{POSITION_DELETE_FILE_PATH_ID, POSITION_DELETE_POS_ID};
+
+use crate::{
+    error::Error, file_format::parquet::ParquetProperties, object_store::Bucket, table::Table,
+    util::partition_struct_to_vec,
+};
+
+use super::write::{
+    create_arrow_writer, generate_partition_path, write_equality_deletes_parquet_partitioned,
+};
+
+/// Returns the schema of position delete files
+///
+/// The `file_path` column contains the full path of the data file and the `pos` column the
+/// position of the deleted row in the data file, starting at 0.
+pub fn position_delete_schema() -> Result<Schema, Error> {
+    Schema::builder()
+        .with_struct_field(StructField {
+            id: POSITION_DELETE_FILE_PATH_ID,
+            name: "file_path".to_owned(),
+            required: true,
+            field_type: Type::Primitive(PrimitiveType::String),
+            doc: Some("Path of a file in which a deleted row is stored".to_owned()),
+        })
+        .with_struct_field(StructField {
+            id: POSITION_DELETE_POS_ID,
+            name: "pos".to_owned(),
+            required: true,
+            field_type: Type::Primitive(PrimitiveType::Long),
+            doc: Some("Ordinal position of a deleted row in the data file".to_owned()),
+        })
+        .build()
+        .map_err(Error::from)
+}
+
+/// Writes position delete files for rows of the data files of a table
+///
+/// The deleted rows are collected in memory. [`PositionDeleteWriter::finish`] writes one
+/// delete file per partition, because a position delete file may only reference data files of
+/// its own partition. The rows of every file are sorted by `file_path` and `pos` as required by
+/// the spec.
+///
+/// # Example
+/// ```no_run
+/// # use iceberg_rust::{arrow::delete::PositionDeleteWriter, table::Table, error::Error};
+/// # async fn example(table: &mut Table, file_path: &str) -> Result<(), Error> {
+/// let mut writer = PositionDeleteWriter::new(table, None);
+/// writer.delete(file_path, 0);
+/// writer.delete(file_path, 7);
+/// let delete_files = writer.finish().await?;
+///
+/// table
+///     .new_transaction(None)
+///     .row_delta(Vec::new(), delete_files)
+///     .commit()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PositionDeleteWriter<'a> {
+    table: &'a Table,
+    branch: Option<String>,
+    deletes: BTreeMap<String, Vec<i64>>,
+    /// Partitions of data files that are not committed yet by their path
+    data_files: HashMap<String, Struct>,
+}
+
+impl<'a> PositionDeleteWriter<'a> {
+    /// Creates a writer for deletes of rows in the data files of a branch of the table
+    pub fn new(table: &'a Table, branch: Option<&str>) -> Self {
+        Self {
+            table,
+            branch: branch.map(ToOwned::to_owned),
+            deletes: BTreeMap::new(),
+            data_files: HashMap::new(),
+        }
+    }
+
+    /// Allows to delete rows of data files that are committed in the same row delta as the
+    /// delete files
+    pub fn with_data_files(mut self, data_files: &[DataFile]) -> Self {
+        self.data_files.extend(
+            data_files
+                .iter()
+                .map(|file| (file.file_path().clone(), file.partition().clone())),
+        );
+        self
+    }
+
+    /// Marks the row at `position` of the data file at `file_path` as deleted
+    pub fn delete(&mut self, file_path: &str, position: i64) {
+        self.deletes
+            .entry(file_path.to_owned())
+            .or_default()
+            .push(position);
+    }
+
+    /// Writes the delete files
+    ///
+    /// # Returns
+    /// * `Result<Vec<DataFile>, Error>` - The written delete files with
+    ///   `Content::PositionDeletes`, one for every partition with deleted rows
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * A data file is neither part of the current snapshot of the branch nor added with
+    ///   [`PositionDeleteWriter::with_data_files`]
+    /// * The manifests of the table cannot be read
+    /// * Writing a delete file fails
+    pub async fn finish(self) -> Result<Vec<DataFile>, Error> {
+        if self.deletes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut partitions = self.partitions().await?;
+        partitions.extend(self.data_files);
+
+        let mut deletes_by_partition: BTreeMap<&Struct, Vec<(String, Vec<i64>)>> = BTreeMap::new();
+        for (file_path, mut positions) in self.deletes {
+            let partition = partitions
+                .get(&file_path)
+                .ok_or(Error::NotFound(format!("Data file {file_path}")))?;
+            positions.sort_unstable();
+            positions.dedup();
+            deletes_by_partition
+                .entry(partition)
+                .or_default()
+                .push((file_path, positions));
+        }
+
+        let metadata = self.table.metadata();
+        let object_store = self.table.object_store();
+        let schema = position_delete_schema()?;
+        let arrow_schema: Arc<ArrowSchema> =
+            Arc::new((schema.fields()).try_into().map_err(Error::from)?);
+        let partition_fields = metadata.current_partition_fields(self.branch.as_deref())?;
+        let partition_names = partition_fields
+            .iter()
+            .map(|field| field.name())
+            .collect::<Vec<_>>();
+        let data_location = metadata
+            .properties
+            .get(WRITE_DATA_PATH)
+            .map(ToOwned::to_owned)
+            .unwrap_or(metadata.location.clone() + "/data/");
+        let bucket = Bucket::from_path(&data_location)?.to_string();
+        let object_storage = metadata
+            .properties
+            .get(WRITE_OBJECT_STORAGE_ENABLED)
+            .is_some_and(|x| x == "true");
+
+        let mut delete_files = Vec::with_capacity(deletes_by_partition.len());
+        for (partition, deletes) in deletes_by_partition {
+            // Partitions with null values are written to the data location directly
+            let partition_path = match partition_struct_to_vec(partition, &partition_names) {
+                Ok(values) => generate_partition_path(&partition_fields, &values)?,
+                Err(_) => String::new(),
+            };
+
+            let (file_paths, positions): (Vec<&str>, Vec<i64>) = deletes
+                .iter()
+                .flat_map(|(file_path, positions)| {
+                    positions
+                        .iter()
+                        .map(move |position| (file_path.as_str(), *position))
+                })
+                .unzip();
+            let record_count = positions.len() as i64;
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![
+                    Arc::new(StringArray::from(file_paths)),
+                    Arc::new(Int64Array::from(positions)),
+                ],
+            )?;
+
+            let (path, mut writer) = create_arrow_writer(
+                &data_location,
+                &partition_path,
+                object_storage,
+                &arrow_schema,
+                metadata.parquet_writer_properties()?,
+                object_store.clone(),
+            )
+            .await?;
+            writer.write(&batch).await?;
+            writer.close().await?;
+
+            let file_size = object_store.head(&path.as_str().into()).await?.size;
+
+            let mut lower_bounds = HashMap::new();
+            let mut upper_bounds = HashMap::new();
+            if let (Some((first, _)), Some((last, _))) = (deletes.first(), deletes.last()) {
+                lower_bounds.insert(POSITION_DELETE_FILE_PATH_ID, Value::String(first.clone()));
+                upper_bounds.insert(POSITION_DELETE_FILE_PATH_ID, Value::String(last.clone()));
+            }
+
+            delete_files.push(
+                DataFile::builder()
+                    .with_content(Content::PositionDeletes)
+                    .with_file_path(bucket.clone() + &path)
+                    .with_partition(partition.clone())
+                    .with_record_count(record_count)
+                    .with_file_size_in_bytes(file_size as i64)
+                    .with_lower_bounds(Some(lower_bounds))
+                    .with_upper_bounds(Some(upper_bounds))
+                    .build()
+                    .map_err(iceberg_rust_spec::error::Error::from)?,
+            );
+        }
+
+        Ok(delete_files)
+    }
+
+    /// Returns the partitions of the data files with deleted rows by their path
+    async fn partitions(&self) -> Result<HashMap<String, Struct>, Error> {
+        let Some(snapshot) = self
+            .table
+            .metadata()
+            .current_snapshot(self.branch.as_deref())?
+        else {
+            return Ok(HashMap::new());
+        };
+        let manifests = self
+            .table
+            .manifests(None, Some(*snapshot.snapshot_id()))
+            .await?;
+        let partitions = self
+            .table
+            .data_files(&manifests, None, None, (None, None))
+            .await?
+            .try_filter_map(|entry| {
+                let data_file = entry.data_file();
+                future::ready(Ok((*entry.status() != Status::Deleted
+                    && self.deletes.contains_key(data_file.file_path()))
+                .then(|| (data_file.file_path().clone(), data_file.partition().clone()))))
+            })
+            .try_collect()
+            .await?;
+        Ok(partitions)
+    }
+}
+
+/// Writes equality delete files for the rows of a table with the given values
+///
+/// An equality delete file deletes all rows of older data files whose values of the equality
+/// columns are equal to a row of the delete file. Only primitive top-level columns can be used
+/// as equality columns. The columns of partitioned tables must include the source columns of
+/// the partition fields, because every delete file belongs to a single partition.
+///
+/// The written files only contain the equality columns and list their field ids as
+/// `equality_ids`.
+///
+/// # Example
+/// ```no_run
+/// # use arrow::record_batch::RecordBatch;
+/// # use iceberg_rust::{arrow::delete::EqualityDeleteWriter, table::Table, error::Error};
+/// # async fn example(table: &mut Table, deleted_rows: &RecordBatch) -> Result<(), Error> {
+/// let mut writer = EqualityDeleteWriter::try_new(table, None, &[1])?;
+/// writer.delete(deleted_rows)?;
+/// let delete_files = writer.finish().await?;
+///
+/// table
+///     .new_transaction(None)
+///     .row_delta(Vec::new(), delete_files)
+///     .commit()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct EqualityDeleteWriter<'a> {
+    table: &'a Table,
+    branch: Option<String>,
+    equality_ids: Vec<i32>,
+    schema: Arc<ArrowSchema>,
+    batches: Vec<RecordBatch>,
+}
+
+impl<'a> EqualityDeleteWriter<'a> {
+    /// Creates a writer for deletes of rows of a branch of the table by the columns with the
+    /// field ids `equality_ids`
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * No equality ids are given
+    /// * An equality id is not the id of a primitive top-level column of the current schema
+    /// * The source column of a partition field is not an equality column
+    pub fn try_new(
+        table: &'a Table,
+        branch: Option<&str>,
+        equality_ids: &[i32],
+    ) -> Result<Self, Error> {
+        if equality_ids.is_empty() {
+            return Err(Error::InvalidFormat("Empty equality ids".to_owned()));
+        }
+
+        let metadata = table.metadata();
+        let schema = metadata.current_schema(branch)?;
+        for id in equality_ids {
+            match schema.fields().get(*id as usize) {
+                Some(field) if matches!(field.field_type, Type::Primitive(_)) => (),
+                Some(field) => {
+                    return Err(Error::NotSupported(format!(
+                        "Equality deletes on the non-primitive column {}",
+                        field.name
+                    )))
+                }
+                None => return Err(Error::NotFound(format!("Column with field id {id}"))),
+            }
+        }
+
+        for field in metadata.current_partition_fields(branch)? {
+            if !equality_ids.contains(&field.source_id()) {
+                return Err(Error::NotSupported(format!(
+                    "Equality deletes without the partition source column {}",
+                    field.source_name()
+                )));
+            }
+        }
+
+        let schema: Arc<ArrowSchema> = Arc::new(
+            (schema.project(equality_ids)?.fields())
+                .try_into()
+                .map_err(Error::from)?,
+        );
+
+        Ok(Self {
+            table,
+            branch: branch.map(ToOwned::to_owned),
+            equality_ids: equality_ids.to_vec(),
+            schema,
+            batches: Vec::new(),
+        })
+    }
+
+    /// Deletes all rows with the values of the equality columns of the rows of `batch`
+    ///
+    /// The batch may contain other columns than the equality columns, they are not written to
+    /// the delete files.
+    ///
+    /// # Errors
+    /// Returns an error if the batch doesn't contain an equality column or the type of an
+    /// equality column doesn't match the schema of the table.
+    pub fn delete(&mut self, batch: &RecordBatch) -> Result<(), Error> {
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| {
+                batch
+                    .column_by_name(field.name())
+                    .cloned()
+                    .ok_or(Error::NotFound(format!("Column {} in batch", field.name())))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.batches
+            .push(RecordBatch::try_new(self.schema.clone(), columns)?);
+        Ok(())
+    }
+
+    /// Writes the delete files
+    ///
+    /// # Returns
+    /// * `Result<Vec<DataFile>, Error>` - The written delete files with
+    ///   `Content::EqualityDeletes` and the field ids of the equality columns as `equality_ids`
+    ///
+    /// # Errors
+    /// Returns an error if writing a delete file fails.
+    pub async fn finish(self) -> Result<Vec<DataFile>, Error> {
+        if self.batches.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        write_equality_deletes_parquet_partitioned(
+            self.table,
+            stream::iter(self.batches.into_iter().map(Ok)),
+            self.branch.as_deref(),
+            &self.equality_ids,
+        )
+        .await
+        .map_err(Error::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{Int64Array, StringArray},
+        datatypes::Schema as ArrowSchema,
+        record_batch::RecordBatch,
+    };
+    use futures::{stream, TryStreamExt};
+    use iceberg_rust_spec::spec::{
+        manifest::{Content, DataFile},
+        manifest_list::Content as ManifestContent,
+        schema::Schema,
+        snapshot::Operation as SnapshotOperation,
+        types::{PrimitiveType, StructField, Type},
+    };
+
+    use crate::{
+        arrow::write::write_parquet_partitioned, error::Error, table::Table, test_util::TestTable,
+    };
+
+    use super::{EqualityDeleteWriter, PositionDeleteWriter};
+
+    fn id_field() -> StructField {
+        StructField {
+            id: 1,
+            name: "id".to_string(),
+            required: true,
+            field_type: Type::Primitive(PrimitiveType::Long),
+            doc: None,
+        }
+    }
+
+    async fn test_table() -> Table {
+        let schema = Schema::builder()
+            .with_struct_field(id_field())
+            .build()
+            .unwrap();
+        TestTable::new(schema).await.unwrap()
+    }
+
+    fn batch(table: &Table, ids: Vec<i64>) -> RecordBatch {
+        let arrow_schema: ArrowSchema = table
+            .current_schema(None)
+            .unwrap()
+            .fields()
+            .try_into()
+            .unwrap();
+        RecordBatch::try_new(
+            Arc::new(arrow_schema),
+            vec![Arc::new(Int64Array::from(ids))],
+        )
+        .unwrap()
+    }
+
+    async fn write(table: &Table, batch: RecordBatch) -> Vec<DataFile> {
+        write_parquet_partitioned(table, stream::iter(vec![Ok(batch)]), None)
+            .await
+            .unwrap()
+    }
+
+    /// Appends the ids in a single data file and returns its path
+    async fn append(table: &mut Table, ids: Vec<i64>) -> String {
+        let files = write(table, batch(table, ids)).await;
+        let data_file = files[0].file_path().clone();
+        table
+            .new_transaction(None)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+        data_file
+    }
+
+    #[tokio::test]
+    async fn write_position_deletes() {
+        let mut table = test_table().await;
+        let data_file = append(&mut table, vec![1, 2, 3]).await;
+
+        let mut writer = PositionDeleteWriter::new(&table, None);
+        writer.delete(&data_file, 2);
+        writer.delete(&data_file, 0);
+        writer.delete(&data_file, 2);
+        let delete_files = writer.finish().await.unwrap();
+
+        assert_eq!(delete_files.len(), 1);
+        assert_eq!(*delete_files[0].content(), Content::PositionDeletes);
+        assert_eq!(*delete_files[0].record_count(), 2);
+
+        let mut writer = PositionDeleteWriter::new(&table, None);
+        writer.delete("/test/table/data/missing.parquet", 0);
+        assert!(matches!(writer.finish().await, Err(Error::NotFound(_))));
+
+        table
+            .new_transaction(None)
+            .append_delete(delete_files)
+            .commit()
+            .await
+            .unwrap();
+
+        assert!(table.datafiles_contains_delete(None, None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn write_equality_deletes() {
+        let schema = Schema::builder()
+            .with_struct_field(id_field())
+            .with_struct_field(StructField {
+                id: 2,
+                name: "name".to_string(),
+                required: false,
+                field_type: Type::Primitive(PrimitiveType::String),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+
+        let mut table = TestTable::new(schema).await.unwrap();
+
+        assert!(matches!(
+            EqualityDeleteWriter::try_new(&table, None, &[3]),
+            Err(Error::NotFound(_))
+        ));
+
+        let arrow_schema: ArrowSchema = table
+            .current_schema(None)
+            .unwrap()
+            .fields()
+            .try_into()
+            .unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2])),
+                Arc::new(StringArray::from(vec![Some("a"), None])),
+            ],
+        )
+        .unwrap();
+
+        let mut writer = EqualityDeleteWriter::try_new(&table, None, &[1]).unwrap();
+        writer.delete(&batch).unwrap();
+        let delete_files = writer.finish().await.unwrap();
+
+        assert_eq!(delete_files.len(), 1);
+        assert_eq!(*delete_files[0].content(), Content::EqualityDeletes);
+        assert_eq!(*delete_files[0].equality_ids(), Some(vec![1]));
+        assert_eq!(*delete_files[0].record_count(), 2);
+
+        table
+            .new_transaction(None)
+            .append_delete(delete_files)
+            .commit()
+            .await
+            .unwrap();
+
+        assert!(table.datafiles_contains_delete(None, None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn row_delta_sequence_numbers() {
+        let mut table = test_table().await;
+        let data_file = append(&mut table, vec![1, 2, 3]).await;
+
+        // Update the row with id 2
+        let mut writer = PositionDeleteWriter::new(&table, None);
+        writer.delete(&data_file, 1);
+        let delete_files = writer.finish().await.unwrap();
+        let files = write(&table, batch(&table, vec![2])).await;
+        table
+            .new_transaction(None)
+            .row_delta(files, delete_files)
+            .commit()
+            .await
+            .unwrap();
+
+        let snapshot = table.metadata().current_snapshot(None).unwrap().unwrap();
+        assert_eq!(snapshot.summary().operation, SnapshotOperation::Overwrite);
+        assert_eq!(*snapshot.sequence_number(), 2);
+
+        let manifests = table.manifests(None, None).await.unwrap();
+        assert_eq!(manifests.len(), 3);
+        assert_eq!(
+            manifests
+                .iter()
+                .filter(|manifest| manifest.content == ManifestContent::Deletes)
+                .count(),
+            1
+        );
+
+        let entries = table
+            .datafiles(&manifests, None, (None, None))
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        for entry in entries {
+            let expected = if entry.data_file().file_path() == &data_file {
+                1
+            } else {
+                2
+            };
+            assert_eq!(*entry.sequence_number(), Some(expected));
+        }
+
+        // The position deletes only apply to the data file they reference
+        let tasks = table.plan_scan(None, None).await.unwrap();
+        assert_eq!(tasks.len(), 2);
+        for task in tasks {
+            let expected = if task.file_path() == data_file { 1 } else { 0 };
+            assert_eq!(task.delete_files.len(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn row_delta_position_deletes_of_added_data_files() {
+        let mut table = test_table().await;
+
+        let files = write(&table, batch(&table, vec![1, 2, 3])).await;
+        let data_file = files[0].file_path().clone();
+
+        // The data file isn't committed yet
+        let mut writer = PositionDeleteWriter::new(&table, None);
+        writer.delete(&data_file, 1);
+        assert!(writer.finish().await.is_err());
+
+        let mut writer = PositionDeleteWriter::new(&table, None).with_data_files(&files);
+        writer.delete(&data_file, 1);
+        let delete_files = writer.finish().await.unwrap();
+        table
+            .new_transaction(None)
+            .row_delta(files, delete_files)
+            .commit()
+            .await
+            .unwrap();
+
+        // The position deletes apply to the data file of the same snapshot
+        let tasks = table.plan_scan(None, None).await.unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].file_path(), data_file);
+        assert_eq!(tasks[0].sequence_number, 1);
+        assert_eq!(tasks[0].delete_files.len(), 1);
+        assert_eq!(*tasks[0].delete_files[0].record_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn separate_data_and_delete_manifests() {
+        let mut table = test_table().await;
+        append(&mut table, vec![1, 2, 3]).await;
+
+        let batch = batch(&table, vec![1, 2, 3]);
+        let mut writer = EqualityDeleteWriter::try_new(&table, None, &[1]).unwrap();
+        writer.delete(&batch).unwrap();
+        let delete_files = writer.finish().await.unwrap();
+        let files = write(&table, batch).await;
+        table
+            .new_transaction(None)
+            .append_data(files.clone())
+            .append_delete(delete_files)
+            .commit()
+            .await
+            .unwrap();
+
+        // New data files are never added to a delete manifest
+        table
+            .new_transaction(None)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+
+        let manifests = table.manifests(None, None).await.unwrap();
+        assert_eq!(
+            manifests
+                .iter()
+                .filter(|manifest| manifest.content == ManifestContent::Deletes)
+                .count(),
+            1
+        );
+        for manifest in &manifests {
+            let entries = table
+                .datafiles(std::slice::from_ref(manifest), None, (None, None))
+                .await
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            assert!(!entries.is_empty());
+            for entry in entries {
+                let is_data = *entry.data_file().content() == Content::Data;
+                assert_eq!(is_data, manifest.content == ManifestContent::Data);
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+/*!
+ * Functions to load CSV and JSON files into an iceberg table
+*/
+
+use std::{
+    io::{BufRead, Read},
+    sync::Arc,
+};
+
+use arrow::{
+    csv,
+    datatypes::{Schema as ArrowSchema, SchemaRef},
+    error::ArrowError,
+    json,
+    record_batch::RecordBatch,
+};
+use futures::{stream, Stream};
+use regex::Regex;
+use tokio::sync::mpsc;
+
+use crate::{error::Error, table::Table};
+
+use super::write::write_parquet_partitioned;
+
+/// Number of parsed record batches that are buffered between the parser and the writer
+static BATCH_BUFFER_SIZE: usize = 4;
+
+/// Options of the CSV parser used by [write_csv_to_table]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Character that separates the values of a record
+    pub delimiter: u8,
+    /// Whether the first line contains the column names and is skipped
+    pub has_header: bool,
+    /// Values that are read as null, empty values are null if no markers are given
+    pub null_values: Vec<String>,
+    /// Number of records per record batch
+    pub batch_size: usize,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_header: true,
+            null_values: Vec::new(),
+            batch_size: 8192,
+        }
+    }
+}
+
+/// Parses the CSV records of the reader against the current schema of the table and appends them
+/// to the table.
+///
+/// The records are written to parquet data files with [write_parquet_partitioned] and committed
+/// in a single append operation. The reader is blocking and is read on a thread of the blocking
+/// pool of the tokio runtime.
+pub async fn write_csv_to_table<R: Read + Send + 'static>(
+    table: &mut Table,
+    reader: R,
+    options: &CsvOptions,
+    branch: Option<&str>,
+) -> Result<(), Error> {
+    let mut builder = csv::ReaderBuilder::new(arrow_schema(table, branch)?)
+        .with_delimiter(options.delimiter)
+        .with_header(options.has_header)
+        .with_batch_size(options.batch_size);
+    if !options.null_values.is_empty() {
+        let pattern = options
+            .null_values
+            .iter()
+            .map(|value| regex::escape(value.as_str()))
+            .collect::<Vec<_>>()
+            .join("|");
+        let null_regex = Regex::new(&format!("^(?:{pattern})$"))
+            .map_err(|_| Error::InvalidFormat("CSV null values".to_owned()))?;
+        builder = builder.with_null_regex(null_regex);
+    }
+    let batches = read_blocking(move || builder.build(reader));
+
+    let files = write_parquet_partitioned(table, batches, branch).await?;
+    table
+        .new_transaction(branch)
+        .append_data(files)
+        .commit()
+        .await
+}
+
+/// Parses the newline delimited JSON records of the reader against the current schema of the
+/// table and appends them to the table.
+///
+/// The records are written to parquet data files with [write_parquet_partitioned] and committed
+/// in a single append operation. The reader is blocking and is read on a thread of the blocking
+/// pool of the tokio runtime.
+pub async fn write_json_to_table<R: BufRead + Send + 'static>(
+    table: &mut Table,
+    reader: R,
+    branch: Option<&str>,
+) -> Result<(), Error> {
+    let builder = json::ReaderBuilder::new(arrow_schema(table, branch)?);
+    let batches = read_blocking(move || builder.build(reader));
+
+    let files = write_parquet_partitioned(table, batches, branch).await?;
+    table
+        .new_transaction(branch)
+        .append_data(files)
+        .commit()
+        .await
+}
+
+/// Parses the record batches of a blocking reader on the blocking thread pool and returns them as
+/// a stream
+fn read_blocking<I>(
+    reader: impl FnOnce() -> Result<I, ArrowError> + Send + 'static,
+) -> impl Stream<Item = Result<RecordBatch, ArrowError>> + Send + 'static
+where
+    I: Iterator<Item = Result<RecordBatch, ArrowError>>,
+{
+    let (sender, receiver) = mpsc::channel(BATCH_BUFFER_SIZE);
+
+    tokio::task::spawn_blocking(move || match reader() {
+        Ok(batches) => {
+            for batch in batches {
+                if sender.blocking_send(batch).is_err() {
+                    break;
+                }
+            }
+        }
+        Err(err) => {
+            let _ = sender.blocking_send(Err(err));
+        }
+    });
+
+    stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|batch| (batch, receiver))
+    })
+}
+
+fn arrow_schema(table: &Table, branch: Option<&str>) -> Result<SchemaRef, Error> {
+    let schema: ArrowSchema = table.current_schema(branch)?.fields().try_into()?;
+    Ok(Arc::new(schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use arrow::record_batch::RecordBatch;
+    use futures::TryStreamExt;
+    use iceberg_rust_spec::spec::{
+        schema::Schema,
+        types::{PrimitiveType, StructField, Type},
+    };
+
+    use crate::{error::Error, test_util::TestTable};
+
+    use super::{write_csv_to_table, write_json_to_table, CsvOptions};
+
+    #[tokio::test]
+    async fn ingest_csv_and_json() {
+        let schema = Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .with_struct_field(StructField {
+                id: 2,
+                name: "name".to_string(),
+                required: false,
+                field_type: Type::Primitive(PrimitiveType::String),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+
+        let mut table = TestTable::new(schema).await.unwrap();
+
+        let options = CsvOptions {
+            delimiter: b';',
+            null_values: vec!["NULL".to_owned()],
+            ..Default::default()
+        };
+        let csv = "id;name\n1;a\n2;NULL\n";
+        write_csv_to_table(&mut table, Cursor::new(csv), &options, None)
+            .await
+            .unwrap();
+
+        let json = "{\"id\": 3, \"name\": \"c\"}\n";
+        write_json_to_table(&mut table, Cursor::new(json), None)
+            .await
+            .unwrap();
+
+        let batches = table
+            .scan(None, None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 3);
+        assert_eq!(table.metadata().snapshots.len(), 2);
+
+        // Parser errors are returned from the append
+        let result =
+            write_csv_to_table(&mut table, Cursor::new("id;name\nx;a\n"), &options, None).await;
+        assert!(matches!(result, Err(Error::Arrow(_))));
+    }
+}
@@ -3,6 +3,8 @@
 //! This module provides functionality for working with Apache Arrow data structures
 //! in Apache Iceberg:
 //!
+//! - `delete`: Writes delete files for row level deletes of existing rows
+//! - `ingest`: Loads CSV and JSON files into Iceberg tables
 //! - `partition`: Handles partitioning of Arrow arrays according to Iceberg partition specs
 //! - `read`: Provides utilities for reading Iceberg data into Arrow arrays and record batches
 //! - `transform`: Implements Iceberg partition transforms for Arrow arrays
@@ -11,6 +13,8 @@
 //! The Arrow integration allows efficient in-memory processing of Iceberg data using
 //! Arrow's columnar format and computational libraries.
 
+pub mod delete;
+pub mod ingest;
 pub mod partition;
 pub mod read;
 pub mod transform;
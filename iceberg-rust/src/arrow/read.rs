@@ -5,17 +5,17 @@
 use std::{convert, sync::Arc};
 
 use arrow::record_batch::RecordBatch;
-use futures::{stream, Stream, StreamExt};
-use iceberg_rust_spec::util;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use iceberg_rust_spec::{spec::types::StructField, util};
 use object_store::ObjectStore;
 use parquet::{
-    arrow::{async_reader::ParquetObjectReader, ParquetRecordBatchStreamBuilder},
+    arrow::{async_reader::ParquetObjectReader, ParquetRecordBatchStreamBuilder, ProjectionMask},
     errors::ParquetError,
 };
 
 use crate::error::Error;
 
-use iceberg_rust_spec::spec::manifest::{FileFormat, ManifestEntry};
+use iceberg_rust_spec::spec::manifest::{DataFile, FileFormat, ManifestEntry};
 
 /// Read a parquet file into a stream of arrow recordbatches. The record batches are read asynchronously and are unordered
 pub async fn read(
@@ -46,3 +46,84 @@ pub async fn read(
         .filter_map(|x| async move { x.ok() })
         .flat_map_unordered(None, convert::identity)
 }
+
+/// Read a single data file into a stream of arrow recordbatches.
+///
+/// If a projection is given, only the projected columns are read and the columns of the record
+/// batches are in the order of the projection. The columns of the parquet file are matched by
+/// their field id and by their name if the file doesn't contain field ids.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            file = %data_file.file_path(),
+            bytes = data_file.file_size_in_bytes(),
+            records = data_file.record_count()
+        )
+    )
+)]
+pub(crate) async fn read_data_file(
+    data_file: &DataFile,
+    projection: Option<&[StructField]>,
+    object_store: Arc<dyn ObjectStore>,
+) -> Result<impl Stream<Item = Result<RecordBatch, Error>>, Error> {
+    if *data_file.file_format() != FileFormat::Parquet {
+        return Err(Error::NotSupported(format!(
+            "fileformat {:?}",
+            data_file.file_format()
+        )));
+    }
+    let object_meta = object_store
+        .head(&util::strip_prefix(data_file.file_path()).into())
+        .await?;
+
+    let object_reader = ParquetObjectReader::new(object_store, object_meta);
+    let mut builder = ParquetRecordBatchStreamBuilder::new(object_reader).await?;
+
+    let mut order = None;
+    if let Some(projection) = projection {
+        let columns = builder.parquet_schema().root_schema().get_fields();
+        let indices = projection
+            .iter()
+            .map(|field| {
+                columns
+                    .iter()
+                    .position(|column| {
+                        let info = column.get_basic_info();
+                        if info.has_id() {
+                            info.id() == field.id
+                        } else {
+                            info.name() == field.name
+                        }
+                    })
+                    .ok_or_else(|| Error::Schema(field.name.clone(), data_file.file_path().clone()))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // The projection mask keeps the order of the file, reorder the columns afterwards
+        let mut sorted = indices.clone();
+        sorted.sort_unstable();
+        order = Some(
+            indices
+                .iter()
+                .map(|index| sorted.binary_search(index).unwrap_or_default())
+                .collect::<Vec<_>>(),
+        );
+
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+        builder = builder.with_projection(mask);
+    }
+
+    Ok(builder
+        .build()?
+        .map_err(Error::from)
+        .and_then(move |batch| {
+            let batch = match &order {
+                Some(order) => batch.project(order).map_err(Error::from),
+                None => Ok(batch),
+            };
+            futures::future::ready(batch)
+        }))
+}
@@ -11,9 +11,10 @@
 //! - [`write_equality_deletes_parquet_partitioned`]: Write equality delete files
 //!
 //! The module handles:
-//! - Automatic file size management and splitting
+//! - Rolling over to a new file once `write.target-file-size-bytes` is reached
 //! - Parquet compression and encoding
 //! - Partition path generation
+//! - Checking whether the rows of a file are sorted by the default sort order of the table
 //! - Object store integration
 //! - Metadata collection for written files
 //!
@@ -37,40 +38,49 @@ use futures::{
     StreamExt, TryStreamExt,
 };
 use object_store::{buffered::BufWriter, ObjectStore};
-use std::fmt::Write;
 use std::sync::Arc;
 use tokio::task::JoinSet;
 
-use arrow::{datatypes::Schema as ArrowSchema, error::ArrowError, record_batch::RecordBatch};
+use arrow::{
+    compute::SortOptions,
+    datatypes::Schema as ArrowSchema,
+    error::ArrowError,
+    record_batch::RecordBatch,
+    row::{OwnedRow, RowConverter, SortField as RowSortField},
+};
 use futures::Stream;
 use iceberg_rust_spec::{
-    partition::BoundPartitionField,
-    spec::{manifest::DataFile, schema::Schema, values::Value},
+    partition::{BoundPartitionField, Transform},
+    spec::{
+        manifest::DataFile,
+        schema::Schema,
+        sort::{NullOrder, SortDirection, SortOrder},
+        values::Value,
+    },
     table_metadata::{self, WRITE_DATA_PATH, WRITE_OBJECT_STORAGE_ENABLED},
     util::strip_prefix,
 };
-use parquet::{
-    arrow::AsyncArrowWriter,
-    basic::{Compression, ZstdLevel},
-    file::properties::WriterProperties,
-    format::FileMetaData,
-};
+use parquet::{arrow::AsyncArrowWriter, file::properties::WriterProperties, format::FileMetaData};
 use uuid::Uuid;
 
 use crate::{
-    error::Error, file_format::parquet::parquet_to_datafile, object_store::Bucket, table::Table,
+    error::Error,
+    file_format::parquet::{parquet_to_datafile, ParquetProperties},
+    object_store::Bucket,
+    table::Table,
 };
 
 use super::partition::PartitionStream;
 
-const MAX_PARQUET_SIZE: usize = 512_000_000;
-
 #[inline]
 /// Writes Arrow record batches as partitioned Parquet files.
 ///
 /// This function writes Arrow record batches to Parquet files, partitioning them according
 /// to the table's partition spec.
 ///
+/// Files whose rows are sorted by the default sort order of the table reference the sort order
+/// with their `sort_order_id`. The sort order id of all other files is not set.
+///
 /// # Arguments
 /// * `table` - The Iceberg table to write data for
 /// * `batches` - Stream of Arrow record batches to write
@@ -162,7 +172,7 @@ async fn store_parquet_partitioned(
     );
     // project the schema on to the equality_ids for equality deletes
     let schema = if let Some(equality_ids) = equality_ids {
-        Arc::new(schema.project(equality_ids))
+        Arc::new(schema.project(equality_ids).map_err(Error::from)?)
     } else {
         schema
     };
@@ -178,6 +188,17 @@ async fn store_parquet_partitioned(
         .current_partition_fields(branch)
         .map_err(Error::from)?;
 
+    // The rows of equality delete files are not checked against the sort order
+    let sort_order = match equality_ids {
+        None => metadata
+            .sort_orders
+            .get(&metadata.default_sort_order_id)
+            .filter(|x| !x.fields.is_empty())
+            .cloned()
+            .map(Arc::new),
+        Some(_) => None,
+    };
+
     let data_location = &metadata
         .properties
         .get(WRITE_DATA_PATH)
@@ -187,25 +208,30 @@ async fn store_parquet_partitioned(
     let arrow_schema: Arc<ArrowSchema> =
         Arc::new((schema.fields()).try_into().map_err(Error::from)?);
 
+    let object_storage = metadata
+        .properties
+        .get(WRITE_OBJECT_STORAGE_ENABLED)
+        .is_some_and(|x| x == "true");
+
+    let writer_properties = metadata.parquet_writer_properties()?;
+    let row_group_size = metadata.parquet_row_group_size_bytes()?;
+    let target_file_size = metadata.target_file_size_bytes()?;
+
     if partition_fields.is_empty() {
-        let partition_path = if metadata
-            .properties
-            .get(WRITE_OBJECT_STORAGE_ENABLED)
-            .is_some_and(|x| x == "true")
-        {
-            Some("".to_owned())
-        } else {
-            None
-        };
         let files = write_parquet_files(
             data_location,
             &schema,
             &arrow_schema,
             partition_fields,
-            partition_path,
+            String::new(),
+            object_storage,
+            writer_properties,
+            row_group_size,
+            target_file_size,
             batches,
             object_store.clone(),
             equality_ids,
+            sort_order.as_deref(),
         )
         .await?;
         Ok(files)
@@ -223,18 +249,9 @@ async fn store_parquet_partitioned(
                 let schema = schema.clone();
                 let partition_spec = partition_spec.clone();
                 let equality_ids = equality_ids.map(Vec::from);
-                let partition_path = if metadata
-                    .properties
-                    .get(WRITE_OBJECT_STORAGE_ENABLED)
-                    .is_some_and(|x| x == "true")
-                {
-                    None
-                } else {
-                    Some(generate_partition_path(
-                        partition_fields,
-                        &partition_values,
-                    )?)
-                };
+                let writer_properties = writer_properties.clone();
+                let sort_order = sort_order.clone();
+                let partition_path = generate_partition_path(partition_fields, &partition_values)?;
                 async move {
                     let partition_fields =
                         table_metadata::partition_fields(&partition_spec, &schema)
@@ -245,9 +262,14 @@ async fn store_parquet_partitioned(
                         &arrow_schema,
                         &partition_fields,
                         partition_path,
+                        object_storage,
+                        writer_properties,
+                        row_group_size,
+                        target_file_size,
                         batches,
                         object_store.clone(),
                         equality_ids.as_deref(),
+                        sort_order.as_deref(),
                     )
                     .await?;
                     Ok::<_, Error>(files)
@@ -265,8 +287,8 @@ async fn store_parquet_partitioned(
     }
 }
 
-type ArrowSender = Sender<(String, FileMetaData)>;
-type ArrowReciever = Receiver<(String, FileMetaData)>;
+type ArrowSender = Sender<(String, FileMetaData, Option<i32>)>;
+type ArrowReciever = Receiver<(String, FileMetaData, Option<i32>)>;
 
 /// Writes a stream of Arrow record batches to multiple Parquet files.
 ///
@@ -278,10 +300,15 @@ type ArrowReciever = Receiver<(String, FileMetaData)>;
 /// * `schema` - Iceberg schema for the data
 /// * `arrow_schema` - Arrow schema for the record batches
 /// * `partition_fields` - List of partition fields if data is partitioned
-/// * `partition_path` - Optional partition path component
+/// * `partition_path` - Partition path component, empty for unpartitioned tables
+/// * `object_storage` - Whether to prefix the file paths with hash directories
+/// * `writer_properties` - Parquet writer properties derived from the table properties
+/// * `row_group_size` - Target size of a row group in bytes
+/// * `target_file_size` - Size in bytes after which a new file is started
 /// * `batches` - Stream of record batches to write
 /// * `object_store` - Object store to write files to
 /// * `equality_ids` - Optional list of field IDs for equality deletes
+/// * `sort_order` - Sort order to check the rows of every file against
 ///
 /// # Returns
 /// * `Result<Vec<DataFile>, ArrowError>` - List of metadata for the written files
@@ -298,10 +325,15 @@ async fn write_parquet_files(
     schema: &Schema,
     arrow_schema: &ArrowSchema,
     partition_fields: &[BoundPartitionField<'_>],
-    partition_path: Option<String>,
+    partition_path: String,
+    object_storage: bool,
+    writer_properties: WriterProperties,
+    row_group_size: usize,
+    target_file_size: usize,
     batches: impl Stream<Item = Result<RecordBatch, ArrowError>> + Send,
     object_store: Arc<dyn ObjectStore>,
     equality_ids: Option<&[i32]>,
+    sort_order: Option<&SortOrder>,
 ) -> Result<Vec<DataFile>, ArrowError> {
     let bucket = Bucket::from_path(data_location)?;
     let (mut writer_sender, writer_reciever): (ArrowSender, ArrowReciever) = channel(1);
@@ -309,8 +341,10 @@ async fn write_parquet_files(
     // Create initial writer
     let initial_writer = create_arrow_writer(
         data_location,
-        partition_path.clone(),
+        &partition_path,
+        object_storage,
         arrow_schema,
+        writer_properties.clone(),
         object_store.clone(),
     )
     .await?;
@@ -319,6 +353,8 @@ async fn write_parquet_files(
     struct WriterState {
         writer: (String, AsyncArrowWriter<BufWriter>),
         bytes_written: usize,
+        row_group_bytes: usize,
+        sort_check: Option<SortCheck>,
     }
 
     let final_state = batches
@@ -326,44 +362,61 @@ async fn write_parquet_files(
             WriterState {
                 writer: initial_writer,
                 bytes_written: 0,
+                row_group_bytes: 0,
+                sort_check: SortCheck::try_new(sort_order, schema, arrow_schema)?,
             },
             |mut state, batch| {
                 let object_store = object_store.clone();
                 let data_location = data_location.to_owned();
                 let partition_path = partition_path.clone();
                 let arrow_schema = arrow_schema.clone();
+                let writer_properties = writer_properties.clone();
                 let mut writer_sender = writer_sender.clone();
 
                 async move {
                     let batch_size = record_batch_size(&batch);
                     let new_size = state.bytes_written + batch_size;
 
-                    if new_size > MAX_PARQUET_SIZE {
+                    if new_size > target_file_size && state.bytes_written > 0 {
                         // Send current writer to channel
                         let finished_writer = state.writer;
                         let file = finished_writer.1.close().await?;
                         writer_sender
-                            .try_send((finished_writer.0, file))
+                            .try_send((
+                                finished_writer.0,
+                                file,
+                                state.sort_check.and_then(SortCheck::sort_order_id),
+                            ))
                             .map_err(|err| ArrowError::ComputeError(err.to_string()))?;
 
                         // Create new writer
                         let new_writer = create_arrow_writer(
                             &data_location,
-                            partition_path,
+                            &partition_path,
+                            object_storage,
                             &arrow_schema,
+                            writer_properties,
                             object_store,
                         )
                         .await?;
 
                         state.writer = new_writer;
                         state.bytes_written = batch_size;
+                        state.row_group_bytes = batch_size;
+                        state.sort_check = SortCheck::try_new(sort_order, schema, &arrow_schema)?;
                     } else {
                         state.bytes_written = new_size;
-                        if new_size % 64_000_000 >= 32_000_000 {
+                        state.row_group_bytes += batch_size;
+                        // Start a new row group once the target size is reached
+                        if state.row_group_bytes >= row_group_size {
                             state.writer.1.flush().await?;
+                            state.row_group_bytes = 0;
                         }
                     }
 
+                    if let Some(sort_check) = &mut state.sort_check {
+                        sort_check.check(&batch)?;
+                    }
                     state.writer.1.write(&batch).await?;
                     Ok(state)
                 }
@@ -374,7 +427,11 @@ async fn write_parquet_files(
     // Handle the last writer
     let file = final_state.writer.1.close().await?;
     writer_sender
-        .try_send((final_state.writer.0, file))
+        .try_send((
+            final_state.writer.0,
+            file,
+            final_state.sort_check.and_then(SortCheck::sort_order_id),
+        ))
         .map_err(|err| ArrowError::ComputeError(err.to_string()))?;
     writer_sender.close_channel();
 
@@ -400,6 +457,7 @@ async fn write_parquet_files(
                     schema,
                     partition_fields,
                     equality_ids,
+                    writer.2,
                 )?)
             }
         })
@@ -407,6 +465,85 @@ async fn write_parquet_files(
         .await
 }
 
+/// Checks whether the rows written to a file are sorted by a sort order
+///
+/// Only sort orders with identity transforms on top level columns are checked. Files of other
+/// sort orders are treated as unsorted.
+struct SortCheck {
+    order_id: i32,
+    columns: Vec<usize>,
+    converter: RowConverter,
+    last_row: Option<OwnedRow>,
+    sorted: bool,
+}
+
+impl SortCheck {
+    /// Creates the check for a new file, `None` if the sort order can't be checked
+    fn try_new(
+        sort_order: Option<&SortOrder>,
+        schema: &Schema,
+        arrow_schema: &ArrowSchema,
+    ) -> Result<Option<Self>, ArrowError> {
+        let Some(sort_order) = sort_order else {
+            return Ok(None);
+        };
+        let mut columns = Vec::with_capacity(sort_order.fields.len());
+        let mut sort_fields = Vec::with_capacity(sort_order.fields.len());
+        for field in &sort_order.fields {
+            if field.transform != Transform::Identity {
+                return Ok(None);
+            }
+            let Some(column) = schema.fields().iter().position(|x| x.id == field.source_id) else {
+                return Ok(None);
+            };
+            columns.push(column);
+            sort_fields.push(RowSortField::new_with_options(
+                arrow_schema.field(column).data_type().clone(),
+                SortOptions {
+                    descending: field.direction == SortDirection::Descending,
+                    nulls_first: field.null_order == NullOrder::First,
+                },
+            ));
+        }
+        Ok(Some(Self {
+            order_id: sort_order.order_id,
+            columns,
+            converter: RowConverter::new(sort_fields)?,
+            last_row: None,
+            sorted: true,
+        }))
+    }
+
+    /// Checks the rows of the next batch written to the file
+    fn check(&mut self, batch: &RecordBatch) -> Result<(), ArrowError> {
+        if !self.sorted || batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| batch.column(*column).clone())
+            .collect::<Vec<_>>();
+        let rows = self.converter.convert_columns(&columns)?;
+
+        let mut previous = self.last_row.as_ref().map(OwnedRow::row);
+        for row in rows.iter() {
+            if previous.is_some_and(|previous| previous > row) {
+                self.sorted = false;
+                return Ok(());
+            }
+            previous = Some(row);
+        }
+        self.last_row = previous.map(|row| row.owned());
+        Ok(())
+    }
+
+    /// Returns the sort order id if all rows of the file were sorted
+    fn sort_order_id(self) -> Option<i32> {
+        self.sorted.then_some(self.order_id)
+    }
+}
+
 /// Generates a partition path string from partition fields and their values.
 ///
 /// Creates a path string in the format "field1=value1/field2=value2/..." for each
@@ -424,7 +561,7 @@ async fn write_parquet_files(
 /// * The partition field name cannot be processed
 /// * The partition value cannot be converted to a string
 #[inline]
-fn generate_partition_path(
+pub(crate) fn generate_partition_path(
     partition_fields: &[BoundPartitionField<'_>],
     partiton_values: &[Value],
 ) -> Result<String, ArrowError> {
@@ -441,12 +578,14 @@ fn generate_partition_path(
 /// Creates a new Arrow writer for writing record batches to a Parquet file.
 ///
 /// This internal function creates a new buffered writer and configures it with
-/// appropriate Parquet compression settings.
+/// the Parquet writer properties of the table.
 ///
 /// # Arguments
 /// * `data_location` - Base path where data files should be written
-/// * `partition_path` - Optional partition path component
+/// * `partition_path` - Partition path component, empty for unpartitioned tables
+/// * `object_storage` - Whether to prefix the file path with hash directories
 /// * `schema` - Arrow schema for the record batches
+/// * `writer_properties` - Parquet writer properties derived from the table properties
 /// * `object_store` - Object store to write files to
 ///
 /// # Returns
@@ -457,10 +596,12 @@ fn generate_partition_path(
 /// * Random number generation fails
 /// * The writer properties cannot be configured
 /// * The Arrow writer cannot be created
-async fn create_arrow_writer(
+pub(crate) async fn create_arrow_writer(
     data_location: &str,
-    partition_path: Option<String>,
+    partition_path: &str,
+    object_storage: bool,
     schema: &arrow::datatypes::Schema,
+    writer_properties: WriterProperties,
     object_store: Arc<dyn ObjectStore>,
 ) -> Result<(String, AsyncArrowWriter<BufWriter>), ArrowError> {
     let mut rand = [0u8; 6];
@@ -468,35 +609,49 @@ async fn create_arrow_writer(
         .map_err(|err| ArrowError::ExternalError(Box::new(err)))
         .unwrap();
 
-    let path = partition_path.unwrap_or_else(|| {
-        rand[0..3]
-            .iter()
-            .fold(String::with_capacity(8), |mut acc, x| {
-                write!(&mut acc, "{:x}", x).unwrap();
-                acc
-            })
-            + "/"
-    });
+    let file_name = Uuid::now_v1(&rand).to_string() + ".parquet";
 
-    let parquet_path =
-        strip_prefix(data_location) + &path + &Uuid::now_v1(&rand).to_string() + ".parquet";
+    let hash_path = if object_storage {
+        object_storage_hash_path(&file_name)
+    } else {
+        String::new()
+    };
+
+    let parquet_path = strip_prefix(data_location) + &hash_path + partition_path + &file_name;
 
     let writer = BufWriter::new(object_store.clone(), parquet_path.clone().into());
 
     Ok((
         parquet_path,
-        AsyncArrowWriter::try_new(
-            writer,
-            Arc::new(schema.clone()),
-            Some(
-                WriterProperties::builder()
-                    .set_compression(Compression::ZSTD(ZstdLevel::try_new(1)?))
-                    .build(),
-            ),
-        )?,
+        AsyncArrowWriter::try_new(writer, Arc::new(schema.clone()), Some(writer_properties))?,
     ))
 }
 
+/// Generates the hash directories of the object storage layout for a file name.
+///
+/// Like the Java implementation, the 20 least significant bits of the murmur3 hash of the
+/// file name are written as a binary string. The string is split into three directories
+/// of 4 bits and a fourth directory with the remaining 8 bits, which spreads the files
+/// evenly across object store prefixes.
+///
+/// # Arguments
+/// * `file_name` - Name of the data file
+///
+/// # Returns
+/// * `String` - The hash directories ending with a slash, e.g. "0101/1100/0010/10110110/"
+#[inline]
+fn object_storage_hash_path(file_name: &str) -> String {
+    let hash = murmur3::murmur3_32(&mut file_name.as_bytes(), 0).unwrap_or_default() & 0xFFFFF;
+    let bits = format!("{:020b}", hash);
+    format!(
+        "{}/{}/{}/{}/",
+        &bits[0..4],
+        &bits[4..8],
+        &bits[8..12],
+        &bits[12..20]
+    )
+}
+
 /// Calculates the approximate size in bytes of an Arrow record batch.
 ///
 /// This function estimates the memory footprint of a record batch by multiplying
@@ -547,4 +702,76 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "month=10/");
     }
+
+    #[test]
+    fn test_object_storage_hash_path() {
+        let path = super::object_storage_hash_path("00000-0-data.parquet");
+
+        assert_eq!(
+            path,
+            super::object_storage_hash_path("00000-0-data.parquet")
+        );
+        assert_eq!(path.len(), 24);
+        assert_eq!(
+            path.split('/').map(str::len).collect::<Vec<_>>(),
+            vec![4, 4, 4, 8, 0]
+        );
+        assert!(path.chars().all(|x| x == '0' || x == '1' || x == '/'));
+    }
+
+    #[test]
+    fn test_sort_check() {
+        use std::sync::Arc;
+
+        use arrow::{
+            array::Int64Array, datatypes::Schema as ArrowSchema, record_batch::RecordBatch,
+        };
+        use iceberg_rust_spec::spec::{
+            schema::Schema,
+            sort::{NullOrder, SortDirection, SortField, SortOrder},
+            types::PrimitiveType,
+        };
+
+        let schema = Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_owned(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+        let arrow_schema: ArrowSchema = schema.fields().try_into().unwrap();
+        let sort_order = SortOrder {
+            order_id: 1,
+            fields: vec![SortField {
+                source_id: 1,
+                transform: Transform::Identity,
+                direction: SortDirection::Ascending,
+                null_order: NullOrder::Last,
+            }],
+        };
+        let batch = |values: Vec<i64>| {
+            RecordBatch::try_new(
+                Arc::new(arrow_schema.clone()),
+                vec![Arc::new(Int64Array::from(values))],
+            )
+            .unwrap()
+        };
+
+        let mut check = super::SortCheck::try_new(Some(&sort_order), &schema, &arrow_schema)
+            .unwrap()
+            .unwrap();
+        check.check(&batch(vec![1, 2, 2])).unwrap();
+        check.check(&batch(vec![3, 5])).unwrap();
+        assert_eq!(check.sort_order_id(), Some(1));
+
+        let mut check = super::SortCheck::try_new(Some(&sort_order), &schema, &arrow_schema)
+            .unwrap()
+            .unwrap();
+        check.check(&batch(vec![1, 4])).unwrap();
+        check.check(&batch(vec![3, 5])).unwrap();
+        assert_eq!(check.sort_order_id(), None);
+    }
 }
@@ -0,0 +1,158 @@
+/*!
+ * Read-through cache for the metadata files loaded by a catalog
+ *
+ * A metadata file is never changed after it was written, so the metadata of a table at a given
+ * metadata location can be cached without invalidation. Entries are only evicted when the cache
+ * is full or after the time to live has passed.
+*/
+
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use iceberg_rust_spec::spec::tabular::TabularMetadata;
+use lru::LruCache;
+
+use crate::error::Error;
+
+use super::identifier::Identifier;
+
+/// Cached metadata by identifier and metadata location
+type Entries = LruCache<(Identifier, String), (Instant, TabularMetadata)>;
+
+/// Read-through cache for the metadata of tables, views and materialized views
+///
+/// The cache is shared between clones, so a catalog can be cloned without losing its cache.
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    entries: Arc<Mutex<Entries>>,
+    ttl: Duration,
+}
+
+impl MetadataCache {
+    /// Creates a new cache
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of cached metadata files, at least 1
+    /// * `ttl` - The time after which a cached metadata file is loaded again
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
+            ))),
+            ttl,
+        }
+    }
+
+    /// Returns the cached metadata of the tabular at the metadata location
+    ///
+    /// Entries that are older than the time to live are removed and not returned.
+    pub fn get(&self, identifier: &Identifier, metadata_location: &str) -> Option<TabularMetadata> {
+        let mut entries = self.entries.lock().unwrap();
+        let key = (identifier.clone(), metadata_location.to_owned());
+        match entries.get(&key) {
+            Some((inserted, metadata)) if inserted.elapsed() < self.ttl => Some(metadata.clone()),
+            Some(_) => {
+                entries.pop(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Caches the metadata of the tabular at the metadata location
+    pub fn insert(
+        &self,
+        identifier: &Identifier,
+        metadata_location: &str,
+        metadata: TabularMetadata,
+    ) {
+        self.entries.lock().unwrap().put(
+            (identifier.clone(), metadata_location.to_owned()),
+            (Instant::now(), metadata),
+        );
+    }
+
+    /// Returns the cached metadata of the tabular or loads and caches it
+    ///
+    /// # Arguments
+    /// * `identifier` - The identifier of the tabular
+    /// * `metadata_location` - The location of the metadata file
+    /// * `load` - Loads the metadata file, it is only awaited if the metadata is not cached
+    ///
+    /// # Errors
+    /// Returns the error of `load` if the metadata is not cached and loading it fails
+    pub async fn get_or_load(
+        &self,
+        identifier: &Identifier,
+        metadata_location: &str,
+        load: impl Future<Output = Result<TabularMetadata, Error>>,
+    ) -> Result<TabularMetadata, Error> {
+        if let Some(metadata) = self.get(identifier, metadata_location) {
+            return Ok(metadata);
+        }
+        let metadata = load.await?;
+        self.insert(identifier, metadata_location, metadata.clone());
+        Ok(metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use iceberg_rust_spec::spec::tabular::TabularMetadata;
+
+    use crate::{catalog::identifier::Identifier, error::Error, test_util::VIEW_METADATA};
+
+    use super::MetadataCache;
+
+    fn view_metadata() -> TabularMetadata {
+        serde_json::from_str(VIEW_METADATA).unwrap()
+    }
+
+    #[tokio::test]
+    async fn read_through() {
+        let identifier = Identifier::new(&["default".to_string()], "event_agg");
+        let loads = AtomicUsize::new(0);
+        let load = || async {
+            loads.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, Error>(view_metadata())
+        };
+
+        let cache = MetadataCache::new(1, Duration::from_secs(60));
+        for _ in 0..2 {
+            cache
+                .get_or_load(&identifier, "s3://bucket/v1.metadata.json", load())
+                .await
+                .unwrap();
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        // A new metadata location is loaded and evicts the previous one
+        cache
+            .get_or_load(&identifier, "s3://bucket/v2.metadata.json", load())
+            .await
+            .unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+        assert!(cache
+            .get(&identifier, "s3://bucket/v1.metadata.json")
+            .is_none());
+
+        // Expired entries are loaded again
+        let cache = MetadataCache::new(10, Duration::ZERO);
+        for _ in 0..2 {
+            cache
+                .get_or_load(&identifier, "s3://bucket/v1.metadata.json", load())
+                .await
+                .unwrap();
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+    }
+}
@@ -9,7 +9,10 @@
 //! All changes are made atomically - either all updates succeed or none are applied.
 //! Requirements are checked first to ensure concurrent modifications don't corrupt state.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use iceberg_rust_spec::{
     spec::{
@@ -18,9 +21,9 @@ use iceberg_rust_spec::{
         snapshot::{Snapshot, SnapshotReference},
         sort::SortOrder,
         table_metadata::TableMetadata,
-        view_metadata::{GeneralViewMetadata, Version},
+        view_metadata::{GeneralViewMetadata, Version, VersionLogStruct},
     },
-    table_metadata::SnapshotLog,
+    table_metadata::{FormatVersion, SnapshotLog},
     view_metadata::Materialization,
 };
 use serde_derive::{Deserialize, Serialize};
@@ -371,6 +374,44 @@ pub fn check_table_requirements(
     })
 }
 
+/// Validates that table metadata meets all specified requirements
+///
+/// In contrast to [check_table_requirements] this function reports which requirement failed.
+/// A reference that was moved by a concurrent commit results in an [Error::CommitConflict],
+/// which can be resolved by retrying the commit with reloaded metadata.
+///
+/// # Arguments
+/// * `requirements` - List of requirements that must be satisfied
+/// * `metadata` - Current table metadata to validate against
+///
+/// # Returns
+/// * `Ok(())` if all requirements are met
+/// * `Err(Error::CommitConflict)` if a reference points to a different snapshot
+/// * `Err(Error::InvalidFormat)` if any other requirement is not satisfied
+pub fn validate_table_requirements(
+    requirements: &[TableRequirement],
+    metadata: &TableMetadata,
+) -> Result<(), Error> {
+    for requirement in requirements {
+        if let TableRequirement::AssertRefSnapshotId { r#ref, snapshot_id } = requirement {
+            let actual = metadata.refs.get(r#ref).map(|x| x.snapshot_id);
+            if actual != Some(*snapshot_id) {
+                return Err(Error::CommitConflict {
+                    reference: r#ref.clone(),
+                    expected: *snapshot_id,
+                    actual: actual.unwrap_or(-1),
+                });
+            }
+        }
+    }
+    if !check_table_requirements(requirements, metadata) {
+        return Err(Error::InvalidFormat(
+            "Table requirements not valid".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
 /// Validates that view metadata meets all specified requirements
 ///
 /// This function checks if the current view metadata satisfies all the requirements
@@ -419,8 +460,18 @@ pub fn apply_table_updates(
 ) -> Result<(), Error> {
     for update in updates {
         match update {
-            TableUpdate::UpgradeFormatVersion { format_version: _ } => {
-                unimplemented!();
+            TableUpdate::UpgradeFormatVersion { format_version } => {
+                let format_version = u8::try_from(format_version)
+                    .map_err(|_| Error::Conversion(format_version.to_string(), "u8".to_owned()))
+                    .and_then(|x| FormatVersion::try_from(x).map_err(Error::from))?;
+                if format_version == FormatVersion::V1
+                    && metadata.format_version == FormatVersion::V2
+                {
+                    return Err(Error::NotSupported(
+                        "downgrading the table format version".to_owned(),
+                    ));
+                }
+                metadata.format_version = format_version;
             }
             TableUpdate::AssignUUID { uuid } => {
                 metadata.table_uuid = Uuid::parse_str(&uuid)?;
@@ -438,6 +489,9 @@ pub fn apply_table_updates(
                 metadata.current_schema_id = schema_id;
             }
             TableUpdate::AddPartitionSpec { spec } => {
+                if let Some(last_partition_id) = spec.fields().iter().map(|x| *x.field_id()).max() {
+                    metadata.last_partition_id = metadata.last_partition_id.max(last_partition_id);
+                }
                 metadata.partition_specs.insert(*spec.spec_id(), spec);
             }
             TableUpdate::SetDefaultSpec { spec_id } => {
@@ -467,6 +521,9 @@ pub fn apply_table_updates(
                 metadata.refs.insert(ref_name, snapshot_reference);
             }
             TableUpdate::RemoveSnapshots { snapshot_ids } => {
+                metadata
+                    .snapshot_log
+                    .retain(|entry| !snapshot_ids.contains(&entry.snapshot_id));
                 for id in snapshot_ids {
                     metadata.snapshots.remove(&id);
                 }
@@ -546,6 +603,13 @@ pub fn apply_view_updates<T: Materialization + 'static>(
             }
             ViewUpdate::SetCurrentViewVersion { view_version_id } => {
                 metadata.current_version_id = view_version_id;
+                metadata.version_log.push(VersionLogStruct {
+                    timestamp_ms: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as i64,
+                    version_id: view_version_id,
+                });
             }
         };
     }
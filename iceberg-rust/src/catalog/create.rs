@@ -10,7 +10,7 @@
 //! The module ensures proper initialization of metadata like UUIDs and timestamps.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -24,7 +24,7 @@ use iceberg_rust_spec::{
         schema::{Schema, DEFAULT_SCHEMA_ID},
         sort::{SortOrder, DEFAULT_SORT_ORDER_ID},
         table_metadata::TableMetadata,
-        view_metadata::{Version, ViewMetadata, DEFAULT_VERSION_ID},
+        view_metadata::{Version, ViewMetadata, ViewRepresentation, DEFAULT_VERSION_ID},
     },
     view_metadata::Materialization,
 };
@@ -33,7 +33,10 @@ use uuid::Uuid;
 
 use crate::{
     error::Error,
-    materialized_view::{MaterializedView, STORAGE_TABLE_POSTFIX},
+    materialized_view::{
+        MaterializedView, RefreshMode, REFRESH_MODE, SOURCE_TABLES, STORAGE_TABLE_POSTFIX,
+    },
+    sql::find_relations,
     table::Table,
     view::View,
 };
@@ -80,6 +83,10 @@ pub struct CreateTable {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[builder(setter(strip_option, each(name = "with_property")), default)]
     pub properties: Option<HashMap<String, String>>,
+    /// Capacity of the manifest list cache of the created table, not sent to the catalog
+    #[serde(skip)]
+    #[builder(setter(strip_option, name = "with_manifest_cache"), default)]
+    pub manifest_cache: Option<usize>,
 }
 
 impl CreateTableBuilder {
@@ -107,9 +114,15 @@ impl CreateTableBuilder {
         let identifier = Identifier::new(namespace, name);
 
         let create = self.create()?;
+        let manifest_cache = create.manifest_cache;
 
         // Register table in catalog
-        catalog.clone().create_table(identifier, create).await
+        let table = catalog.clone().create_table(identifier, create).await?;
+
+        Ok(match manifest_cache {
+            Some(capacity) => table.with_manifest_cache(capacity),
+            None => table,
+        })
     }
 }
 
@@ -175,7 +188,10 @@ impl TryInto<TableMetadata> for CreateTable {
 /// can be serialized/deserialized using serde.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Builder)]
 #[serde(rename_all = "kebab-case")]
-#[builder(build_fn(name = "create"), setter(prefix = "with"))]
+#[builder(
+    build_fn(name = "create", validate = "Self::validate"),
+    setter(prefix = "with")
+)]
 pub struct CreateView<T: Materialization> {
     /// Name of the view
     #[builder(setter(into))]
@@ -193,7 +209,40 @@ pub struct CreateView<T: Materialization> {
     pub properties: HashMap<String, String>,
 }
 
+impl<T: Materialization> CreateViewBuilder<T> {
+    /// Checks that the view version has at most one SQL representation per dialect
+    fn validate(&self) -> Result<(), String> {
+        let Some(version) = &self.view_version else {
+            return Ok(());
+        };
+        let mut dialects = HashSet::new();
+        for representation in version.representations() {
+            let ViewRepresentation::Sql { dialect, .. } = representation;
+            if !dialects.insert(dialect.to_lowercase()) {
+                return Err(format!(
+                    "View version has multiple representations for dialect {dialect}"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl CreateViewBuilder<Option<()>> {
+    /// Adds the SQL definition of the view in the given dialect to the representations of the
+    /// view version
+    ///
+    /// Creates a new view version if none was set. Engines pick the representation in their
+    /// dialect, so a view can be defined once for every dialect, like `"spark"` or `"trino"`.
+    ///
+    /// # Arguments
+    /// * `sql` - The SQL query that defines the view
+    /// * `dialect` - The SQL dialect of the query
+    pub fn with_sql(&mut self, sql: &str, dialect: &str) -> &mut Self {
+        push_sql(&mut self.view_version, sql, dialect);
+        self
+    }
+
     /// Builds and registers a new view in the catalog
     ///
     /// # Arguments
@@ -320,6 +369,25 @@ pub struct CreateMaterializedView {
 }
 
 impl CreateMaterializedViewBuilder {
+    /// Adds the SQL definition of the materialized view in the given dialect to the
+    /// representations of the view version
+    ///
+    /// Creates a new view version if none was set. The source tables of the view are extracted
+    /// from the first representation when the view is built.
+    ///
+    /// # Arguments
+    /// * `sql` - The SQL query that defines the view
+    /// * `dialect` - The SQL dialect of the query
+    pub fn with_sql(&mut self, sql: &str, dialect: &str) -> &mut Self {
+        push_sql(&mut self.view_version, sql, dialect);
+        self
+    }
+
+    /// Sets how the storage table of the materialized view is refreshed
+    pub fn with_refresh_mode(&mut self, refresh_mode: RefreshMode) -> &mut Self {
+        self.with_property((REFRESH_MODE.to_owned(), refresh_mode.to_string()))
+    }
+
     /// Builds and registers a new materialized view in the catalog
     ///
     /// # Arguments
@@ -333,6 +401,7 @@ impl CreateMaterializedViewBuilder {
     /// This method finalizes the materialized view configuration and registers it in the specified catalog.
     /// It automatically:
     /// * Sets default namespace and catalog values if not specified
+    /// * Records the fully qualified identifiers of the source tables of the view
     /// * Creates the underlying storage table with the appropriate name suffix
     /// * Registers both the view and its storage table in the catalog
     pub async fn build(
@@ -356,6 +425,21 @@ impl CreateMaterializedViewBuilder {
 
         let mut create = self.create()?;
 
+        if !create.properties.contains_key(SOURCE_TABLES) {
+            if let Some(ViewRepresentation::Sql { sql, .. }) =
+                create.view_version.representations.first()
+            {
+                let source_tables = qualified_source_tables(
+                    sql,
+                    catalog.name(),
+                    &create.view_version.default_namespace,
+                )?;
+                create
+                    .properties
+                    .insert(SOURCE_TABLES.to_owned(), source_tables.join(","));
+            }
+        }
+
         let version = Version {
             version_id: create.view_version.version_id,
             schema_id: create.view_version.schema_id,
@@ -381,6 +465,28 @@ impl CreateMaterializedViewBuilder {
     }
 }
 
+/// Returns the relations of a query as `catalog.namespace.name` identifiers
+///
+/// Relations without a catalog or namespace are resolved with the defaults of the view.
+fn qualified_source_tables(
+    sql: &str,
+    default_catalog: &str,
+    default_namespace: &[String],
+) -> Result<Vec<String>, Error> {
+    let default_namespace = default_namespace.join(".");
+    find_relations(sql)?
+        .into_iter()
+        .map(
+            |relation| match relation.split('.').collect::<Vec<_>>().as_slice() {
+                [name] => Ok(format!("{default_catalog}.{default_namespace}.{name}")),
+                [namespace, name] => Ok(format!("{default_catalog}.{namespace}.{name}")),
+                [_, _, _] => Ok(relation),
+                _ => Err(Error::InvalidFormat(format!("Source table {relation}"))),
+            },
+        )
+        .collect()
+}
+
 impl From<CreateMaterializedView> for (CreateView<FullIdentifier>, CreateTable) {
     fn from(val: CreateMaterializedView) -> Self {
         let storage_table = val.view_version.storage_table.name().to_owned();
@@ -400,7 +506,103 @@ impl From<CreateMaterializedView> for (CreateView<FullIdentifier>, CreateTable)
                 write_order: val.write_order,
                 stage_create: val.stage_create,
                 properties: val.table_properties,
+                manifest_cache: None,
             },
         )
     }
 }
+
+/// Adds the SQL representation to the view version, which is created if none was set
+fn push_sql<T: Materialization>(view_version: &mut Option<Version<T>>, sql: &str, dialect: &str) {
+    view_version
+        .get_or_insert_with(|| Version {
+            version_id: DEFAULT_VERSION_ID,
+            schema_id: DEFAULT_SCHEMA_ID,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as i64,
+            ..Default::default()
+        })
+        .representations
+        .push(ViewRepresentation::sql(sql, Some(dialect)));
+}
+
+#[cfg(test)]
+mod tests {
+    use iceberg_rust_spec::spec::{
+        schema::Schema,
+        types::{PrimitiveType, StructField, Type},
+        view_metadata::{ViewMetadata, ViewRepresentation, DEFAULT_VERSION_ID},
+    };
+
+    use super::{qualified_source_tables, CreateViewBuilder};
+
+    fn schema() -> Schema {
+        Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn create_view_with_multiple_dialects() {
+        let create = CreateViewBuilder::<Option<()>>::default()
+            .with_name("view")
+            .with_location("s3://bucket/view")
+            .with_schema(schema())
+            .with_sql("SELECT id FROM orders", "spark")
+            .with_sql("SELECT \"id\" FROM \"orders\"", "trino")
+            .create()
+            .unwrap();
+
+        let metadata: ViewMetadata = create.try_into().unwrap();
+        let version = metadata.current_version(None).unwrap();
+        assert_eq!(*version.version_id(), DEFAULT_VERSION_ID);
+        assert_eq!(
+            version.representations(),
+            &vec![
+                ViewRepresentation::sql("SELECT id FROM orders", Some("spark")),
+                ViewRepresentation::sql("SELECT \"id\" FROM \"orders\"", Some("trino")),
+            ]
+        );
+    }
+
+    #[test]
+    fn create_view_with_duplicate_dialect() {
+        let result = CreateViewBuilder::<Option<()>>::default()
+            .with_name("view")
+            .with_schema(schema())
+            .with_sql("SELECT id FROM orders", "spark")
+            .with_sql("SELECT * FROM orders", "Spark")
+            .create();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn source_tables_of_materialized_view() {
+        let source_tables = qualified_source_tables(
+            "SELECT o.id, c.name FROM orders o JOIN crm.customers c ON o.customer = c.id \
+             JOIN other.sales.regions r ON c.region = r.id",
+            "iceberg",
+            &["sales".to_owned()],
+        )
+        .unwrap();
+
+        assert_eq!(
+            source_tables,
+            vec![
+                "iceberg.sales.orders",
+                "iceberg.crm.customers",
+                "other.sales.regions"
+            ]
+        );
+    }
+}
@@ -12,6 +12,7 @@
 //! - [`CatalogList`]: Interface for managing multiple catalogs
 //! - [`namespace`]: Types for organizing tables into hierarchies
 //! - [`identifier`]: Types for uniquely identifying catalog objects
+//! - [`cache`]: Read-through cache for the metadata files loaded by catalogs
 //!
 //! # Common Operations
 //!
@@ -41,10 +42,20 @@ use self::namespace::Namespace;
 use self::tabular::Tabular;
 use crate::object_store::Bucket;
 
+pub mod cache;
 pub mod commit;
 pub mod create;
 pub mod tabular;
 
+/// A page of identifiers returned by [`Catalog::list_tables_paginated`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabularPage {
+    /// Identifiers of the tabular objects in the page
+    pub identifiers: Vec<Identifier>,
+    /// Token to request the next page with. None if this is the last page.
+    pub next_page_token: Option<String>,
+}
+
 /// A trait representing an Iceberg catalog that manages tables, views, and namespaces.
 ///
 /// The Catalog trait provides methods to:
@@ -161,6 +172,42 @@ pub trait Catalog: Send + Sync + Debug {
     /// * The catalog cannot be accessed
     /// * The listing operation fails
     async fn list_tabulars(&self, namespace: &Namespace) -> Result<Vec<Identifier>, Error>;
+    /// Lists one page of the tables, views, and materialized views in the given namespace.
+    ///
+    /// The default implementation lists all tabular objects of the namespace and orders them by
+    /// name. The page token is the name of the last identifier of the previous page.
+    ///
+    /// # Arguments
+    /// * `namespace` - The namespace to list tabular objects from
+    /// * `page_token` - Token returned with the previous page. If None, the first page is returned.
+    /// * `page_size` - Maximum number of identifiers in the page. If None, all remaining identifiers are returned.
+    ///
+    /// # Returns
+    /// * `Result<TabularPage, Error>` - The identifiers in the page and the token for the next page
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The namespace doesn't exist
+    /// * The page token is invalid
+    /// * The listing operation fails
+    async fn list_tables_paginated(
+        &self,
+        namespace: &Namespace,
+        page_token: Option<&str>,
+        page_size: Option<usize>,
+    ) -> Result<TabularPage, Error> {
+        let mut identifiers = self.list_tabulars(namespace).await?;
+        identifiers.sort_by(|left, right| left.name().cmp(right.name()));
+        identifiers.retain(|identifier| page_token.is_none_or(|token| identifier.name() > token));
+        let page_size = page_size.unwrap_or(identifiers.len()).max(1);
+        let next_page_token =
+            (identifiers.len() > page_size).then(|| identifiers[page_size - 1].name().to_owned());
+        identifiers.truncate(page_size);
+        Ok(TabularPage {
+            identifiers,
+            next_page_token,
+        })
+    }
     /// Lists all namespaces under an optional parent namespace.
     ///
     /// # Arguments
@@ -189,6 +236,19 @@ pub trait Catalog: Send + Sync + Debug {
     /// * The catalog cannot be accessed
     /// * The existence check operation fails
     async fn tabular_exists(&self, identifier: &Identifier) -> Result<bool, Error>;
+    /// Checks if a table exists in the catalog without loading its metadata.
+    ///
+    /// # Arguments
+    /// * `identifier` - The identifier of the table to check
+    ///
+    /// # Returns
+    /// * `Result<bool, Error>` - True if the table exists, false otherwise
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The catalog cannot be accessed
+    /// * The existence check operation fails
+    async fn table_exists(&self, identifier: &Identifier) -> Result<bool, Error>;
     /// Drops a table from the catalog and deletes all associated data and metadata files.
     ///
     /// # Arguments
@@ -248,6 +308,30 @@ pub trait Catalog: Send + Sync + Debug {
     /// * The metadata is invalid or corrupted
     /// * The catalog cannot be accessed
     async fn load_tabular(self: Arc<Self>, identifier: &Identifier) -> Result<Tabular, Error>;
+    /// Loads a table from the catalog.
+    ///
+    /// Use [Catalog::load_tabular] to load an entity that may also be a view or a materialized
+    /// view.
+    ///
+    /// # Arguments
+    /// * `identifier` - The identifier of the table to load
+    ///
+    /// # Returns
+    /// * `Result<Table, Error>` - The loaded table
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The tabular object can't be loaded
+    /// * The tabular object is a view or a materialized view
+    async fn load_table(self: Arc<Self>, identifier: &Identifier) -> Result<Table, Error> {
+        match self.load_tabular(identifier).await? {
+            Tabular::Table(table) => Ok(table),
+            _ => Err(Error::InvalidFormat(format!(
+                "Entity {} is not a table",
+                identifier
+            ))),
+        }
+    }
     /// Creates a new table in the catalog with the specified configuration.
     ///
     /// # Arguments
@@ -363,8 +447,32 @@ pub trait Catalog: Send + Sync + Debug {
         self: Arc<Self>,
         commit: CommitView<FullIdentifier>,
     ) -> Result<MaterializedView, Error>;
+    /// Commits updates to multiple tables atomically.
+    ///
+    /// Either all table commits are applied or none of them. The requirements of every commit are
+    /// validated against the current metadata of its table.
+    ///
+    /// # Arguments
+    /// * `changes` - The commit operations for the tables involved in the transaction
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Ok if all commits were applied
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The catalog doesn't support multi-table transactions
+    /// * One of the tables doesn't exist
+    /// * One of the commit operations is invalid
+    /// * Concurrent modifications conflict with one of the updates
+    async fn commit_transaction(&self, changes: Vec<CommitTable>) -> Result<(), Error> {
+        let _ = changes;
+        Err(Error::NotSupported("Multi-table transactions".to_owned()))
+    }
     /// Registers an existing table in the catalog using its metadata location.
     ///
+    /// The metadata file is read but not rewritten, the catalog only records the pointer to it.
+    /// This allows to adopt tables that were written by other Iceberg implementations.
+    ///
     /// # Arguments
     /// * `identifier` - The identifier to register the table under
     /// * `metadata_location` - Location of the table's metadata file
@@ -383,6 +491,23 @@ pub trait Catalog: Send + Sync + Debug {
         identifier: Identifier,
         metadata_location: &str,
     ) -> Result<Table, Error>;
+    /// Renames a table. The table can be moved to a different namespace.
+    ///
+    /// The metadata files of the table are not changed, only the catalog entry is updated.
+    ///
+    /// # Arguments
+    /// * `from` - The current identifier of the table
+    /// * `to` - The new identifier of the table
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Ok if the table was successfully renamed
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The table doesn't exist
+    /// * A tabular object already exists with the destination identifier
+    /// * The catalog fails to update the table entry
+    async fn rename_table(&self, from: &Identifier, to: &Identifier) -> Result<(), Error>;
     /// Returns an object store instance for the given bucket.
     ///
     /// # Arguments
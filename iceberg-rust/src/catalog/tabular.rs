@@ -4,11 +4,12 @@
 
 use std::sync::Arc;
 
-use iceberg_rust_spec::spec::tabular::TabularMetadata;
+use iceberg_rust_spec::{spec::tabular::TabularMetadata, util::strip_prefix};
 use object_store::ObjectStore;
 
 use crate::error::Error;
 use crate::materialized_view::MaterializedView;
+use crate::object_store::store::parse_metadata;
 use crate::table::Table;
 use crate::view::View;
 
@@ -64,15 +65,7 @@ impl Tabular {
     pub async fn reload(&mut self) -> Result<(), Error> {
         match self {
             Tabular::Table(table) => {
-                let new = if let Tabular::Table(table) =
-                    table.catalog().load_tabular(table.identifier()).await?
-                {
-                    Ok(table)
-                } else {
-                    Err(Error::InvalidFormat(
-                        "Tabular type from catalog response".to_string(),
-                    ))
-                }?;
+                let new = table.catalog().load_table(table.identifier()).await?;
                 let _ = std::mem::replace(table, new);
             }
             Tabular::View(view) => {
@@ -114,5 +107,184 @@ pub async fn get_tabular_metadata(
         .await?
         .bytes()
         .await?;
-    Ok(serde_json::from_slice(&bytes)?)
+    parse_metadata(&bytes)
+}
+
+/// Resolves the location of the current metadata file of a table from its version hint
+///
+/// Tables that are written without a transactional catalog, for example by a Hadoop catalog,
+/// point to their current metadata file with the `metadata/version-hint.text` file. The hint
+/// contains the version `N` of the metadata file `metadata/vN.metadata.json`. Hints that contain
+/// the location of the metadata file itself are supported as well.
+///
+/// # Arguments
+/// * `location` - The base location of the table, like `s3://bucket/warehouse/db/table`
+/// * `object_store` - The object store of the table location
+///
+/// # Errors
+/// Returns an error if the version hint doesn't exist or the metadata file of the version can't be found
+pub async fn version_hint_metadata_location(
+    location: &str,
+    object_store: &dyn ObjectStore,
+) -> Result<String, Error> {
+    let location = location.trim_end_matches('/');
+    let bytes = object_store
+        .get(&strip_prefix(&format!("{location}/metadata/version-hint.text")).into())
+        .await?
+        .bytes()
+        .await?;
+    let hint = std::str::from_utf8(&bytes)?.trim();
+
+    match hint.parse::<u64>() {
+        Ok(version) => {
+            // Metadata files may be gzip compressed
+            for metadata_location in [
+                format!("{location}/metadata/v{version}.metadata.json"),
+                format!("{location}/metadata/v{version}.gz.metadata.json"),
+            ] {
+                match object_store
+                    .head(&strip_prefix(&metadata_location).into())
+                    .await
+                {
+                    Ok(_) => return Ok(metadata_location),
+                    Err(object_store::Error::NotFound { .. }) => continue,
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            Err(Error::NotFound(format!(
+                "Metadata file of version {version} of table {location}"
+            )))
+        }
+        Err(_) if hint.contains('/') => Ok(hint.to_owned()),
+        Err(_) => Ok(format!("{location}/metadata/{hint}")),
+    }
+}
+
+/// Fetch the current metadata of a tabular structure from the version hint in its location
+///
+/// # Arguments
+/// * `location` - The base location of the tabular, like `s3://bucket/warehouse/db/table`
+/// * `object_store` - The object store of the location
+///
+/// # Returns
+/// * The location of the current metadata file and the parsed metadata
+///
+/// # Errors
+/// Returns an error if the version hint or the metadata file can't be read or parsed
+pub async fn get_tabular_metadata_from_version_hint(
+    location: &str,
+    object_store: Arc<dyn ObjectStore>,
+) -> Result<(String, TabularMetadata), Error> {
+    let metadata_location = version_hint_metadata_location(location, object_store.as_ref()).await?;
+    let bytes = object_store
+        .get(&strip_prefix(&metadata_location).into())
+        .await?
+        .bytes()
+        .await?;
+    Ok((metadata_location, parse_metadata(&bytes)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, sync::Arc};
+
+    use flate2::{write::GzEncoder, Compression};
+    use iceberg_rust_spec::util::strip_prefix;
+    use object_store::{memory::InMemory, ObjectStore};
+
+    use crate::test_util::VIEW_METADATA;
+
+    use super::{get_tabular_metadata_from_version_hint, version_hint_metadata_location};
+
+    async fn put(object_store: &dyn ObjectStore, location: &str, bytes: Vec<u8>) {
+        object_store
+            .put(&strip_prefix(location).into(), bytes.into())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn read_version_hint() {
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let location = "s3://bucket/warehouse/default.db/event_agg";
+
+        assert!(
+            version_hint_metadata_location(location, object_store.as_ref())
+                .await
+                .is_err()
+        );
+
+        put(
+            object_store.as_ref(),
+            &format!("{location}/metadata/v1.metadata.json"),
+            VIEW_METADATA.as_bytes().to_vec(),
+        )
+        .await;
+        put(
+            object_store.as_ref(),
+            &format!("{location}/metadata/version-hint.text"),
+            b"1\n".to_vec(),
+        )
+        .await;
+
+        let (metadata_location, _) =
+            get_tabular_metadata_from_version_hint(location, object_store.clone())
+                .await
+                .unwrap();
+        assert_eq!(
+            metadata_location,
+            format!("{location}/metadata/v1.metadata.json")
+        );
+
+        // Gzip compressed metadata files
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(VIEW_METADATA.as_bytes()).unwrap();
+        put(
+            object_store.as_ref(),
+            &format!("{location}/metadata/v2.gz.metadata.json"),
+            encoder.finish().unwrap(),
+        )
+        .await;
+        put(
+            object_store.as_ref(),
+            &format!("{location}/metadata/version-hint.text"),
+            b"2".to_vec(),
+        )
+        .await;
+
+        let (metadata_location, _) =
+            get_tabular_metadata_from_version_hint(location, object_store.clone())
+                .await
+                .unwrap();
+        assert_eq!(
+            metadata_location,
+            format!("{location}/metadata/v2.gz.metadata.json")
+        );
+
+        // Hints with the location of the metadata file
+        put(
+            object_store.as_ref(),
+            &format!("{location}/metadata/version-hint.text"),
+            format!("{location}/metadata/v1.metadata.json").into_bytes(),
+        )
+        .await;
+        assert_eq!(
+            version_hint_metadata_location(location, object_store.as_ref())
+                .await
+                .unwrap(),
+            format!("{location}/metadata/v1.metadata.json")
+        );
+
+        put(
+            object_store.as_ref(),
+            &format!("{location}/metadata/version-hint.text"),
+            b"3".to_vec(),
+        )
+        .await;
+        assert!(
+            version_hint_metadata_location(location, object_store.as_ref())
+                .await
+                .is_err()
+        );
+    }
 }
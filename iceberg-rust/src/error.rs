@@ -29,6 +29,36 @@ pub enum Error {
     /// Not found in catalog
     #[error("Entity not found in catalog")]
     CatalogNotFound,
+    /// A concurrent commit changed the snapshot of a reference
+    #[error(
+        "Commit conflict: reference {reference} points to snapshot {actual}, expected {expected}."
+    )]
+    CommitConflict {
+        /// Name of the branch or tag
+        reference: String,
+        /// Snapshot id the commit was based on
+        expected: i64,
+        /// Snapshot id the reference currently points to, -1 if the reference doesn't exist
+        actual: i64,
+    },
+    /// A commit was overtaken by concurrent commits in every attempt
+    #[error("Commit conflict: {identifier} was updated concurrently in all {attempts} attempts.")]
+    ConcurrentCommits {
+        /// Identifier of the table or view
+        identifier: String,
+        /// Number of attempts to commit
+        attempts: usize,
+    },
+    /// A snapshot is not in the history of a branch
+    #[error("Snapshot {snapshot_id} is not an ancestor of snapshot {head} of branch {branch}.")]
+    NotAncestor {
+        /// Name of the branch
+        branch: String,
+        /// Snapshot id that was expected in the history of the branch
+        snapshot_id: i64,
+        /// Snapshot id the branch currently points to, -1 if the branch doesn't exist
+        head: i64,
+    },
     /// External error
     #[error(transparent)]
     External(Box<dyn std::error::Error + Send + Sync>),
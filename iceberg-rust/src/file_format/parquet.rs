@@ -3,7 +3,7 @@
 */
 
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     sync::Arc,
 };
 
@@ -16,9 +16,19 @@ use iceberg_rust_spec::{
         types::Type,
         values::{Struct, Value},
     },
+    table_metadata::{
+        TableMetadata, WRITE_PARQUET_COMPRESSION_CODEC, WRITE_PARQUET_COMPRESSION_LEVEL,
+        WRITE_PARQUET_PAGE_SIZE_BYTES, WRITE_PARQUET_ROW_GROUP_SIZE_BYTES,
+        WRITE_TARGET_FILE_SIZE_BYTES,
+    },
 };
 use parquet::{
-    file::{metadata::RowGroupMetaData, writer::TrackedWrite},
+    basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel},
+    file::{
+        metadata::{ParquetMetaData, RowGroupMetaData},
+        properties::WriterProperties,
+        writer::TrackedWrite,
+    },
     format::FileMetaData,
     schema::types::{from_thrift, SchemaDescriptor},
 };
@@ -26,7 +36,258 @@ use thrift::protocol::{TCompactOutputProtocol, TSerializable};
 
 use crate::error::Error;
 
+/// Default target size of a row group, same as the Java implementation
+pub const DEFAULT_ROW_GROUP_SIZE_BYTES: usize = 128 * 1024 * 1024;
+
+/// Default target size of a data file, same as the Java implementation
+pub const DEFAULT_TARGET_FILE_SIZE_BYTES: usize = 512 * 1024 * 1024;
+
+/// Parquet writer configuration derived from the table properties
+pub trait ParquetProperties {
+    /// Creates the parquet writer properties from the compression codec, compression level
+    /// and page size properties of the table
+    fn parquet_writer_properties(&self) -> Result<WriterProperties, Error>;
+    /// Returns the target size of a row group in bytes
+    fn parquet_row_group_size_bytes(&self) -> Result<usize, Error>;
+    /// Returns the size in bytes after which a new data file is started
+    fn target_file_size_bytes(&self) -> Result<usize, Error>;
+}
+
+impl ParquetProperties for TableMetadata {
+    fn parquet_writer_properties(&self) -> Result<WriterProperties, Error> {
+        let level = self
+            .properties
+            .get(WRITE_PARQUET_COMPRESSION_LEVEL)
+            .map(|x| x.parse::<i32>())
+            .transpose()?;
+
+        let compression = match self
+            .properties
+            .get(WRITE_PARQUET_COMPRESSION_CODEC)
+            .map(|x| x.to_lowercase())
+            .as_deref()
+        {
+            None | Some("zstd") => Compression::ZSTD(ZstdLevel::try_new(level.unwrap_or(1))?),
+            Some("gzip") => Compression::GZIP(GzipLevel::try_new(
+                level.map(u32::try_from).transpose()?.unwrap_or(6),
+            )?),
+            Some("brotli") => Compression::BROTLI(BrotliLevel::try_new(
+                level.map(u32::try_from).transpose()?.unwrap_or(1),
+            )?),
+            Some("snappy") => Compression::SNAPPY,
+            Some("lz4") => Compression::LZ4_RAW,
+            Some("uncompressed") => Compression::UNCOMPRESSED,
+            Some(codec) => {
+                return Err(Error::NotSupported(format!(
+                    "Parquet compression codec {}",
+                    codec
+                )))
+            }
+        };
+
+        let mut builder = WriterProperties::builder().set_compression(compression);
+
+        if let Some(page_size) = self.properties.get(WRITE_PARQUET_PAGE_SIZE_BYTES) {
+            builder = builder.set_data_page_size_limit(page_size.parse()?);
+        }
+
+        Ok(builder.build())
+    }
+
+    fn parquet_row_group_size_bytes(&self) -> Result<usize, Error> {
+        Ok(self
+            .properties
+            .get(WRITE_PARQUET_ROW_GROUP_SIZE_BYTES)
+            .map(|x| x.parse())
+            .transpose()?
+            .unwrap_or(DEFAULT_ROW_GROUP_SIZE_BYTES))
+    }
+
+    fn target_file_size_bytes(&self) -> Result<usize, Error> {
+        Ok(self
+            .properties
+            .get(WRITE_TARGET_FILE_SIZE_BYTES)
+            .map(|x| x.parse())
+            .transpose()?
+            .unwrap_or(DEFAULT_TARGET_FILE_SIZE_BYTES))
+    }
+}
+
+/// Default length to which string and binary bounds are truncated, same as the Java implementation
+pub const DEFAULT_TRUNCATE_LENGTH: usize = 16;
+
+/// Column statistics of a data file keyed by field id
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DataFileStats {
+    /// Compressed size of the column chunks
+    pub column_sizes: HashMap<i32, i64>,
+    /// Number of values including nulls
+    pub value_counts: HashMap<i32, i64>,
+    /// Number of null values
+    pub null_value_counts: HashMap<i32, i64>,
+    /// Number of distinct values
+    pub distinct_counts: HashMap<i32, i64>,
+    /// Lower bounds of primitive columns
+    pub lower_bounds: HashMap<i32, Value>,
+    /// Upper bounds of primitive columns
+    pub upper_bounds: HashMap<i32, Value>,
+}
+
+/// Computes the column statistics of a data file from the statistics of its parquet row groups.
+///
+/// String and binary bounds are truncated to [`DEFAULT_TRUNCATE_LENGTH`]. Truncated upper bounds
+/// are incremented so that they remain upper bounds. If a row group has no min/max statistics
+/// for a column with non-null values, the column gets no bounds. Parquet doesn't record NaN
+/// counts, so they are not part of the statistics.
+pub fn datafile_stats_from_parquet(
+    metadata: &ParquetMetaData,
+    schema: &Schema,
+) -> Result<DataFileStats, Error> {
+    stats_from_row_groups(metadata.row_groups(), schema)
+}
+
+fn stats_from_row_groups(
+    row_groups: &[RowGroupMetaData],
+    schema: &Schema,
+) -> Result<DataFileStats, Error> {
+    let mut stats = DataFileStats::default();
+    // Columns for which at least one row group has no lower or upper bound
+    let mut unbounded_lower = HashSet::new();
+    let mut unbounded_upper = HashSet::new();
+
+    for row_group in row_groups {
+        for column in row_group.columns() {
+            let column_name = column.column_descr().path().string();
+            let field = schema
+                .get_name(&column_name)
+                .ok_or_else(|| Error::Schema(column_name.clone(), "".to_string()))?;
+            let id = field.id;
+
+            *stats.column_sizes.entry(id).or_insert(0) += column.compressed_size();
+            *stats.value_counts.entry(id).or_insert(0) += row_group.num_rows();
+
+            let Some(statistics) = column.statistics() else {
+                unbounded_lower.insert(id);
+                unbounded_upper.insert(id);
+                continue;
+            };
+
+            let null_count = statistics.null_count_opt();
+            if let Some(null_count) = null_count {
+                *stats.null_value_counts.entry(id).or_insert(0) += null_count as i64;
+            }
+            if let Some(distinct_count) = statistics.distinct_count_opt() {
+                *stats.distinct_counts.entry(id).or_insert(0) += distinct_count as i64;
+            }
+
+            if !matches!(field.field_type, Type::Primitive(_)) {
+                continue;
+            }
+            let only_nulls = null_count.is_some_and(|x| x as i64 == row_group.num_rows());
+
+            match statistics.min_bytes_opt() {
+                Some(min_bytes) => {
+                    let min = truncate_lower_bound(
+                        Value::try_from_bytes(min_bytes, &field.field_type)?,
+                        DEFAULT_TRUNCATE_LENGTH,
+                    );
+                    match stats.lower_bounds.entry(id) {
+                        Entry::Occupied(mut entry) => {
+                            if min < *entry.get() {
+                                entry.insert(min);
+                            }
+                        }
+                        Entry::Vacant(entry) => {
+                            entry.insert(min);
+                        }
+                    }
+                }
+                None if !only_nulls => {
+                    unbounded_lower.insert(id);
+                }
+                None => (),
+            }
+
+            match statistics.max_bytes_opt().map(|max_bytes| {
+                Value::try_from_bytes(max_bytes, &field.field_type)
+                    .map(|max| truncate_upper_bound(max, DEFAULT_TRUNCATE_LENGTH))
+            }) {
+                Some(Ok(Some(max))) => match stats.upper_bounds.entry(id) {
+                    Entry::Occupied(mut entry) => {
+                        if max > *entry.get() {
+                            entry.insert(max);
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(max);
+                    }
+                },
+                Some(Err(err)) => return Err(err.into()),
+                Some(Ok(None)) => {
+                    unbounded_upper.insert(id);
+                }
+                None if !only_nulls => {
+                    unbounded_upper.insert(id);
+                }
+                None => (),
+            }
+        }
+    }
+
+    for id in unbounded_lower {
+        stats.lower_bounds.remove(&id);
+    }
+    for id in unbounded_upper {
+        stats.upper_bounds.remove(&id);
+    }
+
+    Ok(stats)
+}
+
+/// Truncates string and binary lower bounds to the given length
+fn truncate_lower_bound(value: Value, length: usize) -> Value {
+    match value {
+        Value::String(x) if x.chars().count() > length => {
+            Value::String(x.chars().take(length).collect())
+        }
+        Value::Binary(x) if x.len() > length => Value::Binary(x[..length].to_vec()),
+        x => x,
+    }
+}
+
+/// Truncates string and binary upper bounds to the given length. The last character or byte
+/// is incremented, so that the truncated value is still an upper bound. Returns None if no
+/// truncated upper bound exists.
+fn truncate_upper_bound(value: Value, length: usize) -> Option<Value> {
+    match value {
+        Value::String(x) if x.chars().count() > length => {
+            let mut chars: Vec<char> = x.chars().take(length).collect();
+            while let Some(last) = chars.pop() {
+                if let Some(next) = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32) {
+                    chars.push(next);
+                    return Some(Value::String(chars.into_iter().collect()));
+                }
+            }
+            None
+        }
+        Value::Binary(x) if x.len() > length => {
+            let mut bytes = x[..length].to_vec();
+            while let Some(last) = bytes.pop() {
+                if last < u8::MAX {
+                    bytes.push(last + 1);
+                    return Some(Value::Binary(bytes));
+                }
+            }
+            None
+        }
+        x => Some(x),
+    }
+}
+
 /// Read datafile statistics from parquetfile
+///
+/// `sort_order_id` is the id of the sort order the rows of the file are sorted by, `None` if
+/// the file is unsorted.
 pub fn parquet_to_datafile(
     location: &str,
     file_size: usize,
@@ -34,6 +295,7 @@ pub fn parquet_to_datafile(
     schema: &Schema,
     partition_fields: &[BoundPartitionField<'_>],
     equality_ids: Option<&[i32]>,
+    sort_order_id: Option<i32>,
 ) -> Result<DataFile, Error> {
     let mut partition = partition_fields
         .iter()
@@ -50,190 +312,50 @@ pub fn parquet_to_datafile(
         .collect::<Result<HashMap<String, PartitionField>, Error>>()?;
     let parquet_schema = Arc::new(SchemaDescriptor::new(from_thrift(&file_metadata.schema)?));
 
-    let mut column_sizes = AvroMap(HashMap::new());
-    let mut value_counts = AvroMap(HashMap::new());
-    let mut null_value_counts = AvroMap(HashMap::new());
-    let mut distinct_counts = AvroMap(HashMap::new());
-    let mut lower_bounds: HashMap<i32, Value> = HashMap::new();
-    let mut upper_bounds: HashMap<i32, Value> = HashMap::new();
+    let row_groups = file_metadata
+        .row_groups
+        .iter()
+        .map(|row_group| RowGroupMetaData::from_thrift(parquet_schema.clone(), row_group.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    for row_group in &file_metadata.row_groups {
-        let row_group = RowGroupMetaData::from_thrift(parquet_schema.clone(), row_group.clone())?;
+    let stats = stats_from_row_groups(&row_groups, schema)?;
 
+    // The partition values are derived from the untruncated bounds of the source columns
+    for row_group in &row_groups {
         for column in row_group.columns() {
-            let column_name = column.column_descr().name();
-            let id = schema
-                .get_name(column_name)
-                .ok_or_else(|| Error::Schema(column_name.to_string(), "".to_string()))?
-                .id;
-            column_sizes
-                .entry(id)
-                .and_modify(|x| *x += column.compressed_size())
-                .or_insert(column.compressed_size());
-            value_counts
-                .entry(id)
-                .and_modify(|x| *x += row_group.num_rows())
-                .or_insert(row_group.num_rows());
-
-            if let Some(statistics) = column.statistics() {
-                if let Some(null_count) = statistics.null_count_opt() {
-                    null_value_counts
-                        .entry(id)
-                        .and_modify(|x| *x += null_count as i64)
-                        .or_insert(null_count as i64);
-                }
-                if let Some(distinct_count) = statistics.distinct_count_opt() {
-                    distinct_counts
-                        .entry(id)
-                        .and_modify(|x| *x += distinct_count as i64)
-                        .or_insert(distinct_count as i64);
-                }
-                let data_type = &schema
-                    .fields()
-                    .get(id as usize)
-                    .ok_or_else(|| Error::Schema(column_name.to_string(), "".to_string()))?
-                    .field_type;
-
-                if let Some(min_bytes) = statistics.min_bytes_opt() {
-                    if let Type::Primitive(_) = &data_type {
-                        let new = Value::try_from_bytes(min_bytes, data_type)?;
-                        match lower_bounds.entry(id) {
-                            Entry::Occupied(mut entry) => {
-                                let entry = entry.get_mut();
-                                match (&entry, &new) {
-                                    (Value::Int(current), Value::Int(new_val)) => {
-                                        if *current > *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::LongInt(current), Value::LongInt(new_val)) => {
-                                        if *current > *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Float(current), Value::Float(new_val)) => {
-                                        if *current > *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Double(current), Value::Double(new_val)) => {
-                                        if *current > *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Date(current), Value::Date(new_val)) => {
-                                        if *current > *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Time(current), Value::Time(new_val)) => {
-                                        if *current > *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Timestamp(current), Value::Timestamp(new_val)) => {
-                                        if *current > *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::TimestampTZ(current), Value::TimestampTZ(new_val)) => {
-                                        if *current > *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    _ => (),
-                                }
-                            }
-                            Entry::Vacant(entry) => {
-                                entry.insert(new);
-                            }
-                        }
-                    }
-                    if let Some(max_bytes) = statistics.max_bytes_opt() {
-                        let new = Value::try_from_bytes(max_bytes, data_type)?;
-                        match upper_bounds.entry(id) {
-                            Entry::Occupied(mut entry) => {
-                                let entry = entry.get_mut();
-                                match (&entry, &new) {
-                                    (Value::Int(current), Value::Int(new_val)) => {
-                                        if *current < *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::LongInt(current), Value::LongInt(new_val)) => {
-                                        if *current < *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Float(current), Value::Float(new_val)) => {
-                                        if *current < *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Double(current), Value::Double(new_val)) => {
-                                        if *current < *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Date(current), Value::Date(new_val)) => {
-                                        if *current < *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Time(current), Value::Time(new_val)) => {
-                                        if *current < *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::Timestamp(current), Value::Timestamp(new_val)) => {
-                                        if *current < *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    (Value::TimestampTZ(current), Value::TimestampTZ(new_val)) => {
-                                        if *current < *new_val {
-                                            *entry = new
-                                        }
-                                    }
-                                    _ => (),
-                                }
-                            }
-                            Entry::Vacant(entry) => {
-                                entry.insert(new);
-                            }
-                        }
-
-                        if let Some(partition_field) = partition_fields.get(column_name) {
-                            if let Some(partition_value) = partition.get_mut(partition_field.name())
-                            {
-                                if partition_value.is_none() {
-                                    let partition_field =
-                                        partition_fields.get(column_name).ok_or_else(|| {
-                                            Error::InvalidFormat("transform".to_string())
-                                        })?;
-                                    if let (Some(min_bytes), Some(max_bytes)) =
-                                        (statistics.min_bytes_opt(), statistics.max_bytes_opt())
-                                    {
-                                        let min = Value::try_from_bytes(min_bytes, data_type)?
-                                            .transform(partition_field.transform())?;
-                                        let max = Value::try_from_bytes(max_bytes, data_type)?
-                                            .transform(partition_field.transform())?;
-                                        if min == max {
-                                            *partition_value = Some(min)
-                                        } else {
-                                            return Err(Error::InvalidFormat(
-                                                "Partition value of data file".to_owned(),
-                                            ));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+            let column_name = column.column_descr().path().string();
+            let Some(partition_field) = partition_fields.get(&column_name) else {
+                continue;
+            };
+            let Some(partition_value) = partition.get_mut(partition_field.name()) else {
+                continue;
+            };
+            if partition_value.is_some() {
+                continue;
+            }
+            let data_type = &schema
+                .get_name(&column_name)
+                .ok_or_else(|| Error::Schema(column_name.clone(), "".to_string()))?
+                .field_type;
+            if let Some((min_bytes, max_bytes)) = column
+                .statistics()
+                .and_then(|x| x.min_bytes_opt().zip(x.max_bytes_opt()))
+            {
+                let min = Value::try_from_bytes(min_bytes, data_type)?
+                    .transform(partition_field.transform())?;
+                let max = Value::try_from_bytes(max_bytes, data_type)?
+                    .transform(partition_field.transform())?;
+                if min == max {
+                    *partition_value = Some(min)
+                } else {
+                    return Err(Error::InvalidFormat(
+                        "Partition value of data file".to_owned(),
+                    ));
                 }
             }
         }
     }
+
     let mut builder = DataFile::builder();
     builder
         .with_content(if equality_ids.is_none() {
@@ -246,13 +368,14 @@ pub fn parquet_to_datafile(
         .with_partition(partition)
         .with_record_count(file_metadata.num_rows)
         .with_file_size_in_bytes(file_size as i64)
-        .with_column_sizes(Some(column_sizes))
-        .with_value_counts(Some(value_counts))
-        .with_null_value_counts(Some(null_value_counts))
+        .with_column_sizes(Some(AvroMap(stats.column_sizes)))
+        .with_value_counts(Some(AvroMap(stats.value_counts)))
+        .with_null_value_counts(Some(AvroMap(stats.null_value_counts)))
         .with_nan_value_counts(None)
-        .with_distinct_counts(Some(distinct_counts))
-        .with_lower_bounds(Some(lower_bounds))
-        .with_upper_bounds(Some(upper_bounds));
+        .with_distinct_counts(Some(AvroMap(stats.distinct_counts)))
+        .with_lower_bounds(Some(stats.lower_bounds))
+        .with_upper_bounds(Some(stats.upper_bounds))
+        .with_sort_order_id(sort_order_id);
 
     if let Some(equality_ids) = equality_ids {
         builder.with_equality_ids(Some(equality_ids.to_vec()));
@@ -271,3 +394,45 @@ pub fn thrift_size<T: TSerializable>(metadata: &T) -> Result<usize, Error> {
     metadata.write_to_out_protocol(&mut protocol)?;
     Ok(buffer.bytes_written())
 }
+
+#[cfg(test)]
+mod tests {
+    use iceberg_rust_spec::spec::values::Value;
+
+    use super::{truncate_lower_bound, truncate_upper_bound};
+
+    #[test]
+    fn test_truncate_string_bounds() {
+        let value = Value::String("iceberg-rust-parquet".to_owned());
+        assert_eq!(
+            truncate_lower_bound(value.clone(), 7),
+            Value::String("iceberg".to_owned())
+        );
+        assert_eq!(
+            truncate_upper_bound(value, 7),
+            Some(Value::String("iceberh".to_owned()))
+        );
+        assert_eq!(
+            truncate_upper_bound(Value::String("short".to_owned()), 7),
+            Some(Value::String("short".to_owned()))
+        );
+        assert_eq!(
+            truncate_upper_bound(Value::String("a\u{10FFFF}b".to_owned()), 2),
+            Some(Value::String("b".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_truncate_binary_bounds() {
+        let value = Value::Binary(vec![1, 255, 3]);
+        assert_eq!(
+            truncate_lower_bound(value.clone(), 2),
+            Value::Binary(vec![1, 255])
+        );
+        assert_eq!(truncate_upper_bound(value, 2), Some(Value::Binary(vec![2])));
+        assert_eq!(
+            truncate_upper_bound(Value::Binary(vec![255, 255, 0]), 2),
+            None
+        );
+    }
+}
@@ -0,0 +1,477 @@
+/*!
+Pluggable IO layer for the files of a table
+
+All files of a table, like metadata files, manifests and data files, are read and written through
+a [`FileIO`]. The default implementation [`ObjectStoreFileIO`] wraps an [`ObjectStore`].
+Systems that already have their own IO layer, for example a custom HDFS client or a cache, can
+implement [`FileIO`] and pass it to [`Table::with_file_io`](crate::table::Table::with_file_io).
+
+Engines that require an [`ObjectStore`], like the Parquet reader and writer, access a custom
+[`FileIO`] through the [`FileIOObjectStore`] adapter.
+*/
+
+use std::{fmt::Display, ops::Range, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    FutureExt, StreamExt,
+};
+use iceberg_rust_spec::util::strip_prefix;
+use object_store::{
+    path::Path, Attributes, GetOptions, GetRange, GetResult, GetResultPayload, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload,
+    PutResult, UploadPart,
+};
+
+use crate::error::Error;
+
+/// Creates the files that are read and written by a table
+///
+/// Locations are full paths including the scheme and bucket, like `s3://bucket/path/file.avro`.
+#[async_trait]
+pub trait FileIO: std::fmt::Debug + Send + Sync {
+    /// Returns a file that can be read from
+    fn new_input(&self, location: &str) -> Result<Box<dyn InputFile>, Error>;
+    /// Returns a file that can be written to
+    fn new_output(&self, location: &str) -> Result<Box<dyn OutputFile>, Error>;
+    /// Deletes the file at the location
+    async fn delete(&self, location: &str) -> Result<(), Error>;
+}
+
+/// A file that can be read from
+///
+/// A file that doesn't exist should be reported as [`Error::NotFound`].
+#[async_trait]
+pub trait InputFile: Send + Sync {
+    /// Location of the file
+    fn location(&self) -> &str;
+    /// Returns the size of the file in bytes
+    async fn length(&self) -> Result<u64, Error>;
+    /// Reads the whole file
+    async fn read(&self) -> Result<Bytes, Error>;
+    /// Reads the given byte range of the file
+    async fn read_range(&self, range: Range<u64>) -> Result<Bytes, Error>;
+}
+
+/// A file that can be written to
+#[async_trait]
+pub trait OutputFile: Send + Sync {
+    /// Location of the file
+    fn location(&self) -> &str;
+    /// Writes the file, an existing file is overwritten
+    async fn write(&self, bytes: Bytes) -> Result<(), Error>;
+    /// Writes the file only if it doesn't exist yet
+    ///
+    /// Returns an error instead of overwriting the file of another writer.
+    async fn create(&self, bytes: Bytes) -> Result<(), Error>;
+}
+
+/// [`FileIO`] that reads and writes the files with an [`ObjectStore`]
+#[derive(Debug, Clone)]
+pub struct ObjectStoreFileIO {
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreFileIO {
+    /// Creates a new file io for the object store
+    pub fn new(object_store: Arc<dyn ObjectStore>) -> Self {
+        Self { object_store }
+    }
+
+    /// Returns the wrapped object store
+    pub fn object_store(&self) -> Arc<dyn ObjectStore> {
+        self.object_store.clone()
+    }
+}
+
+#[async_trait]
+impl FileIO for ObjectStoreFileIO {
+    fn new_input(&self, location: &str) -> Result<Box<dyn InputFile>, Error> {
+        Ok(Box::new(ObjectStoreFile::new(
+            location,
+            self.object_store.clone(),
+        )))
+    }
+
+    fn new_output(&self, location: &str) -> Result<Box<dyn OutputFile>, Error> {
+        Ok(Box::new(ObjectStoreFile::new(
+            location,
+            self.object_store.clone(),
+        )))
+    }
+
+    async fn delete(&self, location: &str) -> Result<(), Error> {
+        self.object_store
+            .delete(&strip_prefix(location).into())
+            .await?;
+        Ok(())
+    }
+}
+
+/// Input and output file of an [`ObjectStoreFileIO`]
+struct ObjectStoreFile {
+    location: String,
+    path: Path,
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreFile {
+    fn new(location: &str, object_store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            location: location.to_owned(),
+            path: strip_prefix(location).into(),
+            object_store,
+        }
+    }
+}
+
+#[async_trait]
+impl InputFile for ObjectStoreFile {
+    fn location(&self) -> &str {
+        &self.location
+    }
+
+    async fn length(&self) -> Result<u64, Error> {
+        Ok(u64::try_from(
+            self.object_store.head(&self.path).await?.size,
+        )?)
+    }
+
+    async fn read(&self) -> Result<Bytes, Error> {
+        Ok(self.object_store.get(&self.path).await?.bytes().await?)
+    }
+
+    async fn read_range(&self, range: Range<u64>) -> Result<Bytes, Error> {
+        let range = usize::try_from(range.start)?..usize::try_from(range.end)?;
+        Ok(self.object_store.get_range(&self.path, range).await?)
+    }
+}
+
+#[async_trait]
+impl OutputFile for ObjectStoreFile {
+    fn location(&self) -> &str {
+        &self.location
+    }
+
+    async fn write(&self, bytes: Bytes) -> Result<(), Error> {
+        self.object_store.put(&self.path, bytes.into()).await?;
+        Ok(())
+    }
+
+    async fn create(&self, bytes: Bytes) -> Result<(), Error> {
+        let result = self
+            .object_store
+            .put_opts(&self.path, bytes.clone().into(), PutMode::Create.into())
+            .await;
+        match result {
+            Ok(_) => Ok(()),
+            // Check for an existing file if the object store doesn't support conditional puts
+            Err(object_store::Error::NotImplemented) => {
+                match self.object_store.head(&self.path).await {
+                    Ok(_) => Err(object_store::Error::AlreadyExists {
+                        path: self.path.to_string(),
+                        source: format!("File {} already exists", self.location).into(),
+                    }
+                    .into()),
+                    Err(object_store::Error::NotFound { .. }) => {
+                        self.object_store.put(&self.path, bytes.into()).await?;
+                        Ok(())
+                    }
+                    Err(err) => Err(err.into()),
+                }
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Exposes a [`FileIO`] as an [`ObjectStore`] for the files of one bucket
+///
+/// The paths of the object store are prefixed with the bucket to get the locations of the files.
+/// Listing files is not supported. Multipart uploads are buffered in memory and written with a
+/// single write when they are completed.
+#[derive(Debug)]
+pub struct FileIOObjectStore {
+    file_io: Arc<dyn FileIO>,
+    prefix: String,
+}
+
+impl FileIOObjectStore {
+    /// Creates a new object store for the files with the given prefix, like `s3://bucket`
+    pub fn new(file_io: Arc<dyn FileIO>, prefix: &str) -> Self {
+        Self {
+            file_io,
+            prefix: prefix.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    fn location(&self, path: &Path) -> String {
+        format!("{}/{}", self.prefix, path)
+    }
+
+    async fn meta(&self, path: &Path, input: &dyn InputFile) -> object_store::Result<ObjectMeta> {
+        let size = input
+            .length()
+            .await
+            .map_err(|err| object_store_error(err, path))?;
+        Ok(ObjectMeta {
+            location: path.clone(),
+            // The file io doesn't provide the modification time
+            last_modified: Default::default(),
+            size: usize::try_from(size).map_err(|err| object_store_error(err.into(), path))?,
+            e_tag: None,
+            version: None,
+        })
+    }
+}
+
+impl Display for FileIOObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FileIOObjectStore({})", self.prefix)
+    }
+}
+
+/// Converts the error of a [`FileIO`] into an object store error
+fn object_store_error(err: Error, path: &Path) -> object_store::Error {
+    match err {
+        Error::ObjectStore(err) => err,
+        Error::NotFound(_) => object_store::Error::NotFound {
+            path: path.to_string(),
+            source: Box::new(err),
+        },
+        err => object_store::Error::Generic {
+            store: "FileIO",
+            source: Box::new(err),
+        },
+    }
+}
+
+/// Returns the byte range of a get request for a file with the given size
+fn get_range(range: &GetRange, size: usize) -> Range<usize> {
+    match range {
+        GetRange::Bounded(range) => range.start.min(size)..range.end.min(size),
+        GetRange::Offset(offset) => (*offset).min(size)..size,
+        GetRange::Suffix(suffix) => size.saturating_sub(*suffix)..size,
+    }
+}
+
+#[async_trait]
+impl ObjectStore for FileIOObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        let output = self
+            .file_io
+            .new_output(&self.location(location))
+            .map_err(|err| object_store_error(err, location))?;
+        match opts.mode {
+            PutMode::Overwrite => output.write(payload.into()).await,
+            PutMode::Create => output.create(payload.into()).await,
+            PutMode::Update(_) => return Err(object_store::Error::NotImplemented),
+        }
+        .map_err(|err| object_store_error(err, location))?;
+        Ok(PutResult {
+            e_tag: None,
+            version: None,
+        })
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        _opts: PutMultipartOpts,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        let output = self
+            .file_io
+            .new_output(&self.location(location))
+            .map_err(|err| object_store_error(err, location))?;
+        Ok(Box::new(BufferedUpload {
+            output,
+            path: location.clone(),
+            parts: Vec::new(),
+        }))
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        let input = self
+            .file_io
+            .new_input(&self.location(location))
+            .map_err(|err| object_store_error(err, location))?;
+        let meta = self.meta(location, input.as_ref()).await?;
+        let range = options
+            .range
+            .as_ref()
+            .map(|range| get_range(range, meta.size))
+            .unwrap_or(0..meta.size);
+        let bytes = if options.head {
+            Bytes::new()
+        } else {
+            input
+                .read_range(range.start as u64..range.end as u64)
+                .await
+                .map_err(|err| object_store_error(err, location))?
+        };
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream::once(future::ready(Ok(bytes))).boxed()),
+            meta,
+            range,
+            attributes: Attributes::default(),
+        })
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> object_store::Result<Bytes> {
+        self.file_io
+            .new_input(&self.location(location))
+            .map_err(|err| object_store_error(err, location))?
+            .read_range(range.start as u64..range.end as u64)
+            .await
+            .map_err(|err| object_store_error(err, location))
+    }
+
+    async fn head(&self, location: &Path) -> object_store::Result<ObjectMeta> {
+        let input = self
+            .file_io
+            .new_input(&self.location(location))
+            .map_err(|err| object_store_error(err, location))?;
+        self.meta(location, input.as_ref()).await
+    }
+
+    async fn delete(&self, location: &Path) -> object_store::Result<()> {
+        self.file_io
+            .delete(&self.location(location))
+            .await
+            .map_err(|err| object_store_error(err, location))
+    }
+
+    fn list(&self, _prefix: Option<&Path>) -> BoxStream<'_, object_store::Result<ObjectMeta>> {
+        stream::once(future::ready(Err(object_store::Error::NotImplemented))).boxed()
+    }
+
+    async fn list_with_delimiter(
+        &self,
+        _prefix: Option<&Path>,
+    ) -> object_store::Result<ListResult> {
+        Err(object_store::Error::NotImplemented)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        let bytes = self.get(from).await?.bytes().await?;
+        self.put(to, bytes.into()).await?;
+        Ok(())
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        let bytes = self.get(from).await?.bytes().await?;
+        self.put_opts(to, bytes.into(), PutMode::Create.into())
+            .await?;
+        Ok(())
+    }
+}
+
+/// Multipart upload that collects the parts in memory and writes them when it is completed
+struct BufferedUpload {
+    output: Box<dyn OutputFile>,
+    path: Path,
+    parts: Vec<PutPayload>,
+}
+
+impl std::fmt::Debug for BufferedUpload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BufferedUpload({})", self.output.location())
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for BufferedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.parts.push(data);
+        future::ready(Ok(())).boxed()
+    }
+
+    async fn complete(&mut self) -> object_store::Result<PutResult> {
+        let mut bytes = Vec::with_capacity(self.parts.iter().map(PutPayload::content_length).sum());
+        for part in self.parts.drain(..) {
+            part.iter().for_each(|chunk| bytes.extend_from_slice(chunk));
+        }
+        self.output
+            .write(bytes.into())
+            .await
+            .map_err(|err| object_store_error(err, &self.path))?;
+        Ok(PutResult {
+            e_tag: None,
+            version: None,
+        })
+    }
+
+    async fn abort(&mut self) -> object_store::Result<()> {
+        self.parts.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::{memory::InMemory, ObjectStore, PutMode};
+
+    use super::{FileIO, FileIOObjectStore, ObjectStoreFileIO};
+
+    #[tokio::test]
+    async fn object_store_round_trip() {
+        let file_io: Arc<dyn FileIO> = Arc::new(ObjectStoreFileIO::new(Arc::new(InMemory::new())));
+        let location = "s3://bucket/metadata/v1.metadata.json";
+
+        file_io
+            .new_output(location)
+            .unwrap()
+            .write("first".into())
+            .await
+            .unwrap();
+        assert!(file_io
+            .new_output(location)
+            .unwrap()
+            .create("second".into())
+            .await
+            .is_err());
+
+        let input = file_io.new_input(location).unwrap();
+        assert_eq!(input.length().await.unwrap(), 5);
+        assert_eq!(&input.read_range(1..3).await.unwrap()[..], b"ir");
+
+        // The object store adapter resolves the paths relative to the bucket
+        let object_store = FileIOObjectStore::new(file_io.clone(), "s3://bucket");
+        let path = "metadata/v1.metadata.json".into();
+        let bytes = object_store
+            .get(&path)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"first");
+        assert_eq!(object_store.head(&path).await.unwrap().size, 5);
+        assert!(matches!(
+            object_store
+                .put_opts(&path, "second".into(), PutMode::Create.into())
+                .await,
+            Err(object_store::Error::AlreadyExists { .. })
+        ));
+
+        object_store.delete(&path).await.unwrap();
+        assert!(matches!(
+            object_store.get(&path).await,
+            Err(object_store::Error::NotFound { .. })
+        ));
+    }
+}
@@ -22,6 +22,7 @@
 //! * [`catalog`] - Catalog implementations for metadata storage
 //! * [`arrow`] - Integration with Apache Arrow
 //! * [`view`] - View and materialized view support
+//! * [`file_io`] - Pluggable IO layer for reading and writing files
 //! * [`error`] - Error types and handling
 //!
 //! # Example
@@ -51,10 +52,13 @@ pub mod arrow;
 pub mod catalog;
 pub mod error;
 pub mod file_format;
+pub mod file_io;
 pub mod materialized_view;
 pub mod object_store;
 pub mod spec;
 pub mod sql;
 pub mod table;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 pub(crate) mod util;
 pub mod view;
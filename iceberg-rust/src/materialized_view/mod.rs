@@ -29,10 +29,11 @@
 //! # }
 //! ```
 
-use std::sync::Arc;
+use std::{fmt, str::FromStr, sync::Arc};
 
 use iceberg_rust_spec::spec::{
     materialized_view_metadata::MaterializedViewMetadata, schema::Schema,
+    view_metadata::ViewRepresentation,
 };
 use object_store::ObjectStore;
 
@@ -42,6 +43,7 @@ use crate::{
     },
     error::Error,
     object_store::Bucket,
+    sql::find_relations,
 };
 
 use self::{storage_table::StorageTable, transaction::Transaction as MaterializedViewTransaction};
@@ -53,6 +55,10 @@ pub mod transaction;
 pub static STORAGE_TABLE_POSTFIX: &str = "__storage";
 /// Flag to mark a table as a storage table
 pub static STORAGE_TABLE_FLAG: &str = "materialize.storage_table";
+/// View property with the comma separated, fully qualified identifiers of the source tables
+pub static SOURCE_TABLES: &str = "materialize.source_tables";
+/// View property with the [RefreshMode] of the materialized view
+pub static REFRESH_MODE: &str = "materialize.refresh_mode";
 
 #[derive(Debug, Clone)]
 /// A materialized view in Apache Iceberg that maintains a physical copy of query results
@@ -83,6 +89,37 @@ pub enum SourceTableState {
     Invalid,
 }
 
+/// How the storage table of a materialized view is refreshed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RefreshMode {
+    /// Only the changes of the source tables since the last refresh are computed if the query
+    /// of the view allows it
+    #[default]
+    Incremental,
+    /// The query of the view is executed for every refresh
+    Full,
+}
+
+impl fmt::Display for RefreshMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RefreshMode::Incremental => write!(f, "incremental"),
+            RefreshMode::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl FromStr for RefreshMode {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "incremental" => Ok(RefreshMode::Incremental),
+            "full" => Ok(RefreshMode::Full),
+            _ => Err(Error::InvalidFormat(format!("Refresh mode {s}"))),
+        }
+    }
+}
+
 /// Public interface of the table.
 impl MaterializedView {
     /// Creates a new builder for configuring and creating a materialized view
@@ -175,10 +212,115 @@ impl MaterializedView {
     /// * `Result<StorageTable, Error>` - The storage table or an error if it cannot be loaded
     pub async fn storage_table(&self) -> Result<StorageTable, Error> {
         let identifier = self.metadata().current_version(None)?.storage_table();
-        if let Tabular::Table(table) = self.catalog().load_tabular(&identifier.into()).await? {
-            Ok(StorageTable::new(table))
-        } else {
-            Err(Error::InvalidFormat("storage table".to_string()))
+        let table = self.catalog().load_table(&identifier.into()).await?;
+        Ok(StorageTable::new(table))
+    }
+    /// Returns the identifiers of the source tables the view depends on
+    ///
+    /// Views created with the builder record the fully qualified source tables in their
+    /// properties. For other views the relations are extracted from the SQL query.
+    ///
+    /// # Returns
+    /// * `Result<Vec<String>, Error>` - The identifiers of the source tables
+    pub fn source_tables(&self) -> Result<Vec<String>, Error> {
+        if let Some(source_tables) = self.metadata.properties.get(SOURCE_TABLES) {
+            return Ok(source_tables
+                .split(',')
+                .filter(|x| !x.is_empty())
+                .map(ToOwned::to_owned)
+                .collect());
+        }
+        let version = self.metadata.current_version(None)?;
+        match version
+            .representations
+            .first()
+            .ok_or(Error::NotFound("SQL representation of view".to_owned()))?
+        {
+            ViewRepresentation::Sql { sql, .. } => find_relations(sql),
+        }
+    }
+    /// Returns how the storage table of the view is refreshed, [RefreshMode::Incremental] if
+    /// the view doesn't specify a refresh mode
+    pub fn refresh_mode(&self) -> Result<RefreshMode, Error> {
+        self.metadata
+            .properties
+            .get(REFRESH_MODE)
+            .map(|x| x.parse())
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+    /// Checks whether the storage table reflects the current state of all source tables
+    ///
+    /// Compares the snapshot ids of the source tables recorded at the last refresh with the
+    /// current snapshot ids of the source tables. No data is read and no refresh is triggered,
+    /// which allows a query planner to decide whether to read the storage table or to execute
+    /// the query of the view.
+    ///
+    /// # Returns
+    /// * `Result<bool, Error>` - False if the view was never refreshed for its current version or
+    ///   any source table changed since the last refresh
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The storage table or a source table can't be loaded
+    /// * A source table belongs to a different catalog than the view
+    pub async fn is_fresh(&self) -> Result<bool, Error> {
+        let version = self.metadata.current_version(None)?;
+
+        let storage_table = self.storage_table().await?;
+        let Some(refresh_state) = storage_table
+            .refresh_state(self.metadata.current_version_id, None)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        for relation in self.source_tables()? {
+            let parts = relation.split('.').collect::<Vec<_>>();
+            let identifier = match parts.as_slice() {
+                [name] => Identifier::new(version.default_namespace(), name),
+                [namespace, name] => Identifier::new(&[namespace.to_string()], name),
+                [catalog, namespace, name] if *catalog == self.catalog.name() => {
+                    Identifier::new(&[namespace.to_string()], name)
+                }
+                _ => {
+                    return Err(Error::NotSupported(format!(
+                        "Source table {relation} outside of catalog {}",
+                        self.catalog.name()
+                    )))
+                }
+            };
+
+            let (uuid, snapshot_id) = match self.catalog.clone().load_tabular(&identifier).await? {
+                Tabular::Table(table) => (
+                    table.metadata().table_uuid,
+                    table
+                        .metadata()
+                        .current_snapshot(None)?
+                        .map(|x| *x.snapshot_id()),
+                ),
+                Tabular::MaterializedView(matview) => {
+                    let storage_table = matview.storage_table().await?;
+                    (
+                        *matview.metadata().as_ref().uuid(),
+                        storage_table
+                            .metadata()
+                            .current_snapshot(None)?
+                            .map(|x| *x.snapshot_id()),
+                    )
+                }
+                Tabular::View(_) => return Err(Error::InvalidFormat("source table".to_string())),
+            };
+
+            if refresh_state
+                .source_table_states
+                .get(&(uuid, None))
+                .copied()
+                != snapshot_id
+            {
+                return Ok(false);
+            }
         }
+        Ok(true)
     }
 }
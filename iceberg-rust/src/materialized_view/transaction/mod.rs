@@ -14,7 +14,10 @@ use crate::{
     error::Error,
     table::{
         delete_all_table_files,
-        transaction::{operation::Operation as TableOperation, APPEND_KEY, REPLACE_KEY},
+        transaction::{
+            operation::{IdSource, Operation as TableOperation},
+            APPEND_KEY, REPLACE_KEY,
+        },
     },
     view::transaction::operation::Operation as ViewOperation,
 };
@@ -196,6 +199,8 @@ impl<'view> Transaction<'view> {
                     .execute(
                         storage_table.metadata(),
                         self.materialized_view.object_store(),
+                        storage_table.metadata_path_resolver().as_ref(),
+                        &IdSource::default(),
                     )
                     .await?;
 
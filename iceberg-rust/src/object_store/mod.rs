@@ -5,15 +5,17 @@ Defining the [Bucket] struct for specifying buckets for the ObjectStore.
 use std::{fmt::Display, path::Path, str::FromStr, sync::Arc};
 
 use object_store::{
-    aws::{AmazonS3Builder, AmazonS3ConfigKey, S3CopyIfNotExists},
+    aws::{resolve_bucket_region, AmazonS3Builder, AmazonS3ConfigKey, S3CopyIfNotExists},
+    azure::{AzureConfigKey, MicrosoftAzureBuilder},
     gcp::{GoogleCloudStorageBuilder, GoogleConfigKey},
     local::LocalFileSystem,
     memory::InMemory,
-    ObjectStore,
+    ClientOptions, ObjectStore,
 };
 
 use crate::error::Error;
 
+pub mod retry;
 pub mod store;
 
 /// Type for buckets for different cloud providers
@@ -23,6 +25,13 @@ pub enum Bucket<'s> {
     S3(&'s str),
     /// GCS bucket
     GCS(&'s str),
+    /// Azure Blob Storage or ADLS Gen2 container
+    Azure {
+        /// Name of the container
+        container: &'s str,
+        /// Name of the storage account if it is part of the path
+        account: Option<&'s str>,
+    },
     /// No bucket
     Local,
 }
@@ -32,6 +41,14 @@ impl Display for Bucket<'_> {
         match self {
             Bucket::S3(s) => write!(f, "s3://{}", s),
             Bucket::GCS(s) => write!(f, "gcs://{}", s),
+            Bucket::Azure {
+                container,
+                account: Some(account),
+            } => write!(f, "abfss://{}@{}.dfs.core.windows.net", container, account),
+            Bucket::Azure {
+                container,
+                account: None,
+            } => write!(f, "az://{}", container),
             Bucket::Local => write!(f, ""),
         }
     }
@@ -39,19 +56,38 @@ impl Display for Bucket<'_> {
 
 impl Bucket<'_> {
     /// Get the bucket and coud provider from the location string
+    ///
+    /// Azure locations are given as `abfss://container@account.dfs.core.windows.net/path`,
+    /// `wasbs://container@account.blob.core.windows.net/path` or `az://container/path`.
     pub fn from_path(path: &str) -> Result<Bucket, Error> {
-        if path.starts_with("s3://") {
-            path.trim_start_matches("s3://")
-                .split('/')
+        if let Some(rest) = path.strip_prefix("s3://") {
+            rest.split('/')
                 .next()
                 .map(Bucket::S3)
                 .ok_or(Error::NotFound(format!("Bucket in path {path}")))
-        } else if path.starts_with("gcs://") {
-            path.trim_start_matches("gcs://")
-                .split('/')
+        } else if let Some(rest) = path
+            .strip_prefix("gcs://")
+            .or_else(|| path.strip_prefix("gs://"))
+        {
+            rest.split('/')
                 .next()
                 .map(Bucket::GCS)
                 .ok_or(Error::NotFound(format!("Bucket in path {path}")))
+        } else if let Some(rest) = ["abfss://", "abfs://", "wasbs://", "wasb://", "az://"]
+            .iter()
+            .find_map(|scheme| path.strip_prefix(scheme))
+        {
+            let authority = rest.split('/').next().unwrap_or(rest);
+            match authority.split_once('@') {
+                Some((container, host)) => Ok(Bucket::Azure {
+                    container,
+                    account: host.split('.').next(),
+                }),
+                None => Ok(Bucket::Azure {
+                    container: authority,
+                    account: None,
+                }),
+            }
         } else {
             Ok(Bucket::Local)
         }
@@ -65,6 +101,8 @@ pub enum ObjectStoreBuilder {
     S3(AmazonS3Builder),
     /// Google Cloud Storage builder
     GCS(GoogleCloudStorageBuilder),
+    /// Azure Blob Storage and ADLS Gen2 builder
+    Azure(MicrosoftAzureBuilder),
     /// Filesystem builder
     Filesystem(Arc<LocalFileSystem>),
     /// In memory builder
@@ -77,6 +115,8 @@ pub enum ConfigKey {
     AWS(AmazonS3ConfigKey),
     /// Configuration keys for GCS
     GCS(GoogleConfigKey),
+    /// Configuration keys for Azure
+    Azure(AzureConfigKey),
 }
 
 impl FromStr for ConfigKey {
@@ -88,6 +128,9 @@ impl FromStr for ConfigKey {
         if let Ok(x) = s.parse() {
             return Ok(ConfigKey::GCS(x));
         };
+        if let Ok(x) = s.parse() {
+            return Ok(ConfigKey::Azure(x));
+        };
         Err(object_store::Error::UnknownConfigurationKey {
             store: "",
             key: s.to_string(),
@@ -103,6 +146,10 @@ impl ObjectStoreBuilder {
     pub fn gcs() -> Self {
         ObjectStoreBuilder::GCS(GoogleCloudStorageBuilder::from_env())
     }
+    /// Create new Azure Blob Storage Object Store builder
+    pub fn azure() -> Self {
+        ObjectStoreBuilder::Azure(MicrosoftAzureBuilder::from_env())
+    }
     /// Create a new FileSystem ObjectStoreBuilder
     pub fn filesystem(prefix: impl AsRef<Path>) -> Self {
         ObjectStoreBuilder::Filesystem(Arc::new(LocalFileSystem::new_with_prefix(prefix).unwrap()))
@@ -120,9 +167,74 @@ impl ObjectStoreBuilder {
             (ObjectStoreBuilder::GCS(gcs), ConfigKey::GCS(key)) => {
                 ObjectStoreBuilder::GCS(gcs.with_config(key, value))
             }
+            (ObjectStoreBuilder::Azure(azure), ConfigKey::Azure(key)) => {
+                ObjectStoreBuilder::Azure(azure.with_config(key, value))
+            }
             (x, _) => x,
         }
     }
+    /// Set config values from Iceberg storage properties like `s3.access-key-id` or `s3.region`.
+    ///
+    /// Keys that are object store configuration keys are applied directly.
+    /// Unknown keys are ignored.
+    pub fn with_iceberg_properties<'a>(
+        self,
+        properties: impl IntoIterator<Item = (&'a String, &'a String)>,
+    ) -> Self {
+        properties
+            .into_iter()
+            .fold(self, |builder, (key, value)| match key.as_str() {
+                "s3.access-key-id" => {
+                    builder.with_config(ConfigKey::AWS(AmazonS3ConfigKey::AccessKeyId), value)
+                }
+                "s3.secret-access-key" => {
+                    builder.with_config(ConfigKey::AWS(AmazonS3ConfigKey::SecretAccessKey), value)
+                }
+                "s3.session-token" => {
+                    builder.with_config(ConfigKey::AWS(AmazonS3ConfigKey::Token), value)
+                }
+                "s3.region" | "client.region" => {
+                    builder.with_config(ConfigKey::AWS(AmazonS3ConfigKey::Region), value)
+                }
+                "s3.endpoint" => {
+                    builder.with_config(ConfigKey::AWS(AmazonS3ConfigKey::Endpoint), value)
+                }
+                "s3.path-style-access" => builder.with_config(
+                    ConfigKey::AWS(AmazonS3ConfigKey::VirtualHostedStyleRequest),
+                    (value != "true").to_string(),
+                ),
+                "adls.auth.shared-key.account.name" => {
+                    builder.with_config(ConfigKey::Azure(AzureConfigKey::AccountName), value)
+                }
+                "adls.auth.shared-key.account.key" => {
+                    builder.with_config(ConfigKey::Azure(AzureConfigKey::AccessKey), value)
+                }
+                key if key.starts_with("adls.sas-token.") => {
+                    builder.with_config(ConfigKey::Azure(AzureConfigKey::SasKey), value)
+                }
+                _ => match key.parse() {
+                    Ok(key) => builder.with_config(key, value),
+                    Err(_) => builder,
+                },
+            })
+    }
+    /// Set the region of the builder to the region of the given S3 bucket.
+    ///
+    /// The region is looked up with a HeadBucket request, which allows to access buckets in a
+    /// different region than the configured one. Builders for other providers are returned unchanged.
+    pub async fn with_bucket_region(self, bucket: &Bucket<'_>) -> Result<Self, Error> {
+        match (self, bucket) {
+            (ObjectStoreBuilder::S3(aws), Bucket::S3(bucket)) => {
+                let region = resolve_bucket_region(bucket, &ClientOptions::default())
+                    .await
+                    .map_err(Error::from)?;
+                Ok(ObjectStoreBuilder::S3(
+                    aws.with_config(AmazonS3ConfigKey::Region, region),
+                ))
+            }
+            (x, _) => Ok(x),
+        }
+    }
     /// Create objectstore from template
     pub fn build(&self, bucket: Bucket) -> Result<Arc<dyn ObjectStore>, Error> {
         match (bucket, self) {
@@ -141,9 +253,89 @@ impl ObjectStoreBuilder {
                     .build()
                     .map_err(Error::from)?,
             )),
+            (Bucket::Azure { container, account }, Self::Azure(builder)) => {
+                let builder = builder.clone().with_container_name(container);
+                let builder = match account {
+                    Some(account) => builder.with_account(account),
+                    None => builder,
+                };
+                Ok::<_, Error>(Arc::new(builder.build().map_err(Error::from)?))
+            }
             (Bucket::Local, Self::Filesystem(object_store)) => Ok(object_store.clone()),
             (Bucket::Local, Self::Memory(object_store)) => Ok(object_store.clone()),
             _ => Err(Error::NotSupported("Object store protocol".to_owned())),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Bucket;
+
+    #[test]
+    fn bucket_from_path() {
+        assert!(matches!(
+            Bucket::from_path("s3://warehouse/tpch/lineitem").unwrap(),
+            Bucket::S3("warehouse")
+        ));
+        assert!(matches!(
+            Bucket::from_path("gs://warehouse/tpch/lineitem").unwrap(),
+            Bucket::GCS("warehouse")
+        ));
+        assert!(matches!(
+            Bucket::from_path("gcs://warehouse/tpch/lineitem").unwrap(),
+            Bucket::GCS("warehouse")
+        ));
+        assert!(matches!(
+            Bucket::from_path("abfss://warehouse@account.dfs.core.windows.net/tpch/lineitem")
+                .unwrap(),
+            Bucket::Azure {
+                container: "warehouse",
+                account: Some("account")
+            }
+        ));
+        assert!(matches!(
+            Bucket::from_path("abfs://warehouse@account.dfs.core.windows.net/tpch/lineitem")
+                .unwrap(),
+            Bucket::Azure {
+                container: "warehouse",
+                account: Some("account")
+            }
+        ));
+        assert!(matches!(
+            Bucket::from_path("wasbs://warehouse@account.blob.core.windows.net/tpch/lineitem")
+                .unwrap(),
+            Bucket::Azure {
+                container: "warehouse",
+                account: Some("account")
+            }
+        ));
+        assert!(matches!(
+            Bucket::from_path("az://warehouse/tpch/lineitem").unwrap(),
+            Bucket::Azure {
+                container: "warehouse",
+                account: None
+            }
+        ));
+        assert!(matches!(
+            Bucket::from_path("/tmp/warehouse/tpch/lineitem").unwrap(),
+            Bucket::Local
+        ));
+    }
+
+    #[test]
+    fn azure_bucket_to_string() {
+        assert_eq!(
+            Bucket::from_path("abfss://warehouse@account.dfs.core.windows.net/tpch")
+                .unwrap()
+                .to_string(),
+            "abfss://warehouse@account.dfs.core.windows.net"
+        );
+        assert_eq!(
+            Bucket::from_path("az://warehouse/tpch")
+                .unwrap()
+                .to_string(),
+            "az://warehouse"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+/*!
+Object store wrapper that retries requests which failed with a transient error
+*/
+
+use std::{fmt::Display, future::Future, io::ErrorKind, ops::Range, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+};
+
+/// Configuration of the retries of a [RetryingObjectStore]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Maximum number of retries of a request
+    pub max_retries: usize,
+    /// Backoff before the first retry, it is doubled for every further retry
+    pub initial_backoff: Duration,
+    /// Upper limit for the backoff between two retries
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns the backoff before the given retry with a random jitter between half and the full
+    /// exponential backoff, so that concurrent requests don't retry at the same time
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        let mut random = [0u8; 8];
+        let jitter = match getrandom::fill(&mut random) {
+            Ok(()) => u64::from_le_bytes(random) as f64 / u64::MAX as f64,
+            Err(_) => 1.0,
+        };
+        backoff.mul_f64(0.5 + 0.5 * jitter)
+    }
+}
+
+/// Wraps an [ObjectStore] and retries requests that failed with a transient error, like a
+/// `503 Slow Down` from S3, with exponential backoff.
+///
+/// Only requests that can be repeated safely are retried. Conditional puts, copies that must
+/// not overwrite, listings and multipart uploads are passed to the inner store unchanged.
+#[derive(Debug)]
+pub struct RetryingObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    config: RetryConfig,
+}
+
+impl RetryingObjectStore {
+    /// Creates a new object store that retries the requests to `inner`
+    pub fn new(inner: Arc<dyn ObjectStore>, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    async fn retry<T, F, Fut>(&self, mut request: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut retry = 0;
+        loop {
+            match request().await {
+                Err(err) if retry < self.config.max_retries && is_transient(&err) => {
+                    tokio::time::sleep(self.config.backoff(retry as u32)).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Errors of the object store implementations that are not caused by the request itself, like
+/// server errors, timeouts and interrupted connections
+fn is_transient(err: &object_store::Error) -> bool {
+    match err {
+        object_store::Error::JoinError { .. } => true,
+        object_store::Error::Generic { source, .. } => {
+            let mut source: Option<&(dyn std::error::Error + 'static)> = Some(source.as_ref());
+            while let Some(err) = source {
+                if let Some(err) = err.downcast_ref::<std::io::Error>() {
+                    if matches!(
+                        err.kind(),
+                        ErrorKind::TimedOut
+                            | ErrorKind::Interrupted
+                            | ErrorKind::ConnectionReset
+                            | ErrorKind::ConnectionAborted
+                            | ErrorKind::BrokenPipe
+                    ) {
+                        return true;
+                    }
+                }
+                // The error type of the HTTP client of object_store is private, server errors
+                // and requests that failed after its own retries can only be told by the message
+                let message = err.to_string();
+                if message.starts_with("Server error") || message.starts_with("Error after") {
+                    return true;
+                }
+                source = err.source();
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
+impl Display for RetryingObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RetryingObjectStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for RetryingObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        // A conditional put that reached the store before it failed would fail again with a
+        // conflict, so only overwrites are retried
+        match opts.mode {
+            PutMode::Overwrite => {
+                self.retry(|| self.inner.put_opts(location, payload.clone(), opts.clone()))
+                    .await
+            }
+            PutMode::Create | PutMode::Update(_) => {
+                self.inner.put_opts(location, payload, opts).await
+            }
+        }
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.retry(|| self.inner.get_opts(location, options.clone()))
+            .await
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        self.retry(|| self.inner.get_range(location, range.clone()))
+            .await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
+        self.retry(|| self.inner.get_ranges(location, ranges)).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.retry(|| self.inner.head(location)).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.retry(|| self.inner.delete(location)).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.retry(|| self.inner.list_with_delimiter(prefix)).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.retry(|| self.inner.copy(from, to)).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, time::Duration};
+
+    use super::{is_transient, RetryConfig};
+
+    #[test]
+    fn backoff() {
+        let config = RetryConfig {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+        let backoff = config.backoff(0);
+        assert!(backoff >= Duration::from_millis(50) && backoff <= Duration::from_millis(100));
+        let backoff = config.backoff(2);
+        assert!(backoff >= Duration::from_millis(200) && backoff <= Duration::from_millis(400));
+        let backoff = config.backoff(10);
+        assert!(backoff >= Duration::from_millis(250) && backoff <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn transient_errors() {
+        let generic =
+            |source: Box<dyn std::error::Error + Send + Sync>| object_store::Error::Generic {
+                store: "test",
+                source,
+            };
+        assert!(is_transient(&generic(Box::new(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "timeout"
+        )))));
+        assert!(is_transient(&generic(
+            "Server error, body contains Error, with status 503 Service Unavailable: Slow Down"
+                .into()
+        )));
+        assert!(!is_transient(&generic(
+            "Client error with status 400 Bad Request: No Body".into()
+        )));
+        assert!(!is_transient(&generic(Box::new(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid"
+        )))));
+        assert!(!is_transient(&object_store::Error::NotFound {
+            path: "test".to_owned(),
+            source: "not found".into(),
+        }));
+    }
+}
@@ -1,11 +1,14 @@
 /*! Helpers for Intarting with object storage
 */
+use std::io::Read;
+
 use async_trait::async_trait;
+use flate2::read::GzDecoder;
 use iceberg_rust_spec::{
     tabular::{TabularMetadata, TabularMetadataRef},
     util::strip_prefix,
 };
-use object_store::{Attributes, ObjectStore, PutOptions, TagSet};
+use object_store::{Attributes, ObjectStore, PutMode, PutOptions, PutPayload, TagSet};
 
 use crate::error::Error;
 
@@ -32,7 +35,7 @@ impl<T: ObjectStore> IcebergStore for T {
             .await?
             .bytes()
             .await?;
-        serde_json::from_slice(&bytes).map_err(Error::from)
+        parse_metadata(&bytes)
     }
 
     async fn put_metadata(
@@ -69,6 +72,54 @@ impl<T: ObjectStore> IcebergStore for T {
     }
 }
 
+/// Writes a file that must not exist yet, like a manifest or manifest list whose name contains
+/// the uuid of the commit.
+///
+/// Uses a conditional put if the object store supports it and otherwise checks for an existing
+/// file before writing. Returns an error instead of overwriting the file of another writer.
+pub(crate) async fn put_if_not_exists(
+    object_store: &dyn ObjectStore,
+    location: &str,
+    payload: PutPayload,
+) -> Result<(), Error> {
+    let path = strip_prefix(location).into();
+    let result = object_store
+        .put_opts(&path, payload.clone(), PutMode::Create.into())
+        .await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(object_store::Error::AlreadyExists { .. }) => Err(already_exists(location)),
+        Err(object_store::Error::NotImplemented) => match object_store.head(&path).await {
+            Ok(_) => Err(already_exists(location)),
+            Err(object_store::Error::NotFound { .. }) => {
+                object_store.put(&path, payload).await?;
+                Ok(())
+            }
+            Err(err) => Err(err.into()),
+        },
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn already_exists(location: &str) -> Error {
+    Error::InvalidFormat(format!("File {location} already exists. Location"))
+}
+
+/// Magic bytes at the start of a gzip file
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Parses a metadata file, which may be gzip compressed like `*.gz.metadata.json` or
+/// `*.metadata.json.gz` files written by other Iceberg implementations
+pub fn parse_metadata(bytes: &[u8]) -> Result<TabularMetadata, Error> {
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+        serde_json::from_slice(&decompressed).map_err(Error::from)
+    } else {
+        serde_json::from_slice(bytes).map_err(Error::from)
+    }
+}
+
 fn version_hint_path(original: &str) -> Option<String> {
     Some(
         std::path::Path::new(original)
@@ -81,8 +132,32 @@ fn version_hint_path(original: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
+    use crate::test_util::VIEW_METADATA;
+
     use super::*;
 
+    #[tokio::test]
+    async fn test_put_if_not_exists() {
+        let object_store = object_store::memory::InMemory::new();
+        let location = "s3://bucket/metadata/snap-1-0-uuid.avro";
+
+        put_if_not_exists(&object_store, location, "first".into())
+            .await
+            .unwrap();
+        assert!(put_if_not_exists(&object_store, location, "second".into())
+            .await
+            .is_err());
+
+        let bytes = object_store
+            .get(&strip_prefix(location).into())
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"first");
+    }
+
     #[test]
     fn test_version_hint_path_normal_case() {
         let input = "/path/to/metadata/v1.metadata.json";
@@ -123,4 +198,21 @@ mod tests {
         let expected = "/path/to/version-hint.text";
         assert_eq!(version_hint_path(input), Some(expected.to_string()));
     }
+
+    #[test]
+    fn test_parse_gzip_metadata() {
+        use std::io::Write;
+
+        use flate2::{write::GzEncoder, Compression};
+
+        let data = VIEW_METADATA;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(
+            parse_metadata(&compressed).unwrap(),
+            parse_metadata(data.as_bytes()).unwrap()
+        );
+    }
 }
@@ -0,0 +1,99 @@
+//! Read-only metadata tables for Iceberg tables
+//!
+//! This module provides the rows of the metadata tables that describe the state and
+//! the evolution of a table:
+//!
+//! * [`HistoryEntry`] - A change of the current snapshot of the table
+//! * [`SnapshotMetadata`] - A snapshot of the table together with its summary
+//! * [`DataFileMetadata`] - A live data or delete file of the table
+//! * [`FileStats`] - The number and the total size of the live data files of the table
+
+use std::collections::HashMap;
+
+use iceberg_rust_spec::spec::{
+    manifest::{Content, DataFile, FileFormat},
+    snapshot::{Operation, Snapshot},
+    values::Struct,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A change of the current snapshot of the table
+pub struct HistoryEntry {
+    /// Timestamp when the snapshot became the current snapshot
+    pub made_current_at: i64,
+    /// Id of the snapshot
+    pub snapshot_id: i64,
+    /// Id of the parent snapshot
+    pub parent_id: Option<i64>,
+    /// Whether the snapshot is an ancestor of the current snapshot
+    pub is_current_ancestor: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A snapshot of the table together with its summary
+pub struct SnapshotMetadata {
+    /// Timestamp when the snapshot was committed
+    pub committed_at: i64,
+    /// Id of the snapshot
+    pub snapshot_id: i64,
+    /// Id of the parent snapshot
+    pub parent_id: Option<i64>,
+    /// The operation that produced the snapshot
+    pub operation: Operation,
+    /// Location of the manifest list of the snapshot
+    pub manifest_list: String,
+    /// Additional summary of the changes in the snapshot
+    pub summary: HashMap<String, String>,
+}
+
+impl From<&Snapshot> for SnapshotMetadata {
+    fn from(value: &Snapshot) -> Self {
+        SnapshotMetadata {
+            committed_at: *value.timestamp_ms(),
+            snapshot_id: *value.snapshot_id(),
+            parent_id: *value.parent_snapshot_id(),
+            operation: value.summary().operation.clone(),
+            manifest_list: value.manifest_list().clone(),
+            summary: value.summary().other.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A live data or delete file of the table
+pub struct DataFileMetadata {
+    /// Type of content stored by the file
+    pub content: Content,
+    /// Full URI for the file with FS scheme
+    pub file_path: String,
+    /// Format of the file
+    pub file_format: FileFormat,
+    /// Partition values of the file
+    pub partition: Struct,
+    /// Number of records in the file
+    pub record_count: i64,
+    /// Total file size in bytes
+    pub file_size_in_bytes: i64,
+}
+
+impl From<&DataFile> for DataFileMetadata {
+    fn from(value: &DataFile) -> Self {
+        DataFileMetadata {
+            content: value.content().clone(),
+            file_path: value.file_path().clone(),
+            file_format: value.file_format().clone(),
+            partition: value.partition().clone(),
+            record_count: *value.record_count(),
+            file_size_in_bytes: *value.file_size_in_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The number and the total size of the live data files of a snapshot
+pub struct FileStats {
+    /// Number of live data files
+    pub data_file_count: u64,
+    /// Total size of the live data files in bytes
+    pub total_data_size: u64,
+}
@@ -25,18 +25,23 @@ use apache_avro::{
     to_value, types::Value as AvroValue, Reader as AvroReader, Schema as AvroSchema,
     Writer as AvroWriter,
 };
+use bytes::Bytes;
+use futures::{stream, Stream, StreamExt};
 use iceberg_rust_spec::{
     manifest::{Content, ManifestEntry, ManifestEntryV1, ManifestEntryV2, Status},
     manifest_list::{self, FieldSummary, ManifestListEntry},
     partition::{PartitionField, PartitionSpec},
     schema::{Schema, SchemaV1, SchemaV2},
     table_metadata::{FormatVersion, TableMetadata},
-    util::strip_prefix,
     values::{Struct, Value},
 };
 use object_store::ObjectStore;
+use tokio::sync::mpsc;
 
-use crate::{error::Error, spec};
+use crate::{error::Error, object_store::store::put_if_not_exists, spec};
+
+/// Number of chunks and entries that are buffered when a manifest is read from a stream
+static STREAM_BUFFER_SIZE: usize = 16;
 
 type ReaderZip<'a, R> = Zip<AvroReader<'a, R>, Repeat<Arc<(Schema, PartitionSpec, FormatVersion)>>>;
 type ReaderMap<'a, R> = Map<
@@ -59,12 +64,58 @@ type ReaderMap<'a, R> = Map<
 /// * `R` - The type implementing `Read` that provides the manifest data
 pub(crate) struct ManifestReader<'a, R: Read> {
     reader: ReaderMap<'a, R>,
+    validation: Option<ManifestValidation>,
+}
+
+/// Expected and actual number of added, existing and deleted entries of a manifest
+struct ManifestValidation {
+    manifest_path: String,
+    expected: [Option<i32>; 3],
+    actual: [i64; 3],
+}
+
+impl ManifestValidation {
+    fn validate(&self) -> Result<(), Error> {
+        for ((status, expected), actual) in ["added", "existing", "deleted"]
+            .iter()
+            .zip(self.expected.iter())
+            .zip(self.actual.iter())
+        {
+            if let Some(expected) = expected {
+                if *expected as i64 != *actual {
+                    return Err(Error::InvalidFormat(format!(
+                        "Manifest {} with {} {} entries instead of {}",
+                        self.manifest_path, actual, status, expected
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<R: Read> Iterator for ManifestReader<'_, R> {
     type Item = Result<ManifestEntry, Error>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.reader.next()
+        match self.reader.next() {
+            Some(Ok(entry)) => {
+                if let Some(validation) = &mut self.validation {
+                    let index = match entry.status() {
+                        Status::Added => 0,
+                        Status::Existing => 1,
+                        Status::Deleted => 2,
+                    };
+                    validation.actual[index] += 1;
+                }
+                Some(Ok(entry))
+            }
+            Some(Err(err)) => Some(Err(err)),
+            None => self
+                .validation
+                .take()
+                .and_then(|validation| validation.validate().err())
+                .map(Err),
+        }
     }
 }
 
@@ -87,6 +138,25 @@ impl<R: Read> ManifestReader<'_, R> {
     /// * Format version is invalid
     /// * Schema or partition spec information cannot be parsed
     pub(crate) fn new(reader: R) -> Result<Self, Error> {
+        Self::for_spec(reader, None)
+    }
+
+    /// Creates a new ManifestReader that decodes the partition values with the given partition spec.
+    ///
+    /// Manifests of tables with an evolved partitioning were written with different partition specs.
+    /// The spec is resolved from the table metadata by the `partition_spec_id` of the manifest list
+    /// entry. If no spec is given, the partition spec stored in the manifest metadata is used.
+    ///
+    /// # Arguments
+    /// * `reader` - A type implementing the `Read` trait that provides access to the manifest file data
+    /// * `partition_spec` - The partition spec the manifest was written with
+    ///
+    /// # Returns
+    /// * `Result<Self, Error>` - A new ManifestReader instance or an error if initialization fails
+    pub(crate) fn for_spec(
+        reader: R,
+        partition_spec: Option<&PartitionSpec>,
+    ) -> Result<Self, Error> {
         let reader = AvroReader::new(reader)?;
         let metadata = reader.user_metadata();
 
@@ -115,28 +185,123 @@ impl<R: Read> ManifestReader<'_, R> {
             )?)?,
         };
 
-        let partition_fields: Vec<PartitionField> = serde_json::from_slice(
-            metadata
-                .get("partition-spec")
-                .ok_or(Error::InvalidFormat("manifest metadata".to_string()))?,
-        )?;
-        let spec_id: i32 = metadata
-            .get("partition-spec-id")
-            .map(|x| String::from_utf8(x.clone()))
-            .transpose()?
-            .unwrap_or("0".to_string())
-            .parse()?;
-        let partition_spec = PartitionSpec::builder()
-            .with_spec_id(spec_id)
-            .with_fields(partition_fields)
-            .build()
-            .map_err(spec::error::Error::from)?;
+        let partition_spec = match partition_spec {
+            Some(partition_spec) => partition_spec.clone(),
+            None => {
+                let partition_fields: Vec<PartitionField> = serde_json::from_slice(
+                    metadata
+                        .get("partition-spec")
+                        .ok_or(Error::InvalidFormat("manifest metadata".to_string()))?,
+                )?;
+                let spec_id: i32 = metadata
+                    .get("partition-spec-id")
+                    .map(|x| String::from_utf8(x.clone()))
+                    .transpose()?
+                    .unwrap_or("0".to_string())
+                    .parse()?;
+                PartitionSpec::builder()
+                    .with_spec_id(spec_id)
+                    .with_fields(partition_fields)
+                    .build()
+                    .map_err(spec::error::Error::from)?
+            }
+        };
         Ok(Self {
             reader: reader
                 .zip(repeat(Arc::new((schema, partition_spec, format_version))))
                 .map(avro_value_to_manifest_entry),
+            validation: None,
         })
     }
+
+    /// Checks the number of added, existing and deleted entries against the counts of the
+    /// manifest list entry. If they don't match, an [`Error::InvalidFormat`] is returned after
+    /// the last entry.
+    pub(crate) fn with_validation(mut self, manifest: &ManifestListEntry) -> Self {
+        self.validation = Some(ManifestValidation {
+            manifest_path: manifest.manifest_path.clone(),
+            expected: [
+                manifest.added_files_count,
+                manifest.existing_files_count,
+                manifest.deleted_files_count,
+            ],
+            actual: [0; 3],
+        });
+        self
+    }
+}
+
+impl ManifestReader<'static, ChunkReader> {
+    /// Creates a stream of manifest entries from a stream of the bytes of a manifest file.
+    ///
+    /// The chunks of the byte stream are parsed as they arrive, so the manifest file doesn't
+    /// have to be loaded into memory at once. Since the Avro reader is synchronous, the
+    /// manifest is parsed on a blocking thread of the tokio runtime.
+    ///
+    /// # Arguments
+    /// * `stream` - The bytes of the manifest file, for example from [`object_store::GetResult::into_stream`]
+    ///
+    /// # Returns
+    /// * `impl Stream<Item = Result<ManifestEntry, Error>>` - A stream of the manifest entries
+    pub(crate) fn from_stream(
+        stream: impl Stream<Item = Result<Bytes, object_store::Error>> + Send + 'static,
+    ) -> impl Stream<Item = Result<ManifestEntry, Error>> {
+        let (chunk_sender, chunk_receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+        let (entry_sender, entry_receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+
+        tokio::task::spawn(async move {
+            let mut stream = Box::pin(stream);
+            while let Some(chunk) = stream.next().await {
+                if chunk_sender.send(chunk).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        tokio::task::spawn_blocking(move || {
+            let reader = ChunkReader {
+                receiver: chunk_receiver,
+                chunk: Bytes::new(),
+            };
+            match ManifestReader::new(reader) {
+                Ok(manifest_reader) => {
+                    for entry in manifest_reader {
+                        if entry_sender.blocking_send(entry).is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(err) => {
+                    let _ = entry_sender.blocking_send(Err(err));
+                }
+            }
+        });
+
+        stream::unfold(entry_receiver, |mut receiver| async move {
+            receiver.recv().await.map(|entry| (entry, receiver))
+        })
+    }
+}
+
+/// A blocking reader over the chunks of a byte stream that are received from a channel
+pub(crate) struct ChunkReader {
+    receiver: mpsc::Receiver<Result<Bytes, object_store::Error>>,
+    chunk: Bytes,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.receiver.blocking_recv() {
+                Some(Ok(chunk)) => self.chunk = chunk,
+                Some(Err(err)) => return Err(std::io::Error::other(err)),
+                None => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.chunk.len());
+        buf[..len].copy_from_slice(&self.chunk.split_to(len));
+        Ok(len)
+    }
 }
 
 /// A writer for Iceberg manifest files that handles creating and updating manifest entries.
@@ -152,15 +317,20 @@ impl<R: Read> ManifestReader<'_, R> {
 /// * `table_metadata` - Reference to the table's metadata containing schema and partition information
 /// * `manifest` - The manifest list entry being built or modified
 /// * `writer` - The underlying Avro writer for serializing manifest entries
+/// * `empty` - Whether no entries were written yet, the first entry determines the content
 pub(crate) struct ManifestWriter<'schema, 'metadata> {
     table_metadata: &'metadata TableMetadata,
     manifest: ManifestListEntry,
     writer: AvroWriter<'schema, Vec<u8>>,
+    empty: bool,
 }
 
 impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
     /// Creates a new ManifestWriter for writing manifest entries to a new manifest file.
     ///
+    /// The content of the manifest is determined by the first appended entry. Manifests
+    /// with data files have `Content::Data`, manifests with delete files `Content::Deletes`.
+    ///
     /// # Arguments
     /// * `manifest_location` - The location where the manifest file will be written
     /// * `snapshot_id` - The ID of the snapshot this manifest belongs to
@@ -182,6 +352,39 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
         schema: &'schema AvroSchema,
         table_metadata: &'metadata TableMetadata,
         branch: Option<&str>,
+    ) -> Result<Self, Error> {
+        Self::for_spec(
+            manifest_location,
+            snapshot_id,
+            schema,
+            table_metadata,
+            branch,
+            table_metadata.default_spec_id,
+        )
+    }
+
+    /// Creates a new ManifestWriter for entries that were written with the given partition spec.
+    ///
+    /// Manifests only contain files of a single partition spec. Entries of files that were
+    /// written before the partitioning of the table evolved are written with their original spec.
+    ///
+    /// # Arguments
+    /// * `manifest_location` - The location where the manifest file will be written
+    /// * `snapshot_id` - The ID of the snapshot this manifest belongs to
+    /// * `schema` - The Avro schema of the manifest entries with the partition values of the spec
+    /// * `table_metadata` - The table metadata containing schema and partition information
+    /// * `branch` - Optional branch name to get the current schema from
+    /// * `spec_id` - The id of the partition spec of the entries
+    ///
+    /// # Returns
+    /// * `Result<Self, Error>` - A new ManifestWriter instance or an error if initialization fails
+    pub(crate) fn for_spec(
+        manifest_location: &str,
+        snapshot_id: i64,
+        schema: &'schema AvroSchema,
+        table_metadata: &'metadata TableMetadata,
+        branch: Option<&str>,
+        spec_id: i32,
     ) -> Result<Self, Error> {
         let mut writer = AvroWriter::new(schema, Vec::new());
 
@@ -210,8 +413,6 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
             serde_json::to_string(&table_metadata.current_schema(branch)?.schema_id())?,
         )?;
 
-        let spec_id = table_metadata.default_spec_id;
-
         writer.add_user_metadata(
             "partition-spec".to_string(),
             serde_json::to_string(
@@ -234,7 +435,7 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
             format_version: table_metadata.format_version,
             manifest_path: manifest_location.to_owned(),
             manifest_length: 0,
-            partition_spec_id: table_metadata.default_spec_id,
+            partition_spec_id: spec_id,
             content: manifest_list::Content::Data,
             sequence_number: table_metadata.last_sequence_number + 1,
             min_sequence_number: table_metadata.last_sequence_number + 1,
@@ -253,6 +454,7 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
             manifest,
             writer,
             table_metadata,
+            empty: true,
         })
     }
 
@@ -260,11 +462,14 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
     ///
     /// This method reads an existing manifest file and creates a new writer that includes
     /// all the existing entries with their status updated to "Existing". It also updates
-    /// sequence numbers and snapshot IDs as needed.
+    /// sequence numbers and snapshot IDs as needed. The entries are written to a new manifest
+    /// file, the existing manifest is still referenced by the previous snapshots.
     ///
     /// # Arguments
     /// * `bytes` - The raw bytes of the existing manifest file
     /// * `manifest` - The manifest list entry describing the existing manifest
+    /// * `manifest_location` - The location where the new manifest file will be written
+    /// * `snapshot_id` - The ID of the snapshot the new manifest belongs to
     /// * `schema` - The Avro schema used for serializing manifest entries
     /// * `table_metadata` - The table metadata containing schema and partition information
     /// * `branch` - Optional branch name to get the current schema from
@@ -281,11 +486,18 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
     pub(crate) fn from_existing(
         bytes: &[u8],
         mut manifest: ManifestListEntry,
+        manifest_location: &str,
+        snapshot_id: i64,
         schema: &'schema AvroSchema,
         table_metadata: &'metadata TableMetadata,
         branch: Option<&str>,
     ) -> Result<Self, Error> {
-        let manifest_reader = ManifestReader::new(bytes)?;
+        let manifest_reader = ManifestReader::for_spec(
+            bytes,
+            table_metadata
+                .partition_specs
+                .get(&manifest.partition_spec_id),
+        )?;
 
         let mut writer = AvroWriter::new(schema, Vec::new());
 
@@ -314,7 +526,7 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
             serde_json::to_string(&table_metadata.current_schema(branch)?.schema_id())?,
         )?;
 
-        let spec_id = table_metadata.default_spec_id;
+        let spec_id = manifest.partition_spec_id;
 
         writer.add_user_metadata(
             "partition-spec".to_string(),
@@ -332,14 +544,18 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
             serde_json::to_string(&spec_id)?,
         )?;
 
-        writer.add_user_metadata("content".to_string(), "data")?;
+        writer.add_user_metadata("content".to_string(), content_name(&manifest.content))?;
 
+        // Deleted entries only belong to the snapshot that deleted the files, they are not
+        // carried over to later snapshots
         writer.extend(
             manifest_reader
+                .filter(|entry| !matches!(entry, Ok(entry) if *entry.status() == Status::Deleted))
                 .map(|entry| {
                     let mut entry = entry
                         .map_err(|err| apache_avro::Error::DeserializeValue(err.to_string()))?;
                     *entry.status_mut() = Status::Existing;
+                    *entry.format_version_mut() = table_metadata.format_version;
                     if entry.sequence_number().is_none() {
                         *entry.sequence_number_mut() = Some(manifest.sequence_number);
                     }
@@ -351,18 +567,26 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
                 .filter_map(Result::ok),
         )?;
 
+        manifest.manifest_path = manifest_location.to_owned();
+        manifest.manifest_length = 0;
+        manifest.added_snapshot_id = snapshot_id;
         manifest.sequence_number = table_metadata.last_sequence_number + 1;
 
+        manifest.format_version = table_metadata.format_version;
+
         manifest.existing_files_count = Some(
             manifest.existing_files_count.unwrap_or(0) + manifest.added_files_count.unwrap_or(0),
         );
 
         manifest.added_files_count = None;
+        manifest.deleted_files_count = Some(0);
+        manifest.deleted_rows_count = Some(0);
 
         Ok(ManifestWriter {
             manifest,
             writer,
             table_metadata,
+            empty: false,
         })
     }
 
@@ -382,17 +606,44 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
     ///
     /// # Errors
     /// Returns an error if:
+    /// * The entry is a delete file and the manifest contains data files, or vice versa
     /// * The entry cannot be serialized
     /// * Partition statistics cannot be updated
-    /// * The default partition spec is not found
-    pub(crate) fn append(&mut self, manifest_entry: ManifestEntry) -> Result<(), Error> {
+    /// * The partition spec of the manifest is not found
+    pub(crate) fn append(&mut self, mut manifest_entry: ManifestEntry) -> Result<(), Error> {
+        // Entries of manifests that were written before a format version upgrade
+        *manifest_entry.format_version_mut() = self.table_metadata.format_version;
+
+        // Data files and delete files are never written to the same manifest
+        let content = match manifest_entry.data_file().content() {
+            Content::Data => manifest_list::Content::Data,
+            Content::PositionDeletes | Content::EqualityDeletes => manifest_list::Content::Deletes,
+        };
+        if self.empty {
+            self.writer
+                .add_user_metadata("content".to_string(), content_name(&content))?;
+            self.manifest.content = content;
+            self.empty = false;
+        } else if self.manifest.content != content {
+            return Err(Error::InvalidFormat(format!(
+                "Manifest {} with data and delete files",
+                self.manifest.manifest_path
+            )));
+        }
+
         let mut added_rows_count = 0;
         let mut deleted_rows_count = 0;
 
+        let spec_id = self.manifest.partition_spec_id;
+        let partition_spec = self
+            .table_metadata
+            .partition_specs
+            .get(&spec_id)
+            .ok_or(Error::NotFound(format!("Partition spec with id {spec_id}")))?;
+
         if self.manifest.partitions.is_none() {
             self.manifest.partitions = Some(
-                self.table_metadata
-                    .default_partition_spec()?
+                partition_spec
                     .fields()
                     .iter()
                     .map(|_| FieldSummary {
@@ -405,7 +656,11 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
             );
         }
 
+        let status = *manifest_entry.status();
         match manifest_entry.data_file().content() {
+            Content::Data if status == Status::Deleted => {
+                deleted_rows_count += manifest_entry.data_file().record_count();
+            }
             Content::Data => {
                 added_rows_count += manifest_entry.data_file().record_count();
             }
@@ -414,12 +669,11 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
             }
             _ => (),
         }
-        let status = *manifest_entry.status();
 
         update_partitions(
             self.manifest.partitions.as_mut().unwrap(),
             manifest_entry.data_file().partition(),
-            self.table_metadata.default_partition_spec()?.fields(),
+            partition_spec.fields(),
         )?;
 
         if let Some(sequence_number) = manifest_entry.sequence_number() {
@@ -443,7 +697,12 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
                     None => Some(1),
                 };
             }
-            Status::Deleted => (),
+            Status::Deleted => {
+                self.manifest.deleted_files_count = match self.manifest.deleted_files_count {
+                    Some(count) => Some(count + 1),
+                    None => Some(1),
+                };
+            }
         }
 
         self.manifest.added_rows_count = match self.manifest.added_rows_count {
@@ -466,6 +725,9 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
     /// 2. Updates the manifest length
     /// 3. Writes the manifest file to the object store
     ///
+    /// The manifest file must not exist yet, so that two writers with the same commit uuid
+    /// can't overwrite each other's manifests.
+    ///
     /// # Arguments
     /// * `object_store` - The object store to write the manifest file to
     ///
@@ -476,6 +738,7 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
     /// Returns an error if:
     /// * The writer cannot be finalized
     /// * The manifest file cannot be written to storage
+    /// * The manifest file already exists
     pub(crate) async fn finish(
         mut self,
         object_store: Arc<dyn ObjectStore>,
@@ -486,16 +749,24 @@ impl<'schema, 'metadata> ManifestWriter<'schema, 'metadata> {
 
         self.manifest.manifest_length += manifest_length;
 
-        object_store
-            .put(
-                &strip_prefix(&self.manifest.manifest_path).as_str().into(),
-                manifest_bytes.into(),
-            )
-            .await?;
+        put_if_not_exists(
+            &*object_store,
+            &self.manifest.manifest_path,
+            manifest_bytes.into(),
+        )
+        .await?;
         Ok(self.manifest)
     }
 }
 
+/// Returns the value of the `content` metadata of a manifest file
+fn content_name(content: &manifest_list::Content) -> &'static str {
+    match content {
+        manifest_list::Content::Data => "data",
+        manifest_list::Content::Deletes => "deletes",
+    }
+}
+
 #[allow(clippy::type_complexity)]
 /// Convert avro value to ManifestEntry based on the format version of the table.
 fn avro_value_to_manifest_entry(
@@ -524,108 +795,53 @@ fn avro_value_to_manifest_entry(
     }
 }
 
+/// Updates the partition field summaries of a manifest with the partition values of a new entry.
+///
+/// Null values set `contains_null`, NaN values set `contains_nan` and all other values widen
+/// the lower and upper bounds.
 fn update_partitions(
     partitions: &mut [FieldSummary],
     partition_values: &Struct,
     partition_columns: &[PartitionField],
 ) -> Result<(), Error> {
     for (field, summary) in partition_columns.iter().zip(partitions.iter_mut()) {
-        let value = partition_values.get(field.name()).and_then(|x| x.as_ref());
-        if let Some(value) = value {
-            if summary.lower_bound.is_none() {
-                summary.lower_bound = Some(value.clone());
-            } else if let Some(lower_bound) = &mut summary.lower_bound {
-                match (value, lower_bound) {
-                    (Value::Int(val), Value::Int(current)) => {
-                        if *current > *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::LongInt(val), Value::LongInt(current)) => {
-                        if *current > *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Float(val), Value::Float(current)) => {
-                        if *current > *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Double(val), Value::Double(current)) => {
-                        if *current > *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Date(val), Value::Date(current)) => {
-                        if *current > *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Time(val), Value::Time(current)) => {
-                        if *current > *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Timestamp(val), Value::Timestamp(current)) => {
-                        if *current > *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::TimestampTZ(val), Value::TimestampTZ(current)) => {
-                        if *current > *val {
-                            *current = *val
-                        }
-                    }
-                    _ => {}
+        let Some(value) = partition_values.get(field.name()).and_then(|x| x.as_ref()) else {
+            summary.contains_null = true;
+            continue;
+        };
+        match value {
+            Value::Float(val) if val.is_nan() => {
+                summary.contains_nan = Some(true);
+                continue;
+            }
+            Value::Double(val) if val.is_nan() => {
+                summary.contains_nan = Some(true);
+                continue;
+            }
+            Value::Float(_) | Value::Double(_) => {
+                summary.contains_nan = Some(summary.contains_nan.unwrap_or(false));
+            }
+            _ => (),
+        }
+        match &mut summary.lower_bound {
+            Some(lower_bound) => {
+                if std::mem::discriminant(value) == std::mem::discriminant(lower_bound)
+                    && *value < *lower_bound
+                {
+                    *lower_bound = value.clone();
                 }
             }
-            if summary.upper_bound.is_none() {
-                summary.upper_bound = Some(value.clone());
-            } else if let Some(upper_bound) = &mut summary.upper_bound {
-                match (value, upper_bound) {
-                    (Value::Int(val), Value::Int(current)) => {
-                        if *current < *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::LongInt(val), Value::LongInt(current)) => {
-                        if *current < *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Float(val), Value::Float(current)) => {
-                        if *current < *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Double(val), Value::Double(current)) => {
-                        if *current < *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Date(val), Value::Date(current)) => {
-                        if *current < *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Time(val), Value::Time(current)) => {
-                        if *current < *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::Timestamp(val), Value::Timestamp(current)) => {
-                        if *current < *val {
-                            *current = *val
-                        }
-                    }
-                    (Value::TimestampTZ(val), Value::TimestampTZ(current)) => {
-                        if *current < *val {
-                            *current = *val
-                        }
-                    }
-                    _ => {}
+            None => summary.lower_bound = Some(value.clone()),
+        }
+        match &mut summary.upper_bound {
+            Some(upper_bound) => {
+                if std::mem::discriminant(value) == std::mem::discriminant(upper_bound)
+                    && *value > *upper_bound
+                {
+                    *upper_bound = value.clone();
                 }
             }
+            None => summary.upper_bound = Some(value.clone()),
         }
     }
     Ok(())
@@ -633,4 +849,56 @@ fn update_partitions(
 
 /// TODO
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use iceberg_rust_spec::{
+        manifest_list::FieldSummary,
+        partition::{PartitionField, Transform},
+        values::{Struct, Value},
+    };
+
+    use super::{update_partitions, ManifestValidation};
+
+    #[test]
+    fn validate_manifest_counts() {
+        let validation = ManifestValidation {
+            manifest_path: "s3://bucket/metadata/manifest.avro".to_owned(),
+            expected: [Some(2), Some(1), None],
+            actual: [2, 1, 5],
+        };
+        assert!(validation.validate().is_ok());
+
+        let validation = ManifestValidation {
+            actual: [1, 1, 0],
+            ..validation
+        };
+        assert!(validation.validate().is_err());
+    }
+
+    #[test]
+    fn update_partition_summaries() {
+        let columns = vec![PartitionField::new(1, 1000, "city", Transform::Identity)];
+        let mut partitions = vec![FieldSummary {
+            contains_null: false,
+            contains_nan: None,
+            lower_bound: None,
+            upper_bound: None,
+        }];
+        for city in [Some("Paris"), Some("Berlin"), None, Some("Rome")] {
+            let partition = Struct::from_iter(vec![(
+                "city".to_owned(),
+                city.map(|city| Value::String(city.to_owned())),
+            )]);
+            update_partitions(&mut partitions, &partition, &columns).unwrap();
+        }
+        assert!(partitions[0].contains_null);
+        assert_eq!(partitions[0].contains_nan, None);
+        assert_eq!(
+            partitions[0].lower_bound,
+            Some(Value::String("Berlin".to_owned()))
+        );
+        assert_eq!(
+            partitions[0].upper_bound,
+            Some(Value::String("Rome".to_owned()))
+        );
+    }
+}
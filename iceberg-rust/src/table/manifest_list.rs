@@ -9,23 +9,31 @@ use std::{
 };
 
 use apache_avro::{types::Value as AvroValue, Reader as AvroReader, Schema as AvroSchema};
+use futures::{stream, StreamExt};
 use iceberg_rust_spec::{
     manifest_list::{
         avro_value_to_manifest_list_entry, manifest_list_schema_v1, manifest_list_schema_v2,
         ManifestListEntry,
     },
     snapshot::Snapshot,
-    table_metadata::{FormatVersion, TableMetadata},
+    table_metadata::TableMetadata,
     util::strip_prefix,
 };
+use itertools::Itertools;
 use object_store::ObjectStore;
 
 use crate::error::Error;
 
-type ReaderZip<'a, 'metadata, R> = Zip<AvroReader<'a, R>, Repeat<&'metadata TableMetadata>>;
+type ReaderZip<'a, 'metadata, R> =
+    Zip<AvroReader<'a, R>, Repeat<(&'metadata TableMetadata, &'static AvroSchema)>>;
 type ReaderMap<'a, 'metadata, R> = Map<
     ReaderZip<'a, 'metadata, R>,
-    fn((Result<AvroValue, apache_avro::Error>, &TableMetadata)) -> Result<ManifestListEntry, Error>,
+    fn(
+        (
+            Result<AvroValue, apache_avro::Error>,
+            (&TableMetadata, &'static AvroSchema),
+        ),
+    ) -> Result<ManifestListEntry, Error>,
 >;
 
 /// A reader for Iceberg manifest list files that provides an iterator over manifest list entries.
@@ -37,23 +45,29 @@ type ReaderMap<'a, 'metadata, R> = Map<
 /// * `'a` - The lifetime of the underlying Avro reader
 /// * `'metadata` - The lifetime of the table metadata reference
 /// * `R` - The type implementing `Read` that provides the manifest list data
-pub(crate) struct ManifestListReader<'a, 'metadata, R: Read> {
+pub struct ManifestListReader<'a, 'metadata, R: Read> {
     reader: ReaderMap<'a, 'metadata, R>,
+    sequence_number_range: (Option<i64>, Option<i64>),
 }
 
 impl<R: Read> Iterator for ManifestListReader<'_, '_, R> {
     type Item = Result<ManifestListEntry, Error>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.reader.next()
+        let range = self.sequence_number_range;
+        self.reader.find(|entry| {
+            entry
+                .as_ref()
+                .map_or(true, |manifest| in_sequence_number_range(manifest, range))
+        })
     }
 }
 
 impl<'metadata, R: Read> ManifestListReader<'_, 'metadata, R> {
     /// Creates a new ManifestListReader from a reader and table metadata.
     ///
-    /// This method initializes a reader that can parse manifest list files according to
-    /// the table's format version (V1/V2). It uses the appropriate Avro schema based on
-    /// the format version from the table metadata.
+    /// This method initializes a reader that can parse manifest list files of both format
+    /// versions (V1/V2). The Avro schema is chosen by the format version the file was written
+    /// with, which can be older than the format version of the table after an upgrade.
     ///
     /// # Arguments
     /// * `reader` - A type implementing the `Read` trait that provides the manifest list data
@@ -66,19 +80,57 @@ impl<'metadata, R: Read> ManifestListReader<'_, 'metadata, R> {
     /// Returns an error if:
     /// * The Avro reader cannot be created with the schema
     /// * The manifest list format is invalid
-    pub(crate) fn new(reader: R, table_metadata: &'metadata TableMetadata) -> Result<Self, Error> {
-        let schema: &AvroSchema = match table_metadata.format_version {
-            FormatVersion::V1 => manifest_list_schema_v1(),
-            FormatVersion::V2 => manifest_list_schema_v2(),
-        };
+    pub fn new(reader: R, table_metadata: &'metadata TableMetadata) -> Result<Self, Error> {
+        let reader = AvroReader::new(reader)?;
+        let schema = manifest_list_schema(reader.writer_schema());
         Ok(Self {
-            reader: AvroReader::with_schema(schema, reader)?
-                .zip(repeat(table_metadata))
-                .map(|(avro_value_res, meta)| {
-                    avro_value_to_manifest_list_entry(avro_value_res, meta).map_err(Error::from)
-                }),
+            reader: reader.zip(repeat((table_metadata, schema))).map(
+                |(avro_value_res, (meta, schema))| {
+                    avro_value_to_manifest_list_entry(
+                        avro_value_res.and_then(|value| value.resolve(schema)),
+                        meta,
+                    )
+                    .map_err(Error::from)
+                },
+            ),
+            sequence_number_range: (None, None),
         })
     }
+
+    /// Skips the manifests that can't contain entries in the sequence number range.
+    ///
+    /// # Arguments
+    /// * `range` - Tuple of (start, end) sequence numbers. Manifests that were added at or before
+    ///   `start` or whose minimum sequence number is after `end` are skipped
+    ///
+    /// # Returns
+    /// * `Self` - The reader that only yields manifests in the range
+    pub fn filter_by_sequence_number(mut self, range: (Option<i64>, Option<i64>)) -> Self {
+        self.sequence_number_range = range;
+        self
+    }
+}
+
+/// Returns the manifest list schema of the format version a manifest list file was written with.
+///
+/// The format version of a table can be upgraded from V1 to V2, so the manifest lists of older
+/// snapshots may still use the V1 schema, which doesn't have sequence numbers.
+fn manifest_list_schema(writer_schema: &AvroSchema) -> &'static AvroSchema {
+    match writer_schema {
+        AvroSchema::Record(record) if !record.lookup.contains_key("sequence_number") => {
+            manifest_list_schema_v1()
+        }
+        _ => manifest_list_schema_v2(),
+    }
+}
+
+/// Checks whether a manifest can contain entries in the sequence number range (start, end]
+pub(crate) fn in_sequence_number_range(
+    manifest: &ManifestListEntry,
+    (start, end): (Option<i64>, Option<i64>),
+) -> bool {
+    start.is_none_or(|start| manifest.sequence_number > start)
+        && end.is_none_or(|end| manifest.min_sequence_number <= end)
 }
 
 /// Reads a snapshot's manifest list file and returns an iterator over its manifest list entries.
@@ -116,3 +168,78 @@ pub(crate) async fn read_snapshot<'metadata>(
     );
     ManifestListReader::new(bytes, table_metadata).map_err(Into::into)
 }
+
+/// Reads a snapshot's manifest list file and converts its entries concurrently.
+///
+/// The Avro values are decoded sequentially. Converting them into manifest list entries is
+/// split into chunks that are processed by up to `concurrency` tasks in parallel.
+///
+/// # Arguments
+/// * `snapshot` - The snapshot containing the manifest list location
+/// * `table_metadata` - Reference to the table metadata for format version info
+/// * `object_store` - The object store to read the manifest list file from
+/// * `concurrency` - The maximum number of concurrent tasks
+///
+/// # Returns
+/// * `Result<Vec<ManifestListEntry>, Error>` - The manifest list entries ordered by their sequence number
+pub(crate) async fn read_snapshot_concurrently(
+    snapshot: &Snapshot,
+    table_metadata: &TableMetadata,
+    object_store: Arc<dyn ObjectStore>,
+    concurrency: usize,
+) -> Result<Vec<ManifestListEntry>, Error> {
+    let bytes: Cursor<Vec<u8>> = Cursor::new(
+        object_store
+            .get(&strip_prefix(snapshot.manifest_list()).into())
+            .await?
+            .bytes()
+            .await?
+            .into(),
+    );
+    let reader = AvroReader::new(bytes)?;
+    let schema = manifest_list_schema(reader.writer_schema());
+    let values = reader
+        .map(|value| value.and_then(|value| value.resolve(schema)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let concurrency = concurrency.max(1);
+    let chunk_size = values.len().div_ceil(concurrency).max(1);
+    let table_metadata = Arc::new(table_metadata.clone());
+
+    let mut chunks = stream::iter(
+        values
+            .into_iter()
+            .chunks(chunk_size)
+            .into_iter()
+            .map(Iterator::collect::<Vec<_>>)
+            .enumerate()
+            .collect::<Vec<_>>(),
+    )
+    .map(|(i, chunk)| {
+        let table_metadata = table_metadata.clone();
+        tokio::task::spawn(async move {
+            let entries = chunk
+                .into_iter()
+                .map(|value| avro_value_to_manifest_list_entry(Ok(value), &table_metadata))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok::<_, Error>((i, entries))
+        })
+    })
+    .buffer_unordered(concurrency)
+    .collect::<Vec<_>>()
+    .await
+    .into_iter()
+    .map(|chunk| chunk?)
+    .collect::<Result<Vec<_>, Error>>()?;
+
+    // Restore the order of the manifest list before sorting, so that the result is deterministic
+    chunks.sort_by_key(|(i, _)| *i);
+
+    let mut entries = chunks
+        .into_iter()
+        .flat_map(|(_, entries)| entries)
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.sequence_number);
+
+    Ok(entries)
+}
@@ -0,0 +1,73 @@
+//! Naming scheme of the metadata files of a table
+//!
+//! The [`MetadataPathResolver`] trait determines where the manifests, manifest lists and
+//! metadata files of a table are written. [`DefaultMetadataPathResolver`] places all of them
+//! in the `metadata` directory below the table location. A custom implementation can be
+//! set with [`Table::with_metadata_path_resolver`](crate::table::Table::with_metadata_path_resolver),
+//! for example to separate the metadata prefix from the data prefix.
+
+use std::fmt::Debug;
+
+use iceberg_rust_spec::spec::table_metadata::{new_metadata_location, TableMetadata};
+
+/// Generates the paths of new metadata files of a table
+pub trait MetadataPathResolver: Debug + Send + Sync {
+    /// Returns the path of a new manifest file
+    ///
+    /// # Arguments
+    /// * `metadata` - The metadata of the table before the commit
+    /// * `commit_uuid` - Unique id of the commit that writes the manifest
+    /// * `index` - Index of the manifest within the commit
+    fn manifest_path(&self, metadata: &TableMetadata, commit_uuid: &str, index: usize) -> String;
+
+    /// Returns the path of a new manifest list file
+    ///
+    /// # Arguments
+    /// * `metadata` - The metadata of the table before the commit
+    /// * `snapshot_id` - Id of the snapshot the manifest list belongs to
+    /// * `attempt` - Number of the commit attempt
+    /// * `commit_uuid` - Unique id of the commit that writes the manifest list
+    fn manifest_list_path(
+        &self,
+        metadata: &TableMetadata,
+        snapshot_id: i64,
+        attempt: i64,
+        commit_uuid: &str,
+    ) -> String;
+
+    /// Returns the path of a new table metadata file
+    ///
+    /// # Arguments
+    /// * `metadata` - The metadata that will be written to the file
+    fn metadata_file_path(&self, metadata: &TableMetadata) -> String;
+}
+
+#[derive(Debug, Clone, Default)]
+/// Places all metadata files in the `metadata` directory of the table location
+pub struct DefaultMetadataPathResolver;
+
+impl MetadataPathResolver for DefaultMetadataPathResolver {
+    fn manifest_path(&self, metadata: &TableMetadata, commit_uuid: &str, index: usize) -> String {
+        format!(
+            "{}/metadata/{}-m{}.avro",
+            metadata.location, commit_uuid, index
+        )
+    }
+
+    fn manifest_list_path(
+        &self,
+        metadata: &TableMetadata,
+        snapshot_id: i64,
+        attempt: i64,
+        commit_uuid: &str,
+    ) -> String {
+        format!(
+            "{}/metadata/snap-{}-{}-{}.avro",
+            metadata.location, snapshot_id, attempt, commit_uuid
+        )
+    }
+
+    fn metadata_file_path(&self, metadata: &TableMetadata) -> String {
+        new_metadata_location(metadata)
+    }
+}
@@ -10,46 +10,84 @@
 //! Tables can be created using [`Table::builder()`] and modified using transactions
 //! created by [`Table::new_transaction()`].
 
-use std::{io::Cursor, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Cursor,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
 
+use arrow::record_batch::RecordBatch;
 use futures::future;
 use itertools::Itertools;
+use lru::LruCache;
 use manifest::ManifestReader;
-use manifest_list::read_snapshot;
+use manifest_list::{in_sequence_number_range, read_snapshot, read_snapshot_concurrently};
 use object_store::{path::Path, ObjectStore};
 
 use futures::{stream, Stream, StreamExt, TryFutureExt, TryStreamExt};
 use iceberg_rust_spec::util::{self};
 use iceberg_rust_spec::{
+    expression::BoundPredicate,
     spec::{
-        manifest::{Content, ManifestEntry},
-        manifest_list::ManifestListEntry,
+        manifest::{Content, ManifestEntry, Status},
+        manifest_list::{Content as ManifestContent, ManifestListEntry},
+        partition::{BoundPartitionField, PartitionSpec},
         schema::Schema,
+        snapshot::{Snapshot, SnapshotReference, SnapshotRetention},
+        sort::SortOrder,
         table_metadata::TableMetadata,
+        values::Struct,
     },
     table_metadata::{
-        WRITE_OBJECT_STORAGE_ENABLED, WRITE_PARQUET_COMPRESSION_CODEC,
+        FormatVersion, WRITE_OBJECT_STORAGE_ENABLED, WRITE_PARQUET_COMPRESSION_CODEC,
         WRITE_PARQUET_COMPRESSION_LEVEL,
     },
 };
 
 use crate::{
+    arrow::read::read_data_file,
     catalog::{create::CreateTableBuilder, identifier::Identifier, Catalog},
     error::Error,
-    object_store::Bucket,
-    table::transaction::TableTransaction,
+    file_io::{FileIO, FileIOObjectStore, ObjectStoreFileIO},
+    object_store::{
+        retry::{RetryConfig, RetryingObjectStore},
+        Bucket,
+    },
+    table::{
+        inspect::{DataFileMetadata, FileStats, HistoryEntry, SnapshotMetadata},
+        metadata_path::{DefaultMetadataPathResolver, MetadataPathResolver},
+        scan::FileScanTask,
+        transaction::TableTransaction,
+    },
 };
 
+pub mod inspect;
 pub mod manifest;
 pub mod manifest_list;
+pub mod metadata_path;
+pub mod scan;
 pub mod transaction;
 
+/// Default number of concurrent tasks used to read table metadata
+static DEFAULT_IO_CONCURRENCY: usize = 16;
+
+/// Parsed manifest lists by snapshot id
+type ManifestCache = Arc<Mutex<LruCache<i64, Arc<Vec<ManifestListEntry>>>>>;
+
 #[derive(Debug, Clone)]
 /// Iceberg table
 pub struct Table {
     identifier: Identifier,
     catalog: Arc<dyn Catalog>,
     metadata: TableMetadata,
+    io_concurrency: usize,
+    manifest_cache: Option<ManifestCache>,
+    metadata_path_resolver: Arc<dyn MetadataPathResolver>,
+    retry_config: Option<RetryConfig>,
+    file_io: Option<Arc<dyn FileIO>>,
+    object_store: Option<Arc<dyn ObjectStore>>,
+    validate_manifests: bool,
 }
 
 /// Public interface of the table.
@@ -105,8 +143,120 @@ impl Table {
             identifier,
             catalog,
             metadata,
+            io_concurrency: DEFAULT_IO_CONCURRENCY,
+            manifest_cache: None,
+            metadata_path_resolver: Arc::new(DefaultMetadataPathResolver),
+            retry_config: None,
+            file_io: None,
+            object_store: None,
+            validate_manifests: false,
         })
     }
+    /// Sets the maximum number of concurrent tasks used to read table metadata
+    ///
+    /// The limit also bounds the number of manifests that are fetched from the object store
+    /// at the same time, for example by [`Table::datafiles`] and [`Table::plan_scan`].
+    ///
+    /// # Arguments
+    /// * `io_concurrency` - The maximum number of concurrent tasks, at least 1
+    ///
+    /// # Returns
+    /// * `Table` - The table with the updated concurrency limit
+    pub fn with_io_concurrency(mut self, io_concurrency: usize) -> Self {
+        self.io_concurrency = io_concurrency.max(1);
+        self
+    }
+    #[inline]
+    /// Returns the maximum number of concurrent tasks used to read table metadata
+    pub fn io_concurrency(&self) -> usize {
+        self.io_concurrency
+    }
+    /// Sets the naming scheme for the metadata files written by transactions on the table
+    ///
+    /// # Arguments
+    /// * `resolver` - Generates the paths of new manifests, manifest lists and metadata files
+    ///
+    /// # Returns
+    /// * `Table` - The table using the given resolver
+    pub fn with_metadata_path_resolver(mut self, resolver: Arc<dyn MetadataPathResolver>) -> Self {
+        self.metadata_path_resolver = resolver;
+        self
+    }
+    #[inline]
+    /// Returns the naming scheme for the metadata files of the table
+    pub fn metadata_path_resolver(&self) -> Arc<dyn MetadataPathResolver> {
+        self.metadata_path_resolver.clone()
+    }
+    /// Retries requests to the object store of the table that fail with a transient error
+    ///
+    /// Reads of metadata and data files as well as the writes of commits are retried with
+    /// exponential backoff, see [`RetryingObjectStore`].
+    ///
+    /// # Arguments
+    /// * `config` - The number of retries and the backoff between them
+    ///
+    /// # Returns
+    /// * `Table` - The table retrying failed requests
+    pub fn with_retry(mut self, config: RetryConfig) -> Self {
+        self.retry_config = Some(config);
+        self
+    }
+    /// Reads and writes the files of the table with the given file io instead of the object store
+    /// of the catalog
+    ///
+    /// # Arguments
+    /// * `file_io` - The IO layer used for metadata and data files
+    ///
+    /// # Returns
+    /// * `Table` - The table using the file io
+    pub fn with_file_io(mut self, file_io: Arc<dyn FileIO>) -> Self {
+        self.file_io = Some(file_io);
+        self
+    }
+    /// Reads and writes the files of the table with the given object store instead of the object
+    /// store of the catalog for the table's bucket
+    ///
+    /// Catalogs use this for object stores with credentials that only apply to a single table.
+    ///
+    /// # Arguments
+    /// * `object_store` - The object store for the table's location
+    ///
+    /// # Returns
+    /// * `Table` - The table using the object store
+    pub fn with_object_store(mut self, object_store: Arc<dyn ObjectStore>) -> Self {
+        self.object_store = Some(object_store);
+        self
+    }
+    /// Checks the entries of every manifest that is read against the added, existing and deleted
+    /// file counts of its manifest list entry
+    ///
+    /// A manifest that doesn't match its counts is reported as [`Error::InvalidFormat`] instead of
+    /// silently returning a wrong set of files.
+    ///
+    /// # Arguments
+    /// * `validate` - Whether the manifests are validated
+    ///
+    /// # Returns
+    /// * `Table` - The table with the updated validation setting
+    pub fn with_manifest_validation(mut self, validate: bool) -> Self {
+        self.validate_manifests = validate;
+        self
+    }
+    /// Enables an in-memory cache for the parsed manifest lists of the table
+    ///
+    /// The cache memoizes the manifest list entries of the most recently used snapshots.
+    /// It is cleared whenever a transaction on the table is committed.
+    ///
+    /// # Arguments
+    /// * `capacity` - The maximum number of snapshots whose manifest lists are cached
+    ///
+    /// # Returns
+    /// * `Table` - The table with the manifest cache enabled, or disabled if the capacity is 0
+    pub fn with_manifest_cache(mut self, capacity: usize) -> Self {
+        self.manifest_cache =
+            NonZeroUsize::new(capacity).map(|x| Arc::new(Mutex::new(LruCache::new(x))));
+        self
+    }
     #[inline]
     /// Returns the unique identifier for this table in the catalog
     ///
@@ -130,17 +280,44 @@ impl Table {
         self.catalog.clone()
     }
     #[inline]
+    /// Returns the IO layer for this table's files
+    ///
+    /// This is the file io set with [`Table::with_file_io`] or otherwise the object store of the
+    /// table, see [`Table::object_store`].
+    ///
+    /// # Returns
+    /// * `Arc<dyn FileIO>` - A thread-safe reference to the table's file io
+    pub fn file_io(&self) -> Arc<dyn FileIO> {
+        match &self.file_io {
+            Some(file_io) => file_io.clone(),
+            None => Arc::new(ObjectStoreFileIO::new(self.object_store())),
+        }
+    }
+    #[inline]
     /// Returns the object store for this table's location
     ///
     /// The object store is determined by the table's location and is used for
     /// reading and writing table data files. The returned store is wrapped in
     /// an Arc to allow shared ownership and thread-safe access.
+    /// If the table uses a custom [`FileIO`], the object store is an adapter for it. Otherwise
+    /// the object store set with [`Table::with_object_store`] is used before the object store of
+    /// the catalog.
     ///
     /// # Returns
     /// * `Arc<dyn ObjectStore>` - A thread-safe reference to the table's object store
     pub fn object_store(&self) -> Arc<dyn ObjectStore> {
-        self.catalog
-            .object_store(Bucket::from_path(&self.metadata.location).unwrap())
+        let bucket = Bucket::from_path(&self.metadata.location).unwrap();
+        let object_store: Arc<dyn ObjectStore> = match &self.file_io {
+            Some(file_io) => Arc::new(FileIOObjectStore::new(file_io.clone(), &bucket.to_string())),
+            None => match &self.object_store {
+                Some(object_store) => object_store.clone(),
+                None => self.catalog.object_store(bucket),
+            },
+        };
+        match &self.retry_config {
+            Some(config) => Arc::new(RetryingObjectStore::new(object_store, config.clone())),
+            None => object_store,
+        }
     }
     #[inline]
     /// Returns the current schema for this table, optionally for a specific branch
@@ -157,6 +334,87 @@ impl Table {
         self.metadata.current_schema(branch).map_err(Error::from)
     }
     #[inline]
+    /// Returns the current snapshot of the table, optionally for a specific branch
+    ///
+    /// # Arguments
+    /// * `branch` - Optional branch name to get the snapshot for. If None, returns the snapshot of the main branch
+    ///
+    /// # Returns
+    /// * `Result<Option<&Snapshot>, Error>` - The current snapshot, or None if the table or the branch doesn't have a snapshot
+    ///
+    /// # Errors
+    /// Returns an error if the main branch has no snapshot although the table has snapshots
+    pub fn current_snapshot(&self, branch: Option<&str>) -> Result<Option<&Snapshot>, Error> {
+        self.metadata.current_snapshot(branch).map_err(Error::from)
+    }
+    #[inline]
+    /// Returns the default sort order of the table
+    ///
+    /// Data files written by the Arrow writer reference this sort order with their
+    /// `sort_order_id` if their rows are sorted by it. A sort order without fields means that
+    /// the table is unsorted.
+    ///
+    /// # Returns
+    /// * `Result<&SortOrder, Error>` - The default sort order
+    ///
+    /// # Errors
+    /// Returns an error if the default sort order id cannot be found in the table metadata
+    pub fn sort_order(&self) -> Result<&SortOrder, Error> {
+        self.metadata
+            .sort_orders
+            .get(&self.metadata.default_sort_order_id)
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "Sort order with id {}",
+                    self.metadata.default_sort_order_id
+                ))
+            })
+    }
+    #[inline]
+    /// Returns the fields of the default partition spec bound to their source columns in the
+    /// current schema, optionally for a specific branch
+    ///
+    /// # Arguments
+    /// * `branch` - Optional branch name to get the schema for. If None, uses the main branch schema
+    ///
+    /// # Returns
+    /// * `Result<Vec<BoundPartitionField>, Error>` - The partition fields with their transforms and
+    ///   source columns, empty for an unpartitioned table
+    ///
+    /// # Errors
+    /// Returns an error if the schema or the partition spec cannot be found or a source column
+    /// is not part of the schema
+    pub fn partition_fields(
+        &self,
+        branch: Option<&str>,
+    ) -> Result<Vec<BoundPartitionField<'_>>, Error> {
+        self.metadata
+            .current_partition_fields(branch)
+            .map_err(Error::from)
+    }
+    #[inline]
+    /// Returns all branches and tags of the table
+    ///
+    /// # Returns
+    /// * `&HashMap<String, SnapshotReference>` - The references of the table by their name
+    pub fn refs(&self) -> &HashMap<String, SnapshotReference> {
+        &self.metadata.refs
+    }
+    /// Returns the branches of the table
+    ///
+    /// # Returns
+    /// * `Vec<(&str, &SnapshotReference)>` - The names and references of the branches, ordered by name
+    pub fn branches(&self) -> Vec<(&str, &SnapshotReference)> {
+        self.refs_with(|retention| matches!(retention, SnapshotRetention::Branch { .. }))
+    }
+    /// Returns the tags of the table
+    ///
+    /// # Returns
+    /// * `Vec<(&str, &SnapshotReference)>` - The names and references of the tags, ordered by name
+    pub fn tags(&self) -> Vec<(&str, &SnapshotReference)> {
+        self.refs_with(|retention| matches!(retention, SnapshotRetention::Tag { .. }))
+    }
+    #[inline]
     /// Returns a reference to this table's metadata
     ///
     /// The metadata contains all table information including:
@@ -183,15 +441,66 @@ impl Table {
     pub fn into_metadata(self) -> TableMetadata {
         self.metadata
     }
+    /// Returns the history of the current snapshot of the table
+    ///
+    /// Every entry corresponds to a change of the current snapshot. The history is derived
+    /// from the snapshot log of the table metadata and doesn't require any IO.
+    ///
+    /// # Returns
+    /// * `Vec<HistoryEntry>` - The history entries ordered by the time they became current
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        let metadata = self.metadata();
+
+        let mut ancestors = HashSet::new();
+        let mut current = metadata.current_snapshot_id;
+        while let Some(snapshot) = current.and_then(|id| metadata.snapshots.get(&id)) {
+            if !ancestors.insert(*snapshot.snapshot_id()) {
+                break;
+            }
+            current = *snapshot.parent_snapshot_id();
+        }
+
+        metadata
+            .snapshot_log
+            .iter()
+            .filter_map(|entry| {
+                let snapshot = metadata.snapshots.get(&entry.snapshot_id)?;
+                Some(HistoryEntry {
+                    made_current_at: entry.timestamp_ms,
+                    snapshot_id: entry.snapshot_id,
+                    parent_id: *snapshot.parent_snapshot_id(),
+                    is_current_ancestor: ancestors.contains(&entry.snapshot_id),
+                })
+            })
+            .collect()
+    }
+    /// Returns the snapshots of the table together with their summaries
+    ///
+    /// The snapshots are read from the table metadata and don't require any IO.
+    ///
+    /// # Returns
+    /// * `Vec<SnapshotMetadata>` - The snapshots ordered by their commit timestamp
+    pub fn snapshots_metadata(&self) -> Vec<SnapshotMetadata> {
+        let mut snapshots = self
+            .metadata()
+            .snapshots
+            .values()
+            .map(SnapshotMetadata::from)
+            .collect::<Vec<_>>();
+        snapshots.sort_by_key(|x| x.committed_at);
+        snapshots
+    }
     /// Returns manifest list entries for snapshots within the given sequence range
     ///
     /// # Arguments
     /// * `start` - Optional starting snapshot ID (exclusive). If None, includes from the beginning
     /// * `end` - Optional ending snapshot ID (inclusive). If None, uses the current snapshot
     ///
+    /// The manifest list entries are converted concurrently, see [`Table::with_io_concurrency`].
+    ///
     /// # Returns
-    /// * `Result<Vec<ManifestListEntry>, Error>` - Vector of manifest entries in the range,
-    ///    or an empty vector if no current snapshot exists
+    /// * `Result<Vec<ManifestListEntry>, Error>` - Vector of manifest entries in the range ordered
+    ///   by their sequence number, or an empty vector if no current snapshot exists
     ///
     /// # Errors
     /// Returns an error if:
@@ -206,7 +515,7 @@ impl Table {
         let end_snapshot = match end.and_then(|id| metadata.snapshots.get(&id)) {
             Some(snapshot) => snapshot,
             None => {
-                if let Some(current) = metadata.current_snapshot(None)? {
+                if let Some(current) = self.current_snapshot(None)? {
                     current
                 } else {
                     return Ok(vec![]);
@@ -224,16 +533,20 @@ impl Table {
                         Some(sequence_number)
                     }
                 });
-        let iter = read_snapshot(end_snapshot, metadata, self.object_store().clone()).await?;
+        let manifests = self.snapshot_manifests(end_snapshot).await?;
         match start_sequence_number {
-            Some(start) => iter
-                .filter_ok(|manifest| manifest.sequence_number > start)
-                .collect(),
-            None => iter.collect(),
+            Some(start) => Ok(manifests
+                .iter()
+                .filter(|manifest| in_sequence_number_range(manifest, (Some(start), None)))
+                .cloned()
+                .collect()),
+            None => Ok(manifests.as_ref().clone()),
         }
     }
     /// Returns a stream of manifest entries for the given manifest list entries
     ///
+    /// Works like [`Table::datafiles_matching`] without a predicate on the table columns.
+    ///
     /// # Arguments
     /// * `manifests` - List of manifest entries to read data files from
     /// * `filter` - Optional vector of boolean predicates to filter manifest entries
@@ -255,11 +568,597 @@ impl Table {
         filter: Option<Vec<bool>>,
         sequence_number_range: (Option<i64>, Option<i64>),
     ) -> Result<impl Stream<Item = Result<ManifestEntry, Error>> + 'a, Error> {
-        datafiles(
+        self.datafiles_matching(manifests, filter, None, sequence_number_range)
+            .await
+    }
+    /// Returns a stream of the manifest entries for the given manifest list entries whose data
+    /// files might match the predicate
+    ///
+    /// # Arguments
+    /// * `manifests` - List of manifest entries to read data files from
+    /// * `filter` - Optional vector of boolean predicates to filter manifest entries
+    /// * `predicate` - Optional predicate on the table columns. Files whose column bounds can't match
+    ///   the predicate are skipped
+    /// * `sequence_number_range` - Tuple of (start, end) sequence numbers to filter entries by
+    ///
+    /// # Returns
+    /// * `Result<impl Stream<Item = Result<ManifestEntry, Error>>, Error>` - Stream of manifest entries
+    ///   that match the given filters
+    ///
+    /// # Type Parameters
+    /// * `'a` - Lifetime of the manifest list entries reference
+    ///
+    /// # Errors
+    /// Returns an error if reading any manifest file fails
+    #[inline]
+    pub async fn datafiles_matching<'a>(
+        &self,
+        manifests: &'a [ManifestListEntry],
+        filter: Option<Vec<bool>>,
+        predicate: Option<&BoundPredicate>,
+        sequence_number_range: (Option<i64>, Option<i64>),
+    ) -> Result<impl Stream<Item = Result<ManifestEntry, Error>> + 'a, Error> {
+        let predicate = predicate.cloned();
+        Ok(datafiles(
             self.object_store(),
+            &self.metadata().partition_specs,
             manifests,
             filter,
             sequence_number_range,
+            self.io_concurrency,
+            self.validate_manifests,
+        )
+        .await?
+        .try_filter(move |entry| {
+            future::ready(
+                predicate
+                    .as_ref()
+                    .is_none_or(|predicate| entry.data_file().might_match(predicate)),
+            )
+        }))
+    }
+    /// Returns a stream of the manifest entries of data files for the given manifest list entries
+    ///
+    /// Works like [`Table::datafiles_matching`], but entries of position and equality delete files are skipped.
+    ///
+    /// # Arguments
+    /// * `manifests` - List of manifest entries to read data files from
+    /// * `filter` - Optional vector of boolean predicates to filter manifest entries
+    /// * `predicate` - Optional predicate on the table columns. Files whose column bounds can't match
+    ///   the predicate are skipped
+    /// * `sequence_number_range` - Tuple of (start, end) sequence numbers to filter entries by
+    ///
+    /// # Returns
+    /// * `Result<impl Stream<Item = Result<ManifestEntry, Error>>, Error>` - Stream of the manifest
+    ///   entries of data files that match the given filters
+    ///
+    /// # Errors
+    /// Returns an error if reading any manifest file fails
+    pub async fn data_files<'a>(
+        &self,
+        manifests: &'a [ManifestListEntry],
+        filter: Option<Vec<bool>>,
+        predicate: Option<&BoundPredicate>,
+        sequence_number_range: (Option<i64>, Option<i64>),
+    ) -> Result<impl Stream<Item = Result<ManifestEntry, Error>> + 'a, Error> {
+        Ok(self
+            .datafiles_matching(manifests, filter, predicate, sequence_number_range)
+            .await?
+            .try_filter(|entry| {
+                future::ready(matches!(entry.data_file().content(), Content::Data))
+            }))
+    }
+    /// Returns a stream of manifest entries for the manifests whose partitions might match the predicate
+    ///
+    /// The predicate is evaluated against the partition field summaries of each manifest list entry.
+    /// Manifests that can't contain matching partitions are skipped without being read.
+    ///
+    /// # Arguments
+    /// * `manifests` - List of manifest entries to read data files from
+    /// * `partition_predicate` - Predicate on the partition fields, referenced by their partition field id
+    /// * `sequence_number_range` - Tuple of (start, end) sequence numbers to filter entries by
+    ///
+    /// # Returns
+    /// * `Result<impl Stream<Item = Result<ManifestEntry, Error>>, Error>` - Stream of manifest entries
+    ///   of the manifests that might match the predicate
+    ///
+    /// # Errors
+    /// Returns an error if reading any manifest file fails
+    pub async fn datafiles_with_predicate<'a>(
+        &self,
+        manifests: &'a [ManifestListEntry],
+        partition_predicate: &BoundPredicate,
+        sequence_number_range: (Option<i64>, Option<i64>),
+    ) -> Result<impl Stream<Item = Result<ManifestEntry, Error>> + 'a, Error> {
+        let filter = manifests
+            .iter()
+            .map(|manifest| {
+                scan::manifest_might_match_partition(manifest, partition_predicate, self.metadata())
+            })
+            .collect();
+        datafiles(
+            self.object_store(),
+            &self.metadata().partition_specs,
+            manifests,
+            Some(filter),
+            sequence_number_range,
+            self.io_concurrency,
+            self.validate_manifests,
+        )
+        .await
+    }
+    /// Returns a stream of the manifest entries whose partition values equal the given partition
+    ///
+    /// Manifests whose partition summaries can't contain the partition are skipped without being read.
+    ///
+    /// # Arguments
+    /// * `manifests` - List of manifest entries to read data files from
+    /// * `partition` - The partition values, referenced by the partition field names
+    /// * `sequence_number_range` - Tuple of (start, end) sequence numbers to filter entries by
+    ///
+    /// # Returns
+    /// * `Result<impl Stream<Item = Result<ManifestEntry, Error>>, Error>` - Stream of manifest entries
+    ///   in the partition
+    ///
+    /// # Errors
+    /// Returns an error if reading any manifest file fails
+    pub async fn datafiles_in_partition<'a>(
+        &self,
+        manifests: &'a [ManifestListEntry],
+        partition: &'a Struct,
+        sequence_number_range: (Option<i64>, Option<i64>),
+    ) -> Result<impl Stream<Item = Result<ManifestEntry, Error>> + 'a, Error> {
+        let filter = manifests
+            .iter()
+            .map(|manifest| {
+                scan::manifest_might_contain_partition(manifest, partition, self.metadata())
+            })
+            .collect();
+        Ok(datafiles(
+            self.object_store(),
+            &self.metadata().partition_specs,
+            manifests,
+            Some(filter),
+            sequence_number_range,
+            self.io_concurrency,
+            self.validate_manifests,
+        )
+        .await?
+        .try_filter(move |entry| future::ready(entry.data_file().partition() == partition)))
+    }
+    /// Returns a stream of the positional and equality delete files for the given manifest list entries
+    ///
+    /// # Arguments
+    /// * `manifests` - List of manifest entries to read delete files from
+    /// * `sequence_number_range` - Tuple of (start, end) sequence numbers to filter entries by
+    ///
+    /// # Returns
+    /// * `Result<impl Stream<Item = Result<ManifestEntry, Error>>, Error>` - Stream of manifest entries
+    ///   whose content is `Content::PositionDeletes` or `Content::EqualityDeletes`
+    ///
+    /// # Errors
+    /// Returns an error if reading any manifest file fails
+    #[inline]
+    pub async fn delete_files<'a>(
+        &self,
+        manifests: &'a [ManifestListEntry],
+        sequence_number_range: (Option<i64>, Option<i64>),
+    ) -> Result<impl Stream<Item = Result<ManifestEntry, Error>> + 'a, Error> {
+        Ok(datafiles(
+            self.object_store(),
+            &self.metadata().partition_specs,
+            manifests,
+            None,
+            sequence_number_range,
+            self.io_concurrency,
+            self.validate_manifests,
+        )
+        .await?
+        .try_filter(|entry| {
+            future::ready(matches!(
+                entry.data_file().content(),
+                Content::PositionDeletes | Content::EqualityDeletes
+            ))
+        }))
+    }
+    /// Plans a scan of the table and returns the data files that have to be read
+    ///
+    /// Manifests and data files whose partition values can't match the filter are skipped.
+    /// Every returned task contains the delete files that have to be applied to its data file.
+    ///
+    /// # Arguments
+    /// * `filter` - Optional predicate on the columns of the table schema
+    /// * `branch` - Optional branch to scan. If None, the main branch is scanned
+    ///
+    /// # Returns
+    /// * `Result<Vec<FileScanTask>, Error>` - The scan tasks grouped by partition
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The branch doesn't exist
+    /// * Reading the manifest list or a manifest fails
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                table = %self.identifier,
+                snapshot_id = tracing::field::Empty,
+                manifests = tracing::field::Empty,
+                files = tracing::field::Empty
+            )
+        )
+    )]
+    pub async fn plan_scan(
+        &self,
+        filter: Option<&BoundPredicate>,
+        branch: Option<&str>,
+    ) -> Result<Vec<FileScanTask>, Error> {
+        let Some(snapshot) = self.metadata.current_snapshot(branch)? else {
+            return Ok(Vec::new());
+        };
+        let manifests = self.snapshot_manifests(snapshot).await?;
+        let tasks = scan::plan_scan(
+            self.metadata(),
+            self.object_store(),
+            &manifests,
+            filter,
+            self.io_concurrency,
+            self.validate_manifests,
+        )
+        .await?;
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record("snapshot_id", *snapshot.snapshot_id())
+            .record("manifests", manifests.len())
+            .record("files", tasks.len());
+
+        Ok(tasks)
+    }
+    /// Reads the data of the current snapshot as a stream of arrow record batches
+    ///
+    /// The data files are read with the parquet reader without requiring a query engine. The
+    /// record batches are returned in the order of the scan tasks, see [`Table::plan_scan`].
+    ///
+    /// # Arguments
+    /// * `projection` - Optional field ids of the columns to read. The columns of the record
+    ///   batches are in the order of the field ids. If None, all columns are read
+    /// * `branch` - Optional branch to read. If None, the main branch is read
+    ///
+    /// # Returns
+    /// * `impl Stream<Item = Result<RecordBatch, Error>>` - Stream of the record batches, empty if
+    ///   no current snapshot exists
+    ///
+    /// # Errors
+    /// The stream yields an error if:
+    /// * The branch doesn't exist
+    /// * A projected field id is not part of the schema
+    /// * The snapshot contains delete files, merge-on-read deletes are not supported
+    /// * Reading a manifest or a data file fails
+    pub fn scan<'a>(
+        &'a self,
+        projection: Option<Vec<i32>>,
+        branch: Option<&'a str>,
+    ) -> impl Stream<Item = Result<RecordBatch, Error>> + 'a {
+        stream::once(async move {
+            let schema = self.current_schema(branch)?;
+            let projection = projection
+                .map(|ids| {
+                    ids.into_iter()
+                        .map(|id| {
+                            schema.get(id as usize).cloned().ok_or_else(|| {
+                                Error::Schema(id.to_string(), schema.schema_id().to_string())
+                            })
+                        })
+                        .collect::<Result<Vec<_>, Error>>()
+                })
+                .transpose()?;
+
+            let tasks = self.plan_scan(None, branch).await?;
+            if let Some(task) = tasks.iter().find(|task| !task.delete_files.is_empty()) {
+                return Err(Error::NotSupported(format!(
+                    "Reading data file {} with merge-on-read delete files",
+                    task.file_path()
+                )));
+            }
+
+            let object_store = self.object_store();
+            Ok(stream::iter(tasks)
+                .map(move |task| {
+                    let object_store = object_store.clone();
+                    let projection = projection.clone();
+                    async move {
+                        read_data_file(&task.data_file, projection.as_deref(), object_store).await
+                    }
+                })
+                .buffered(self.io_concurrency)
+                .try_flatten())
+        })
+        .try_flatten()
+    }
+    /// Returns a stream of all live data and delete files of the current snapshot
+    ///
+    /// # Arguments
+    /// * `branch` - Optional branch to list the files of. If None, the main branch is used
+    ///
+    /// # Returns
+    /// * `Result<impl Stream<Item = Result<DataFileMetadata, Error>>, Error>` - Stream of the
+    ///   metadata of the files, or an empty stream if no current snapshot exists
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The branch doesn't exist
+    /// * Reading the manifest list or a manifest fails
+    pub async fn files(
+        &self,
+        branch: Option<&str>,
+    ) -> Result<impl Stream<Item = Result<DataFileMetadata, Error>>, Error> {
+        let metadata = self.metadata();
+        let object_store = self.object_store();
+        let manifests: Vec<ManifestListEntry> = match metadata.current_snapshot(branch)? {
+            Some(snapshot) => self.snapshot_manifests(snapshot).await?.as_ref().clone(),
+            None => Vec::new(),
+        };
+        Ok(stream::iter(manifests)
+            .then(move |manifest| {
+                let object_store = object_store.clone();
+                async move {
+                    let path: Path = util::strip_prefix(&manifest.manifest_path).into();
+                    let bytes = object_store.get(&path).await?.into_stream();
+                    Ok::<_, Error>(ManifestReader::from_stream(bytes))
+                }
+            })
+            .try_flatten()
+            .try_filter(|entry| future::ready(*entry.status() != Status::Deleted))
+            .map_ok(|entry| DataFileMetadata::from(entry.data_file())))
+    }
+    /// Returns a stream of the data files that were added between two snapshots
+    ///
+    /// Only data files that were added by a snapshot in the ancestor chain after
+    /// `from_snapshot_id` up to and including `to_snapshot_id` and that are still live in
+    /// `to_snapshot_id` are returned. Files that were added and deleted again within the range
+    /// are skipped. For v2 tables, manifests that were written before `from_snapshot_id` aren't read.
+    ///
+    /// # Arguments
+    /// * `from_snapshot_id` - The snapshot after which the files were added (exclusive)
+    /// * `to_snapshot_id` - The snapshot up to which the files were added (inclusive)
+    ///
+    /// # Returns
+    /// * `Result<impl Stream<Item = Result<ManifestEntry, Error>>, Error>` - Stream of the manifest
+    ///   entries of the added data files
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * One of the snapshots doesn't exist
+    /// * `from_snapshot_id` is not an ancestor of `to_snapshot_id`
+    /// * Reading the manifest list or a manifest fails
+    pub async fn incremental_files(
+        &self,
+        from_snapshot_id: i64,
+        to_snapshot_id: i64,
+    ) -> Result<impl Stream<Item = Result<ManifestEntry, Error>>, Error> {
+        let metadata = self.metadata();
+        let get_snapshot = |id: i64| {
+            metadata
+                .snapshots
+                .get(&id)
+                .ok_or(Error::NotFound(format!("Snapshot {id}")))
+        };
+        let from_snapshot = get_snapshot(from_snapshot_id)?;
+        let to_snapshot = get_snapshot(to_snapshot_id)?;
+
+        // Snapshots in the ancestor chain between the two snapshots
+        let mut snapshot_ids = HashSet::new();
+        let mut current = to_snapshot;
+        while *current.snapshot_id() != from_snapshot_id {
+            snapshot_ids.insert(*current.snapshot_id());
+            current = current
+                .parent_snapshot_id()
+                .and_then(|id| metadata.snapshots.get(&id))
+                .ok_or(Error::NotFound(format!(
+                    "Snapshot {from_snapshot_id} in the ancestors of snapshot {to_snapshot_id}"
+                )))?;
+        }
+
+        let from_sequence_number = *from_snapshot.sequence_number();
+        let manifests: Vec<ManifestListEntry> = self
+            .snapshot_manifests(to_snapshot)
+            .await?
+            .iter()
+            .filter(|manifest| {
+                metadata.format_version == FormatVersion::V1
+                    || manifest.sequence_number > from_sequence_number
+            })
+            .cloned()
+            .collect();
+
+        let object_store = self.object_store();
+        let partition_specs = Arc::new(metadata.partition_specs.clone());
+        Ok(stream::iter(manifests)
+            .then(move |manifest| {
+                let object_store = object_store.clone();
+                let partition_specs = partition_specs.clone();
+                async move {
+                    let path: Path = util::strip_prefix(&manifest.manifest_path).into();
+                    let bytes = Cursor::new(Vec::from(
+                        object_store
+                            .get(&path)
+                            .and_then(|file| file.bytes())
+                            .await?,
+                    ));
+                    ManifestReader::for_spec(
+                        bytes,
+                        partition_specs.get(&manifest.partition_spec_id),
+                    )?
+                    .map_ok(|mut entry| {
+                        if entry.snapshot_id().is_none() {
+                            *entry.snapshot_id_mut() = Some(manifest.added_snapshot_id);
+                        }
+                        if entry.sequence_number().is_none() {
+                            *entry.sequence_number_mut() = Some(manifest.sequence_number);
+                        }
+                        entry
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+                }
+            })
+            .map_ok(|entries| stream::iter(entries.into_iter().map(Ok::<_, Error>)))
+            .try_flatten()
+            .try_filter(move |entry| {
+                future::ready(
+                    *entry.status() != Status::Deleted
+                        && *entry.data_file().content() == Content::Data
+                        && entry
+                            .snapshot_id()
+                            .is_some_and(|id| snapshot_ids.contains(&id)),
+                )
+            }))
+    }
+    /// Returns the number and the total size of the live data files of a branch
+    ///
+    /// The number of data files is summed from the file counts of the manifest list, only
+    /// manifests without these counts are read. The total size is taken from the
+    /// `total-files-size` summary of the snapshot if the snapshot doesn't have delete manifests,
+    /// otherwise all data manifests are read.
+    ///
+    /// # Arguments
+    /// * `branch` - Optional branch name. If None, uses the main branch
+    ///
+    /// # Returns
+    /// * `Result<FileStats, Error>` - The file statistics, or empty statistics if no current snapshot exists
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The branch doesn't exist
+    /// * Reading the manifest list or a manifest fails
+    pub async fn file_stats(&self, branch: Option<&str>) -> Result<FileStats, Error> {
+        let metadata = self.metadata();
+        let Some(snapshot) = metadata.current_snapshot(branch)? else {
+            return Ok(FileStats::default());
+        };
+        let manifests = self.snapshot_manifests(snapshot).await?;
+
+        let summary_size = if manifests
+            .iter()
+            .all(|manifest| manifest.content == ManifestContent::Data)
+        {
+            snapshot
+                .summary()
+                .other
+                .get("total-files-size")
+                .and_then(|x| x.parse::<u64>().ok())
+        } else {
+            None
+        };
+
+        let mut data_file_count = 0;
+        let mut unsummarized = Vec::new();
+        for manifest in manifests
+            .iter()
+            .filter(|manifest| manifest.content == ManifestContent::Data)
+        {
+            match (manifest.added_files_count, manifest.existing_files_count) {
+                (Some(added), Some(existing)) if summary_size.is_some() => {
+                    data_file_count += (added + existing) as u64
+                }
+                _ => unsummarized.push(manifest.clone()),
+            }
+        }
+
+        let (scanned_count, scanned_size) = datafiles(
+            self.object_store(),
+            &self.metadata().partition_specs,
+            &unsummarized,
+            None,
+            (None, None),
+            self.io_concurrency,
+            self.validate_manifests,
+        )
+        .await?
+        .try_filter(|entry| {
+            future::ready(
+                *entry.status() != Status::Deleted && *entry.data_file().content() == Content::Data,
+            )
+        })
+        .try_fold((0u64, 0u64), |(count, size), entry| {
+            future::ready(Ok::<_, Error>((
+                count + 1,
+                size + *entry.data_file().file_size_in_bytes() as u64,
+            )))
+        })
+        .await?;
+
+        Ok(FileStats {
+            data_file_count: data_file_count + scanned_count,
+            total_data_size: summary_size.unwrap_or(scanned_size),
+        })
+    }
+    /// Returns the number of added, existing and deleted manifest entries of a snapshot
+    ///
+    /// The counts are summed from the file counts of the manifest list entries, the manifests
+    /// themselves are not read. Manifests without file counts, which are optional in format
+    /// version 1, don't contribute to the counts.
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The id of the snapshot
+    ///
+    /// # Returns
+    /// * `Result<(usize, usize, usize), Error>` - The number of added, existing and deleted entries
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The snapshot doesn't exist
+    /// * Reading the manifest list fails
+    pub async fn entry_status_counts(
+        &self,
+        snapshot_id: i64,
+    ) -> Result<(usize, usize, usize), Error> {
+        let snapshot = self
+            .metadata()
+            .snapshots
+            .get(&snapshot_id)
+            .ok_or(Error::NotFound(format!("Snapshot {snapshot_id}")))?;
+        let manifests = self.snapshot_manifests(snapshot).await?;
+
+        let count = |files_count: Option<i32>| files_count.unwrap_or_default() as usize;
+        Ok(manifests
+            .iter()
+            .fold((0, 0, 0), |(added, existing, deleted), manifest| {
+                (
+                    added + count(manifest.added_files_count),
+                    existing + count(manifest.existing_files_count),
+                    deleted + count(manifest.deleted_files_count),
+                )
+            }))
+    }
+    /// Deletes all data files, manifests and manifest lists of the table
+    ///
+    /// The table is not removed from the catalog. Every processed file is reported to
+    /// `on_progress`. With `ignore_missing`, files that were already deleted are skipped
+    /// instead of aborting the cleanup, which allows to resume a partially deleted table.
+    ///
+    /// # Arguments
+    /// * `ignore_missing` - Whether files that don't exist are skipped
+    /// * `on_progress` - Callback that is called for every deleted or skipped file
+    ///
+    /// # Returns
+    /// * `Result<DeleteSummary, Error>` - The number of deleted and skipped files
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * Reading the manifest list or a manifest fails
+    /// * Deleting a file fails
+    pub async fn purge_files(
+        &self,
+        ignore_missing: bool,
+        on_progress: impl Fn(DeletedFile) + Send + Sync,
+    ) -> Result<DeleteSummary, Error> {
+        delete_all_table_files_with_progress(
+            self.metadata(),
+            self.object_store(),
+            ignore_missing,
+            &on_progress,
         )
         .await
     }
@@ -288,14 +1187,110 @@ impl Table {
     pub fn new_transaction(&mut self, branch: Option<&str>) -> TableTransaction {
         TableTransaction::new(self, branch)
     }
+    /// Makes a previous snapshot the current snapshot of the main branch
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The id of the snapshot to roll back to. It has to be an ancestor of the current snapshot.
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Ok if the table was rolled back, or an error if the snapshot
+    ///   is not an ancestor of the current snapshot or the commit failed
+    ///
+    /// Use [`TableTransaction::set_current_snapshot`] to roll back other branches or to
+    /// move a branch to a snapshot outside of its history.
+    pub async fn rollback_to_snapshot(&mut self, snapshot_id: i64) -> Result<(), Error> {
+        self.new_transaction(None)
+            .set_current_snapshot(snapshot_id, false)
+            .commit()
+            .await
+    }
+    /// Returns the references whose retention policy matches the predicate, ordered by name
+    fn refs_with(
+        &self,
+        predicate: impl Fn(&SnapshotRetention) -> bool,
+    ) -> Vec<(&str, &SnapshotReference)> {
+        let mut refs = self
+            .metadata
+            .refs
+            .iter()
+            .filter(|(_, reference)| predicate(&reference.retention))
+            .map(|(name, reference)| (name.as_str(), reference))
+            .collect::<Vec<_>>();
+        refs.sort_by_key(|(name, _)| *name);
+        refs
+    }
+    /// Returns the manifest list entries of a snapshot, using the manifest cache if enabled
+    async fn snapshot_manifests(
+        &self,
+        snapshot: &Snapshot,
+    ) -> Result<Arc<Vec<ManifestListEntry>>, Error> {
+        let snapshot_id = *snapshot.snapshot_id();
+        if let Some(cache) = &self.manifest_cache {
+            if let Some(manifests) = cache.lock().unwrap().get(&snapshot_id) {
+                return Ok(manifests.clone());
+            }
+        }
+        let manifests = Arc::new(
+            read_snapshot_concurrently(
+                snapshot,
+                self.metadata(),
+                self.object_store(),
+                self.io_concurrency,
+            )
+            .await?,
+        );
+        if let Some(cache) = &self.manifest_cache {
+            cache.lock().unwrap().put(snapshot_id, manifests.clone());
+        }
+        Ok(manifests)
+    }
+    /// Takes over the configuration of a previous instance of the table and clears the manifest cache
+    pub(crate) fn inherit_config(&mut self, previous: &Table) {
+        self.io_concurrency = previous.io_concurrency;
+        self.metadata_path_resolver = previous.metadata_path_resolver.clone();
+        self.retry_config = previous.retry_config.clone();
+        self.file_io = previous.file_io.clone();
+        self.object_store = previous.object_store.clone();
+        self.validate_manifests = previous.validate_manifests;
+        self.manifest_cache = previous.manifest_cache.clone();
+        if let Some(cache) = &self.manifest_cache {
+            cache.lock().unwrap().clear();
+        }
+    }
+}
+
+/// Fetches the content of a manifest file
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(manifest = %manifest.manifest_path, bytes = manifest.manifest_length)
+    )
+)]
+async fn fetch_manifest(
+    object_store: &dyn ObjectStore,
+    manifest: &ManifestListEntry,
+) -> Result<Cursor<Vec<u8>>, Error> {
+    let path: Path = util::strip_prefix(&manifest.manifest_path).into();
+    let bytes = object_store
+        .get(&path)
+        .and_then(|file| file.bytes())
+        .await?;
+    Ok(Cursor::new(Vec::from(bytes)))
 }
 
-async fn datafiles(
+/// Reads the entries of the manifests. At most `concurrency` manifests are fetched and read at the same time.
+/// If `validate` is set, the entries of every manifest are checked against the file counts of its manifest list entry.
+async fn datafiles<'a>(
     object_store: Arc<dyn ObjectStore>,
-    manifests: &'_ [ManifestListEntry],
+    partition_specs: &HashMap<i32, PartitionSpec>,
+    manifests: &'a [ManifestListEntry],
     filter: Option<Vec<bool>>,
     sequence_number_range: (Option<i64>, Option<i64>),
-) -> Result<impl Stream<Item = Result<ManifestEntry, Error>> + '_, Error> {
+    concurrency: usize,
+    validate: bool,
+) -> Result<impl Stream<Item = Result<ManifestEntry, Error>> + 'a, Error> {
     // filter manifest files according to filter vector
     let iter: Box<dyn Iterator<Item = &ManifestListEntry> + Send + Sync> = match filter {
         Some(predicate) => {
@@ -309,26 +1304,36 @@ async fn datafiles(
         None => Box::new(manifests.iter()),
     };
 
+    // The partition values of every manifest are decoded with the spec the manifest was written with
+    let partition_specs = Arc::new(partition_specs.clone());
+
     // Collect a vector of data files by creating a stream over the manifst files, fetch their content and return a flatten stream over their entries.
+    let concurrency = concurrency.max(1);
     Ok(stream::iter(iter)
-        .then(move |file| {
+        .map(move |file| {
             let object_store = object_store.clone();
             async move {
-                let path: Path = util::strip_prefix(&file.manifest_path).into();
-                let bytes = Cursor::new(Vec::from(
-                    object_store
-                        .get(&path)
-                        .and_then(|file| file.bytes())
-                        .await?,
-                ));
-                Ok::<_, Error>((bytes, file.sequence_number))
+                let bytes = fetch_manifest(object_store.as_ref(), file).await?;
+                Ok::<_, Error>((bytes, file))
             }
         })
-        .flat_map_unordered(None, move |result| {
-            let (bytes, sequence_number) = result.unwrap();
-
-            let reader = ManifestReader::new(bytes).unwrap();
-            stream::iter(reader).try_filter_map(move |mut x| {
+        .buffer_unordered(concurrency)
+        .flat_map_unordered(Some(concurrency), move |result| {
+            let reader = result.and_then(|(bytes, file)| {
+                let reader =
+                    ManifestReader::for_spec(bytes, partition_specs.get(&file.partition_spec_id))?;
+                let reader = if validate {
+                    reader.with_validation(file)
+                } else {
+                    reader
+                };
+                Ok((reader, file.sequence_number))
+            });
+            let (reader, sequence_number) = match reader {
+                Ok(reader) => reader,
+                Err(err) => return future::Either::Left(stream::once(future::ready(Err(err)))),
+            };
+            future::Either::Right(stream::iter(reader).try_filter_map(move |mut x| {
                 future::ready({
                     let sequence_number = if let Some(sequence_number) = x.sequence_number() {
                         *sequence_number
@@ -351,7 +1356,7 @@ async fn datafiles(
                         Ok(None)
                     }
                 })
-            })
+            }))
         }))
 }
 
@@ -360,52 +1365,272 @@ pub(crate) async fn delete_all_table_files(
     metadata: &TableMetadata,
     object_store: Arc<dyn ObjectStore>,
 ) -> Result<(), Error> {
+    delete_all_table_files_with_progress(metadata, object_store, false, &|_| ()).await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Kind of a file of a table
+pub enum TableFileKind {
+    /// Data or delete file
+    DataFile,
+    /// Manifest file
+    Manifest,
+    /// Manifest list file
+    ManifestList,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A file that was processed while deleting the files of a table
+pub struct DeletedFile {
+    /// Path of the file in the object store
+    pub path: String,
+    /// Kind of the file
+    pub kind: TableFileKind,
+    /// Whether the file was skipped because it didn't exist anymore
+    pub skipped: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Number of files that were deleted or skipped while deleting the files of a table
+pub struct DeleteSummary {
+    /// Number of deleted files
+    pub deleted: usize,
+    /// Number of files that didn't exist anymore
+    pub skipped: usize,
+}
+
+/// delete all datafiles, manifests and manifest lists and report every processed file
+pub(crate) async fn delete_all_table_files_with_progress(
+    metadata: &TableMetadata,
+    object_store: Arc<dyn ObjectStore>,
+    ignore_missing: bool,
+    on_progress: &(dyn Fn(DeletedFile) + Send + Sync),
+) -> Result<DeleteSummary, Error> {
     let Some(snapshot) = metadata.current_snapshot(None)? else {
-        return Ok(());
+        return Ok(DeleteSummary::default());
     };
     let manifests: Vec<ManifestListEntry> = read_snapshot(snapshot, metadata, object_store.clone())
         .await?
         .collect::<Result<_, _>>()?;
 
-    let datafiles = datafiles(object_store.clone(), &manifests, None, (None, None)).await?;
-    let snapshots = &metadata.snapshots;
+    let datafiles = datafiles(
+        object_store.clone(),
+        &metadata.partition_specs,
+        &manifests,
+        None,
+        (None, None),
+        DEFAULT_IO_CONCURRENCY,
+        false,
+    )
+    .await?
+    .map_ok(|entry| entry.data_file().file_path().clone());
+    let data_summary = delete_files_with_progress(
+        datafiles,
+        TableFileKind::DataFile,
+        &object_store,
+        ignore_missing,
+        on_progress,
+    )
+    .await?;
+
+    let manifest_paths =
+        stream::iter(manifests.iter()).map(|manifest| Ok(manifest.manifest_path.clone()));
+    let manifest_summary = delete_files_with_progress(
+        manifest_paths,
+        TableFileKind::Manifest,
+        &object_store,
+        ignore_missing,
+        on_progress,
+    )
+    .await?;
+
+    let manifest_list_paths = stream::iter(metadata.snapshots.values())
+        .map(|snapshot| Ok(snapshot.manifest_list().clone()));
+    let manifest_list_summary = delete_files_with_progress(
+        manifest_list_paths,
+        TableFileKind::ManifestList,
+        &object_store,
+        ignore_missing,
+        on_progress,
+    )
+    .await?;
 
-    // stream::iter(datafiles.into_iter())
-    datafiles
-        .try_for_each_concurrent(None, |datafile| {
+    Ok(DeleteSummary {
+        deleted: data_summary.deleted + manifest_summary.deleted + manifest_list_summary.deleted,
+        skipped: data_summary.skipped + manifest_summary.skipped + manifest_list_summary.skipped,
+    })
+}
+
+/// Deletes the files of the stream. Files that don't exist are skipped if `ignore_missing` is set.
+///
+/// Stores that support bulk deletes, like S3, delete the files in batches of up to 1000 files.
+/// Other stores fall back to deleting every file individually.
+async fn delete_files_with_progress(
+    files: impl Stream<Item = Result<String, Error>> + Send,
+    kind: TableFileKind,
+    object_store: &Arc<dyn ObjectStore>,
+    ignore_missing: bool,
+    on_progress: &(dyn Fn(DeletedFile) + Send + Sync),
+) -> Result<DeleteSummary, Error> {
+    let locations = files
+        .map_ok(|path| Path::from(util::strip_prefix(&path)))
+        .map_err(|err| object_store::Error::Generic {
+            store: "iceberg",
+            source: Box::new(err),
+        })
+        .boxed();
+
+    object_store
+        .delete_stream(locations)
+        .map(|result| match result {
+            Ok(location) => Ok((location.to_string(), false)),
+            Err(object_store::Error::NotFound { path, .. }) if ignore_missing => Ok((path, true)),
+            Err(err) => Err(Error::from(err)),
+        })
+        .try_fold(DeleteSummary::default(), |mut summary, (path, skipped)| {
+            on_progress(DeletedFile {
+                path,
+                kind,
+                skipped,
+            });
+            if skipped {
+                summary.skipped += 1;
+            } else {
+                summary.deleted += 1;
+            }
+            future::ready(Ok(summary))
+        })
+        .await
+}
+
+/// delete manifest lists, manifests and datafiles that are only referenced by snapshots that
+/// were removed from the table metadata. At most `concurrency` files are read or deleted at
+/// the same time.
+pub(crate) async fn delete_expired_files(
+    old_metadata: &TableMetadata,
+    new_metadata: &TableMetadata,
+    object_store: Arc<dyn ObjectStore>,
+    concurrency: usize,
+) -> Result<(), Error> {
+    let expired_snapshots = old_metadata
+        .snapshots
+        .iter()
+        .filter(|(id, _)| !new_metadata.snapshots.contains_key(id))
+        .map(|(_, snapshot)| snapshot)
+        .collect::<Vec<_>>();
+
+    if expired_snapshots.is_empty() {
+        return Ok(());
+    }
+
+    let retained_manifests = snapshot_manifests(
+        new_metadata.snapshots.values(),
+        new_metadata,
+        object_store.clone(),
+    )
+    .await?;
+    let retained_manifest_paths = retained_manifests
+        .iter()
+        .map(|manifest| manifest.manifest_path.as_str())
+        .collect::<HashSet<_>>();
+
+    let expired_manifests = snapshot_manifests(
+        expired_snapshots.iter().copied(),
+        old_metadata,
+        object_store.clone(),
+    )
+    .await?
+    .into_iter()
+    .filter(|manifest| !retained_manifest_paths.contains(manifest.manifest_path.as_str()))
+    .collect::<Vec<_>>();
+
+    let retained_datafiles = datafiles(
+        object_store.clone(),
+        &new_metadata.partition_specs,
+        &retained_manifests,
+        None,
+        (None, None),
+        concurrency,
+        false,
+    )
+    .await?
+    .map_ok(|entry| entry.data_file().file_path().clone())
+    .try_collect::<HashSet<_>>()
+    .await?;
+
+    datafiles(
+        object_store.clone(),
+        &old_metadata.partition_specs,
+        &expired_manifests,
+        None,
+        (None, None),
+        concurrency,
+        false,
+    )
+    .await?
+    .try_filter(|entry| future::ready(!retained_datafiles.contains(entry.data_file().file_path())))
+    .try_for_each_concurrent(Some(concurrency), |entry| {
+        let object_store = object_store.clone();
+        async move {
+            object_store
+                .delete(&util::strip_prefix(entry.data_file().file_path()).into())
+                .await?;
+            Ok(())
+        }
+    })
+    .await?;
+
+    stream::iter(expired_manifests.iter())
+        .map(Ok::<_, Error>)
+        .try_for_each_concurrent(Some(concurrency), |manifest| {
             let object_store = object_store.clone();
             async move {
                 object_store
-                    .delete(&datafile.data_file().file_path().as_str().into())
+                    .delete(&util::strip_prefix(&manifest.manifest_path).into())
                     .await?;
                 Ok(())
             }
         })
         .await?;
 
-    stream::iter(manifests.into_iter())
+    stream::iter(expired_snapshots)
         .map(Ok::<_, Error>)
-        .try_for_each_concurrent(None, |manifest| {
+        .try_for_each_concurrent(Some(concurrency), |snapshot| {
             let object_store = object_store.clone();
             async move {
-                object_store.delete(&manifest.manifest_path.into()).await?;
+                object_store
+                    .delete(&util::strip_prefix(snapshot.manifest_list()).into())
+                    .await?;
                 Ok(())
             }
         })
         .await?;
 
-    stream::iter(snapshots.values())
-        .map(Ok::<_, Error>)
-        .try_for_each_concurrent(None, |snapshot| {
+    Ok(())
+}
+
+/// read the manifest list entries of all the given snapshots, removing duplicate manifests
+async fn snapshot_manifests<'a>(
+    snapshots: impl Iterator<Item = &'a Snapshot>,
+    metadata: &TableMetadata,
+    object_store: Arc<dyn ObjectStore>,
+) -> Result<Vec<ManifestListEntry>, Error> {
+    let manifests = stream::iter(snapshots)
+        .then(|snapshot| {
             let object_store = object_store.clone();
             async move {
-                object_store
-                    .delete(&snapshot.manifest_list().as_str().into())
-                    .await?;
-                Ok(())
+                read_snapshot(snapshot, metadata, object_store)
+                    .await?
+                    .collect::<Result<Vec<_>, _>>()
             }
         })
+        .try_collect::<Vec<_>>()
         .await?;
 
-    Ok(())
+    Ok(manifests
+        .into_iter()
+        .flatten()
+        .unique_by(|manifest| manifest.manifest_path.clone())
+        .collect())
 }
@@ -0,0 +1,280 @@
+//! Scan planning for Iceberg tables
+//!
+//! This module turns the metadata of a table snapshot into a list of [`FileScanTask`]s that a
+//! query engine can execute independently. Scan planning:
+//!
+//! * Skips manifests whose partition summaries can't match the filter
+//! * Skips data files whose partition values can't match the filter
+//! * Associates every data file with the delete files that have to be applied to it, based on
+//!   their partition, their sequence numbers and, for position deletes, the referenced paths
+//!
+//! Only identity partition fields are used to prune manifests and data files.
+
+use std::{collections::HashMap, sync::Arc};
+
+use futures::TryStreamExt;
+use iceberg_rust_spec::{
+    expression::{BoundPredicate, FieldBounds},
+    manifest::{Content, DataFile, ManifestEntry, Status},
+    manifest_list::ManifestListEntry,
+    partition::Transform,
+    table_metadata::TableMetadata,
+    values::{Struct, Value},
+};
+use object_store::ObjectStore;
+
+use crate::{arrow::delete::POSITION_DELETE_FILE_PATH_ID, error::Error};
+
+use super::datafiles;
+
+#[derive(Debug, Clone)]
+/// A unit of work for scanning a single data file
+pub struct FileScanTask {
+    /// The data file to scan
+    pub data_file: DataFile,
+    /// The sequence number of the data file
+    pub sequence_number: i64,
+    /// Byte ranges given as (offset, length) that can be read independently
+    pub splits: Vec<(i64, i64)>,
+    /// Delete files that have to be applied when reading the data file
+    pub delete_files: Vec<DataFile>,
+}
+
+impl FileScanTask {
+    /// Returns the path of the data file to scan
+    pub fn file_path(&self) -> &str {
+        self.data_file.file_path()
+    }
+}
+
+/// Plans a scan of the given manifests of a snapshot.
+///
+/// The returned tasks are grouped by the partition of their data file. At most `concurrency`
+/// manifests are read at the same time. If `validate` is set, the manifests are checked against
+/// the file counts of their manifest list entries.
+pub(crate) async fn plan_scan(
+    metadata: &TableMetadata,
+    object_store: Arc<dyn ObjectStore>,
+    manifests: &[ManifestListEntry],
+    filter: Option<&BoundPredicate>,
+    concurrency: usize,
+    validate: bool,
+) -> Result<Vec<FileScanTask>, Error> {
+    let manifests: Vec<ManifestListEntry> = manifests
+        .iter()
+        .filter(|manifest| {
+            filter.is_none_or(|filter| manifest_might_match(manifest, filter, metadata))
+        })
+        .cloned()
+        .collect();
+
+    let (data_files, delete_files): (Vec<ManifestEntry>, Vec<ManifestEntry>) = datafiles(
+        object_store,
+        &metadata.partition_specs,
+        &manifests,
+        None,
+        (None, None),
+        concurrency,
+        validate,
+    )
+    .await?
+    .try_filter(|entry| {
+        futures::future::ready(
+            *entry.status() != Status::Deleted
+                && filter.is_none_or(|filter| {
+                    partition_might_match(entry.data_file().partition(), filter, metadata)
+                }),
+        )
+    })
+    .try_collect::<Vec<_>>()
+    .await?
+    .into_iter()
+    .partition(|entry| *entry.data_file().content() == Content::Data);
+
+    let mut tasks = data_files
+        .into_iter()
+        .map(|entry| {
+            let sequence_number = entry.sequence_number().unwrap_or(0);
+            let delete_files = delete_files
+                .iter()
+                .filter(|delete| applies_to(delete, &entry))
+                .map(|delete| delete.data_file().clone())
+                .collect();
+            let data_file = entry.data_file().clone();
+            let splits = splits(&data_file);
+            FileScanTask {
+                data_file,
+                sequence_number,
+                splits,
+                delete_files,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    tasks.sort_by(|left, right| left.data_file.partition().cmp(right.data_file.partition()));
+
+    Ok(tasks)
+}
+
+/// Check whether the partition summaries of a manifest might match the filter
+fn manifest_might_match(
+    manifest: &ManifestListEntry,
+    filter: &BoundPredicate,
+    metadata: &TableMetadata,
+) -> bool {
+    let (Some(spec), Some(summaries)) = (
+        metadata.partition_specs.get(&manifest.partition_spec_id),
+        manifest.partitions.as_ref(),
+    ) else {
+        return true;
+    };
+    filter.might_match(&|id| {
+        spec.fields()
+            .iter()
+            .zip(summaries.iter())
+            .find(|(field, _)| {
+                *field.source_id() == id && matches!(field.transform(), Transform::Identity)
+            })
+            .map(|(_, summary)| FieldBounds {
+                lower: summary.lower_bound.as_ref(),
+                upper: summary.upper_bound.as_ref(),
+                // Null values are not tracked reliably in the summaries
+                contains_null: None,
+            })
+    })
+}
+
+/// Check whether the partition summaries of a manifest might match a predicate on the partition fields
+pub(crate) fn manifest_might_match_partition(
+    manifest: &ManifestListEntry,
+    partition_predicate: &BoundPredicate,
+    metadata: &TableMetadata,
+) -> bool {
+    let (Some(spec), Some(summaries)) = (
+        metadata.partition_specs.get(&manifest.partition_spec_id),
+        manifest.partitions.as_ref(),
+    ) else {
+        return true;
+    };
+    partition_predicate.might_match(&|id| {
+        spec.fields()
+            .iter()
+            .position(|field| *field.field_id() == id)
+            .and_then(|i| summaries.get(i))
+            .map(|summary| FieldBounds {
+                lower: summary.lower_bound.as_ref(),
+                upper: summary.upper_bound.as_ref(),
+                contains_null: Some(summary.contains_null),
+            })
+    })
+}
+
+/// Check whether the partition summaries of a manifest might contain the given partition
+pub(crate) fn manifest_might_contain_partition(
+    manifest: &ManifestListEntry,
+    partition: &Struct,
+    metadata: &TableMetadata,
+) -> bool {
+    let (Some(spec), Some(summaries)) = (
+        metadata.partition_specs.get(&manifest.partition_spec_id),
+        manifest.partitions.as_ref(),
+    ) else {
+        return true;
+    };
+    spec.fields()
+        .iter()
+        .zip(summaries.iter())
+        .all(|(field, summary)| match partition.get(field.name()) {
+            Some(Some(value)) => {
+                summary
+                    .lower_bound
+                    .as_ref()
+                    .is_none_or(|lower| lower <= value)
+                    && summary
+                        .upper_bound
+                        .as_ref()
+                        .is_none_or(|upper| value <= upper)
+            }
+            Some(None) => summary.contains_null,
+            None => true,
+        })
+}
+
+/// Check whether the partition values of a data file might match the filter
+fn partition_might_match(
+    partition: &Struct,
+    filter: &BoundPredicate,
+    metadata: &TableMetadata,
+) -> bool {
+    filter.might_match(&|id| {
+        metadata
+            .partition_specs
+            .values()
+            .flat_map(|spec| spec.fields())
+            .filter(|field| {
+                *field.source_id() == id && matches!(field.transform(), Transform::Identity)
+            })
+            .find_map(|field| partition.get(field.name()))
+            .map(|value| FieldBounds {
+                lower: value.as_ref(),
+                upper: value.as_ref(),
+                contains_null: Some(value.is_none()),
+            })
+    })
+}
+
+/// Check whether a delete file has to be applied to a data file
+///
+/// A delete file only applies to the data files of its partition that were added before it.
+/// Equality delete files of an unpartitioned spec apply to the data files of all partitions.
+/// Position delete files additionally only apply to data files whose path lies within the
+/// bounds of their `file_path` column.
+fn applies_to(delete: &ManifestEntry, data: &ManifestEntry) -> bool {
+    let delete_file = delete.data_file();
+    let data_file = data.data_file();
+    let global = *delete_file.content() == Content::EqualityDeletes
+        && delete_file.partition().fields.is_empty();
+    if !global && delete_file.partition() != data_file.partition() {
+        return false;
+    }
+    let delete_sequence_number = delete.sequence_number().unwrap_or(0);
+    let data_sequence_number = data.sequence_number().unwrap_or(0);
+    match delete_file.content() {
+        Content::PositionDeletes => {
+            data_sequence_number <= delete_sequence_number
+                && might_reference(delete_file, data_file.file_path())
+        }
+        Content::EqualityDeletes => data_sequence_number < delete_sequence_number,
+        Content::Data => false,
+    }
+}
+
+/// Check whether the `file_path` bounds of a position delete file contain the path of a data file
+fn might_reference(delete_file: &DataFile, path: &str) -> bool {
+    path_bound(delete_file.lower_bounds()).is_none_or(|lower| lower <= path)
+        && path_bound(delete_file.upper_bounds()).is_none_or(|upper| path <= upper)
+}
+
+/// Returns the bound of the `file_path` column of a position delete file
+fn path_bound(bounds: &Option<HashMap<i32, Value>>) -> Option<&str> {
+    bounds
+        .as_ref()
+        .and_then(|bounds| bounds.get(&POSITION_DELETE_FILE_PATH_ID))
+        .and_then(|value| match value {
+            Value::String(value) => Some(value.as_str()),
+            _ => None,
+        })
+}
+
+/// Compute the byte ranges of a data file from its split offsets
+fn splits(data_file: &DataFile) -> Vec<(i64, i64)> {
+    let file_size = *data_file.file_size_in_bytes();
+    match data_file.split_offsets() {
+        Some(offsets) if !offsets.is_empty() => offsets
+            .iter()
+            .zip(offsets.iter().skip(1).chain(std::iter::once(&file_size)))
+            .map(|(start, end)| (*start, end - start))
+            .collect(),
+        _ => vec![(0, file_size)],
+    }
+}
@@ -1,6 +1,9 @@
 use std::cmp::Ordering;
 
-use iceberg_rust_spec::{manifest::ManifestEntry, manifest_list::ManifestListEntry};
+use iceberg_rust_spec::{
+    manifest::ManifestEntry,
+    manifest_list::{Content, ManifestListEntry},
+};
 use smallvec::SmallVec;
 
 use crate::{
@@ -97,23 +100,33 @@ pub(crate) fn split_datafiles(
     }
 }
 
+/// The data manifest that new data files are added to, `None` if the snapshot has no data manifest
 pub(crate) struct SelectedManifest {
-    pub manifest: ManifestListEntry,
+    pub manifest: Option<ManifestListEntry>,
     pub file_count_all_entries: usize,
 }
 
 /// Select the manifest that yields the smallest bounding rectangle after the
 /// bounding rectangle of the new values has been added.
+///
+/// Only data manifests that were written with the partition spec `spec_id` are considered.
 pub(crate) fn select_manifest_partitioned(
     manifest_list_reader: ManifestListReader<&[u8]>,
     manifest_list_writer: &mut apache_avro::Writer<Vec<u8>>,
     bounding_partition_values: &Rectangle,
+    spec_id: i32,
 ) -> Result<SelectedManifest, Error> {
     let mut selected_state = None;
     let mut file_count_all_entries = 0;
     for manifest_res in manifest_list_reader {
         let manifest = manifest_res?;
 
+        // Delete manifests and manifests of other partition specs are kept as they are
+        if manifest.content != Content::Data || manifest.partition_spec_id != spec_id {
+            manifest_list_writer.append_ser(manifest)?;
+            continue;
+        }
+
         let mut bounds =
             summary_to_rectangle(manifest.partitions.as_ref().ok_or(Error::NotFound(format!(
                 "Partition struct in manifest {}",
@@ -141,23 +154,30 @@ pub(crate) fn select_manifest_partitioned(
             }
         }
     }
-    selected_state
-        .map(|(_, entry)| SelectedManifest {
-            manifest: entry,
-            file_count_all_entries,
-        })
-        .ok_or(Error::NotFound("Manifest for insert".to_owned()))
+    Ok(SelectedManifest {
+        manifest: selected_state.map(|(_, entry)| entry),
+        file_count_all_entries,
+    })
 }
 
 /// Select the manifest with the smallest number of rows.
+///
+/// Only data manifests that were written with the partition spec `spec_id` are considered.
 pub(crate) fn select_manifest_unpartitioned(
     manifest_list_reader: ManifestListReader<&[u8]>,
     manifest_list_writer: &mut apache_avro::Writer<Vec<u8>>,
+    spec_id: i32,
 ) -> Result<SelectedManifest, Error> {
     let mut selected_state = None;
     let mut file_count_all_entries = 0;
     for manifest_res in manifest_list_reader {
         let manifest = manifest_res?;
+
+        // Delete manifests and manifests of other partition specs are kept as they are
+        if manifest.content != Content::Data || manifest.partition_spec_id != spec_id {
+            manifest_list_writer.append_ser(manifest)?;
+            continue;
+        }
         // TODO: should this also account for existing_rows_count / existing_files_count?
         let row_count = manifest.added_rows_count;
         file_count_all_entries += manifest.added_files_count.unwrap_or(0) as usize;
@@ -182,10 +202,8 @@ pub(crate) fn select_manifest_unpartitioned(
             continue;
         }
     }
-    selected_state
-        .map(|(_, entry)| SelectedManifest {
-            manifest: entry,
-            file_count_all_entries,
-        })
-        .ok_or(Error::NotFound("Manifest for insert".to_owned()))
+    Ok(SelectedManifest {
+        manifest: selected_state.map(|(_, entry)| entry),
+        file_count_all_entries,
+    })
 }
@@ -10,30 +10,68 @@
 //! table consistency. Common operations include:
 //!
 //! * Adding/updating schemas
+//! * Evolving the partition spec
 //! * Appending data files
+//! * Fast appending data files without rewriting manifests
+//! * Adding row level deletes together with new data files
 //! * Replacing data files
+//! * Deleting data files
+//! * Overwriting data files by a filter
+//! * Replacing the sort order
+//! * Moving the table location
 //! * Updating table properties
 //! * Managing snapshots and branches
+//! * Rolling back to previous snapshots
+//! * Fast-forwarding branches
+//! * Upgrading the format version
 
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
-use iceberg_rust_spec::spec::{manifest::DataFile, schema::Schema, snapshot::SnapshotReference};
+use iceberg_rust_spec::spec::{
+    expression::BoundPredicate,
+    manifest::DataFile,
+    schema::Schema,
+    snapshot::{SnapshotReference, SnapshotRetention},
+    sort::SortOrder,
+};
+use uuid::Uuid;
 
-use crate::{catalog::commit::CommitTable, error::Error, table::Table};
+use crate::{
+    catalog::commit::{apply_table_updates, CommitTable, TableRequirement, TableUpdate},
+    error::Error,
+    table::Table,
+};
 
-use self::operation::Operation;
+use self::operation::{IdSource, Operation};
+use self::update_spec::UpdatePartitionSpec;
 
-use super::delete_all_table_files;
+use super::delete_expired_files;
 
 pub(crate) mod append;
 pub(crate) mod operation;
+pub mod update_spec;
 
 pub(crate) static APPEND_KEY: &str = "append";
+pub(crate) static FAST_APPEND_KEY: &str = "fast-append";
+pub(crate) static ROW_DELTA_KEY: &str = "row-delta";
 pub(crate) static REPLACE_KEY: &str = "replace";
+pub(crate) static DELETE_KEY: &str = "delete";
+pub(crate) static OVERWRITE_KEY: &str = "overwrite";
+pub(crate) static EXPIRE_SNAPSHOTS_KEY: &str = "expire-snapshots";
+pub(crate) static REWRITE_MANIFESTS_KEY: &str = "rewrite-manifests";
 pub(crate) static ADD_SCHEMA_KEY: &str = "add-schema";
 pub(crate) static SET_DEFAULT_SPEC_KEY: &str = "set-default-spec";
+pub(crate) static UPDATE_SPEC_KEY: &str = "update-spec";
 pub(crate) static UPDATE_PROPERTIES_KEY: &str = "update-properties";
 pub(crate) static SET_SNAPSHOT_REF_KEY: &str = "set-ref";
+pub(crate) static REPLACE_SORT_ORDER_KEY: &str = "replace-sort-order";
+pub(crate) static SET_LOCATION_KEY: &str = "set-location";
+pub(crate) static SET_CURRENT_SNAPSHOT_KEY: &str = "set-current-snapshot";
+pub(crate) static FAST_FORWARD_KEY: &str = "fast-forward";
+pub(crate) static UPGRADE_FORMAT_VERSION_KEY: &str = "upgrade-format-version";
 
 /// A transaction that can perform multiple operations on a table atomically
 ///
@@ -57,6 +95,8 @@ pub struct TableTransaction<'table> {
     table: &'table mut Table,
     operations: HashMap<String, Operation>,
     branch: Option<String>,
+    retry: Option<(u32, Duration)>,
+    ids: IdSource,
 }
 
 impl<'table> TableTransaction<'table> {
@@ -66,8 +106,83 @@ impl<'table> TableTransaction<'table> {
             table,
             operations: HashMap::new(),
             branch: branch.map(ToString::to_string),
+            retry: None,
+            ids: IdSource::default(),
         }
     }
+    /// Retries the commit if it conflicts with a concurrent commit
+    ///
+    /// If the commit fails with [`Error::CommitConflict`], the table metadata is reloaded
+    /// from the catalog and all operations are executed again against the new metadata.
+    /// Only operations that assert the snapshot of a branch, like appends, deletes,
+    /// overwrites and snapshot reference updates, can cause a conflict and are retried.
+    ///
+    /// # Arguments
+    /// * `max_attempts` - The maximum number of commit attempts, including the first one
+    /// * `backoff` - The time to wait before the first retry, doubled for every further retry
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    pub fn with_retry(mut self, max_attempts: u32, backoff: Duration) -> Self {
+        self.retry = Some((max_attempts.max(1), backoff));
+        self
+    }
+    /// Sets the function that generates the ids of the snapshots written by the transaction
+    ///
+    /// By default the snapshot ids are random. A deterministic source, like a counter, makes
+    /// the written metadata reproducible in tests.
+    ///
+    /// # Arguments
+    /// * `source` - Function that returns a new snapshot id on every call
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    pub fn with_snapshot_id_source(
+        mut self,
+        source: impl Fn() -> i64 + Send + Sync + 'static,
+    ) -> Self {
+        self.ids = self.ids.with_snapshot_id(source);
+        self
+    }
+    /// Sets the function that generates the uuids in the file names of the manifests and
+    /// manifest lists written by the transaction
+    ///
+    /// # Arguments
+    /// * `source` - Function that returns a new uuid on every call
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    pub fn with_commit_uuid_source(
+        mut self,
+        source: impl Fn() -> Uuid + Send + Sync + 'static,
+    ) -> Self {
+        self.ids = self.ids.with_commit_uuid(source);
+        self
+    }
+    /// Sets the branch for all following operations of the transaction
+    ///
+    /// Operations on different branches are committed together, which allows to write
+    /// to multiple branches atomically. The snapshot of every branch is asserted separately.
+    ///
+    /// # Arguments
+    /// * `branch` - The branch the following operations are applied to
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// table.new_transaction(None)
+    ///     .append_data(data_files)
+    ///     .with_branch("staging")
+    ///     .append_data(staging_files)
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.branch = Some(branch.to_owned());
+        self
+    }
     /// Adds a new schema to the table
     ///
     /// This operation adds a new schema version to the table. The schema ID will be
@@ -99,6 +214,89 @@ impl<'table> TableTransaction<'table> {
         );
         self
     }
+    /// Adds a new partition spec that evolves the default spec and makes it the default spec
+    ///
+    /// The partition fields are added and removed against the current default spec and schema.
+    /// The new spec gets an unused spec id. If the result matches an existing spec, that spec
+    /// becomes the default spec instead.
+    ///
+    /// # Arguments
+    /// * `update` - The partition fields to add and remove
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// Files written before the commit keep the spec they were written with. Files appended
+    /// in the same transaction are still written with the previous default spec.
+    ///
+    /// # Examples
+    /// ```
+    /// table.new_transaction(None)
+    ///     .update_spec(UpdatePartitionSpec::new().add_field("ts", Transform::Day))
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn update_spec(mut self, update: UpdatePartitionSpec) -> Self {
+        self.operations
+            .insert(UPDATE_SPEC_KEY.to_owned(), Operation::UpdateSpec(update));
+        self
+    }
+    /// Replaces the default sort order of the table
+    ///
+    /// # Arguments
+    /// * `order` - The new sort order. Its `order_id` is ignored and a new id is assigned
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// Every sort field must reference a column of the current schema, otherwise the
+    /// commit fails with a schema error.
+    pub fn replace_sort_order(mut self, order: SortOrder) -> Self {
+        self.operations.insert(
+            REPLACE_SORT_ORDER_KEY.to_owned(),
+            Operation::ReplaceSortOrder(order),
+        );
+        self
+    }
+    /// Sets the base location of the table
+    ///
+    /// # Arguments
+    /// * `location` - The new location, either an absolute path or an object store URI like `s3://bucket/path`
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// Only files written after the commit are placed below the new location. Existing
+    /// snapshots reference their manifests and data files by absolute path, so they stay
+    /// readable as long as the old files are not removed.
+    pub fn set_location(mut self, location: &str) -> Self {
+        self.operations.insert(
+            SET_LOCATION_KEY.to_owned(),
+            Operation::SetLocation(location.to_owned()),
+        );
+        self
+    }
+    /// Upgrades the format version of the table, for example from V1 to V2
+    ///
+    /// The upgrade is applied before the other operations of the transaction, so that they
+    /// already write the new format. Existing manifest lists and manifests stay readable,
+    /// their format version is derived from the files themselves.
+    ///
+    /// # Arguments
+    /// * `format_version` - The new format version, 1 or 2
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// Upgrading to the current format version is a no-op. A downgrade fails the commit
+    /// with [`Error::NotSupported`].
+    pub fn upgrade_format_version(mut self, format_version: u8) -> Self {
+        self.operations.insert(
+            UPGRADE_FORMAT_VERSION_KEY.to_owned(),
+            Operation::UpgradeFormatVersion(format_version),
+        );
+        self
+    }
     /// Appends new data files to the table
     ///
     /// This operation adds new data files to the table's current snapshot. Multiple
@@ -119,7 +317,7 @@ impl<'table> TableTransaction<'table> {
     /// ```
     pub fn append_data(mut self, files: Vec<DataFile>) -> Self {
         self.operations
-            .entry(APPEND_KEY.to_owned())
+            .entry(branch_key(APPEND_KEY, &self.branch))
             .and_modify(|mut x| {
                 if let Operation::Append {
                     branch: _,
@@ -160,7 +358,7 @@ impl<'table> TableTransaction<'table> {
     /// ```
     pub fn append_delete(mut self, files: Vec<DataFile>) -> Self {
         self.operations
-            .entry(APPEND_KEY.to_owned())
+            .entry(branch_key(APPEND_KEY, &self.branch))
             .and_modify(|mut x| {
                 if let Operation::Append {
                     branch: _,
@@ -180,6 +378,91 @@ impl<'table> TableTransaction<'table> {
             });
         self
     }
+    /// Appends new data files to the table without rewriting existing manifests
+    ///
+    /// The data files are written to new manifests, which are added to the manifest list
+    /// next to the unchanged existing manifests. This reduces the latency of frequent small
+    /// appends at the cost of a growing number of manifests, which can be compacted later
+    /// with [`rewrite_manifests`](Self::rewrite_manifests). Multiple fast append operations
+    /// in the same transaction will be combined.
+    ///
+    /// # Arguments
+    /// * `files` - Vector of data files to append to the table
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .fast_append(data_files)
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn fast_append(mut self, files: Vec<DataFile>) -> Self {
+        self.operations
+            .entry(branch_key(FAST_APPEND_KEY, &self.branch))
+            .and_modify(|mut x| {
+                if let Operation::FastAppend {
+                    branch: _,
+                    files: old,
+                } = &mut x
+                {
+                    old.extend_from_slice(&files)
+                }
+            })
+            .or_insert(Operation::FastAppend {
+                branch: self.branch.clone(),
+                files,
+            });
+        self
+    }
+    /// Adds data files and delete files that remove rows of the table
+    ///
+    /// The data files and the delete files are written to separate new manifests. The delete
+    /// files get the sequence number of the new snapshot, so they apply to all data files
+    /// that were added before. Equality delete files don't apply to the data files of this
+    /// operation, which allows updates to be written as a delete of the old rows and an insert
+    /// of the new rows. Position delete files also apply to the data files of this operation.
+    /// Multiple row delta operations in the same transaction will be combined.
+    ///
+    /// # Arguments
+    /// * `data_files` - Vector of data files to add to the table
+    /// * `delete_files` - Vector of position or equality delete files to add to the table
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .row_delta(data_files, delete_files)
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn row_delta(mut self, data_files: Vec<DataFile>, delete_files: Vec<DataFile>) -> Self {
+        self.operations
+            .entry(branch_key(ROW_DELTA_KEY, &self.branch))
+            .and_modify(|mut x| {
+                if let Operation::RowDelta {
+                    branch: _,
+                    data_files: old_data_files,
+                    delete_files: old_delete_files,
+                    additional_summary: None,
+                } = &mut x
+                {
+                    old_data_files.extend_from_slice(&data_files);
+                    old_delete_files.extend_from_slice(&delete_files);
+                }
+            })
+            .or_insert(Operation::RowDelta {
+                branch: self.branch.clone(),
+                data_files,
+                delete_files,
+                additional_summary: None,
+            });
+        self
+    }
     /// Replaces all data files in the table with new ones
     ///
     /// This operation removes all existing data files and replaces them with the provided
@@ -200,7 +483,7 @@ impl<'table> TableTransaction<'table> {
     /// ```
     pub fn replace(mut self, files: Vec<DataFile>) -> Self {
         self.operations
-            .entry(REPLACE_KEY.to_owned())
+            .entry(branch_key(REPLACE_KEY, &self.branch))
             .and_modify(|mut x| {
                 if let Operation::Replace {
                     branch: _,
@@ -225,7 +508,7 @@ impl<'table> TableTransaction<'table> {
         additional_summary: HashMap<String, String>,
     ) -> Self {
         self.operations
-            .entry(REPLACE_KEY.to_owned())
+            .entry(branch_key(REPLACE_KEY, &self.branch))
             .and_modify(|mut x| {
                 if let Operation::Replace {
                     branch: _,
@@ -244,6 +527,147 @@ impl<'table> TableTransaction<'table> {
             });
         self
     }
+    /// Deletes data files from the table by their path
+    ///
+    /// This operation removes the data files with the given paths from the table's
+    /// current snapshot. Only manifests that contain one of the files are rewritten.
+    /// Multiple delete operations in the same transaction will be combined.
+    ///
+    /// # Arguments
+    /// * `paths` - Vector of data file paths to remove from the table
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .delete_files(vec!["s3://bucket/table/data/file.parquet".to_string()])
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn delete_files(mut self, paths: Vec<String>) -> Self {
+        self.operations
+            .entry(branch_key(DELETE_KEY, &self.branch))
+            .and_modify(|mut x| {
+                if let Operation::Delete {
+                    branch: _,
+                    paths: old,
+                } = &mut x
+                {
+                    old.extend_from_slice(&paths)
+                }
+            })
+            .or_insert(Operation::Delete {
+                branch: self.branch.clone(),
+                paths,
+            });
+        self
+    }
+    /// Overwrites the data files matching a filter with new files
+    ///
+    /// This operation removes every data file whose column bounds or partition values
+    /// guarantee that all of its rows match the filter and appends the new files.
+    /// Data files that only partially overlap the filter are kept. Multiple overwrite
+    /// operations in the same transaction will be combined. Requires format version 2.
+    ///
+    /// # Arguments
+    /// * `filter` - Predicate on the table schema selecting the rows to overwrite
+    /// * `files` - Vector of data files to add to the table
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .overwrite(BoundPredicate::Eq(1, Value::Date(19000)), data_files)
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn overwrite(mut self, filter: BoundPredicate, files: Vec<DataFile>) -> Self {
+        self.operations
+            .entry(branch_key(OVERWRITE_KEY, &self.branch))
+            .and_modify(|mut x| {
+                if let Operation::Overwrite {
+                    branch: _,
+                    filter: old_filter,
+                    files: old,
+                } = &mut x
+                {
+                    if *old_filter != filter {
+                        *old_filter = old_filter.clone().or(filter.clone());
+                    }
+                    old.extend_from_slice(&files)
+                }
+            })
+            .or_insert(Operation::Overwrite {
+                branch: self.branch.clone(),
+                filter,
+                files,
+            });
+        self
+    }
+    /// Expires old snapshots of the table
+    ///
+    /// This operation removes snapshots from the table metadata and deletes all manifest
+    /// lists, manifests and data files that are only referenced by the expired snapshots.
+    /// A snapshot is expired if it matches all of the given criteria. Snapshots that are
+    /// referenced by a branch or tag are always retained.
+    ///
+    /// # Arguments
+    /// * `older_than_ms` - Only expire snapshots with a timestamp older than this value
+    /// * `retain_last` - Always retain this number of the most recent snapshots
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .expire_snapshots(Some(1_700_000_000_000), Some(10))
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn expire_snapshots(
+        mut self,
+        older_than_ms: Option<i64>,
+        retain_last: Option<usize>,
+    ) -> Self {
+        self.operations.insert(
+            EXPIRE_SNAPSHOTS_KEY.to_owned(),
+            Operation::ExpireSnapshots {
+                older_than_ms,
+                retain_last,
+            },
+        );
+        self
+    }
+    /// Rewrites the manifests of the table to reduce their number
+    ///
+    /// This operation reads all live manifest entries of the current snapshot and
+    /// redistributes them into balanced manifests. Data files are not rewritten and
+    /// keep their sequence numbers.
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .rewrite_manifests()
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn rewrite_manifests(mut self) -> Self {
+        self.operations.insert(
+            branch_key(REWRITE_MANIFESTS_KEY, &self.branch),
+            Operation::RewriteManifests {
+                branch: self.branch.clone(),
+            },
+        );
+        self
+    }
     /// Updates the table properties with new key-value pairs
     ///
     /// This operation adds or updates table properties. Multiple update operations
@@ -302,11 +726,180 @@ impl<'table> TableTransaction<'table> {
     /// ```
     pub fn set_snapshot_ref(mut self, entry: (String, SnapshotReference)) -> Self {
         self.operations.insert(
-            SET_SNAPSHOT_REF_KEY.to_owned(),
+            SET_SNAPSHOT_REF_KEY.to_owned() + "-" + &entry.0,
             Operation::SetSnapshotRef(entry),
         );
         self
     }
+    /// Adds a new branch or tag, which must not exist yet
+    fn create_snapshot_ref(mut self, entry: (String, SnapshotReference)) -> Self {
+        self.operations.insert(
+            SET_SNAPSHOT_REF_KEY.to_owned() + "-" + &entry.0,
+            Operation::CreateSnapshotRef(entry),
+        );
+        self
+    }
+    /// Makes a previous snapshot the current snapshot of the branch
+    ///
+    /// The snapshot has to be an ancestor of the current snapshot of the branch, unless
+    /// `force` is set. Snapshots that are newer than the given one remain in the table
+    /// metadata until they are expired.
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The id of the snapshot the branch should point to
+    /// * `force` - Whether to allow snapshots that are not an ancestor of the current snapshot
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .set_current_snapshot(123, false)
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn set_current_snapshot(mut self, snapshot_id: i64, force: bool) -> Self {
+        self.operations.insert(
+            branch_key(SET_CURRENT_SNAPSHOT_KEY, &self.branch),
+            Operation::SetCurrentSnapshot {
+                branch: self.branch.clone(),
+                snapshot_id,
+                force,
+            },
+        );
+        self
+    }
+    /// Makes the latest snapshot at or before the given timestamp the current snapshot of the branch
+    ///
+    /// The snapshot is the newest ancestor of the current snapshot of the branch that was
+    /// committed at or before the timestamp. Snapshots of other branches are not considered.
+    ///
+    /// # Arguments
+    /// * `timestamp_ms` - The timestamp in milliseconds since the Unix epoch
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .rollback_to_time(1700000000000)
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn rollback_to_time(mut self, timestamp_ms: i64) -> Self {
+        self.operations.insert(
+            branch_key(SET_CURRENT_SNAPSHOT_KEY, &self.branch),
+            Operation::RollbackToTime {
+                branch: self.branch.clone(),
+                timestamp_ms,
+            },
+        );
+        self
+    }
+    /// Fast-forwards the target branch to the snapshot of the source branch
+    ///
+    /// This publishes the changes staged on the source branch, for example in a
+    /// write-audit-publish workflow. The current snapshot of the target branch has to be an
+    /// ancestor of the snapshot of the source branch. If the branches have diverged, the
+    /// commit fails with [`Error::NotAncestor`]. A target branch that doesn't exist is created.
+    ///
+    /// # Arguments
+    /// * `target_branch` - The branch that is moved forward
+    /// * `source_branch` - The branch whose snapshot the target branch is moved to
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .fast_forward("main", "audit")
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn fast_forward(mut self, target_branch: &str, source_branch: &str) -> Self {
+        self.operations.insert(
+            FAST_FORWARD_KEY.to_owned() + "-" + target_branch,
+            Operation::FastForward {
+                target: target_branch.to_owned(),
+                source: source_branch.to_owned(),
+            },
+        );
+        self
+    }
+    /// Creates a tag that references the given snapshot
+    ///
+    /// The snapshot doesn't need to be the current snapshot of a branch, which allows
+    /// tagging older states of the table. The commit fails if a branch or tag with the
+    /// name already exists, use [`TableTransaction::set_snapshot_ref`] to move it.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the tag
+    /// * `snapshot_id` - The id of the snapshot that is referenced by the tag
+    /// * `max_ref_age_ms` - The max age of the tag to keep while expiring snapshots
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .create_tag("v1.0", 123, 7 * 24 * 60 * 60 * 1000)
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn create_tag(self, name: &str, snapshot_id: i64, max_ref_age_ms: i64) -> Self {
+        self.create_snapshot_ref((
+            name.to_owned(),
+            SnapshotReference {
+                snapshot_id,
+                retention: SnapshotRetention::Tag { max_ref_age_ms },
+            },
+        ))
+    }
+    /// Creates a branch that starts at the given snapshot
+    ///
+    /// The commit fails if a branch or tag with the name already exists.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the branch
+    /// * `snapshot_id` - The id of the snapshot the branch starts at
+    /// * `min_snapshots_to_keep` - The minimum number of snapshots to keep in the branch while expiring snapshots
+    /// * `max_snapshot_age_ms` - The max age of snapshots to keep in the branch while expiring snapshots
+    /// * `max_ref_age_ms` - The max age of the branch to keep while expiring snapshots
+    ///
+    /// # Returns
+    /// * `Self` - The transaction builder for method chaining
+    ///
+    /// # Examples
+    /// ```
+    /// let transaction = table.new_transaction(None)
+    ///     .create_branch("audit", 123, Some(10), None, None)
+    ///     .commit()
+    ///     .await?;
+    /// ```
+    pub fn create_branch(
+        self,
+        name: &str,
+        snapshot_id: i64,
+        min_snapshots_to_keep: Option<i32>,
+        max_snapshot_age_ms: Option<i64>,
+        max_ref_age_ms: Option<i64>,
+    ) -> Self {
+        self.create_snapshot_ref((
+            name.to_owned(),
+            SnapshotReference {
+                snapshot_id,
+                retention: SnapshotRetention::Branch {
+                    min_snapshots_to_keep,
+                    max_snapshot_age_ms,
+                    max_ref_age_ms,
+                },
+            },
+        ))
+    }
     /// Commits all operations in this transaction atomically
     ///
     /// This method executes all operations in the transaction and updates the table
@@ -321,7 +914,9 @@ impl<'table> TableTransaction<'table> {
     /// Returns an error if:
     /// * Any operation fails to execute
     /// * The catalog update fails
-    /// * Cleanup of old data files fails (for replace operations)
+    /// * Cleanup of old data files fails (for replace and expire operations)
+    ///
+    /// Conflicting commits are retried if configured with [`TableTransaction::with_retry`].
     ///
     /// # Examples
     /// ```
@@ -331,54 +926,159 @@ impl<'table> TableTransaction<'table> {
     ///     .commit()
     ///     .await?;
     /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                table = %self.table.identifier,
+                branch = ?self.branch,
+                operations = self.operations.len()
+            )
+        )
+    )]
     pub async fn commit(self) -> Result<(), Error> {
         let catalog = self.table.catalog();
-        let object_store = self.table.object_store();
         let identifier = self.table.identifier.clone();
+        let (max_attempts, backoff) = self.retry.unwrap_or((1, Duration::ZERO));
 
-        // Save old metadata to be able to remove old data after a rewrite operation
-        let delete_data = if self.operations.values().any(|x| {
-            matches!(
-                x,
-                Operation::Replace {
-                    branch: _,
-                    files: _,
-                    additional_summary: _,
+        let mut attempt = 1;
+        loop {
+            match commit_operations(self.table, &self.operations, &self.ids).await {
+                Err(Error::CommitConflict { .. }) if attempt < max_attempts => {
+                    tokio::time::sleep(backoff * 2u32.saturating_pow(attempt - 1)).await;
+
+                    // Re-base the operations on the latest metadata of the table
+                    let mut table = catalog.clone().load_table(&identifier).await?;
+                    table.inherit_config(self.table);
+                    *self.table = table;
+                    attempt += 1;
                 }
-            )
-        }) {
-            Some(self.table.metadata())
-        } else {
-            None
-        };
+                result => return result,
+            }
+        }
+    }
+}
 
-        // Execute the table operations
-        let (mut requirements, mut updates) = (Vec::new(), Vec::new());
-        for operation in self.operations.into_values() {
-            let (requirement, update) = operation
-                .execute(self.table.metadata(), self.table.object_store())
-                .await?;
+/// Returns the key of an operation that is applied to a branch
+fn branch_key(key: &str, branch: &Option<String>) -> String {
+    match branch {
+        Some(branch) => key.to_owned() + "-" + branch,
+        None => key.to_owned(),
+    }
+}
+
+/// Executes the operations against the current metadata of the table and commits the result to the catalog
+async fn commit_operations(
+    table: &mut Table,
+    operations: &HashMap<String, Operation>,
+    ids: &IdSource,
+) -> Result<(), Error> {
+    let catalog = table.catalog();
+    let object_store = table.object_store();
+    let identifier = table.identifier.clone();
 
-            if let Some(requirement) = requirement {
-                requirements.push(requirement);
+    // Save old metadata to be able to remove the files of snapshots that are removed by a
+    // rewrite operation or expired
+    let old_metadata = if operations.values().any(|x| {
+        matches!(
+            x,
+            Operation::Replace {
+                branch: _,
+                files: _,
+                additional_summary: _,
+            } | Operation::ExpireSnapshots {
+                older_than_ms: _,
+                retain_last: _,
             }
-            updates.extend(update);
-        }
+        )
+    }) {
+        Some(table.metadata().clone())
+    } else {
+        None
+    };
 
-        let new_table = catalog
+    // Execute the table operations one after another. Every operation sees the updates of the
+    // previous ones, so that operations on the same branch build on each other.
+    // A format version upgrade is executed first, so that the other operations write the new format.
+    let expire_snapshots = operations
+        .values()
+        .any(|operation| matches!(operation, Operation::ExpireSnapshots { .. }));
+    let mut operations = operations.iter().collect::<Vec<_>>();
+    operations.sort_by_key(|(key, operation)| {
+        (
+            !matches!(operation, Operation::UpgradeFormatVersion(_)),
+            *key,
+        )
+    });
+
+    let mut metadata = table.metadata().clone();
+    let (mut requirements, mut updates) = (Vec::new(), Vec::new());
+    let mut updated_refs = HashSet::new();
+    for (_, operation) in operations {
+        let (requirement, update) = operation
             .clone()
-            .update_table(CommitTable {
-                identifier,
-                requirements,
-                updates,
-            })
+            .execute(
+                &metadata,
+                object_store.clone(),
+                table.metadata_path_resolver.as_ref(),
+                ids,
+            )
             .await?;
 
-        if let Some(old_metadata) = delete_data {
-            delete_all_table_files(old_metadata, object_store).await?;
+        // Only the snapshot of a branch before the transaction can be asserted
+        match requirement {
+            Some(TableRequirement::AssertRefSnapshotId { r#ref, .. })
+                if updated_refs.contains(&r#ref) => {}
+            Some(requirement) => requirements.push(requirement),
+            None => (),
+        }
+        updated_refs.extend(update.iter().filter_map(|x| match x {
+            TableUpdate::SetSnapshotRef { ref_name, .. } => Some(ref_name.clone()),
+            _ => None,
+        }));
+
+        apply_table_updates(&mut metadata, update.clone())?;
+        updates.extend(update);
+    }
+
+    // The snapshots that are expired depend on the branches and tags of the table. Expiring
+    // snapshots fails if a reference was changed concurrently.
+    if expire_snapshots {
+        for (name, reference) in &table.metadata().refs {
+            let asserted = requirements.iter().any(|requirement| {
+                matches!(requirement, TableRequirement::AssertRefSnapshotId { r#ref, .. } if r#ref == name)
+            });
+            if !asserted {
+                requirements.push(TableRequirement::AssertRefSnapshotId {
+                    r#ref: name.clone(),
+                    snapshot_id: reference.snapshot_id,
+                });
+            }
         }
+    }
 
-        *self.table = new_table;
-        Ok(())
+    let mut new_table = catalog
+        .clone()
+        .update_table(CommitTable {
+            identifier,
+            requirements,
+            updates,
+        })
+        .await?;
+
+    // Files of snapshots that are still referenced by other branches or tags are kept
+    if let Some(old_metadata) = old_metadata {
+        delete_expired_files(
+            &old_metadata,
+            new_table.metadata(),
+            object_store,
+            table.io_concurrency(),
+        )
+        .await?;
     }
+
+    new_table.inherit_config(table);
+    *table = new_table;
+    Ok(())
 }
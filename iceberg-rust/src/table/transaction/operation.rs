@@ -2,29 +2,44 @@
  * Defines the different [Operation]s on a [Table].
 */
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
+use apache_avro::Schema as AvroSchema;
 use bytes::Bytes;
+use iceberg_rust_spec::expression::{BoundPredicate, FieldBounds};
 use iceberg_rust_spec::manifest_list::{
-    manifest_list_schema_v1, manifest_list_schema_v2, ManifestListEntry,
+    manifest_list_schema_v1, manifest_list_schema_v2, Content as ManifestContent, ManifestListEntry,
 };
 use iceberg_rust_spec::snapshot::{Operation as SnapshotOperation, Snapshot};
 use iceberg_rust_spec::spec::table_metadata::TableMetadata;
 use iceberg_rust_spec::spec::{
-    manifest::{partition_value_schema, DataFile, ManifestEntry, Status},
+    manifest::{partition_value_schema, Content, DataFile, ManifestEntry, Status},
+    partition::{BoundPartitionField, Transform},
     schema::Schema,
     snapshot::{
         generate_snapshot_id, SnapshotBuilder, SnapshotReference, SnapshotRetention, Summary,
     },
+    sort::SortOrder,
+    types::{PrimitiveType, Type},
+    values::Value,
+};
+use iceberg_rust_spec::table_metadata::{
+    FormatVersion, MANIFEST_MIN_MERGE_COUNT, MANIFEST_TARGET_SIZE_BYTES,
 };
-use iceberg_rust_spec::table_metadata::FormatVersion;
 use iceberg_rust_spec::util::strip_prefix;
 use object_store::ObjectStore;
 use smallvec::SmallVec;
 use tokio::task::JoinHandle;
+use url::Url;
+use uuid::Uuid;
 
+use crate::object_store::store::put_if_not_exists;
 use crate::table::manifest::{ManifestReader, ManifestWriter};
-use crate::table::manifest_list::ManifestListReader;
+use crate::table::manifest_list::{read_snapshot, ManifestListReader};
+use crate::table::metadata_path::MetadataPathResolver;
 use crate::{
     catalog::commit::{TableRequirement, TableUpdate},
     error::Error,
@@ -34,25 +49,36 @@ use crate::{
 use super::append::{
     select_manifest_partitioned, select_manifest_unpartitioned, split_datafiles, SelectedManifest,
 };
+use super::update_spec::UpdatePartitionSpec;
 
 /// The target number of datafiles per manifest is dynamic, but we don't want to go below this number.
 static MIN_DATAFILES_PER_MANIFEST: usize = 4;
 
-#[derive(Debug)]
+/// Estimated size of a manifest entry in bytes, used to convert a target manifest size into a number of datafiles
+/// if there is no existing manifest to derive the size from.
+static ESTIMATED_MANIFEST_ENTRY_SIZE: usize = 1024;
+
+#[derive(Debug, Clone)]
 ///Table operations
 pub enum Operation {
     /// Update schema
     AddSchema(Schema),
     /// Update spec
     SetDefaultSpec(i32),
+    /// Add a new partition spec and make it the default spec
+    UpdateSpec(UpdatePartitionSpec),
     /// Update table properties
     UpdateProperties(Vec<(String, String)>),
     /// Set Ref
     SetSnapshotRef((String, SnapshotReference)),
+    /// Create a new branch or tag, fails if a reference with the name already exists
+    CreateSnapshotRef((String, SnapshotReference)),
     /// Replace the sort order
-    // ReplaceSortOrder,
-    // /// Update the table location
-    // UpdateLocation,
+    ReplaceSortOrder(SortOrder),
+    /// Update the table location
+    SetLocation(String),
+    /// Upgrade the format version of the table
+    UpgradeFormatVersion(u8),
     /// Append new files to the table
     Append {
         branch: Option<String>,
@@ -60,38 +86,111 @@ pub enum Operation {
         delete_files: Vec<DataFile>,
         additional_summary: Option<HashMap<String, String>>,
     },
-    // /// Quickly append new files to the table
-    // NewFastAppend {
-    //     paths: Vec<String>,
-    //     partition_values: Vec<Struct>,
-    // },
+    /// Append new files to the table by writing new manifests without rewriting existing ones
+    FastAppend {
+        branch: Option<String>,
+        files: Vec<DataFile>,
+    },
     // /// Replace files in the table and commit
     Replace {
         branch: Option<String>,
         files: Vec<DataFile>,
         additional_summary: Option<HashMap<String, String>>,
     },
-    // /// Replace manifests files and commit
-    // RewriteManifests,
-    // /// Replace files in the table by a filter expression
-    // NewOverwrite,
-    // /// Remove or replace rows in existing data files
-    // NewRowDelta,
-    // /// Delete files in the table and commit
-    // NewDelete,
-    // /// Expire snapshots in the table
-    // ExpireSnapshots,
+    /// Replace manifests files and commit
+    RewriteManifests { branch: Option<String> },
+    /// Replace the data files matching a filter expression with new files
+    Overwrite {
+        branch: Option<String>,
+        filter: BoundPredicate,
+        files: Vec<DataFile>,
+    },
+    /// Add data files and delete files that remove rows of the table
+    RowDelta {
+        branch: Option<String>,
+        data_files: Vec<DataFile>,
+        delete_files: Vec<DataFile>,
+        additional_summary: Option<HashMap<String, String>>,
+    },
+    /// Delete files in the table and commit
+    Delete {
+        branch: Option<String>,
+        paths: Vec<String>,
+    },
+    /// Expire snapshots in the table
+    ExpireSnapshots {
+        older_than_ms: Option<i64>,
+        retain_last: Option<usize>,
+    },
+    /// Set the snapshot of a branch to a previous snapshot
+    SetCurrentSnapshot {
+        branch: Option<String>,
+        snapshot_id: i64,
+        force: bool,
+    },
+    /// Set the snapshot of a branch to the latest snapshot at or before a timestamp
+    RollbackToTime {
+        branch: Option<String>,
+        timestamp_ms: i64,
+    },
+    /// Set the snapshot of the target branch to the snapshot of the source branch
+    FastForward { target: String, source: String },
     // /// Manage snapshots in the table
     // ManageSnapshots,
     // /// Read and write table data and metadata files
     // IO,
 }
 
+/// Generates the ids of new snapshots and the uuids in the paths of their manifests and
+/// manifest lists. Tests can replace the random ids with deterministic ones.
+#[derive(Clone)]
+pub(crate) struct IdSource {
+    snapshot_id: Arc<dyn Fn() -> i64 + Send + Sync>,
+    commit_uuid: Arc<dyn Fn() -> Uuid + Send + Sync>,
+}
+
+impl Default for IdSource {
+    fn default() -> Self {
+        Self {
+            snapshot_id: Arc::new(generate_snapshot_id),
+            commit_uuid: Arc::new(Uuid::new_v4),
+        }
+    }
+}
+
+impl std::fmt::Debug for IdSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdSource").finish_non_exhaustive()
+    }
+}
+
+impl IdSource {
+    pub(crate) fn with_snapshot_id(mut self, f: impl Fn() -> i64 + Send + Sync + 'static) -> Self {
+        self.snapshot_id = Arc::new(f);
+        self
+    }
+
+    pub(crate) fn with_commit_uuid(mut self, f: impl Fn() -> Uuid + Send + Sync + 'static) -> Self {
+        self.commit_uuid = Arc::new(f);
+        self
+    }
+
+    fn snapshot_id(&self) -> i64 {
+        (self.snapshot_id)()
+    }
+
+    fn commit_uuid(&self) -> String {
+        (self.commit_uuid)().to_string()
+    }
+}
+
 impl Operation {
     pub async fn execute(
         self,
         table_metadata: &TableMetadata,
         object_store: Arc<dyn ObjectStore>,
+        path_resolver: &dyn MetadataPathResolver,
+        ids: &IdSource,
     ) -> Result<(Option<TableRequirement>, Vec<TableUpdate>), Error> {
         match self {
             Operation::Append {
@@ -100,11 +199,32 @@ impl Operation {
                 delete_files,
                 additional_summary,
             } => {
+                // Without data files no data manifest has to be rewritten, the delete files are
+                // added with a new delete manifest
+                if data_files.is_empty() && !delete_files.is_empty() {
+                    return row_delta(
+                        table_metadata,
+                        object_store,
+                        path_resolver,
+                        ids,
+                        branch,
+                        data_files,
+                        delete_files,
+                        additional_summary,
+                    )
+                    .await;
+                }
+
                 let partition_fields =
                     table_metadata.current_partition_fields(branch.as_deref())?;
                 let schema = table_metadata.current_schema(branch.as_deref())?;
                 let old_snapshot = table_metadata.current_snapshot(branch.as_deref())?;
 
+                validate_partition_values(
+                    data_files.iter().chain(delete_files.iter()),
+                    &partition_fields,
+                )?;
+
                 let snapshot_operation = match (data_files.len(), delete_files.len()) {
                     (0, 0) => Err(Error::InvalidFormat(
                         "Empty data and delete files".to_string(),
@@ -122,9 +242,9 @@ impl Operation {
                     .map(|x| x.name())
                     .collect::<SmallVec<[_; 4]>>();
 
-                let bounding_partition_values = delete_files
+                // Only the data files are added to an existing manifest
+                let bounding_partition_values = data_files
                     .iter()
-                    .chain(data_files.iter())
                     .try_fold(None, |acc, x| {
                         let node = partition_struct_to_vec(x.partition(), &partition_column_names)?;
                         let Some(mut acc) = acc else {
@@ -160,16 +280,18 @@ impl Operation {
                         select_manifest_unpartitioned(
                             manifest_list_reader,
                             &mut manifest_list_writer,
+                            table_metadata.default_spec_id,
                         )?
                     } else {
                         select_manifest_partitioned(
                             manifest_list_reader,
                             &mut manifest_list_writer,
                             &bounding_partition_values,
+                            table_metadata.default_spec_id,
                         )?
                     };
                     existing_file_count = file_count_all_entries;
-                    Some(manifest)
+                    manifest
                 } else {
                     // If manifest list doesn't exist, there is no manifest
                     None
@@ -193,9 +315,13 @@ impl Operation {
                     })
                     .unwrap_or(0) as usize;
 
+                let manifest_target = ManifestTarget::new(&table_metadata.properties)?
+                    .with_entry_size_of(selected_manifest_opt.as_ref());
+
                 let n_splits = compute_n_splits(
+                    &manifest_target,
                     existing_file_count,
-                    delete_files.len() + data_files.len(),
+                    data_files.len(),
                     selected_manifest_file_count,
                 );
 
@@ -210,38 +336,36 @@ impl Operation {
                     })
                     .unwrap_or(bounding_partition_values);
 
-                let snapshot_id = generate_snapshot_id();
-                let commit_uuid = &uuid::Uuid::new_v4().to_string();
+                let mut summary =
+                    append_summary(old_snapshot, data_files.iter().chain(delete_files.iter()));
+                summary.extend(additional_summary.unwrap_or_default());
 
-                let new_datafile_iter =
-                    delete_files
-                        .into_iter()
-                        .chain(data_files.into_iter())
-                        .map(|data_file| {
-                            ManifestEntry::builder()
-                                .with_format_version(table_metadata.format_version)
-                                .with_status(Status::Added)
-                                .with_data_file(data_file)
-                                .build()
-                                .map_err(crate::spec::error::Error::from)
-                                .map_err(Error::from)
-                        });
+                let snapshot_id = ids.snapshot_id();
+                let commit_uuid = &ids.commit_uuid();
+
+                let new_datafile_iter = data_files.into_iter().map(|data_file| {
+                    ManifestEntry::builder()
+                        .with_format_version(table_metadata.format_version)
+                        .with_status(Status::Added)
+                        .with_data_file(data_file)
+                        .build()
+                        .map_err(crate::spec::error::Error::from)
+                        .map_err(Error::from)
+                });
 
                 let manifest_schema = ManifestEntry::schema(
                     &partition_value_schema(&partition_fields)?,
                     &table_metadata.format_version,
                 )?;
 
-                let new_manifest_list_location = new_manifest_list_location(
-                    &table_metadata.location,
-                    snapshot_id,
-                    0,
-                    commit_uuid,
-                );
+                let new_manifest_list_location =
+                    path_resolver.manifest_list_path(table_metadata, snapshot_id, 0, commit_uuid);
 
                 // Write manifest files
                 // Split manifest file if limit is exceeded
                 if n_splits == 0 {
+                    let manifest_location =
+                        path_resolver.manifest_path(table_metadata, commit_uuid, 0);
                     let mut manifest_writer = if let (Some(manifest), Some(manifest_bytes)) =
                         (selected_manifest_opt, selected_manifest_bytes_opt)
                     {
@@ -249,14 +373,13 @@ impl Operation {
                         ManifestWriter::from_existing(
                             &manifest_bytes,
                             manifest,
+                            &manifest_location,
+                            snapshot_id,
                             &manifest_schema,
                             table_metadata,
                             branch.as_deref(),
                         )?
                     } else {
-                        let manifest_location =
-                            new_manifest_location(&table_metadata.location, commit_uuid, 0);
-
                         ManifestWriter::new(
                             &manifest_location,
                             snapshot_id,
@@ -279,19 +402,23 @@ impl Operation {
                         (selected_manifest_opt, selected_manifest_bytes_opt)
                     {
                         let manifest_bytes = manifest_bytes.await??;
-                        let manifest_reader = ManifestReader::new(&*manifest_bytes)?
-                            .map(|x| x.map_err(Error::from))
-                            .map(|entry| {
-                                let mut entry = entry?;
-                                *entry.status_mut() = Status::Existing;
-                                if entry.sequence_number().is_none() {
-                                    *entry.sequence_number_mut() = Some(manifest.sequence_number);
-                                }
-                                if entry.snapshot_id().is_none() {
-                                    *entry.snapshot_id_mut() = Some(manifest.added_snapshot_id);
-                                }
-                                Ok(entry)
-                            });
+                        let manifest_reader = ManifestReader::for_spec(
+                            &*manifest_bytes,
+                            table_metadata
+                                .partition_specs
+                                .get(&manifest.partition_spec_id),
+                        )?
+                        .map(|entry| {
+                            let mut entry = entry?;
+                            *entry.status_mut() = Status::Existing;
+                            if entry.sequence_number().is_none() {
+                                *entry.sequence_number_mut() = Some(manifest.sequence_number);
+                            }
+                            if entry.snapshot_id().is_none() {
+                                *entry.snapshot_id_mut() = Some(manifest.added_snapshot_id);
+                            }
+                            Ok(entry)
+                        });
 
                         split_datafiles(
                             new_datafile_iter.chain(manifest_reader),
@@ -313,7 +440,7 @@ impl Operation {
                         .enumerate()
                         .map(|(i, entries)| {
                             let manifest_location =
-                                new_manifest_location(&table_metadata.location, commit_uuid, i);
+                                path_resolver.manifest_path(table_metadata, commit_uuid, i);
 
                             let mut manifest_writer = ManifestWriter::new(
                                 &manifest_location,
@@ -338,14 +465,31 @@ impl Operation {
                     }
                 };
 
-                let manifest_list_bytes = manifest_list_writer.into_inner()?;
-
-                object_store
-                    .put(
-                        &strip_prefix(&new_manifest_list_location).into(),
-                        manifest_list_bytes.into(),
+                // Delete files are written to a separate manifest after the data manifests
+                if !delete_files.is_empty() {
+                    let manifest_location =
+                        path_resolver.manifest_path(table_metadata, commit_uuid, 1 << n_splits);
+                    let manifest = write_new_manifest(
+                        delete_files,
+                        &manifest_location,
+                        snapshot_id,
+                        &manifest_schema,
+                        table_metadata,
+                        branch.as_deref(),
+                        object_store.clone(),
                     )
                     .await?;
+                    manifest_list_writer.append_ser(manifest)?;
+                }
+
+                let manifest_list_bytes = manifest_list_writer.into_inner()?;
+
+                put_if_not_exists(
+                    &*object_store,
+                    &new_manifest_list_location,
+                    manifest_list_bytes.into(),
+                )
+                .await?;
 
                 let mut snapshot_builder = SnapshotBuilder::default();
                 snapshot_builder
@@ -354,7 +498,176 @@ impl Operation {
                     .with_sequence_number(table_metadata.last_sequence_number + 1)
                     .with_summary(Summary {
                         operation: snapshot_operation,
-                        other: additional_summary.unwrap_or_default(),
+                        other: summary,
+                    })
+                    .with_schema_id(*schema.schema_id());
+                if let Some(snapshot) = old_snapshot {
+                    snapshot_builder.with_parent_snapshot_id(*snapshot.snapshot_id());
+                }
+                let snapshot = snapshot_builder
+                    .build()
+                    .map_err(iceberg_rust_spec::error::Error::from)?;
+
+                Ok((
+                    old_snapshot.map(|x| TableRequirement::AssertRefSnapshotId {
+                        r#ref: branch.clone().unwrap_or("main".to_owned()),
+                        snapshot_id: *x.snapshot_id(),
+                    }),
+                    vec![
+                        TableUpdate::AddSnapshot { snapshot },
+                        TableUpdate::SetSnapshotRef {
+                            ref_name: branch.unwrap_or("main".to_owned()),
+                            snapshot_reference: SnapshotReference {
+                                snapshot_id,
+                                retention: SnapshotRetention::default(),
+                            },
+                        },
+                    ],
+                ))
+            }
+            Operation::FastAppend { branch, files } => {
+                if files.is_empty() {
+                    return Err(Error::InvalidFormat("Empty data files".to_string()));
+                }
+
+                let partition_fields =
+                    table_metadata.current_partition_fields(branch.as_deref())?;
+                let schema = table_metadata.current_schema(branch.as_deref())?;
+                let old_snapshot = table_metadata.current_snapshot(branch.as_deref())?;
+
+                validate_partition_values(files.iter(), &partition_fields)?;
+
+                let old_manifest_list_bytes_opt =
+                    prefetch_manifest_list(old_snapshot, &object_store);
+
+                let partition_column_names = partition_fields
+                    .iter()
+                    .map(|x| x.name())
+                    .collect::<SmallVec<[_; 4]>>();
+
+                let bounding_partition_values = files
+                    .iter()
+                    .try_fold(None, |acc, x| {
+                        let node = partition_struct_to_vec(x.partition(), &partition_column_names)?;
+                        let Some(mut acc) = acc else {
+                            return Ok::<_, Error>(Some(Rectangle::new(node.clone(), node)));
+                        };
+                        acc.expand_with_node(node);
+                        Ok(Some(acc))
+                    })?
+                    .ok_or(Error::NotFound("Bounding partition values".to_owned()))?;
+
+                let summary = append_summary(old_snapshot, files.iter());
+
+                let manifest_list_schema = match table_metadata.format_version {
+                    FormatVersion::V1 => manifest_list_schema_v1(),
+                    FormatVersion::V2 => manifest_list_schema_v2(),
+                };
+
+                let mut manifest_list_writer =
+                    apache_avro::Writer::new(manifest_list_schema, Vec::new());
+
+                // The existing manifests are referenced by the new manifest list as they are
+                let mut existing_file_count = 0;
+                if let Some(old_manifest_list_bytes) = old_manifest_list_bytes_opt {
+                    let old_manifest_list_bytes = old_manifest_list_bytes.await??;
+
+                    let manifest_list_reader =
+                        ManifestListReader::new(old_manifest_list_bytes.as_ref(), table_metadata)?;
+
+                    for manifest in manifest_list_reader {
+                        let manifest = manifest?;
+                        existing_file_count += manifest.added_files_count.unwrap_or(0) as usize
+                            + manifest.existing_files_count.unwrap_or(0) as usize;
+                        manifest_list_writer.append_ser(manifest)?;
+                    }
+                }
+
+                let n_splits = compute_n_splits(
+                    &ManifestTarget::new(&table_metadata.properties)?,
+                    existing_file_count,
+                    files.len(),
+                    0,
+                );
+
+                let snapshot_id = ids.snapshot_id();
+                let commit_uuid = &ids.commit_uuid();
+
+                let new_datafile_iter = files.into_iter().map(|data_file| {
+                    ManifestEntry::builder()
+                        .with_format_version(table_metadata.format_version)
+                        .with_status(Status::Added)
+                        .with_data_file(data_file)
+                        .build()
+                        .map_err(crate::spec::error::Error::from)
+                        .map_err(Error::from)
+                });
+
+                let splits = if n_splits == 0 {
+                    vec![new_datafile_iter.collect::<Result<Vec<_>, _>>()?]
+                } else {
+                    split_datafiles(
+                        new_datafile_iter,
+                        bounding_partition_values,
+                        &partition_column_names,
+                        n_splits,
+                    )?
+                };
+
+                let manifest_schema = ManifestEntry::schema(
+                    &partition_value_schema(&partition_fields)?,
+                    &table_metadata.format_version,
+                )?;
+
+                let manifest_futures = splits
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, entries)| {
+                        let manifest_location =
+                            path_resolver.manifest_path(table_metadata, commit_uuid, i);
+
+                        let mut manifest_writer = ManifestWriter::new(
+                            &manifest_location,
+                            snapshot_id,
+                            &manifest_schema,
+                            table_metadata,
+                            branch.as_deref(),
+                        )?;
+
+                        for manifest_entry in entries {
+                            manifest_writer.append(manifest_entry)?;
+                        }
+
+                        Ok::<_, Error>(manifest_writer.finish(object_store.clone()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let manifests = futures::future::try_join_all(manifest_futures).await?;
+
+                for manifest in manifests {
+                    manifest_list_writer.append_ser(manifest)?;
+                }
+
+                let new_manifest_list_location =
+                    path_resolver.manifest_list_path(table_metadata, snapshot_id, 0, commit_uuid);
+
+                let manifest_list_bytes = manifest_list_writer.into_inner()?;
+
+                put_if_not_exists(
+                    &*object_store,
+                    &new_manifest_list_location,
+                    manifest_list_bytes.into(),
+                )
+                .await?;
+
+                let mut snapshot_builder = SnapshotBuilder::default();
+                snapshot_builder
+                    .with_snapshot_id(snapshot_id)
+                    .with_manifest_list(new_manifest_list_location)
+                    .with_sequence_number(table_metadata.last_sequence_number + 1)
+                    .with_summary(Summary {
+                        operation: SnapshotOperation::Append,
+                        other: summary,
                     })
                     .with_schema_id(*schema.schema_id());
                 if let Some(snapshot) = old_snapshot {
@@ -381,6 +694,24 @@ impl Operation {
                     ],
                 ))
             }
+            Operation::RowDelta {
+                branch,
+                data_files,
+                delete_files,
+                additional_summary,
+            } => {
+                row_delta(
+                    table_metadata,
+                    object_store,
+                    path_resolver,
+                    ids,
+                    branch,
+                    data_files,
+                    delete_files,
+                    additional_summary,
+                )
+                .await
+            }
             Operation::Replace {
                 branch,
                 files,
@@ -416,9 +747,16 @@ impl Operation {
                 let mut manifest_list_writer =
                     apache_avro::Writer::new(manifest_list_schema, Vec::new());
 
-                let n_splits = compute_n_splits(0, files.len(), 0);
+                let n_splits = compute_n_splits(
+                    &ManifestTarget::new(&table_metadata.properties)?,
+                    0,
+                    files.len(),
+                    0,
+                );
 
-                let snapshot_id = generate_snapshot_id();
+                let snapshot_id = ids.snapshot_id();
+                // The replaced snapshots may be older than the heads of other branches, so the
+                // snapshot gets the next sequence number of the table like any other snapshot
                 let sequence_number = table_metadata.last_sequence_number + 1;
 
                 let new_datafile_iter = files.into_iter().map(|data_file| {
@@ -438,13 +776,9 @@ impl Operation {
                     &table_metadata.format_version,
                 )?;
 
-                let snapshot_uuid = &uuid::Uuid::new_v4().to_string();
-                let new_manifest_list_location = new_manifest_list_location(
-                    &table_metadata.location,
-                    snapshot_id,
-                    0,
-                    snapshot_uuid,
-                );
+                let snapshot_uuid = &ids.commit_uuid();
+                let new_manifest_list_location =
+                    path_resolver.manifest_list_path(table_metadata, snapshot_id, 0, snapshot_uuid);
 
                 // Write manifest files
                 // Split manifest file if limit is exceeded
@@ -452,7 +786,7 @@ impl Operation {
                     // If manifest doesn't need to be split
 
                     let manifest_location =
-                        new_manifest_location(&table_metadata.location, snapshot_uuid, 0);
+                        path_resolver.manifest_path(table_metadata, snapshot_uuid, 0);
                     let mut manifest_writer = ManifestWriter::new(
                         &manifest_location,
                         snapshot_id,
@@ -479,7 +813,7 @@ impl Operation {
 
                     for (i, entries) in splits.into_iter().enumerate() {
                         let manifest_location =
-                            new_manifest_location(&table_metadata.location, snapshot_uuid, i);
+                            path_resolver.manifest_path(table_metadata, snapshot_uuid, i);
 
                         let mut manifest_writer = ManifestWriter::new(
                             &manifest_location,
@@ -501,17 +835,17 @@ impl Operation {
 
                 let manifest_list_bytes = manifest_list_writer.into_inner()?;
 
-                object_store
-                    .put(
-                        &strip_prefix(&new_manifest_list_location).into(),
-                        manifest_list_bytes.into(),
-                    )
-                    .await?;
+                put_if_not_exists(
+                    &*object_store,
+                    &new_manifest_list_location,
+                    manifest_list_bytes.into(),
+                )
+                .await?;
 
                 let mut snapshot_builder = SnapshotBuilder::default();
                 snapshot_builder
                     .with_snapshot_id(snapshot_id)
-                    .with_sequence_number(0)
+                    .with_sequence_number(sequence_number)
                     .with_schema_id(*schema.schema_id())
                     .with_manifest_list(new_manifest_list_location)
                     .with_summary(Summary {
@@ -522,8 +856,19 @@ impl Operation {
                     .build()
                     .map_err(iceberg_rust_spec::error::Error::from)?;
 
-                let old_snapshot_ids: Vec<i64> =
-                    table_metadata.snapshots.keys().map(Clone::clone).collect();
+                // Snapshots that are still referenced by other branches and tags are kept
+                let branch_name = branch.as_deref().unwrap_or("main");
+                let old_snapshot_ids: Vec<i64> = table_metadata
+                    .snapshots
+                    .keys()
+                    .filter(|id| {
+                        !table_metadata.refs.iter().any(|(name, reference)| {
+                            name != branch_name
+                                && table_metadata.is_ancestor_of(**id, reference.snapshot_id)
+                        })
+                    })
+                    .cloned()
+                    .collect();
 
                 Ok((
                     old_snapshot.map(|x| TableRequirement::AssertRefSnapshotId {
@@ -545,27 +890,799 @@ impl Operation {
                     ],
                 ))
             }
-            Operation::UpdateProperties(entries) => Ok((
-                None,
-                vec![TableUpdate::SetProperties {
-                    updates: HashMap::from_iter(entries),
-                }],
-            )),
-            Operation::SetSnapshotRef((key, value)) => Ok((
-                table_metadata
-                    .refs
-                    .get(&key)
-                    .map(|x| TableRequirement::AssertRefSnapshotId {
-                        r#ref: key.clone(),
-                        snapshot_id: x.snapshot_id,
-                    }),
-                vec![TableUpdate::SetSnapshotRef {
-                    ref_name: key,
-                    snapshot_reference: value,
-                }],
-            )),
-            Operation::AddSchema(schema) => {
-                let last_column_id = schema.fields().iter().map(|x| x.id).max();
+            Operation::Delete { branch, paths } => {
+                let schema = table_metadata.current_schema(branch.as_deref())?;
+                let old_snapshot = table_metadata
+                    .current_snapshot(branch.as_deref())?
+                    .ok_or(Error::NotFound("Snapshot to delete files from".to_owned()))?;
+
+                let paths: HashSet<String> = paths.into_iter().collect();
+
+                let manifests: Vec<ManifestListEntry> =
+                    read_snapshot(old_snapshot, table_metadata, object_store.clone())
+                        .await?
+                        .collect::<Result<_, _>>()?;
+
+                // Fetch all manifests of the current snapshot concurrently
+                let manifests =
+                    futures::future::try_join_all(manifests.into_iter().map(|manifest| {
+                        let object_store = object_store.clone();
+                        async move {
+                            let bytes = object_store
+                                .get(&strip_prefix(&manifest.manifest_path).as_str().into())
+                                .await?
+                                .bytes()
+                                .await?;
+                            Ok::<_, Error>((manifest, bytes))
+                        }
+                    }))
+                    .await?;
+
+                let manifest_list_schema = match table_metadata.format_version {
+                    FormatVersion::V1 => manifest_list_schema_v1(),
+                    FormatVersion::V2 => manifest_list_schema_v2(),
+                };
+
+                let mut manifest_list_writer =
+                    apache_avro::Writer::new(manifest_list_schema, Vec::new());
+
+                let snapshot_id = ids.snapshot_id();
+                let commit_uuid = &ids.commit_uuid();
+
+                let mut deleted_files_count = 0;
+
+                for (i, (manifest, bytes)) in manifests.into_iter().enumerate() {
+                    let (deleted, existing): (Vec<ManifestEntry>, Vec<ManifestEntry>) =
+                        ManifestReader::for_spec(
+                            &*bytes,
+                            table_metadata
+                                .partition_specs
+                                .get(&manifest.partition_spec_id),
+                        )?
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .filter(|entry| *entry.status() != Status::Deleted)
+                        .partition(|entry| paths.contains(entry.data_file().file_path()));
+
+                    // Manifests without deleted files are kept untouched
+                    if deleted.is_empty() {
+                        manifest_list_writer.append_ser(manifest)?;
+                        continue;
+                    }
+
+                    deleted_files_count += deleted.len();
+
+                    let manifest_location =
+                        path_resolver.manifest_path(table_metadata, commit_uuid, i);
+
+                    // The remaining files keep the partition spec they were written with
+                    let manifest_schema = manifest_schema_for_spec(
+                        table_metadata,
+                        branch.as_deref(),
+                        manifest.partition_spec_id,
+                    )?;
+
+                    let mut manifest_writer = ManifestWriter::for_spec(
+                        &manifest_location,
+                        snapshot_id,
+                        &manifest_schema,
+                        table_metadata,
+                        branch.as_deref(),
+                        manifest.partition_spec_id,
+                    )?;
+
+                    for mut entry in existing {
+                        *entry.status_mut() = Status::Existing;
+                        if entry.sequence_number().is_none() {
+                            *entry.sequence_number_mut() = Some(manifest.sequence_number);
+                        }
+                        if entry.snapshot_id().is_none() {
+                            *entry.snapshot_id_mut() = Some(manifest.added_snapshot_id);
+                        }
+                        manifest_writer.append(entry)?;
+                    }
+
+                    // The deleted files are kept as deleted entries of the new snapshot
+                    for mut entry in deleted {
+                        *entry.status_mut() = Status::Deleted;
+                        if entry.sequence_number().is_none() {
+                            *entry.sequence_number_mut() = Some(manifest.sequence_number);
+                        }
+                        *entry.snapshot_id_mut() = Some(snapshot_id);
+                        manifest_writer.append(entry)?;
+                    }
+
+                    let new_manifest = manifest_writer.finish(object_store.clone()).await?;
+
+                    manifest_list_writer.append_ser(new_manifest)?;
+                }
+
+                if deleted_files_count == 0 {
+                    return Err(Error::NotFound(format!(
+                        "Data files {:?} in table",
+                        paths.into_iter().collect::<Vec<_>>()
+                    )));
+                }
+
+                let new_manifest_list_location =
+                    path_resolver.manifest_list_path(table_metadata, snapshot_id, 0, commit_uuid);
+
+                let manifest_list_bytes = manifest_list_writer.into_inner()?;
+
+                put_if_not_exists(
+                    &*object_store,
+                    &new_manifest_list_location,
+                    manifest_list_bytes.into(),
+                )
+                .await?;
+
+                let snapshot = SnapshotBuilder::default()
+                    .with_snapshot_id(snapshot_id)
+                    .with_parent_snapshot_id(*old_snapshot.snapshot_id())
+                    .with_manifest_list(new_manifest_list_location)
+                    .with_sequence_number(table_metadata.last_sequence_number + 1)
+                    .with_summary(Summary {
+                        operation: SnapshotOperation::Delete,
+                        other: HashMap::from_iter(vec![(
+                            "deleted-data-files".to_owned(),
+                            deleted_files_count.to_string(),
+                        )]),
+                    })
+                    .with_schema_id(*schema.schema_id())
+                    .build()
+                    .map_err(iceberg_rust_spec::error::Error::from)?;
+
+                Ok((
+                    Some(TableRequirement::AssertRefSnapshotId {
+                        r#ref: branch.clone().unwrap_or("main".to_owned()),
+                        snapshot_id: *old_snapshot.snapshot_id(),
+                    }),
+                    vec![
+                        TableUpdate::AddSnapshot { snapshot },
+                        TableUpdate::SetSnapshotRef {
+                            ref_name: branch.unwrap_or("main".to_owned()),
+                            snapshot_reference: SnapshotReference {
+                                snapshot_id,
+                                retention: SnapshotRetention::default(),
+                            },
+                        },
+                    ],
+                ))
+            }
+            Operation::Overwrite {
+                branch,
+                filter,
+                files,
+            } => {
+                if table_metadata.format_version == FormatVersion::V1 {
+                    return Err(Error::NotSupported(
+                        "Overwrite operation for format version 1".to_owned(),
+                    ));
+                }
+
+                let partition_fields =
+                    table_metadata.current_partition_fields(branch.as_deref())?;
+                let schema = table_metadata.current_schema(branch.as_deref())?;
+                let old_snapshot = table_metadata.current_snapshot(branch.as_deref())?;
+
+                let partition_column_names = partition_fields
+                    .iter()
+                    .map(|x| x.name())
+                    .collect::<SmallVec<[_; 4]>>();
+
+                let manifests: Vec<ManifestListEntry> = if let Some(old_snapshot) = old_snapshot {
+                    read_snapshot(old_snapshot, table_metadata, object_store.clone())
+                        .await?
+                        .collect::<Result<_, _>>()?
+                } else {
+                    Vec::new()
+                };
+
+                // Fetch all manifests of the current snapshot concurrently
+                let manifests =
+                    futures::future::try_join_all(manifests.into_iter().map(|manifest| {
+                        let object_store = object_store.clone();
+                        async move {
+                            let bytes = object_store
+                                .get(&strip_prefix(&manifest.manifest_path).as_str().into())
+                                .await?
+                                .bytes()
+                                .await?;
+                            Ok::<_, Error>((manifest, bytes))
+                        }
+                    }))
+                    .await?;
+
+                let manifest_list_schema = match table_metadata.format_version {
+                    FormatVersion::V1 => manifest_list_schema_v1(),
+                    FormatVersion::V2 => manifest_list_schema_v2(),
+                };
+
+                let mut manifest_list_writer =
+                    apache_avro::Writer::new(manifest_list_schema, Vec::new());
+
+                let manifest_schema = ManifestEntry::schema(
+                    &partition_value_schema(&partition_fields)?,
+                    &table_metadata.format_version,
+                )?;
+
+                let snapshot_id = ids.snapshot_id();
+                let sequence_number = table_metadata.last_sequence_number + 1;
+                let commit_uuid = &ids.commit_uuid();
+
+                let n_manifests = manifests.len();
+                let mut deleted_files_count = 0;
+
+                for (i, (manifest, bytes)) in manifests.into_iter().enumerate() {
+                    // The partition values of the files are evaluated with the spec of their manifest
+                    let manifest_spec = table_metadata
+                        .partition_specs
+                        .get(&manifest.partition_spec_id)
+                        .ok_or(Error::NotFound(format!(
+                            "Partition spec with id {}",
+                            manifest.partition_spec_id
+                        )))?;
+                    let manifest_partition_fields =
+                        iceberg_rust_spec::spec::table_metadata::partition_fields(
+                            manifest_spec,
+                            schema,
+                        )?;
+
+                    // Only data files that match the filter entirely are removed. Files that
+                    // partially overlap the filter are kept, the residual rows have to be deleted by the engine.
+                    let (deleted, existing): (Vec<ManifestEntry>, Vec<ManifestEntry>) =
+                        ManifestReader::for_spec(&*bytes, Some(manifest_spec))?
+                            .collect::<Result<Vec<_>, _>>()?
+                            .into_iter()
+                            .filter(|entry| *entry.status() != Status::Deleted)
+                            .partition(|entry| {
+                                *entry.data_file().content() == Content::Data
+                                    && data_file_must_match(
+                                        entry.data_file(),
+                                        &filter,
+                                        &manifest_partition_fields,
+                                    )
+                            });
+
+                    // Manifests without deleted files are kept untouched
+                    if deleted.is_empty() {
+                        manifest_list_writer.append_ser(manifest)?;
+                        continue;
+                    }
+
+                    deleted_files_count += deleted.len();
+
+                    let manifest_location =
+                        path_resolver.manifest_path(table_metadata, commit_uuid, i);
+
+                    let manifest_schema = manifest_schema_for_spec(
+                        table_metadata,
+                        branch.as_deref(),
+                        manifest.partition_spec_id,
+                    )?;
+
+                    let mut manifest_writer = ManifestWriter::for_spec(
+                        &manifest_location,
+                        snapshot_id,
+                        &manifest_schema,
+                        table_metadata,
+                        branch.as_deref(),
+                        manifest.partition_spec_id,
+                    )?;
+
+                    for mut entry in existing {
+                        *entry.status_mut() = Status::Existing;
+                        if entry.sequence_number().is_none() {
+                            *entry.sequence_number_mut() = Some(manifest.sequence_number);
+                        }
+                        if entry.snapshot_id().is_none() {
+                            *entry.snapshot_id_mut() = Some(manifest.added_snapshot_id);
+                        }
+                        manifest_writer.append(entry)?;
+                    }
+
+                    for mut entry in deleted {
+                        *entry.status_mut() = Status::Deleted;
+                        if entry.sequence_number().is_none() {
+                            *entry.sequence_number_mut() = Some(manifest.sequence_number);
+                        }
+                        *entry.snapshot_id_mut() = Some(snapshot_id);
+                        manifest_writer.append(entry)?;
+                    }
+
+                    let new_manifest = manifest_writer.finish(object_store.clone()).await?;
+
+                    manifest_list_writer.append_ser(new_manifest)?;
+                }
+
+                let added_files_count = files.len();
+
+                if !files.is_empty() {
+                    let bounding_partition_values = files
+                        .iter()
+                        .try_fold(None, |acc, x| {
+                            let node =
+                                partition_struct_to_vec(x.partition(), &partition_column_names)?;
+                            let Some(mut acc) = acc else {
+                                return Ok::<_, Error>(Some(Rectangle::new(node.clone(), node)));
+                            };
+                            acc.expand_with_node(node);
+                            Ok(Some(acc))
+                        })?
+                        .ok_or(Error::NotFound("Bounding partition values".to_owned()))?;
+
+                    let n_splits = compute_n_splits(
+                        &ManifestTarget::new(&table_metadata.properties)?,
+                        0,
+                        files.len(),
+                        0,
+                    );
+
+                    let new_datafile_iter = files.into_iter().map(|data_file| {
+                        ManifestEntry::builder()
+                            .with_format_version(table_metadata.format_version)
+                            .with_status(Status::Added)
+                            .with_snapshot_id(snapshot_id)
+                            .with_sequence_number(sequence_number)
+                            .with_data_file(data_file)
+                            .build()
+                            .map_err(crate::spec::error::Error::from)
+                            .map_err(Error::from)
+                    });
+
+                    let splits = if n_splits == 0 {
+                        vec![new_datafile_iter.collect::<Result<Vec<_>, _>>()?]
+                    } else {
+                        split_datafiles(
+                            new_datafile_iter,
+                            bounding_partition_values,
+                            &partition_column_names,
+                            n_splits,
+                        )?
+                    };
+
+                    // The new manifests are numbered after the rewritten ones to avoid collisions
+                    let manifest_futures = splits
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, entries)| {
+                            let manifest_location = path_resolver.manifest_path(
+                                table_metadata,
+                                commit_uuid,
+                                n_manifests + i,
+                            );
+
+                            let mut manifest_writer = ManifestWriter::new(
+                                &manifest_location,
+                                snapshot_id,
+                                &manifest_schema,
+                                table_metadata,
+                                branch.as_deref(),
+                            )?;
+
+                            for manifest_entry in entries {
+                                manifest_writer.append(manifest_entry)?;
+                            }
+
+                            Ok::<_, Error>(manifest_writer.finish(object_store.clone()))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    let manifests = futures::future::try_join_all(manifest_futures).await?;
+
+                    for manifest in manifests {
+                        manifest_list_writer.append_ser(manifest)?;
+                    }
+                }
+
+                let new_manifest_list_location =
+                    path_resolver.manifest_list_path(table_metadata, snapshot_id, 0, commit_uuid);
+
+                let manifest_list_bytes = manifest_list_writer.into_inner()?;
+
+                put_if_not_exists(
+                    &*object_store,
+                    &new_manifest_list_location,
+                    manifest_list_bytes.into(),
+                )
+                .await?;
+
+                let mut snapshot_builder = SnapshotBuilder::default();
+                snapshot_builder
+                    .with_snapshot_id(snapshot_id)
+                    .with_manifest_list(new_manifest_list_location)
+                    .with_sequence_number(sequence_number)
+                    .with_summary(Summary {
+                        operation: SnapshotOperation::Overwrite,
+                        other: HashMap::from_iter(vec![
+                            ("added-data-files".to_owned(), added_files_count.to_string()),
+                            (
+                                "deleted-data-files".to_owned(),
+                                deleted_files_count.to_string(),
+                            ),
+                        ]),
+                    })
+                    .with_schema_id(*schema.schema_id());
+                if let Some(snapshot) = old_snapshot {
+                    snapshot_builder.with_parent_snapshot_id(*snapshot.snapshot_id());
+                }
+                let snapshot = snapshot_builder
+                    .build()
+                    .map_err(iceberg_rust_spec::error::Error::from)?;
+
+                Ok((
+                    old_snapshot.map(|x| TableRequirement::AssertRefSnapshotId {
+                        r#ref: branch.clone().unwrap_or("main".to_owned()),
+                        snapshot_id: *x.snapshot_id(),
+                    }),
+                    vec![
+                        TableUpdate::AddSnapshot { snapshot },
+                        TableUpdate::SetSnapshotRef {
+                            ref_name: branch.unwrap_or("main".to_owned()),
+                            snapshot_reference: SnapshotReference {
+                                snapshot_id,
+                                retention: SnapshotRetention::default(),
+                            },
+                        },
+                    ],
+                ))
+            }
+            Operation::ExpireSnapshots {
+                older_than_ms,
+                retain_last,
+            } => {
+                // Snapshots referenced by a branch or tag are never expired
+                let protected: HashSet<i64> = table_metadata
+                    .refs
+                    .values()
+                    .map(|x| x.snapshot_id)
+                    .chain(table_metadata.current_snapshot_id)
+                    .collect();
+
+                let mut snapshots: Vec<&Snapshot> = table_metadata.snapshots.values().collect();
+                // Snapshots that are committed within the same millisecond are ordered by their
+                // sequence number
+                snapshots
+                    .sort_by_key(|x| std::cmp::Reverse((*x.timestamp_ms(), *x.sequence_number())));
+
+                let snapshot_ids: Vec<i64> = snapshots
+                    .into_iter()
+                    .skip(retain_last.unwrap_or(0))
+                    .filter(|x| {
+                        older_than_ms.is_none_or(|older_than| *x.timestamp_ms() < older_than)
+                    })
+                    .map(|x| *x.snapshot_id())
+                    .filter(|x| !protected.contains(x))
+                    .collect();
+
+                if snapshot_ids.is_empty() {
+                    return Ok((None, Vec::new()));
+                }
+
+                Ok((None, vec![TableUpdate::RemoveSnapshots { snapshot_ids }]))
+            }
+            Operation::RewriteManifests { branch } => {
+                let partition_fields =
+                    table_metadata.current_partition_fields(branch.as_deref())?;
+                let schema = table_metadata.current_schema(branch.as_deref())?;
+                let old_snapshot =
+                    table_metadata
+                        .current_snapshot(branch.as_deref())?
+                        .ok_or(Error::NotFound(
+                            "Snapshot to rewrite manifests of".to_owned(),
+                        ))?;
+
+                let partition_column_names = partition_fields
+                    .iter()
+                    .map(|x| x.name())
+                    .collect::<SmallVec<[_; 4]>>();
+
+                let manifests: Vec<ManifestListEntry> =
+                    read_snapshot(old_snapshot, table_metadata, object_store.clone())
+                        .await?
+                        .collect::<Result<_, _>>()?;
+
+                // Only data manifests of the default partition spec are rewritten, delete
+                // manifests and manifests of previous specs are kept as they are
+                let (manifests, kept_manifests): (Vec<_>, Vec<_>) =
+                    manifests.into_iter().partition(|manifest| {
+                        manifest.content == ManifestContent::Data
+                            && manifest.partition_spec_id == table_metadata.default_spec_id
+                    });
+
+                // Read all live entries, the data files and their sequence numbers are kept
+                let entries =
+                    futures::future::try_join_all(manifests.into_iter().map(|manifest| {
+                        let object_store = object_store.clone();
+                        async move {
+                            let bytes = object_store
+                                .get(&strip_prefix(&manifest.manifest_path).as_str().into())
+                                .await?
+                                .bytes()
+                                .await?;
+                            ManifestReader::for_spec(
+                                &*bytes,
+                                table_metadata
+                                    .partition_specs
+                                    .get(&manifest.partition_spec_id),
+                            )?
+                            .filter(|entry| {
+                                entry
+                                    .as_ref()
+                                    .map_or(true, |entry| *entry.status() != Status::Deleted)
+                            })
+                            .map(|entry| {
+                                let mut entry = entry?;
+                                *entry.status_mut() = Status::Existing;
+                                if entry.sequence_number().is_none() {
+                                    *entry.sequence_number_mut() = Some(manifest.sequence_number);
+                                }
+                                if entry.snapshot_id().is_none() {
+                                    *entry.snapshot_id_mut() = Some(manifest.added_snapshot_id);
+                                }
+                                Ok(entry)
+                            })
+                            .collect::<Result<Vec<_>, Error>>()
+                        }
+                    }))
+                    .await?
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+
+                let manifest_list_schema = match table_metadata.format_version {
+                    FormatVersion::V1 => manifest_list_schema_v1(),
+                    FormatVersion::V2 => manifest_list_schema_v2(),
+                };
+
+                let mut manifest_list_writer =
+                    apache_avro::Writer::new(manifest_list_schema, Vec::new());
+
+                for manifest in kept_manifests {
+                    manifest_list_writer.append_ser(manifest)?;
+                }
+
+                let manifest_schema = ManifestEntry::schema(
+                    &partition_value_schema(&partition_fields)?,
+                    &table_metadata.format_version,
+                )?;
+
+                let snapshot_id = ids.snapshot_id();
+                let commit_uuid = &ids.commit_uuid();
+
+                let n_splits = compute_n_splits(
+                    &ManifestTarget::new(&table_metadata.properties)?,
+                    0,
+                    entries.len(),
+                    0,
+                );
+
+                if n_splits == 0 {
+                    if !entries.is_empty() {
+                        let manifest_location =
+                            path_resolver.manifest_path(table_metadata, commit_uuid, 0);
+
+                        let mut manifest_writer = ManifestWriter::new(
+                            &manifest_location,
+                            snapshot_id,
+                            &manifest_schema,
+                            table_metadata,
+                            branch.as_deref(),
+                        )?;
+
+                        for manifest_entry in entries {
+                            manifest_writer.append(manifest_entry)?;
+                        }
+
+                        let manifest = manifest_writer.finish(object_store.clone()).await?;
+
+                        manifest_list_writer.append_ser(manifest)?;
+                    }
+                } else {
+                    let bounding_partition_values = entries
+                        .iter()
+                        .try_fold(None, |acc, x| {
+                            let node = partition_struct_to_vec(
+                                x.data_file().partition(),
+                                &partition_column_names,
+                            )?;
+                            let Some(mut acc) = acc else {
+                                return Ok::<_, Error>(Some(Rectangle::new(node.clone(), node)));
+                            };
+                            acc.expand_with_node(node);
+                            Ok(Some(acc))
+                        })?
+                        .ok_or(Error::NotFound("Bounding partition values".to_owned()))?;
+
+                    let splits = split_datafiles(
+                        entries.into_iter().map(Ok),
+                        bounding_partition_values,
+                        &partition_column_names,
+                        n_splits,
+                    )?;
+
+                    let manifest_futures = splits
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, entries)| {
+                            let manifest_location =
+                                path_resolver.manifest_path(table_metadata, commit_uuid, i);
+
+                            let mut manifest_writer = ManifestWriter::new(
+                                &manifest_location,
+                                snapshot_id,
+                                &manifest_schema,
+                                table_metadata,
+                                branch.as_deref(),
+                            )?;
+
+                            for manifest_entry in entries {
+                                manifest_writer.append(manifest_entry)?;
+                            }
+
+                            Ok::<_, Error>(manifest_writer.finish(object_store.clone()))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    let manifests = futures::future::try_join_all(manifest_futures).await?;
+
+                    for manifest in manifests {
+                        manifest_list_writer.append_ser(manifest)?;
+                    }
+                }
+
+                let new_manifest_list_location =
+                    path_resolver.manifest_list_path(table_metadata, snapshot_id, 0, commit_uuid);
+
+                let manifest_list_bytes = manifest_list_writer.into_inner()?;
+
+                put_if_not_exists(
+                    &*object_store,
+                    &new_manifest_list_location,
+                    manifest_list_bytes.into(),
+                )
+                .await?;
+
+                // Like every snapshot, the snapshot gets the next sequence number of the table.
+                // The rewritten entries keep the sequence numbers of the snapshots that added
+                // them, so delete files still apply to the same data files.
+                let snapshot = SnapshotBuilder::default()
+                    .with_snapshot_id(snapshot_id)
+                    .with_parent_snapshot_id(*old_snapshot.snapshot_id())
+                    .with_manifest_list(new_manifest_list_location)
+                    .with_sequence_number(table_metadata.last_sequence_number + 1)
+                    .with_summary(Summary {
+                        operation: SnapshotOperation::Replace,
+                        other: HashMap::new(),
+                    })
+                    .with_schema_id(*schema.schema_id())
+                    .build()
+                    .map_err(iceberg_rust_spec::error::Error::from)?;
+
+                Ok((
+                    Some(TableRequirement::AssertRefSnapshotId {
+                        r#ref: branch.clone().unwrap_or("main".to_owned()),
+                        snapshot_id: *old_snapshot.snapshot_id(),
+                    }),
+                    vec![
+                        TableUpdate::AddSnapshot { snapshot },
+                        TableUpdate::SetSnapshotRef {
+                            ref_name: branch.unwrap_or("main".to_owned()),
+                            snapshot_reference: SnapshotReference {
+                                snapshot_id,
+                                retention: SnapshotRetention::default(),
+                            },
+                        },
+                    ],
+                ))
+            }
+            Operation::UpdateProperties(entries) => Ok((
+                None,
+                vec![TableUpdate::SetProperties {
+                    updates: HashMap::from_iter(entries),
+                }],
+            )),
+            Operation::SetCurrentSnapshot {
+                branch,
+                snapshot_id,
+                force,
+            } => set_current_snapshot(table_metadata, branch, snapshot_id, force),
+            Operation::RollbackToTime {
+                branch,
+                timestamp_ms,
+            } => {
+                // Walk the history of the branch back to the newest snapshot at or before the
+                // timestamp. Snapshots of other branches are never selected.
+                let mut current = branch_head(table_metadata, branch.as_deref().unwrap_or("main"));
+                let snapshot_id = loop {
+                    let Some(snapshot) = current.and_then(|id| table_metadata.snapshots.get(&id))
+                    else {
+                        return Err(Error::NotFound(format!(
+                            "Snapshot at or before timestamp {timestamp_ms}"
+                        )));
+                    };
+                    if *snapshot.timestamp_ms() <= timestamp_ms {
+                        break *snapshot.snapshot_id();
+                    }
+                    current = *snapshot.parent_snapshot_id();
+                };
+                set_current_snapshot(table_metadata, branch, snapshot_id, false)
+            }
+            Operation::FastForward { target, source } => {
+                let source_head = branch_head(table_metadata, &source)
+                    .ok_or(Error::NotFound(format!("Branch {source}")))?;
+                let current = table_metadata.refs.get(&target);
+                let target_head = branch_head(table_metadata, &target);
+
+                if let Some(target_head) = target_head {
+                    if !table_metadata.is_ancestor_of(target_head, source_head) {
+                        return Err(Error::NotAncestor {
+                            branch: source,
+                            snapshot_id: target_head,
+                            head: source_head,
+                        });
+                    }
+                }
+
+                let retention = current.map(|x| x.retention.clone()).unwrap_or_default();
+
+                Ok((
+                    target_head.map(|id| TableRequirement::AssertRefSnapshotId {
+                        r#ref: target.clone(),
+                        snapshot_id: id,
+                    }),
+                    vec![TableUpdate::SetSnapshotRef {
+                        ref_name: target,
+                        snapshot_reference: SnapshotReference {
+                            snapshot_id: source_head,
+                            retention,
+                        },
+                    }],
+                ))
+            }
+            Operation::SetSnapshotRef((key, value)) => {
+                if !table_metadata.snapshots.contains_key(&value.snapshot_id) {
+                    return Err(Error::NotFound(format!(
+                        "Snapshot {} for reference {}",
+                        value.snapshot_id, key
+                    )));
+                }
+                Ok((
+                    table_metadata
+                        .refs
+                        .get(&key)
+                        .map(|x| TableRequirement::AssertRefSnapshotId {
+                            r#ref: key.clone(),
+                            snapshot_id: x.snapshot_id,
+                        }),
+                    vec![TableUpdate::SetSnapshotRef {
+                        ref_name: key,
+                        snapshot_reference: value,
+                    }],
+                ))
+            }
+            Operation::CreateSnapshotRef((key, value)) => {
+                if table_metadata.refs.contains_key(&key) {
+                    return Err(Error::InvalidFormat(format!(
+                        "Reference {key} already exists"
+                    )));
+                }
+                if !table_metadata.snapshots.contains_key(&value.snapshot_id) {
+                    return Err(Error::NotFound(format!(
+                        "Snapshot {} for reference {}",
+                        value.snapshot_id, key
+                    )));
+                }
+                Ok((
+                    None,
+                    vec![TableUpdate::SetSnapshotRef {
+                        ref_name: key,
+                        snapshot_reference: value,
+                    }],
+                ))
+            }
+            Operation::AddSchema(schema) => {
+                let last_column_id = schema.fields().iter().map(|x| x.id).max();
                 Ok((
                     None,
                     vec![TableUpdate::AddSchema {
@@ -577,8 +1694,416 @@ impl Operation {
             Operation::SetDefaultSpec(spec_id) => {
                 Ok((None, vec![TableUpdate::SetDefaultSpec { spec_id }]))
             }
+            Operation::UpdateSpec(update) => {
+                let spec = update.apply(table_metadata)?;
+                let requirement = TableRequirement::AssertDefaultSpecId {
+                    default_spec_id: table_metadata.default_spec_id,
+                };
+
+                // Switch back to an existing spec with the same fields instead of adding a copy
+                if let Some(existing) = table_metadata
+                    .partition_specs
+                    .values()
+                    .find(|x| x.fields() == spec.fields())
+                {
+                    return Ok((
+                        Some(requirement),
+                        vec![TableUpdate::SetDefaultSpec {
+                            spec_id: *existing.spec_id(),
+                        }],
+                    ));
+                }
+
+                let spec_id = *spec.spec_id();
+                Ok((
+                    Some(requirement),
+                    vec![
+                        TableUpdate::AddPartitionSpec { spec },
+                        TableUpdate::SetDefaultSpec { spec_id },
+                    ],
+                ))
+            }
+            Operation::SetLocation(location) => {
+                let location = validate_location(&location)?;
+                Ok((None, vec![TableUpdate::SetLocation { location }]))
+            }
+            Operation::UpgradeFormatVersion(format_version) => {
+                let format_version = FormatVersion::try_from(format_version)?;
+                if u8::from(format_version) < u8::from(table_metadata.format_version) {
+                    return Err(Error::NotSupported(
+                        "downgrading the table format version".to_owned(),
+                    ));
+                }
+                if format_version == table_metadata.format_version {
+                    return Ok((None, Vec::new()));
+                }
+                Ok((
+                    None,
+                    vec![TableUpdate::UpgradeFormatVersion {
+                        format_version: match format_version {
+                            FormatVersion::V1 => 1,
+                            FormatVersion::V2 => 2,
+                        },
+                    }],
+                ))
+            }
+            Operation::ReplaceSortOrder(sort_order) => {
+                let schema = table_metadata.current_schema(None)?;
+                for field in &sort_order.fields {
+                    if schema.get(field.source_id as usize).is_none() {
+                        return Err(Error::Schema(
+                            field.source_id.to_string(),
+                            schema.schema_id().to_string(),
+                        ));
+                    }
+                }
+
+                let order_id = table_metadata
+                    .sort_orders
+                    .keys()
+                    .max()
+                    .map(|x| x + 1)
+                    .unwrap_or(1)
+                    .max(1);
+
+                Ok((
+                    Some(TableRequirement::AssertDefaultSortOrderId {
+                        default_sort_order_id: table_metadata.default_sort_order_id,
+                    }),
+                    vec![
+                        TableUpdate::AddSortOrder {
+                            sort_order: SortOrder {
+                                order_id,
+                                fields: sort_order.fields,
+                            },
+                        },
+                        TableUpdate::SetDefaultSortOrder {
+                            sort_order_id: order_id,
+                        },
+                    ],
+                ))
+            }
+        }
+    }
+}
+
+/// Adds data files and delete files to a branch by writing them to new manifests
+///
+/// The existing manifests are referenced by the new manifest list as they are.
+#[allow(clippy::too_many_arguments)]
+async fn row_delta(
+    table_metadata: &TableMetadata,
+    object_store: Arc<dyn ObjectStore>,
+    path_resolver: &dyn MetadataPathResolver,
+    ids: &IdSource,
+    branch: Option<String>,
+    data_files: Vec<DataFile>,
+    delete_files: Vec<DataFile>,
+    additional_summary: Option<HashMap<String, String>>,
+) -> Result<(Option<TableRequirement>, Vec<TableUpdate>), Error> {
+    let snapshot_operation = match (data_files.len(), delete_files.len()) {
+        (0, 0) => Err(Error::InvalidFormat(
+            "Empty data and delete files".to_string(),
+        )),
+        (_, 0) => Ok(SnapshotOperation::Append),
+        (0, _) => Ok(SnapshotOperation::Delete),
+        (_, _) => Ok(SnapshotOperation::Overwrite),
+    }?;
+
+    if !delete_files.is_empty() && table_metadata.format_version == FormatVersion::V1 {
+        return Err(Error::NotSupported(
+            "Delete files in format version 1 tables".to_owned(),
+        ));
+    }
+    if let Some(file) = data_files.iter().find(|x| *x.content() != Content::Data) {
+        return Err(Error::InvalidFormat(format!(
+            "Data file {}",
+            file.file_path()
+        )));
+    }
+    if let Some(file) = delete_files.iter().find(|x| *x.content() == Content::Data) {
+        return Err(Error::InvalidFormat(format!(
+            "Delete file {}",
+            file.file_path()
+        )));
+    }
+
+    let partition_fields = table_metadata.current_partition_fields(branch.as_deref())?;
+    let schema = table_metadata.current_schema(branch.as_deref())?;
+    let old_snapshot = table_metadata.current_snapshot(branch.as_deref())?;
+
+    validate_partition_values(
+        data_files.iter().chain(delete_files.iter()),
+        &partition_fields,
+    )?;
+
+    let old_manifest_list_bytes_opt = prefetch_manifest_list(old_snapshot, &object_store);
+
+    let mut summary = append_summary(old_snapshot, data_files.iter().chain(delete_files.iter()));
+    summary.extend(additional_summary.unwrap_or_default());
+
+    let manifest_list_schema = match table_metadata.format_version {
+        FormatVersion::V1 => manifest_list_schema_v1(),
+        FormatVersion::V2 => manifest_list_schema_v2(),
+    };
+
+    let mut manifest_list_writer = apache_avro::Writer::new(manifest_list_schema, Vec::new());
+
+    // The existing manifests are referenced by the new manifest list as they are
+    if let Some(old_manifest_list_bytes) = old_manifest_list_bytes_opt {
+        let old_manifest_list_bytes = old_manifest_list_bytes.await??;
+
+        let manifest_list_reader =
+            ManifestListReader::new(old_manifest_list_bytes.as_ref(), table_metadata)?;
+
+        for manifest in manifest_list_reader {
+            manifest_list_writer.append_ser(manifest?)?;
+        }
+    }
+
+    let snapshot_id = ids.snapshot_id();
+    let commit_uuid = &ids.commit_uuid();
+
+    let manifest_schema = ManifestEntry::schema(
+        &partition_value_schema(&partition_fields)?,
+        &table_metadata.format_version,
+    )?;
+
+    // The new entries don't have a sequence number and inherit the sequence number of the new
+    // snapshot from their manifest. It is greater than the sequence numbers of all existing data
+    // files, so the delete files apply to them. Equality deletes don't apply to the data files
+    // that are added by this snapshot, position deletes do, because they reference the rows of a
+    // data file by its path. Data files and delete files are written to separate manifests.
+    let manifest_futures = [data_files, delete_files]
+        .into_iter()
+        .filter(|files| !files.is_empty())
+        .enumerate()
+        .map(|(i, files)| {
+            let manifest_location = path_resolver.manifest_path(table_metadata, commit_uuid, i);
+            let manifest_schema = &manifest_schema;
+            let object_store = object_store.clone();
+            let branch = branch.as_deref();
+            async move {
+                write_new_manifest(
+                    files,
+                    &manifest_location,
+                    snapshot_id,
+                    manifest_schema,
+                    table_metadata,
+                    branch,
+                    object_store,
+                )
+                .await
+            }
+        });
+
+    let manifests = futures::future::try_join_all(manifest_futures).await?;
+
+    for manifest in manifests {
+        manifest_list_writer.append_ser(manifest)?;
+    }
+
+    let new_manifest_list_location =
+        path_resolver.manifest_list_path(table_metadata, snapshot_id, 0, commit_uuid);
+
+    let manifest_list_bytes = manifest_list_writer.into_inner()?;
+
+    put_if_not_exists(
+        &*object_store,
+        &new_manifest_list_location,
+        manifest_list_bytes.into(),
+    )
+    .await?;
+
+    let mut snapshot_builder = SnapshotBuilder::default();
+    snapshot_builder
+        .with_snapshot_id(snapshot_id)
+        .with_manifest_list(new_manifest_list_location)
+        .with_sequence_number(table_metadata.last_sequence_number + 1)
+        .with_summary(Summary {
+            operation: snapshot_operation,
+            other: summary,
+        })
+        .with_schema_id(*schema.schema_id());
+    if let Some(snapshot) = old_snapshot {
+        snapshot_builder.with_parent_snapshot_id(*snapshot.snapshot_id());
+    }
+    let snapshot = snapshot_builder
+        .build()
+        .map_err(iceberg_rust_spec::error::Error::from)?;
+
+    Ok((
+        old_snapshot.map(|x| TableRequirement::AssertRefSnapshotId {
+            r#ref: branch.clone().unwrap_or("main".to_owned()),
+            snapshot_id: *x.snapshot_id(),
+        }),
+        vec![
+            TableUpdate::AddSnapshot { snapshot },
+            TableUpdate::SetSnapshotRef {
+                ref_name: branch.unwrap_or("main".to_owned()),
+                snapshot_reference: SnapshotReference {
+                    snapshot_id,
+                    retention: SnapshotRetention::default(),
+                },
+            },
+        ],
+    ))
+}
+
+/// Returns the avro schema of the entries of manifests that were written with the partition
+/// spec `spec_id`
+fn manifest_schema_for_spec(
+    table_metadata: &TableMetadata,
+    branch: Option<&str>,
+    spec_id: i32,
+) -> Result<AvroSchema, Error> {
+    let spec = table_metadata
+        .partition_specs
+        .get(&spec_id)
+        .ok_or(Error::NotFound(format!("Partition spec with id {spec_id}")))?;
+    let partition_fields = iceberg_rust_spec::spec::table_metadata::partition_fields(
+        spec,
+        table_metadata.current_schema(branch)?,
+    )?;
+    Ok(ManifestEntry::schema(
+        &partition_value_schema(&partition_fields)?,
+        &table_metadata.format_version,
+    )?)
+}
+
+/// Writes files to a new manifest with the status `Added`
+///
+/// The entries inherit the snapshot id and the sequence number of the new snapshot from the
+/// manifest.
+async fn write_new_manifest(
+    files: Vec<DataFile>,
+    manifest_location: &str,
+    snapshot_id: i64,
+    manifest_schema: &AvroSchema,
+    table_metadata: &TableMetadata,
+    branch: Option<&str>,
+    object_store: Arc<dyn ObjectStore>,
+) -> Result<ManifestListEntry, Error> {
+    let mut manifest_writer = ManifestWriter::new(
+        manifest_location,
+        snapshot_id,
+        manifest_schema,
+        table_metadata,
+        branch,
+    )?;
+
+    for data_file in files {
+        manifest_writer.append(
+            ManifestEntry::builder()
+                .with_format_version(table_metadata.format_version)
+                .with_status(Status::Added)
+                .with_data_file(data_file)
+                .build()
+                .map_err(crate::spec::error::Error::from)?,
+        )?;
+    }
+
+    manifest_writer.finish(object_store).await
+}
+
+/// Returns the id of the snapshot the branch points to. The main branch falls back to the current snapshot id.
+fn branch_head(table_metadata: &TableMetadata, branch: &str) -> Option<i64> {
+    table_metadata
+        .refs
+        .get(branch)
+        .map(|x| x.snapshot_id)
+        .or(if branch == "main" {
+            table_metadata.current_snapshot_id
+        } else {
+            None
+        })
+        .filter(|id| *id != -1)
+}
+
+/// Points the branch at the given snapshot. Unless forced, the snapshot has to be an ancestor of
+/// the current snapshot of the branch.
+fn set_current_snapshot(
+    table_metadata: &TableMetadata,
+    branch: Option<String>,
+    snapshot_id: i64,
+    force: bool,
+) -> Result<(Option<TableRequirement>, Vec<TableUpdate>), Error> {
+    if !table_metadata.snapshots.contains_key(&snapshot_id) {
+        return Err(Error::NotFound(format!("Snapshot {snapshot_id}")));
+    }
+    let branch_name = branch.unwrap_or("main".to_owned());
+    let current = table_metadata.refs.get(&branch_name);
+    let current_snapshot_id = branch_head(table_metadata, &branch_name);
+
+    if !force
+        && !current_snapshot_id
+            .is_some_and(|current| table_metadata.is_ancestor_of(snapshot_id, current))
+    {
+        return Err(Error::NotAncestor {
+            branch: branch_name,
+            snapshot_id,
+            head: current_snapshot_id.unwrap_or(-1),
+        });
+    }
+
+    let retention = current.map(|x| x.retention.clone()).unwrap_or_default();
+
+    Ok((
+        current_snapshot_id.map(|id| TableRequirement::AssertRefSnapshotId {
+            r#ref: branch_name.clone(),
+            snapshot_id: id,
+        }),
+        vec![TableUpdate::SetSnapshotRef {
+            ref_name: branch_name,
+            snapshot_reference: SnapshotReference {
+                snapshot_id,
+                retention,
+            },
+        }],
+    ))
+}
+
+/// Checks that the location is an absolute path or an object store URI with a bucket.
+/// Returns the location without trailing slashes.
+fn validate_location(location: &str) -> Result<String, Error> {
+    let location = location.trim_end_matches('/');
+    if location.contains("://") {
+        let url = Url::parse(location)?;
+        if url.scheme() != "file" && url.host_str().is_none_or(str::is_empty) {
+            return Err(Error::InvalidFormat(format!("Location {location}")));
         }
+    } else if !location.starts_with('/') {
+        return Err(Error::InvalidFormat(format!("Location {location}")));
     }
+    Ok(location.to_owned())
+}
+
+/// Check whether all rows of a data file match the filter.
+/// Uses the column bounds of the data file and falls back to the identity partition values.
+fn data_file_must_match(
+    data_file: &DataFile,
+    filter: &BoundPredicate,
+    partition_fields: &[BoundPartitionField],
+) -> bool {
+    filter.must_match(&|id| {
+        if let Some(bounds) = data_file
+            .field_bounds(id)
+            .filter(|x| x.lower.is_some() && x.upper.is_some())
+        {
+            return Some(bounds);
+        }
+        partition_fields
+            .iter()
+            .find(|field| {
+                field.source_id() == id && matches!(field.transform(), Transform::Identity)
+            })
+            .and_then(|field| data_file.partition().get(field.name()))
+            .map(|value| FieldBounds {
+                lower: value.as_ref(),
+                upper: value.as_ref(),
+                contains_null: Some(value.is_none()),
+            })
+    })
 }
 
 fn prefetch_manifest(
@@ -623,44 +2148,792 @@ fn prefetch_manifest_list(
         })
 }
 
-fn new_manifest_location(table_metadata_location: &str, commit_uuid: &String, i: usize) -> String {
-    format!(
-        "{}/metadata/{}-m{}.avro",
-        table_metadata_location, commit_uuid, i
-    )
+/// Checks that the partition values of the datafiles have exactly the fields of the partition spec and that the
+/// values have the result types of the partition transforms.
+fn validate_partition_values<'a>(
+    files: impl Iterator<Item = &'a DataFile>,
+    partition_fields: &[BoundPartitionField],
+) -> Result<(), Error> {
+    let data_types = partition_fields
+        .iter()
+        .map(|field| match field.transform() {
+            Transform::Void => Ok(None),
+            transform => field.field_type().tranform(transform).map(Some),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    for file in files {
+        let partition = file.partition();
+        for (field, data_type) in partition_fields.iter().zip(data_types.iter()) {
+            let Some(value) = partition.get(field.name()) else {
+                return Err(Error::Schema(
+                    field.name().to_owned(),
+                    format!("of the partition of data file {}", file.file_path()),
+                ));
+            };
+            let matches = match (value, data_type) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (
+                    Some(Value::Decimal(value)),
+                    Some(Type::Primitive(PrimitiveType::Decimal { scale, .. })),
+                ) => value.scale() == *scale,
+                (Some(Value::Struct(_) | Value::List(_) | Value::Map(_)), Some(_)) => false,
+                (Some(value), Some(data_type)) => value.datatype() == *data_type,
+            };
+            if !matches {
+                return Err(Error::Schema(
+                    format!(
+                        "{} with type {}",
+                        field.name(),
+                        data_type
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or("void".to_owned())
+                    ),
+                    format!("of the partition of data file {}", file.file_path()),
+                ));
+            }
+        }
+        if let Some(name) = partition
+            .keys()
+            .find(|name| !partition_fields.iter().any(|field| field.name() == *name))
+        {
+            return Err(Error::Schema(
+                name.to_owned(),
+                format!("of the partition spec of data file {}", file.file_path()),
+            ));
+        }
+    }
+    Ok(())
 }
 
-fn new_manifest_list_location(
-    table_metadata_location: &str,
-    snapshot_id: i64,
-    attempt: i64,
-    commit_uuid: &String,
-) -> String {
-    format!(
-        "{}/metadata/snap-{}-{}-{}.avro",
-        table_metadata_location, snapshot_id, attempt, commit_uuid
-    )
+/// Computes the standard snapshot summary properties of a snapshot that adds the given data and delete files.
+///
+/// The totals are only written if the previous snapshot has them as well, or if there is no previous snapshot.
+fn append_summary<'a>(
+    old_snapshot: Option<&Snapshot>,
+    files: impl Iterator<Item = &'a DataFile>,
+) -> HashMap<String, String> {
+    let mut data_files = 0;
+    let mut records = 0;
+    let mut files_size = 0;
+    let mut delete_files = 0;
+    let mut position_deletes = 0;
+    let mut equality_deletes = 0;
+    for file in files {
+        files_size += *file.file_size_in_bytes();
+        match file.content() {
+            Content::Data => {
+                data_files += 1;
+                records += *file.record_count();
+            }
+            Content::PositionDeletes => {
+                delete_files += 1;
+                position_deletes += *file.record_count();
+            }
+            Content::EqualityDeletes => {
+                delete_files += 1;
+                equality_deletes += *file.record_count();
+            }
+        }
+    }
+
+    let mut summary = HashMap::new();
+    let mut add = |added: &str, total: &str, value: i64, always: bool| {
+        if always || value != 0 {
+            summary.insert(added.to_owned(), value.to_string());
+        }
+        let previous = match old_snapshot {
+            Some(snapshot) => snapshot
+                .summary()
+                .other
+                .get(total)
+                .and_then(|x| x.parse::<i64>().ok()),
+            None => Some(0),
+        };
+        if let Some(previous) = previous {
+            summary.insert(total.to_owned(), (previous + value).to_string());
+        }
+    };
+    add("added-data-files", "total-data-files", data_files, true);
+    add("added-records", "total-records", records, true);
+    add("added-files-size", "total-files-size", files_size, true);
+    add(
+        "added-delete-files",
+        "total-delete-files",
+        delete_files,
+        false,
+    );
+    add(
+        "added-position-deletes",
+        "total-position-deletes",
+        position_deletes,
+        false,
+    );
+    add(
+        "added-equality-deletes",
+        "total-equality-deletes",
+        equality_deletes,
+        false,
+    );
+    summary
+}
+
+/// Target size of the manifests written by an operation, configured by the table properties
+/// `commit.manifest.target-size-bytes` and `commit.manifest.min-count-to-merge`.
+///
+/// Without a target size, the number of datafiles per manifest grows with the square root of the
+/// number of datafiles in the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ManifestTarget {
+    /// Target size of a manifest in bytes
+    size_bytes: Option<usize>,
+    /// Minimum number of datafiles in a manifest before it is split
+    min_count: usize,
+    /// Size of a manifest entry in bytes
+    entry_size: usize,
+}
+
+impl ManifestTarget {
+    fn new(properties: &HashMap<String, String>) -> Result<Self, Error> {
+        let size_bytes = properties
+            .get(MANIFEST_TARGET_SIZE_BYTES)
+            .map(|x| x.parse::<usize>())
+            .transpose()?;
+        let min_count = properties
+            .get(MANIFEST_MIN_MERGE_COUNT)
+            .map(|x| x.parse::<usize>())
+            .transpose()?
+            .unwrap_or(MIN_DATAFILES_PER_MANIFEST)
+            .max(1);
+        Ok(Self {
+            size_bytes,
+            min_count,
+            entry_size: ESTIMATED_MANIFEST_ENTRY_SIZE,
+        })
+    }
+
+    /// Derives the size of a manifest entry from the length and the number of entries of an existing manifest
+    fn with_entry_size_of(mut self, manifest: Option<&ManifestListEntry>) -> Self {
+        if let Some(manifest) = manifest {
+            let entry_count = manifest.added_files_count.unwrap_or(0) as i64
+                + manifest.existing_files_count.unwrap_or(0) as i64
+                + manifest.deleted_files_count.unwrap_or(0) as i64;
+            if entry_count > 0 && manifest.manifest_length > 0 {
+                self.entry_size = (manifest.manifest_length / entry_count).max(1) as usize;
+            }
+        }
+        self
+    }
+
+    /// Maximum number of datafiles per manifest for a table with *total_file_count* datafiles
+    fn file_limit(&self, total_file_count: usize) -> usize {
+        match self.size_bytes {
+            Some(size_bytes) => (size_bytes / self.entry_size).max(self.min_count),
+            // We want:
+            //   nb manifests per manifest list ~= nb data files per manifest
+            // Since:
+            //   total number of data files = nb manifests per manifest list * nb data files per manifest
+            // We shall have:
+            //   limit = sqrt(total number of data files)
+            None => self.min_count + (total_file_count as f64).sqrt() as usize,
+        }
+    }
 }
 
 /// To achieve fast lookups of the datafiles, the manifest tree should be somewhat balanced, meaning that manifest files should contain a similar number of datafiles.
 /// This means that manifest files might need to be split up when they get too large. Since the number of datafiles being added by a append operation might be really large,
 /// it might even be required to split the manifest file multiple times. *n_splits* stores how many times a manifest file needs to be split to give at most *limit* datafiles per manifest.
+/// The limit is determined by the [ManifestTarget].
 fn compute_n_splits(
+    target: &ManifestTarget,
     existing_file_count: usize,
     new_file_count: usize,
     selected_manifest_file_count: usize,
 ) -> u32 {
-    // We want:
-    //   nb manifests per manifest list ~= nb data files per manifest
-    // Since:
-    //   total number of data files = nb manifests per manifest list * nb data files per manifest
-    // We shall have:
-    //   limit = sqrt(total number of data files)
-    let limit = MIN_DATAFILES_PER_MANIFEST
-        + ((existing_file_count + new_file_count) as f64).sqrt() as usize;
+    let limit = target.file_limit(existing_file_count + new_file_count);
     let new_manifest_file_count = selected_manifest_file_count + new_file_count;
     match new_manifest_file_count / limit {
         0 => 0,
         x => x.ilog2() + 1,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use arrow::{array::Int64Array, datatypes::Schema as ArrowSchema, record_batch::RecordBatch};
+    use futures::{stream, TryStreamExt};
+    use iceberg_rust_spec::{
+        spec::{
+            manifest::{DataFile, Status},
+            schema::Schema,
+            types::{PrimitiveType, StructField, Type},
+        },
+        util::strip_prefix,
+    };
+
+    use crate::{
+        arrow::{delete::PositionDeleteWriter, write::write_parquet_partitioned},
+        table::{scan::FileScanTask, Table},
+        test_util::TestTable,
+    };
+
+    async fn write(table: &Table, ids: Vec<i64>) -> Vec<DataFile> {
+        let arrow_schema: ArrowSchema = table
+            .current_schema(None)
+            .unwrap()
+            .fields()
+            .try_into()
+            .unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema),
+            vec![Arc::new(Int64Array::from(ids))],
+        )
+        .unwrap();
+        write_parquet_partitioned(table, stream::iter(vec![Ok(batch)]), None)
+            .await
+            .unwrap()
+    }
+
+    async fn test_table() -> Table {
+        let schema = Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+        TestTable::new(schema).await.unwrap()
+    }
+
+    /// Appends the ids and returns the id of the new snapshot
+    async fn append(table: &mut Table, ids: Vec<i64>) -> i64 {
+        let files = write(table, ids).await;
+        table
+            .new_transaction(None)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+        *table
+            .metadata()
+            .current_snapshot(None)
+            .unwrap()
+            .unwrap()
+            .snapshot_id()
+    }
+
+    async fn row_count(table: &Table, branch: Option<&str>) -> usize {
+        table
+            .scan(None, branch)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .iter()
+            .map(RecordBatch::num_rows)
+            .sum()
+    }
+
+    fn current_sequence_number(table: &Table) -> i64 {
+        *table
+            .metadata()
+            .current_snapshot(None)
+            .unwrap()
+            .unwrap()
+            .sequence_number()
+    }
+
+    #[tokio::test]
+    async fn replace_advances_sequence_number() {
+        let mut table = test_table().await;
+
+        append(&mut table, vec![1, 2, 3]).await;
+        assert_eq!(current_sequence_number(&table), 1);
+
+        let files = write(&table, vec![1, 2, 3]).await;
+        let replaced = files
+            .iter()
+            .map(|file| file.file_path().clone())
+            .collect::<Vec<_>>();
+        table
+            .new_transaction(None)
+            .replace(files)
+            .commit()
+            .await
+            .unwrap();
+        let replace_snapshot_id = *table
+            .metadata()
+            .current_snapshot(None)
+            .unwrap()
+            .unwrap()
+            .snapshot_id();
+        assert_eq!(current_sequence_number(&table), 2);
+        assert_eq!(table.metadata().last_sequence_number, 2);
+
+        let files = write(&table, vec![4, 5]).await;
+        let appended = files
+            .iter()
+            .map(|file| file.file_path().clone())
+            .collect::<Vec<_>>();
+        table
+            .new_transaction(None)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+        let append_snapshot_id = *table
+            .metadata()
+            .current_snapshot(None)
+            .unwrap()
+            .unwrap()
+            .snapshot_id();
+        assert_eq!(current_sequence_number(&table), 3);
+
+        // Only the files appended after the replace are read incrementally
+        let entries = table
+            .incremental_files(replace_snapshot_id, append_snapshot_id)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.data_file().file_path().clone())
+                .collect::<Vec<_>>(),
+            appended
+        );
+
+        // The appended files are merged into the manifest of the replace, which gets the
+        // sequence number of the append while the replaced files keep theirs
+        let manifests = table.manifests(None, None).await.unwrap();
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].sequence_number, 3);
+        let entries = table
+            .datafiles(&manifests, None, (None, None))
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), replaced.len() + appended.len());
+        for entry in entries {
+            let path = entry.data_file().file_path();
+            if replaced.contains(path) {
+                assert_eq!(*entry.status(), Status::Existing);
+                assert_eq!(*entry.sequence_number(), Some(2));
+            } else {
+                assert!(appended.contains(path));
+                assert_eq!(*entry.status(), Status::Added);
+                assert_eq!(*entry.sequence_number(), Some(3));
+            }
+        }
+        assert_eq!(row_count(&table, None).await, 5);
+    }
+
+    #[tokio::test]
+    async fn replace_on_lagging_branch() {
+        let mut table = test_table().await;
+
+        let first = append(&mut table, vec![1, 2, 3]).await;
+        table
+            .new_transaction(None)
+            .create_branch("staging", first, None, None, None)
+            .commit()
+            .await
+            .unwrap();
+        append(&mut table, vec![4]).await;
+        let main_head = append(&mut table, vec![5]).await;
+        assert_eq!(table.metadata().last_sequence_number, 3);
+
+        let files = write(&table, vec![1, 2, 3]).await;
+        table
+            .new_transaction(Some("staging"))
+            .replace(files)
+            .commit()
+            .await
+            .unwrap();
+        let staging_head = table
+            .metadata()
+            .current_snapshot(Some("staging"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(*staging_head.sequence_number(), 4);
+        assert_eq!(table.metadata().last_sequence_number, 4);
+
+        // The history of main is untouched and its next snapshot doesn't reuse a sequence number
+        assert_eq!(table.metadata().refs["main"].snapshot_id, main_head);
+        assert!(table.metadata().snapshots.contains_key(&first));
+        append(&mut table, vec![6]).await;
+        assert_eq!(current_sequence_number(&table), 5);
+        assert_eq!(row_count(&table, None).await, 6);
+        assert_eq!(row_count(&table, Some("staging")).await, 3);
+
+        let mut sequence_numbers = table
+            .metadata()
+            .snapshots
+            .values()
+            .map(|snapshot| *snapshot.sequence_number())
+            .collect::<Vec<_>>();
+        sequence_numbers.sort();
+        sequence_numbers.dedup();
+        assert_eq!(sequence_numbers.len(), table.metadata().snapshots.len());
+    }
+
+    #[tokio::test]
+    async fn create_tag_at_previous_snapshot() {
+        let mut table = test_table().await;
+        let first = append(&mut table, vec![1, 2, 3]).await;
+        let second = append(&mut table, vec![4, 5]).await;
+
+        table
+            .new_transaction(None)
+            .create_tag("v1", first, 60 * 60 * 1000)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().refs["v1"].snapshot_id, first);
+        assert_eq!(table.metadata().refs["main"].snapshot_id, second);
+        assert_eq!(row_count(&table, Some("v1")).await, 3);
+        assert_eq!(row_count(&table, None).await, 5);
+
+        // Existing references are not overwritten
+        assert!(table
+            .new_transaction(None)
+            .create_tag("v1", second, 60 * 60 * 1000)
+            .commit()
+            .await
+            .is_err());
+        assert!(table
+            .new_transaction(None)
+            .create_branch("main", first, None, None, None)
+            .commit()
+            .await
+            .is_err());
+        assert_eq!(table.metadata().refs["v1"].snapshot_id, first);
+        assert_eq!(table.metadata().refs["main"].snapshot_id, second);
+    }
+
+    #[tokio::test]
+    async fn delete_files_with_manifest_validation() {
+        let mut table = test_table().await.with_manifest_validation(true);
+
+        let files = write(&table, vec![1, 2, 3]).await;
+        let deleted = files
+            .iter()
+            .map(|file| file.file_path().clone())
+            .collect::<Vec<_>>();
+        table
+            .new_transaction(None)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+        append(&mut table, vec![4, 5]).await;
+
+        table
+            .new_transaction(None)
+            .delete_files(deleted.clone())
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(row_count(&table, None).await, 2);
+
+        // The deleted files are kept as deleted entries, which match the counts of the manifest
+        let manifests = table.manifests(None, None).await.unwrap();
+        assert_eq!(
+            manifests
+                .iter()
+                .filter_map(|manifest| manifest.deleted_files_count)
+                .sum::<i32>(),
+            deleted.len() as i32
+        );
+        let entries = table
+            .datafiles(&manifests, None, (None, None))
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert!(entries
+            .iter()
+            .filter(|entry| deleted.contains(entry.data_file().file_path()))
+            .all(|entry| *entry.status() == Status::Deleted));
+
+        // Rewriting the manifest with the next append drops the deleted entries
+        append(&mut table, vec![6]).await;
+        assert_eq!(row_count(&table, None).await, 3);
+    }
+
+    /// Returns whether the manifest list of the snapshot was deleted
+    async fn manifest_list_deleted(table: &Table, manifest_list: &str) -> bool {
+        table
+            .object_store()
+            .head(&strip_prefix(manifest_list).into())
+            .await
+            .is_err()
+    }
+
+    #[tokio::test]
+    async fn expire_snapshots_retain_last() {
+        let mut table = test_table().await;
+        let first = append(&mut table, vec![1, 2, 3]).await;
+        let second = append(&mut table, vec![4, 5]).await;
+        let third = append(&mut table, vec![6]).await;
+        let manifest_list = table.metadata().snapshots[&first].manifest_list().clone();
+
+        table
+            .new_transaction(None)
+            .expire_snapshots(None, Some(2))
+            .commit()
+            .await
+            .unwrap();
+        assert!(!table.metadata().snapshots.contains_key(&first));
+        assert!(table.metadata().snapshots.contains_key(&second));
+        assert!(table.metadata().snapshots.contains_key(&third));
+        assert!(table
+            .metadata()
+            .snapshot_log
+            .iter()
+            .all(|entry| entry.snapshot_id != first));
+        assert!(manifest_list_deleted(&table, &manifest_list).await);
+
+        // The data files of the expired snapshot are still referenced by the current snapshot
+        assert_eq!(row_count(&table, None).await, 6);
+    }
+
+    #[tokio::test]
+    async fn expire_snapshots_older_than() {
+        let mut table = test_table().await;
+        let first = append(&mut table, vec![1, 2, 3]).await;
+        let second = append(&mut table, vec![4, 5]).await;
+        let third = append(&mut table, vec![6]).await;
+        let first_timestamp = *table.metadata().snapshots[&first].timestamp_ms();
+        let third_timestamp = *table.metadata().snapshots[&third].timestamp_ms();
+
+        // No snapshot is strictly older than the first one
+        table
+            .new_transaction(None)
+            .expire_snapshots(Some(first_timestamp), None)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().snapshots.len(), 3);
+
+        // The current snapshot is never expired
+        table
+            .new_transaction(None)
+            .expire_snapshots(Some(third_timestamp + 1), None)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(
+            table
+                .metadata()
+                .snapshots
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![third]
+        );
+        assert_eq!(
+            table
+                .metadata()
+                .snapshot_log
+                .iter()
+                .map(|entry| entry.snapshot_id)
+                .collect::<Vec<_>>(),
+            vec![third]
+        );
+        assert!(!table.metadata().snapshots.contains_key(&second));
+        assert_eq!(row_count(&table, None).await, 6);
+    }
+
+    #[tokio::test]
+    async fn expire_snapshots_keeps_referenced_snapshots() {
+        let mut table = test_table().await;
+        let first = append(&mut table, vec![1, 2, 3]).await;
+        table
+            .new_transaction(None)
+            .create_tag("v1", first, 60 * 60 * 1000)
+            .commit()
+            .await
+            .unwrap();
+        let second = append(&mut table, vec![4, 5]).await;
+        let third = append(&mut table, vec![6]).await;
+        let manifest_list = table.metadata().snapshots[&first].manifest_list().clone();
+
+        table
+            .new_transaction(None)
+            .expire_snapshots(Some(i64::MAX), None)
+            .commit()
+            .await
+            .unwrap();
+        assert!(table.metadata().snapshots.contains_key(&first));
+        assert!(!table.metadata().snapshots.contains_key(&second));
+        assert!(table.metadata().snapshots.contains_key(&third));
+        assert!(!manifest_list_deleted(&table, &manifest_list).await);
+        assert_eq!(row_count(&table, Some("v1")).await, 3);
+        assert_eq!(row_count(&table, None).await, 6);
+    }
+
+    #[tokio::test]
+    async fn rewrite_manifests_keeps_deletes() {
+        let mut table = test_table().await;
+
+        let files = write(&table, vec![1, 2, 3]).await;
+        let deleted_from = files[0].file_path().clone();
+        table
+            .new_transaction(None)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+        let mut writer = PositionDeleteWriter::new(&table, None);
+        writer.delete(&deleted_from, 0);
+        let delete_files = writer.finish().await.unwrap();
+        table
+            .new_transaction(None)
+            .row_delta(Vec::new(), delete_files)
+            .commit()
+            .await
+            .unwrap();
+        append(&mut table, vec![4, 5]).await;
+
+        let delete_counts = |tasks: &[FileScanTask]| {
+            tasks
+                .iter()
+                .map(|task| (task.file_path().to_owned(), task.delete_files.len()))
+                .collect::<HashMap<_, _>>()
+        };
+        let before = delete_counts(&table.plan_scan(None, None).await.unwrap());
+        assert_eq!(before[&deleted_from], 1);
+        assert_eq!(before.values().sum::<usize>(), 1);
+
+        table
+            .new_transaction(None)
+            .rewrite_manifests()
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(current_sequence_number(&table), 4);
+
+        // The delete file still applies to the data file it was written for, but not to the
+        // data file that was appended afterwards
+        let tasks = table.plan_scan(None, None).await.unwrap();
+        assert_eq!(delete_counts(&tasks), before);
+        let task = tasks
+            .iter()
+            .find(|task| task.file_path() == deleted_from)
+            .unwrap();
+        assert_eq!(task.sequence_number, 1);
+    }
+
+    #[tokio::test]
+    async fn set_current_snapshot() {
+        let mut table = test_table().await;
+        let first = append(&mut table, vec![1, 2, 3]).await;
+        append(&mut table, vec![4, 5]).await;
+        let third = append(&mut table, vec![6]).await;
+
+        table
+            .new_transaction(None)
+            .set_current_snapshot(first, false)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().refs["main"].snapshot_id, first);
+        assert_eq!(table.metadata().current_snapshot_id, Some(first));
+        assert_eq!(row_count(&table, None).await, 3);
+
+        // Newer snapshots are no ancestors of the current snapshot anymore
+        assert!(table
+            .new_transaction(None)
+            .set_current_snapshot(third, false)
+            .commit()
+            .await
+            .is_err());
+        assert!(table
+            .new_transaction(None)
+            .set_current_snapshot(-1, true)
+            .commit()
+            .await
+            .is_err());
+
+        table
+            .new_transaction(None)
+            .set_current_snapshot(third, true)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().refs["main"].snapshot_id, third);
+        assert_eq!(row_count(&table, None).await, 6);
+    }
+
+    #[tokio::test]
+    async fn rollback_to_time() {
+        let timestamp = |table: &Table, id: i64| *table.metadata().snapshots[&id].timestamp_ms();
+        // Snapshots in the same millisecond can't be told apart by their timestamp
+        let tick = || tokio::time::sleep(std::time::Duration::from_millis(2));
+
+        let mut table = test_table().await;
+        let first = append(&mut table, vec![1, 2, 3]).await;
+        table
+            .new_transaction(None)
+            .create_branch("staging", first, None, None, None)
+            .commit()
+            .await
+            .unwrap();
+        tick().await;
+        let files = write(&table, vec![10]).await;
+        table
+            .new_transaction(Some("staging"))
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+        let staging = table.metadata().refs["staging"].snapshot_id;
+        tick().await;
+        let second = append(&mut table, vec![4]).await;
+        tick().await;
+        append(&mut table, vec![5]).await;
+        let (first_time, staging_time, second_time) = (
+            timestamp(&table, first),
+            timestamp(&table, staging),
+            timestamp(&table, second),
+        );
+
+        table
+            .new_transaction(None)
+            .rollback_to_time(second_time)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().refs["main"].snapshot_id, second);
+        assert_eq!(row_count(&table, None).await, 4);
+
+        // The newer snapshot of the staging branch is skipped
+        table
+            .new_transaction(None)
+            .rollback_to_time(staging_time)
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().refs["main"].snapshot_id, first);
+        assert_eq!(table.metadata().refs["staging"].snapshot_id, staging);
+        assert_eq!(row_count(&table, None).await, 3);
+
+        assert!(table
+            .new_transaction(None)
+            .rollback_to_time(first_time - 1)
+            .commit()
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,377 @@
+//! Partition spec evolution
+//!
+//! [`UpdatePartitionSpec`] describes the partition fields that are added to or removed from the
+//! default partition spec of a table. When the transaction is committed, the changes are applied
+//! to the current default spec and the result becomes the new default spec of the table.
+//!
+//! Files that were written with a previous spec keep their partition values. Every manifest
+//! records the id of the spec its files were written with, so they stay readable.
+
+use iceberg_rust_spec::spec::{
+    partition::{PartitionField, PartitionSpec, Transform},
+    table_metadata::{FormatVersion, TableMetadata},
+};
+
+use crate::error::Error;
+
+/// Id of the first partition field, partition field ids are assigned starting from this id
+static PARTITION_DATA_ID_START: i32 = 1000;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Changes to the default partition spec of a table
+///
+/// # Examples
+/// ```
+/// table.new_transaction(None)
+///     .update_spec(
+///         UpdatePartitionSpec::new()
+///             .add_field("ts", Transform::Day)
+///             .remove_field("category"),
+///     )
+///     .commit()
+///     .await?;
+/// ```
+pub struct UpdatePartitionSpec {
+    /// Partition fields to add as (name, source column, transform)
+    additions: Vec<(Option<String>, String, Transform)>,
+    /// Names of the partition fields to remove
+    removals: Vec<String>,
+}
+
+impl UpdatePartitionSpec {
+    /// Creates an empty update that keeps the current partition fields
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a partition field that applies the transform to a column of the current schema
+    ///
+    /// The partition field is named after the column and the transform, for example `ts_day`
+    /// for the day transform of the column `ts`. Identity partition fields use the column name.
+    ///
+    /// # Arguments
+    /// * `source_column` - Name of the column in the current schema
+    /// * `transform` - The transform that produces the partition values
+    pub fn add_field(mut self, source_column: &str, transform: Transform) -> Self {
+        self.additions
+            .push((None, source_column.to_owned(), transform));
+        self
+    }
+
+    /// Adds a partition field with the given name
+    ///
+    /// # Arguments
+    /// * `name` - Name of the new partition field
+    /// * `source_column` - Name of the column in the current schema
+    /// * `transform` - The transform that produces the partition values
+    pub fn add_named_field(
+        mut self,
+        name: &str,
+        source_column: &str,
+        transform: Transform,
+    ) -> Self {
+        self.additions
+            .push((Some(name.to_owned()), source_column.to_owned(), transform));
+        self
+    }
+
+    /// Removes a partition field of the current default spec
+    ///
+    /// Format version 1 requires the fields of all specs to be kept, so the field is replaced by
+    /// a void transform instead.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the partition field to remove
+    pub fn remove_field(mut self, name: &str) -> Self {
+        self.removals.push(name.to_owned());
+        self
+    }
+
+    /// Applies the changes to the default partition spec of the table
+    ///
+    /// Partition fields with the same source column and transform as a field of a previous spec
+    /// reuse the field id of that field, all other fields get new ids.
+    ///
+    /// # Returns
+    /// * The new partition spec with an unused spec id
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * A source column doesn't exist in the current schema
+    /// * A transform can't be applied to the type of its source column
+    /// * A removed partition field doesn't exist in the default spec
+    /// * A partition field name or source column and transform combination is not unique
+    pub(crate) fn apply(self, table_metadata: &TableMetadata) -> Result<PartitionSpec, Error> {
+        let schema = table_metadata.current_schema(None)?;
+        let default_spec = table_metadata.default_partition_spec()?;
+
+        let mut fields = default_spec.fields().clone();
+        for name in self.removals {
+            let position = fields
+                .iter()
+                .position(|field| *field.name() == name && *field.transform() != Transform::Void)
+                .ok_or(Error::NotFound(format!("Partition field {name}")))?;
+            match table_metadata.format_version {
+                FormatVersion::V1 => {
+                    // Rename the void field, so that its name can be used by a new field
+                    let field = &fields[position];
+                    let void = PartitionField::new(
+                        *field.source_id(),
+                        *field.field_id(),
+                        &format!("{}_{}", field.name(), field.field_id()),
+                        Transform::Void,
+                    );
+                    fields[position] = void;
+                }
+                FormatVersion::V2 => {
+                    fields.remove(position);
+                }
+            }
+        }
+
+        let mut last_partition_id = table_metadata
+            .partition_specs
+            .values()
+            .flat_map(|spec| spec.fields().iter().map(|field| *field.field_id()))
+            .chain(Some(table_metadata.last_partition_id))
+            .max()
+            .unwrap_or(PARTITION_DATA_ID_START - 1)
+            .max(PARTITION_DATA_ID_START - 1);
+
+        for (name, source_column, transform) in self.additions {
+            let source = schema
+                .get_name(&source_column)
+                .ok_or(Error::NotFound(format!("Column {source_column}")))?;
+            source.field_type.tranform(&transform)?;
+
+            if fields
+                .iter()
+                .any(|field| *field.source_id() == source.id && *field.transform() == transform)
+            {
+                return Err(Error::InvalidFormat(format!(
+                    "Partition field {transform} of column {source_column} already exists"
+                )));
+            }
+
+            let name = name.unwrap_or_else(|| partition_field_name(&source_column, &transform));
+
+            // Reuse the id of the same partition field of a previous spec
+            let field_id = table_metadata
+                .partition_specs
+                .values()
+                .flat_map(|spec| spec.fields())
+                .find(|field| *field.source_id() == source.id && *field.transform() == transform)
+                .map(|field| *field.field_id())
+                .unwrap_or_else(|| {
+                    last_partition_id += 1;
+                    last_partition_id
+                });
+
+            fields.push(PartitionField::new(source.id, field_id, &name, transform));
+        }
+
+        for (i, field) in fields.iter().enumerate() {
+            if fields[..i].iter().any(|x| x.name() == field.name()) {
+                return Err(Error::InvalidFormat(format!(
+                    "Duplicate partition field name {}",
+                    field.name()
+                )));
+            }
+        }
+
+        let spec_id = table_metadata
+            .partition_specs
+            .keys()
+            .max()
+            .map(|x| x + 1)
+            .unwrap_or_default();
+
+        Ok(PartitionSpec::builder()
+            .with_spec_id(spec_id)
+            .with_fields(fields)
+            .build()
+            .map_err(iceberg_rust_spec::error::Error::from)?)
+    }
+}
+
+/// Returns the default name of a partition field
+fn partition_field_name(source_column: &str, transform: &Transform) -> String {
+    match transform {
+        Transform::Identity => source_column.to_owned(),
+        Transform::Bucket(n) => format!("{source_column}_bucket_{n}"),
+        Transform::Truncate(width) => format!("{source_column}_trunc_{width}"),
+        Transform::Year => format!("{source_column}_year"),
+        Transform::Month => format!("{source_column}_month"),
+        Transform::Day => format!("{source_column}_day"),
+        Transform::Hour => format!("{source_column}_hour"),
+        Transform::Void => format!("{source_column}_null"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{Int64Array, StringArray},
+        datatypes::Schema as ArrowSchema,
+        record_batch::RecordBatch,
+    };
+    use futures::{stream, TryStreamExt};
+    use iceberg_rust_spec::spec::{
+        partition::{PartitionField, Transform},
+        schema::Schema,
+        types::{PrimitiveType, StructField, Type},
+    };
+
+    use crate::{arrow::write::write_parquet_partitioned, table::Table, test_util::TestTable};
+
+    use super::UpdatePartitionSpec;
+
+    async fn test_table() -> Table {
+        let schema = Schema::builder()
+            .with_struct_field(StructField {
+                id: 1,
+                name: "id".to_string(),
+                required: true,
+                field_type: Type::Primitive(PrimitiveType::Long),
+                doc: None,
+            })
+            .with_struct_field(StructField {
+                id: 2,
+                name: "category".to_string(),
+                required: false,
+                field_type: Type::Primitive(PrimitiveType::String),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+        TestTable::new(schema).await.unwrap()
+    }
+
+    async fn append(table: &mut Table, ids: Vec<i64>, categories: Vec<Option<&str>>) {
+        let arrow_schema: ArrowSchema = table
+            .current_schema(None)
+            .unwrap()
+            .fields()
+            .try_into()
+            .unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(arrow_schema),
+            vec![
+                Arc::new(Int64Array::from(ids)),
+                Arc::new(StringArray::from(categories)),
+            ],
+        )
+        .unwrap();
+        let files = write_parquet_partitioned(table, stream::iter(vec![Ok(batch)]), None)
+            .await
+            .unwrap();
+        table
+            .new_transaction(None)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn evolve_partition_spec() {
+        let mut table = test_table().await;
+        append(&mut table, vec![1, 2, 3], vec![Some("a"), None, Some("b")]).await;
+
+        table
+            .new_transaction(None)
+            .update_spec(UpdatePartitionSpec::new().add_field("category", Transform::Identity))
+            .commit()
+            .await
+            .unwrap();
+
+        let metadata = table.metadata();
+        assert_eq!(metadata.default_spec_id, 1);
+        assert_eq!(metadata.last_partition_id, 1000);
+        assert_eq!(
+            metadata.default_partition_spec().unwrap().fields(),
+            &vec![PartitionField::new(
+                2,
+                1000,
+                "category",
+                Transform::Identity
+            )]
+        );
+
+        // Files written with the previous spec stay readable
+        let batches = table
+            .scan(None, None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 3);
+
+        // Removing the field switches back to the unpartitioned spec
+        table
+            .new_transaction(None)
+            .update_spec(UpdatePartitionSpec::new().remove_field("category"))
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().default_spec_id, 0);
+        assert_eq!(table.metadata().partition_specs.len(), 2);
+
+        table
+            .new_transaction(None)
+            .update_spec(UpdatePartitionSpec::new().add_field("id", Transform::Bucket(4)))
+            .commit()
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().default_spec_id, 2);
+        assert_eq!(
+            table.metadata().default_partition_spec().unwrap().fields(),
+            &vec![PartitionField::new(
+                1,
+                1001,
+                "id_bucket_4",
+                Transform::Bucket(4)
+            )]
+        );
+
+        assert!(table
+            .new_transaction(None)
+            .update_spec(UpdatePartitionSpec::new().add_field("missing", Transform::Identity))
+            .commit()
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn append_after_partition_spec_evolution() {
+        let mut table = test_table().await;
+        append(&mut table, vec![1, 2, 3], vec![Some("a"), None, Some("b")]).await;
+
+        table
+            .new_transaction(None)
+            .update_spec(UpdatePartitionSpec::new().add_field("category", Transform::Identity))
+            .commit()
+            .await
+            .unwrap();
+
+        append(&mut table, vec![4, 5], vec![Some("a"), Some("c")]).await;
+
+        // The files of the new spec are not merged into the manifest of the previous spec
+        let manifests = table.manifests(None, None).await.unwrap();
+        let mut spec_ids = manifests
+            .iter()
+            .map(|manifest| manifest.partition_spec_id)
+            .collect::<Vec<_>>();
+        spec_ids.sort();
+        spec_ids.dedup();
+        assert_eq!(spec_ids, vec![0, 1]);
+
+        let batches = table
+            .scan(None, None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 5);
+    }
+}
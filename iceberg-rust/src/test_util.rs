@@ -0,0 +1,625 @@
+/*!
+Helpers to create tables in tests without a catalog server or cloud credentials.
+
+The [MemoryCatalog] keeps the table metadata in memory and writes the metadata, manifest and data
+files to an in-memory or local object store. [TestTable] creates a table in a new catalog.
+*/
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use iceberg_rust_spec::{
+    identifier::FullIdentifier,
+    spec::{
+        schema::Schema,
+        table_metadata::{new_metadata_location, TableMetadata},
+        tabular::TabularMetadata,
+    },
+};
+use object_store::{local::LocalFileSystem, memory::InMemory, ObjectStore};
+
+use crate::{
+    catalog::{
+        commit::{
+            apply_table_updates, validate_table_requirements, CommitTable, CommitView, TableUpdate,
+        },
+        create::{CreateMaterializedView, CreateTable, CreateView},
+        identifier::Identifier,
+        namespace::Namespace,
+        tabular::Tabular,
+        Catalog,
+    },
+    error::Error,
+    materialized_view::MaterializedView,
+    object_store::{store::IcebergStore, Bucket},
+    table::Table,
+    view::View,
+};
+
+/// Catalog that keeps the metadata locations of its tables in memory.
+///
+/// Namespaces exist implicitly through their tables. Views and materialized views are not
+/// supported.
+#[derive(Debug)]
+pub struct MemoryCatalog {
+    name: String,
+    object_store: Arc<dyn ObjectStore>,
+    tables: RwLock<HashMap<Identifier, (String, TableMetadata)>>,
+}
+
+impl MemoryCatalog {
+    /// Creates an empty catalog that stores the files of its tables in the given object store
+    pub fn new(name: &str, object_store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            name: name.to_owned(),
+            object_store,
+            tables: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Creates an empty catalog that stores the files of its tables in memory
+    pub fn memory(name: &str) -> Self {
+        Self::new(name, Arc::new(InMemory::new()))
+    }
+
+    /// Creates an empty catalog that stores the files of its tables in the local filesystem
+    /// below `root`
+    pub fn local(name: &str, root: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self::new(
+            name,
+            Arc::new(LocalFileSystem::new_with_prefix(root)?),
+        ))
+    }
+
+    /// Returns the location of the current metadata file of a table
+    pub fn metadata_location(&self, identifier: &Identifier) -> Option<String> {
+        self.tables
+            .read()
+            .unwrap()
+            .get(identifier)
+            .map(|(location, _)| location.clone())
+    }
+}
+
+#[async_trait]
+impl Catalog for MemoryCatalog {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    async fn create_namespace(
+        &self,
+        _namespace: &Namespace,
+        _properties: Option<HashMap<String, String>>,
+    ) -> Result<HashMap<String, String>, Error> {
+        Err(Error::NotSupported(
+            "Namespaces in memory catalog".to_owned(),
+        ))
+    }
+    async fn drop_namespace(&self, _namespace: &Namespace) -> Result<(), Error> {
+        Err(Error::NotSupported(
+            "Namespaces in memory catalog".to_owned(),
+        ))
+    }
+    async fn load_namespace(
+        &self,
+        _namespace: &Namespace,
+    ) -> Result<HashMap<String, String>, Error> {
+        Err(Error::NotSupported(
+            "Namespaces in memory catalog".to_owned(),
+        ))
+    }
+    async fn update_namespace(
+        &self,
+        _namespace: &Namespace,
+        _updates: Option<HashMap<String, String>>,
+        _removals: Option<Vec<String>>,
+    ) -> Result<(), Error> {
+        Err(Error::NotSupported(
+            "Namespaces in memory catalog".to_owned(),
+        ))
+    }
+    async fn namespace_exists(&self, namespace: &Namespace) -> Result<bool, Error> {
+        Ok(self
+            .tables
+            .read()
+            .unwrap()
+            .keys()
+            .any(|identifier| identifier.namespace() == namespace))
+    }
+    async fn list_tabulars(&self, namespace: &Namespace) -> Result<Vec<Identifier>, Error> {
+        Ok(self
+            .tables
+            .read()
+            .unwrap()
+            .keys()
+            .filter(|identifier| identifier.namespace() == namespace)
+            .cloned()
+            .collect())
+    }
+    async fn list_namespaces(&self, _parent: Option<&str>) -> Result<Vec<Namespace>, Error> {
+        Ok(self
+            .tables
+            .read()
+            .unwrap()
+            .keys()
+            .map(|identifier| identifier.namespace().clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect())
+    }
+    async fn tabular_exists(&self, identifier: &Identifier) -> Result<bool, Error> {
+        Ok(self.tables.read().unwrap().contains_key(identifier))
+    }
+    async fn table_exists(&self, identifier: &Identifier) -> Result<bool, Error> {
+        self.tabular_exists(identifier).await
+    }
+    async fn drop_table(&self, identifier: &Identifier) -> Result<(), Error> {
+        self.tables
+            .write()
+            .unwrap()
+            .remove(identifier)
+            .map(|_| ())
+            .ok_or(Error::CatalogNotFound)
+    }
+    async fn drop_view(&self, _identifier: &Identifier) -> Result<(), Error> {
+        Err(Error::NotSupported("Views in memory catalog".to_owned()))
+    }
+    async fn drop_materialized_view(&self, _identifier: &Identifier) -> Result<(), Error> {
+        Err(Error::NotSupported(
+            "Materialized views in memory catalog".to_owned(),
+        ))
+    }
+    async fn load_tabular(self: Arc<Self>, identifier: &Identifier) -> Result<Tabular, Error> {
+        let metadata = self
+            .tables
+            .read()
+            .unwrap()
+            .get(identifier)
+            .map(|(_, metadata)| metadata.clone())
+            .ok_or(Error::CatalogNotFound)?;
+        Ok(Tabular::Table(
+            Table::new(identifier.clone(), self.clone(), metadata).await?,
+        ))
+    }
+    async fn create_table(
+        self: Arc<Self>,
+        identifier: Identifier,
+        mut create_table: CreateTable,
+    ) -> Result<Table, Error> {
+        if self.tabular_exists(&identifier).await? {
+            return Err(Error::InvalidFormat(
+                "Table already exists. Identifier".to_owned(),
+            ));
+        }
+        if create_table.location.is_none() {
+            create_table.location = Some(format!(
+                "/{}/{}",
+                identifier.namespace().join("/"),
+                identifier.name()
+            ));
+        }
+        let metadata: TableMetadata = create_table.try_into()?;
+
+        let metadata_location = new_metadata_location(&metadata);
+        self.object_store
+            .put_metadata(&metadata_location, metadata.as_ref())
+            .await?;
+
+        self.tables
+            .write()
+            .unwrap()
+            .insert(identifier.clone(), (metadata_location, metadata.clone()));
+
+        Table::new(identifier, self.clone(), metadata).await
+    }
+    async fn create_view(
+        self: Arc<Self>,
+        _identifier: Identifier,
+        _create_view: CreateView<Option<()>>,
+    ) -> Result<View, Error> {
+        Err(Error::NotSupported("Views in memory catalog".to_owned()))
+    }
+    async fn create_materialized_view(
+        self: Arc<Self>,
+        _identifier: Identifier,
+        _create_view: CreateMaterializedView,
+    ) -> Result<MaterializedView, Error> {
+        Err(Error::NotSupported(
+            "Materialized views in memory catalog".to_owned(),
+        ))
+    }
+    async fn update_table(self: Arc<Self>, commit: CommitTable) -> Result<Table, Error> {
+        let identifier = commit.identifier;
+        let (previous_metadata_location, mut metadata) = self
+            .tables
+            .read()
+            .unwrap()
+            .get(&identifier)
+            .cloned()
+            .ok_or(Error::CatalogNotFound)?;
+
+        validate_table_requirements(&commit.requirements, &metadata)?;
+
+        // Branch that is updated by the commit, used to report a concurrent update
+        let reference = commit
+            .updates
+            .iter()
+            .find_map(|update| match update {
+                TableUpdate::SetSnapshotRef { ref_name, .. } => Some(ref_name.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| "main".to_owned());
+        let expected = snapshot_id(&metadata, &reference);
+
+        apply_table_updates(&mut metadata, commit.updates)?;
+
+        let metadata_location = new_metadata_location(&metadata);
+        self.object_store
+            .put_metadata(&metadata_location, metadata.as_ref())
+            .await?;
+
+        {
+            let mut tables = self.tables.write().unwrap();
+            let current = tables.get(&identifier).ok_or(Error::CatalogNotFound)?;
+            if current.0 != previous_metadata_location {
+                validate_table_requirements(&commit.requirements, &current.1)?;
+                return Err(Error::CommitConflict {
+                    actual: snapshot_id(&current.1, &reference),
+                    reference,
+                    expected,
+                });
+            }
+            tables.insert(identifier.clone(), (metadata_location, metadata.clone()));
+        }
+
+        Table::new(identifier, self.clone(), metadata).await
+    }
+    async fn update_view(self: Arc<Self>, _commit: CommitView<Option<()>>) -> Result<View, Error> {
+        Err(Error::NotSupported("Views in memory catalog".to_owned()))
+    }
+    async fn update_materialized_view(
+        self: Arc<Self>,
+        _commit: CommitView<FullIdentifier>,
+    ) -> Result<MaterializedView, Error> {
+        Err(Error::NotSupported(
+            "Materialized views in memory catalog".to_owned(),
+        ))
+    }
+    async fn register_table(
+        self: Arc<Self>,
+        identifier: Identifier,
+        metadata_location: &str,
+    ) -> Result<Table, Error> {
+        if self.tabular_exists(&identifier).await? {
+            return Err(Error::InvalidFormat(
+                "Table already exists. Identifier".to_owned(),
+            ));
+        }
+        let TabularMetadata::Table(metadata) =
+            self.object_store.get_metadata(metadata_location).await?
+        else {
+            return Err(Error::InvalidFormat(
+                "Entity is not a table. Metadata".to_owned(),
+            ));
+        };
+
+        self.tables.write().unwrap().insert(
+            identifier.clone(),
+            (metadata_location.to_owned(), metadata.clone()),
+        );
+
+        Table::new(identifier, self.clone(), metadata).await
+    }
+    async fn rename_table(&self, from: &Identifier, to: &Identifier) -> Result<(), Error> {
+        let mut tables = self.tables.write().unwrap();
+        if tables.contains_key(to) {
+            return Err(Error::InvalidFormat(
+                "Table already exists. Identifier".to_owned(),
+            ));
+        }
+        let entry = tables.remove(from).ok_or(Error::CatalogNotFound)?;
+        tables.insert(to.clone(), entry);
+        Ok(())
+    }
+    fn object_store(&self, _bucket: Bucket) -> Arc<dyn ObjectStore> {
+        self.object_store.clone()
+    }
+}
+
+/// Snapshot id the reference points to, -1 if the reference doesn't exist
+fn snapshot_id(metadata: &TableMetadata, reference: &str) -> i64 {
+    metadata.refs.get(reference).map_or(-1, |x| x.snapshot_id)
+}
+
+/// Creates tables that are ready to be written to in a new [MemoryCatalog]
+pub struct TestTable;
+
+impl TestTable {
+    /// Creates the table `test.table` with the given schema whose files are stored in memory
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new(schema: Schema) -> Result<Table, Error> {
+        Self::create(schema, MemoryCatalog::memory("test")).await
+    }
+
+    /// Creates the table `test.table` with the given schema whose files are stored in the local
+    /// filesystem below `root`
+    pub async fn local(schema: Schema, root: impl AsRef<Path>) -> Result<Table, Error> {
+        Self::create(schema, MemoryCatalog::local("test", root)?).await
+    }
+
+    async fn create(schema: Schema, catalog: MemoryCatalog) -> Result<Table, Error> {
+        Table::builder()
+            .with_name("table")
+            .with_location("/test/table")
+            .with_schema(schema)
+            .build(&["test".to_owned()], Arc::new(catalog))
+            .await
+    }
+}
+
+/// Metadata of a view, used in tests that read and parse metadata files
+#[cfg(test)]
+pub(crate) const VIEW_METADATA: &str = r#"
+    {
+    "view-uuid": "fa6506c3-7681-40c8-86dc-e36561f83385",
+    "format-version" : 1,
+    "location" : "s3://bucket/warehouse/default.db/event_agg",
+    "current-version-id" : 1,
+    "properties" : {},
+    "versions" : [ {
+        "version-id" : 1,
+        "timestamp-ms" : 1573518431292,
+        "schema-id" : 1,
+        "default-namespace" : [ "default" ],
+        "summary" : {
+        "operation" : "create"
+        },
+        "representations" : [ {
+        "type" : "sql",
+        "sql" : "SELECT event_count FROM events",
+        "dialect" : "spark"
+        } ]
+    } ],
+    "schemas": [ {
+        "schema-id": 1,
+        "type" : "struct",
+        "fields" : [ {
+        "id" : 1,
+        "name" : "event_count",
+        "required" : false,
+        "type" : "int"
+        } ]
+    } ],
+    "version-log" : [ {
+        "timestamp-ms" : 1573518431292,
+        "version-id" : 1
+    } ]
+    }
+    "#;
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicI64, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use arrow::{
+        array::{ArrayRef, Int64Array, StringArray},
+        datatypes::Schema as ArrowSchema,
+        record_batch::RecordBatch,
+    };
+    use futures::{stream, TryStreamExt};
+    use iceberg_rust_spec::spec::{
+        manifest::DataFile,
+        schema::Schema,
+        types::{PrimitiveType, StructField, Type},
+    };
+    use uuid::Uuid;
+
+    use crate::{
+        arrow::write::write_parquet_partitioned,
+        catalog::{identifier::Identifier, namespace::Namespace, Catalog},
+        error::Error,
+        table::Table,
+    };
+
+    use super::{MemoryCatalog, TestTable};
+
+    fn id_field() -> StructField {
+        StructField {
+            id: 1,
+            name: "id".to_string(),
+            required: true,
+            field_type: Type::Primitive(PrimitiveType::Long),
+            doc: None,
+        }
+    }
+
+    fn id_schema() -> Schema {
+        Schema::builder()
+            .with_struct_field(id_field())
+            .build()
+            .unwrap()
+    }
+
+    async fn write(table: &Table, columns: Vec<ArrayRef>) -> Vec<DataFile> {
+        let arrow_schema: ArrowSchema = table
+            .current_schema(None)
+            .unwrap()
+            .fields()
+            .try_into()
+            .unwrap();
+        let batch = RecordBatch::try_new(Arc::new(arrow_schema), columns).unwrap();
+        write_parquet_partitioned(table, stream::iter(vec![Ok(batch)]), None)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn append_and_scan() {
+        let schema = Schema::builder()
+            .with_struct_field(id_field())
+            .with_struct_field(StructField {
+                id: 2,
+                name: "name".to_string(),
+                required: false,
+                field_type: Type::Primitive(PrimitiveType::String),
+                doc: None,
+            })
+            .build()
+            .unwrap();
+
+        let mut table = TestTable::new(schema).await.unwrap();
+
+        let files = write(
+            &table,
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])),
+            ],
+        )
+        .await;
+        table
+            .new_transaction(None)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+
+        assert!(table.metadata().current_snapshot(None).unwrap().is_some());
+
+        let batches = table
+            .scan(Some(vec![2, 1]), None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 3);
+        let schema = batches[0].schema();
+        assert_eq!(schema.field(0).name(), "name");
+        assert_eq!(schema.field(1).name(), "id");
+    }
+
+    #[tokio::test]
+    async fn deterministic_snapshot_ids() {
+        let mut table = TestTable::new(id_schema()).await.unwrap();
+
+        let files = write(&table, vec![Arc::new(Int64Array::from(vec![1, 2, 3]))]).await;
+
+        let counter = Arc::new(AtomicI64::new(1));
+        table
+            .new_transaction(None)
+            .with_snapshot_id_source(move || counter.fetch_add(1, Ordering::SeqCst))
+            .with_commit_uuid_source(Uuid::nil)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+
+        let snapshot = table.metadata().current_snapshot(None).unwrap().unwrap();
+        assert_eq!(*snapshot.snapshot_id(), 1);
+        assert!(snapshot.manifest_list().contains(&Uuid::nil().to_string()));
+    }
+
+    #[tokio::test]
+    async fn stale_table_commit_conflict() {
+        let mut table = TestTable::new(id_schema()).await.unwrap();
+
+        let files = write(&table, vec![Arc::new(Int64Array::from(vec![1, 2, 3]))]).await;
+
+        table
+            .new_transaction(None)
+            .append_data(files.clone())
+            .commit()
+            .await
+            .unwrap();
+
+        let mut stale = table
+            .catalog()
+            .load_table(table.identifier())
+            .await
+            .unwrap();
+
+        table
+            .new_transaction(None)
+            .append_data(files.clone())
+            .commit()
+            .await
+            .unwrap();
+
+        let result = stale
+            .new_transaction(None)
+            .append_data(files.clone())
+            .commit()
+            .await;
+        assert!(matches!(result, Err(Error::CommitConflict { .. })));
+
+        stale
+            .new_transaction(None)
+            .with_retry(2, Duration::ZERO)
+            .append_data(files)
+            .commit()
+            .await
+            .unwrap();
+
+        let table = table
+            .catalog()
+            .load_table(table.identifier())
+            .await
+            .unwrap();
+        assert_eq!(table.metadata().snapshots.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn list_tables_paginated() {
+        let schema = id_schema();
+
+        let catalog: Arc<dyn Catalog> = Arc::new(MemoryCatalog::memory("test"));
+        for name in ["c", "a", "b"] {
+            Table::builder()
+                .with_name(name)
+                .with_location(format!("/test/{name}"))
+                .with_schema(schema.clone())
+                .build(&["test".to_owned()], catalog.clone())
+                .await
+                .unwrap();
+        }
+
+        let namespace = Namespace::try_new(&["test".to_owned()]).unwrap();
+        let first = catalog
+            .list_tables_paginated(&namespace, None, Some(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            first
+                .identifiers
+                .iter()
+                .map(Identifier::name)
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(first.next_page_token.as_deref(), Some("b"));
+
+        let second = catalog
+            .list_tables_paginated(&namespace, first.next_page_token.as_deref(), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            second
+                .identifiers
+                .iter()
+                .map(Identifier::name)
+                .collect::<Vec<_>>(),
+            vec!["c"]
+        );
+        assert_eq!(second.next_page_token, None);
+    }
+}
@@ -199,4 +199,21 @@ impl View {
     pub fn new_transaction(&mut self, branch: Option<&str>) -> ViewTransaction {
         ViewTransaction::new(self, branch)
     }
+    /// Makes a previous version of the view the current version
+    ///
+    /// # Arguments
+    /// * `version_id` - Id of the version to roll back to
+    ///
+    /// # Returns
+    /// * `Result<(), Error>` - Ok if the view was rolled back, or an error if the version
+    ///   doesn't exist or the commit failed
+    ///
+    /// The previous versions of the view are listed in the `versions` and `version_log` of the
+    /// view metadata.
+    pub async fn rollback_to_version(&mut self, version_id: i64) -> Result<(), Error> {
+        self.new_transaction(None)
+            .rollback_to_version(version_id)
+            .commit()
+            .await
+    }
 }
@@ -46,6 +46,14 @@ impl<'view> Transaction<'view> {
             .push(ViewOperation::UpdateProperties(entries));
         self
     }
+    /// Make a previous version of the view the current version
+    pub fn rollback_to_version(mut self, version_id: i64) -> Self {
+        self.operations.push(ViewOperation::Rollback {
+            version_id,
+            branch: self.branch.clone(),
+        });
+        self
+    }
     /// Commit the transaction to perform the [Operation]s with ACID guarantees.
     pub async fn commit(self) -> Result<(), Error> {
         let catalog = self.view.catalog();
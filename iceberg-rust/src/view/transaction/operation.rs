@@ -33,6 +33,13 @@ pub enum Operation {
     },
     /// Update view properties
     UpdateProperties(Vec<(String, String)>),
+    /// Make a previous version the current version
+    Rollback {
+        /// Id of the version to roll back to
+        version_id: i64,
+        /// Branch to roll back
+        branch: Option<String>,
+    },
 }
 
 impl Operation {
@@ -96,6 +103,30 @@ impl Operation {
                     ],
                 ))
             }
+            Operation::Rollback { version_id, branch } => {
+                if !metadata.versions.contains_key(&version_id) {
+                    return Err(Error::NotFound(format!("View version {version_id}")));
+                }
+
+                let branch_name = branch.unwrap_or("main".to_string());
+
+                Ok((
+                    Some(ViewRequirement::AssertViewUuid {
+                        uuid: metadata.view_uuid,
+                    }),
+                    vec![
+                        ViewUpdate::SetCurrentViewVersion {
+                            view_version_id: version_id,
+                        },
+                        ViewUpdate::SetProperties {
+                            updates: HashMap::from_iter(vec![(
+                                REF_PREFIX.to_string() + &branch_name,
+                                version_id.to_string(),
+                            )]),
+                        },
+                    ],
+                ))
+            }
             Operation::UpdateProperties(entries) => Ok((
                 None,
                 vec![ViewUpdate::SetProperties {